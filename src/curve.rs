@@ -8,12 +8,24 @@ mod ecdsa;
 #[cfg(feature = "bip32")]
 mod bip32;
 
+#[cfg(feature = "k256")]
+mod bip340;
+
+#[cfg(feature = "ethereum")]
+pub mod ethereum;
+
 pub use ecdsa::RecoverableSignature;
 
 pub(crate) use arithmetic::{chain_curve, secret_split, Point, Scalar};
 
 #[cfg(feature = "bip32")]
-pub use bip32::{DeriveChildKey, PublicTweakable, SecretTweakable};
+pub use bip32::{Bip32Tweak, DeriveChildKey, PublicTweakable, SecretTweakable};
 
 #[cfg(feature = "bip32")]
 pub(crate) use bip32::{apply_tweaks_public, derive_tweaks};
+
+#[cfg(feature = "k256")]
+pub use bip340::TaprootTweak;
+
+#[cfg(feature = "k256")]
+pub(crate) use bip340::{assemble_bip340_signature, bip340_challenge, parity_factor, TweakedKey};
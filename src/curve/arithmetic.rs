@@ -2,7 +2,7 @@ use alloc::{boxed::Box, format, string::String, vec, vec::Vec};
 use core::ops::{Add, AddAssign, Mul, MulAssign, Neg, Rem, Sub, SubAssign};
 
 use digest::XofReader;
-use ecdsa::VerifyingKey;
+use ecdsa::{SigningKey, VerifyingKey};
 use elliptic_curve::{
     bigint::{ArrayEncoding, Concat, NonZero, Split, Zero},
     generic_array::{typenum::marker_traits::Unsigned, GenericArray},
@@ -13,15 +13,13 @@ use elliptic_curve::{
     sec1::{EncodedPoint, FromEncodedPoint, ModulusSize, ToEncodedPoint},
     subtle::{Choice, ConditionallySelectable, CtOption},
     Curve, CurveArithmetic, Field, FieldBytes, FieldBytesSize, Group, NonZeroScalar, PrimeField, ScalarPrimitive,
+    SecretKey,
 };
 use rand_core::CryptoRngCore;
 use serde::{Deserialize, Deserializer, Serialize, Serializer};
 use serde_encoded_bytes::{Hex, SliceLike};
 use zeroize::Zeroize;
 
-#[cfg(feature = "bip32")]
-use ::{ecdsa::SigningKey, elliptic_curve::SecretKey};
-
 use crate::{
     params::SchemeParams,
     tools::{hashing::Chain, BoxedRng, Secret},
@@ -143,7 +141,6 @@ impl<P: SchemeParams> Scalar<P> {
     }
 }
 
-#[cfg(feature = "bip32")]
 impl<P: SchemeParams> Secret<Scalar<P>> {
     pub fn to_signing_key(&self) -> Option<SigningKey<P::Curve>> {
         let nonzero_scalar: Secret<NonZeroScalar<_>> =
@@ -274,14 +271,6 @@ where
             .ok_or_else(|| "Invalid curve point representation".into())
     }
 
-    pub(crate) fn to_compressed_array(
-        self,
-    ) -> GenericArray<u8, <FieldBytesSize<P::Curve> as ModulusSize>::CompressedPointSize> {
-        GenericArray::from_exact_iter(
-            self.0.to_affine().to_encoded_point(true).as_bytes().iter().cloned(),
-        ).expect("An AffinePoint is composed of elements of the correct size and their slice repr fits in the `CompressedPointSize`-sized array.")
-    }
-
     pub(crate) fn to_backend(self) -> <P::Curve as CurveArithmetic>::ProjectivePoint {
         self.0
     }
@@ -302,7 +291,8 @@ where
     P: SchemeParams,
 {
     fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
-        SliceLike::<Hex>::serialize(&self.to_compressed_array(), serializer)
+        // Not a fixed-size array, since the point at infinity is encoded as a single zero byte.
+        SliceLike::<Hex>::serialize(&self.0.to_affine().to_encoded_point(true), serializer)
     }
 }
 
@@ -1,13 +1,16 @@
 use alloc::vec::Vec;
 
-use bip32::{ChainCode, DerivationPath, PrivateKeyBytes, PublicKey as _};
+use bip32::{ChainCode, DerivationPath, PrivateKey as _, PrivateKeyBytes, PublicKey as _};
 use digest::Digest;
-use ecdsa::{hazmat::DigestPrimitive, VerifyingKey};
+use ecdsa::{hazmat::DigestPrimitive, SigningKey, VerifyingKey};
 use elliptic_curve::{
     sec1::{FromEncodedPoint, ModulusSize, ToEncodedPoint},
     Curve, CurveArithmetic, PrimeCurve,
 };
 
+use super::arithmetic::Scalar;
+use crate::{tools::Secret, SchemeParams};
+
 /// Used for deriving child keys from a parent type.
 pub trait DeriveChildKey<C: CurveArithmetic + PrimeCurve>: Sized {
     /// Return a verifying key derived from the given type using the BIP-32 scheme.
@@ -49,6 +52,43 @@ where
     }
 }
 
+/// The additive tweak that, applied to a secret key, produces the child key for some BIP-32 derivation path.
+///
+/// Can be computed once per derivation path and used for signing under the corresponding child key
+/// without deriving a child key share (see [`InteractiveSigning::with_bip32_tweak`](`crate::InteractiveSigning::with_bip32_tweak`)).
+#[derive(Debug, Clone, Copy)]
+pub struct Bip32Tweak<P: SchemeParams>(Scalar<P>);
+
+impl<P> Bip32Tweak<P>
+where
+    P: SchemeParams,
+    VerifyingKey<P::Curve>: PublicTweakable,
+    SigningKey<P::Curve>: SecretTweakable,
+{
+    /// Computes the tweak for the given parent public key and derivation path.
+    pub fn new(verifying_key: &VerifyingKey<P::Curve>, derivation_path: &DerivationPath) -> Result<Self, bip32::Error> {
+        let tweaks = derive_tweaks::<P::Curve>(&verifying_key.tweakable_pk(), derivation_path)?;
+
+        // The way the tweaks are applied is defined by the curve's `SecretTweakable` implementation,
+        // but they are additive, so applying them to a unit key gives us `1 + tweak`.
+        let unit_key = Secret::init_with(|| Scalar::<P>::ONE)
+            .to_signing_key()
+            .ok_or(bip32::Error::Crypto)?;
+        let mut tweakable_sk = unit_key.tweakable_sk();
+        for tweak in &tweaks {
+            tweakable_sk = tweakable_sk.derive_child(*tweak)?;
+        }
+        let sk: SigningKey<P::Curve> = SecretTweakable::key_from_tweakable_sk(&tweakable_sk);
+        Ok(Self(Scalar::new(*sk.as_nonzero_scalar().as_ref()) - Scalar::ONE))
+    }
+}
+
+impl<P: SchemeParams> Bip32Tweak<P> {
+    pub(crate) fn to_scalar(self) -> Scalar<P> {
+        self.0
+    }
+}
+
 pub(crate) fn derive_tweaks<C>(
     public_key: &(impl bip32::PublicKey + Clone),
     derivation_path: &DerivationPath,
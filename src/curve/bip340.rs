@@ -0,0 +1,161 @@
+//! BIP-340 Schnorr signatures and BIP-341 (Taproot) output key tweaking
+//! for the parameter sets using Secp256k1.
+
+use alloc::string::String;
+
+use elliptic_curve::{point::AffineCoordinates, FieldBytes};
+use k256::{
+    schnorr::{Signature, VerifyingKey},
+    Secp256k1,
+};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use signature::hazmat::PrehashVerifier;
+
+use super::{Point, Scalar};
+use crate::params::SchemeParams;
+
+/// The Taproot (BIP-341) tweak applied to the internal key before signing.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum TaprootTweak {
+    /// No tweak, sign with the (even-Y normalized) internal key itself.
+    None,
+    /// Key path spend for an output with no script tree.
+    KeyPathOnly,
+    /// Key path spend for an output committing to a script tree with the given Merkle root.
+    ScriptTree([u8; 32]),
+}
+
+/// The x-only public key used for signing, and the transformation of the secret key needed to get its secret part.
+#[derive(Debug, Clone, Copy)]
+pub(crate) struct TweakedKey<P: SchemeParams> {
+    /// The public key with an even Y coordinate.
+    pub(crate) public_key: Point<P>,
+    /// The factor (`1` or `-1`) that the secret key must be multiplied by.
+    pub(crate) factor: Scalar<P>,
+    /// The value that must be added to the secret key after multiplying it by `factor`.
+    pub(crate) offset: Scalar<P>,
+}
+
+impl<P: SchemeParams<Curve = Secp256k1>> TweakedKey<P> {
+    /// Normalizes the internal key to have an even Y coordinate, and applies the Taproot tweak.
+    ///
+    /// Fails if the tweak hash is not less than the curve order, as BIP-341 requires.
+    pub fn new(internal_key: &Point<P>, tweak: &TaprootTweak) -> Result<Self, String> {
+        let internal_factor = parity_factor(internal_key);
+        let internal_key = *internal_key * internal_factor;
+
+        let offset = match tweak {
+            TaprootTweak::None => Scalar::ZERO,
+            TaprootTweak::KeyPathOnly => taproot_tweak(&internal_key, &[])?,
+            TaprootTweak::ScriptTree(merkle_root) => taproot_tweak(&internal_key, merkle_root)?,
+        };
+        let output_key = internal_key + offset.mul_by_generator();
+
+        let output_factor = parity_factor(&output_key);
+        Ok(Self {
+            public_key: output_key * output_factor,
+            factor: internal_factor * output_factor,
+            offset: offset * output_factor,
+        })
+    }
+}
+
+/// Returns `1` if the Y coordinate of the point is even, and `-1` otherwise.
+pub(crate) fn parity_factor<P: SchemeParams<Curve = Secp256k1>>(point: &Point<P>) -> Scalar<P> {
+    if bool::from(point.to_backend().to_affine().y_is_odd()) {
+        -Scalar::ONE
+    } else {
+        Scalar::ONE
+    }
+}
+
+/// The BIP-340 challenge for the given nonce point, public key (both assumed to have even Y coordinates),
+/// and message.
+pub(crate) fn bip340_challenge<P: SchemeParams<Curve = Secp256k1>>(
+    nonce: &Point<P>,
+    public_key: &Point<P>,
+    message: &[u8; 32],
+) -> Scalar<P> {
+    let hash = tagged_hash(
+        b"BIP0340/challenge",
+        &[&x_only_bytes(nonce), &x_only_bytes(public_key), message],
+    );
+    Scalar::from_reduced_bytes(hash)
+}
+
+/// Assembles a BIP-340 signature and checks that it is valid for the given public key and message.
+pub(crate) fn assemble_bip340_signature<P: SchemeParams<Curve = Secp256k1>>(
+    nonce: &Point<P>,
+    s: &Scalar<P>,
+    public_key: &Point<P>,
+    message: &[u8; 32],
+) -> Option<Signature> {
+    let mut bytes = [0u8; 64];
+    let (r_bytes, s_bytes) = bytes.split_at_mut(32);
+    r_bytes.copy_from_slice(&x_only_bytes(nonce));
+    s_bytes.copy_from_slice(&s.to_be_bytes());
+
+    let signature = Signature::try_from(bytes.as_slice()).ok()?;
+    let verifying_key = VerifyingKey::from_bytes(&x_only_bytes(public_key)).ok()?;
+    verifying_key.verify_prehash(message, &signature).ok()?;
+    Some(signature)
+}
+
+fn taproot_tweak<P: SchemeParams<Curve = Secp256k1>>(
+    internal_key: &Point<P>,
+    merkle_root: &[u8],
+) -> Result<Scalar<P>, String> {
+    // BIP-341 requires the tweak to be less than the curve order (unlike the BIP-340 challenge, it is not reduced).
+    let hash = tagged_hash(b"TapTweak", &[&x_only_bytes(internal_key), merkle_root]);
+    Scalar::try_from_be_bytes(&hash).map_err(|_| "The Taproot tweak is not less than the curve order".into())
+}
+
+fn x_only_bytes<P: SchemeParams<Curve = Secp256k1>>(point: &Point<P>) -> FieldBytes<Secp256k1> {
+    point.to_backend().to_affine().x()
+}
+
+fn tagged_hash(tag: &[u8], chunks: &[&[u8]]) -> [u8; 32] {
+    let tag_hash = Sha256::digest(tag);
+    let mut digest = Sha256::new().chain_update(tag_hash).chain_update(tag_hash);
+    for chunk in chunks {
+        digest.update(chunk);
+    }
+    digest.finalize().into()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{x_only_bytes, TaprootTweak, TweakedKey};
+    use crate::{curve::Point, k256::ProductionParams112};
+
+    type P = ProductionParams112;
+
+    fn x_only_point(x: &str) -> Point<P> {
+        let mut bytes = hex::decode(x).unwrap();
+        bytes.insert(0, 2);
+        Point::try_from_compressed_bytes(&bytes).unwrap()
+    }
+
+    #[test]
+    fn taproot_tweak_test_vectors() {
+        // Test vectors from BIP-341 (`wallet-test-vectors.json`)
+        let internal_key = x_only_point("d6889cb081036e0faefa3a35157ad71086b123b2b144b649798b494c300a961d");
+        let tweaked = TweakedKey::new(&internal_key, &TaprootTweak::KeyPathOnly).unwrap();
+        assert_eq!(
+            hex::encode(x_only_bytes(&tweaked.public_key)),
+            "53a1f6e454df1aa2776a2814a721372d6258050de330b3c6d10ee8f4e0dda343"
+        );
+
+        let internal_key = x_only_point("187791b6f712a8ea41c8ecdd0ee77fab3e85263b37e1ec18a3651926b3a6cf27");
+        let merkle_root = hex::decode("5b75adecf53548f3ec6ad7d78383bf84cc57b55a3127c72b9a2481752dd88b21")
+            .unwrap()
+            .try_into()
+            .unwrap();
+        let tweaked = TweakedKey::new(&internal_key, &TaprootTweak::ScriptTree(merkle_root)).unwrap();
+        assert_eq!(
+            hex::encode(x_only_bytes(&tweaked.public_key)),
+            "147c9c57132f6e7ecddba9800bb0c4449251c92a1e60371ee77557b6620f3ea3"
+        );
+    }
+}
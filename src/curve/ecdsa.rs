@@ -1,17 +1,31 @@
-use ecdsa::{RecoveryId, Signature as BackendSignature, VerifyingKey};
-use elliptic_curve::group::Curve as _;
+use alloc::boxed::Box;
+use core::ops::Add;
+
+use ecdsa::{
+    der, signature::hazmat::PrehashVerifier, Error as BackendError, RecoveryId, Signature as BackendSignature,
+    VerifyingKey,
+};
+use elliptic_curve::{
+    generic_array::{typenum::Unsigned, ArrayLength},
+    group::Curve as _,
+    FieldBytesSize,
+};
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use serde_encoded_bytes::{Hex, SliceLike};
 
 #[cfg(test)]
 use rand_core::CryptoRngCore;
 
 use super::arithmetic::{Point, Scalar};
-use crate::SchemeParams;
+use crate::{protocols::PrehashedMessage, SchemeParams};
 
 #[cfg(test)]
 use crate::tools::BoxedRng;
 
 /// A wrapper for a signature and public key recovery info.
-#[derive(Debug, Clone)]
+///
+/// Serializes as the `r || s || v` encoding (see [`Self::to_rsv_bytes`]).
+#[derive(Debug, Clone, PartialEq, Eq)]
 pub struct RecoverableSignature<P: SchemeParams> {
     signature: BackendSignature<P::Curve>,
     recovery_id: RecoveryId,
@@ -47,8 +61,255 @@ where
         Some(Self { signature, recovery_id })
     }
 
+    /// Normalizes the `s` component of the signature, adjusting the recovery info accordingly.
+    ///
+    /// Replacing `s` with `-s` corresponds to the nonce point with the opposite Y coordinate,
+    /// so the Y parity bit of the recovery ID is flipped.
+    fn new_normalized(signature: BackendSignature<P::Curve>, recovery_id: RecoveryId) -> Self {
+        match signature.normalize_s() {
+            Some(signature) => Self {
+                signature,
+                recovery_id: RecoveryId::new(!recovery_id.is_y_odd(), recovery_id.is_x_reduced()),
+            },
+            None => Self { signature, recovery_id },
+        }
+    }
+
     /// Unwraps into the signature and recovery info objects from the backend crate.
     pub fn to_backend(self) -> (BackendSignature<P::Curve>, RecoveryId) {
         (self.signature, self.recovery_id)
     }
+
+    /// Creates the object from the signature and recovery info objects from the backend crate.
+    ///
+    /// A signature with a high `s` is normalized, with the recovery info adjusted to match.
+    pub fn from_backend(signature: BackendSignature<P::Curve>, recovery_id: RecoveryId) -> Self {
+        Self::new_normalized(signature, recovery_id)
+    }
+
+    /// Returns the public key recovery info.
+    pub fn recovery_id(&self) -> RecoveryId {
+        self.recovery_id
+    }
+
+    /// Verifies the signature for the given public key and message.
+    ///
+    /// Note that the recovery info is not used.
+    pub fn verify(
+        &self,
+        verifying_key: &VerifyingKey<P::Curve>,
+        message: &PrehashedMessage<P::Curve>,
+    ) -> Result<(), BackendError> {
+        verifying_key.verify_prehash(message, &self.signature)
+    }
+
+    /// Recovers the public key the signature was created with, given the signed message.
+    pub fn recover_verifying_key(
+        &self,
+        message: &PrehashedMessage<P::Curve>,
+    ) -> Result<VerifyingKey<P::Curve>, BackendError> {
+        VerifyingKey::recover_from_prehash(message, &self.signature, self.recovery_id)
+    }
+
+    /// Returns the compact encoding of the signature (`r || s`, each big-endian and of the size of the curve's scalar).
+    ///
+    /// Note that the recovery info is not included.
+    pub fn to_compact_bytes(&self) -> Box<[u8]> {
+        self.signature.to_bytes().as_slice().into()
+    }
+
+    /// Creates the object from the compact encoding of the signature (see [`Self::to_compact_bytes`])
+    /// and the separately stored recovery info.
+    ///
+    /// A signature with a high `s` is normalized, with the recovery info adjusted to match.
+    pub fn from_compact_bytes(bytes: &[u8], recovery_id: RecoveryId) -> Result<Self, BackendError> {
+        let signature = BackendSignature::from_slice(bytes)?;
+        Ok(Self::new_normalized(signature, recovery_id))
+    }
+
+    /// Returns the ASN.1 DER encoding of the signature.
+    ///
+    /// Note that the recovery info is not included.
+    pub fn to_der_bytes(&self) -> Box<[u8]>
+    where
+        der::MaxSize<P::Curve>: ArrayLength<u8>,
+        <FieldBytesSize<P::Curve> as Add>::Output: Add<der::MaxOverhead> + ArrayLength<u8>,
+    {
+        self.signature.to_der().as_bytes().into()
+    }
+
+    /// Creates the object from the ASN.1 DER encoding of the signature (see [`Self::to_der_bytes`])
+    /// and the separately stored recovery info.
+    ///
+    /// A signature with a high `s` is normalized, with the recovery info adjusted to match.
+    pub fn from_der_bytes(bytes: &[u8], recovery_id: RecoveryId) -> Result<Self, BackendError>
+    where
+        der::MaxSize<P::Curve>: ArrayLength<u8>,
+        <FieldBytesSize<P::Curve> as Add>::Output: Add<der::MaxOverhead> + ArrayLength<u8>,
+    {
+        let signature = BackendSignature::from_der(bytes)?;
+        Ok(Self::new_normalized(signature, recovery_id))
+    }
+
+    /// Returns the `r || s || v` encoding of the signature, where `r || s` is the compact encoding
+    /// (see [`Self::to_compact_bytes`]), and `v` is a single byte containing the recovery ID (`0` to `3`).
+    pub fn to_rsv_bytes(&self) -> Box<[u8]> {
+        let mut bytes = self.signature.to_bytes().to_vec();
+        bytes.push(self.recovery_id.to_byte());
+        bytes.into()
+    }
+
+    /// Creates the object from the `r || s || v` encoding of the signature (see [`Self::to_rsv_bytes`]).
+    pub fn from_rsv_bytes(bytes: &[u8]) -> Result<Self, BackendError> {
+        let compact_size = <FieldBytesSize<P::Curve> as Unsigned>::USIZE * 2;
+        if bytes.len() != compact_size + 1 {
+            return Err(BackendError::new());
+        }
+        let (compact, v) = bytes.split_at(compact_size);
+        let recovery_id = v
+            .first()
+            .copied()
+            .and_then(RecoveryId::from_byte)
+            .ok_or_else(BackendError::new)?;
+        Self::from_compact_bytes(compact, recovery_id)
+    }
+}
+
+impl<'a, P> TryFrom<&'a [u8]> for RecoverableSignature<P>
+where
+    P: SchemeParams,
+{
+    type Error = BackendError;
+    fn try_from(val: &'a [u8]) -> Result<Self, Self::Error> {
+        Self::from_rsv_bytes(val)
+    }
+}
+
+impl<P> Serialize for RecoverableSignature<P>
+where
+    P: SchemeParams,
+{
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        SliceLike::<Hex>::serialize(&self.to_rsv_bytes(), serializer)
+    }
+}
+
+impl<'de, P> Deserialize<'de> for RecoverableSignature<P>
+where
+    P: SchemeParams,
+{
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        SliceLike::<Hex>::deserialize(deserializer)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use ecdsa::{signature::hazmat::PrehashSigner, RecoveryId, Signature, SigningKey, VerifyingKey};
+    use elliptic_curve::FieldBytes;
+    use rand_core::{OsRng, RngCore};
+    use serde::{Deserialize, Serialize};
+
+    use super::RecoverableSignature;
+    use crate::{dev::TestParams, SchemeParams};
+
+    type Curve = <TestParams as SchemeParams>::Curve;
+
+    fn make_signature() -> (RecoverableSignature<TestParams>, SigningKey<Curve>, FieldBytes<Curve>) {
+        let signing_key = SigningKey::<Curve>::random(&mut OsRng);
+        let mut message = FieldBytes::<Curve>::default();
+        OsRng.fill_bytes(&mut message);
+        let (signature, recovery_id): (_, RecoveryId) = signing_key.sign_prehash(&message).unwrap();
+        (
+            RecoverableSignature::from_backend(signature, recovery_id),
+            signing_key,
+            message,
+        )
+    }
+
+    #[test]
+    fn verify_and_recover() {
+        let (signature, signing_key, message) = make_signature();
+        let verifying_key = signing_key.verifying_key();
+
+        signature.verify(verifying_key, &message).unwrap();
+        assert_eq!(&signature.recover_verifying_key(&message).unwrap(), verifying_key);
+
+        let mut wrong_message = message;
+        wrong_message[0] ^= 1;
+        assert!(signature.verify(verifying_key, &wrong_message).is_err());
+        assert!(signature
+            .recover_verifying_key(&wrong_message)
+            .map_or(true, |key| &key != verifying_key));
+    }
+
+    #[test]
+    fn encodings_round_trip() {
+        let (signature, _signing_key, _message) = make_signature();
+        let recovery_id = signature.recovery_id();
+
+        let compact = signature.to_compact_bytes();
+        assert_eq!(
+            RecoverableSignature::from_compact_bytes(&compact, recovery_id).unwrap(),
+            signature
+        );
+
+        let der = signature.to_der_bytes();
+        assert_eq!(
+            RecoverableSignature::from_der_bytes(&der, recovery_id).unwrap(),
+            signature
+        );
+
+        let rsv = signature.to_rsv_bytes();
+        assert_eq!(rsv.len(), compact.len() + 1);
+        assert_eq!(rsv[..compact.len()], compact[..]);
+        assert_eq!(rsv[compact.len()], recovery_id.to_byte());
+        assert_eq!(RecoverableSignature::from_rsv_bytes(&rsv).unwrap(), signature);
+
+        // Wrong length or an invalid recovery ID are rejected
+        assert!(RecoverableSignature::<TestParams>::from_rsv_bytes(&compact).is_err());
+        let mut wrong_rsv = rsv.clone();
+        wrong_rsv[compact.len()] = 4;
+        assert!(RecoverableSignature::<TestParams>::from_rsv_bytes(&wrong_rsv).is_err());
+    }
+
+    #[test]
+    fn high_s_is_normalized() {
+        let (signature, signing_key, message) = make_signature();
+        let (low_s_signature, recovery_id) = signature.clone().to_backend();
+        assert!(low_s_signature.normalize_s().is_none());
+
+        // The equivalent signature with a high `s` recovers the same key with the opposite Y parity.
+        let (r, s) = low_s_signature.split_scalars();
+        let high_s_signature = Signature::<Curve>::from_scalars(r, -s).unwrap();
+        let high_s_recovery_id = RecoveryId::new(!recovery_id.is_y_odd(), recovery_id.is_x_reduced());
+        assert_eq!(
+            &VerifyingKey::recover_from_prehash(&message, &high_s_signature, high_s_recovery_id).unwrap(),
+            signing_key.verifying_key()
+        );
+
+        assert_eq!(
+            RecoverableSignature::from_backend(high_s_signature, high_s_recovery_id),
+            signature
+        );
+        assert_eq!(
+            RecoverableSignature::from_compact_bytes(&high_s_signature.to_bytes(), high_s_recovery_id).unwrap(),
+            signature
+        );
+        assert_eq!(
+            RecoverableSignature::from_der_bytes(high_s_signature.to_der().as_bytes(), high_s_recovery_id).unwrap(),
+            signature
+        );
+    }
+
+    #[test]
+    fn serialization_round_trip() {
+        let (signature, _signing_key, _message) = make_signature();
+
+        let serializer = serde_assert::Serializer::builder().build();
+        let tokens = signature.serialize(&serializer).unwrap();
+        let mut deserializer = serde_assert::Deserializer::builder(tokens).build();
+        let deserialized = RecoverableSignature::<TestParams>::deserialize(&mut deserializer).unwrap();
+        assert_eq!(deserialized, signature);
+    }
 }
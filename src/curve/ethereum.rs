@@ -0,0 +1,188 @@
+//! Helpers for creating Ethereum signatures: address derivation,
+//! EIP-191 and EIP-712 message hashing, and legacy and EIP-155 `v` values.
+
+use alloc::string::ToString;
+
+use ecdsa::VerifyingKey;
+use k256::Secp256k1;
+use sha3::{Digest, Keccak256};
+
+use super::RecoverableSignature;
+use crate::{protocols::PrehashedMessage, SchemeParams};
+
+/// Returns the Keccak-256 hash of the given data.
+pub fn keccak256(data: &[u8]) -> [u8; 32] {
+    Keccak256::digest(data).into()
+}
+
+/// Returns the Ethereum address corresponding to the given public key
+/// (e.g. the one returned by [`ThresholdKeyShare::verifying_key`](`crate::ThresholdKeyShare::verifying_key`)).
+pub fn address(verifying_key: &VerifyingKey<Secp256k1>) -> [u8; 20] {
+    let point = verifying_key.to_encoded_point(false);
+    // Skip the SEC1 tag byte, hashing only the coordinates.
+    let (_tag, coordinates) = point.as_bytes().split_at(1);
+    let hash = keccak256(coordinates);
+    let (_, address) = hash.split_at(12);
+    address
+        .try_into()
+        .expect("the address is the last 20 bytes of a 32-byte hash")
+}
+
+/// Hashes a personal message according to EIP-191
+/// (the scheme used by the `personal_sign` and `eth_sign` methods).
+pub fn eip191_hash(message: &[u8]) -> PrehashedMessage<Secp256k1> {
+    let mut digest = Keccak256::new();
+    digest.update(b"\x19Ethereum Signed Message:\n");
+    digest.update(message.len().to_string().as_bytes());
+    digest.update(message);
+    digest.finalize()
+}
+
+/// Hashes typed structured data according to EIP-712,
+/// given the hash of the domain separator and the hash of the message struct.
+pub fn eip712_hash(domain_separator: &[u8; 32], struct_hash: &[u8; 32]) -> PrehashedMessage<Secp256k1> {
+    let mut digest = Keccak256::new();
+    digest.update(b"\x19\x01");
+    digest.update(domain_separator);
+    digest.update(struct_hash);
+    digest.finalize()
+}
+
+/// Returns the `v` value of the signature for a pre-EIP-155 transaction or a message signature (`27` or `28`).
+///
+/// Returns `None` if the signature's `r` was reduced modulo the curve order,
+/// which Ethereum cannot represent (the probability of this happening is negligible).
+pub fn legacy_v<P: SchemeParams<Curve = Secp256k1>>(signature: &RecoverableSignature<P>) -> Option<u8> {
+    y_parity(signature).map(|parity| 27 + parity)
+}
+
+/// Returns the `v` value of the signature for an EIP-155 transaction on the chain with the given ID
+/// (`chain_id * 2 + 35` or `chain_id * 2 + 36`).
+///
+/// Returns `None` if the signature's `r` was reduced modulo the curve order,
+/// which Ethereum cannot represent (the probability of this happening is negligible),
+/// or if the result overflows.
+pub fn eip155_v<P: SchemeParams<Curve = Secp256k1>>(signature: &RecoverableSignature<P>, chain_id: u64) -> Option<u64> {
+    let parity = y_parity(signature)?;
+    chain_id.checked_mul(2)?.checked_add(35 + u64::from(parity))
+}
+
+fn y_parity<P: SchemeParams<Curve = Secp256k1>>(signature: &RecoverableSignature<P>) -> Option<u8> {
+    let recovery_id = signature.recovery_id();
+    if recovery_id.is_x_reduced() {
+        None
+    } else {
+        Some(recovery_id.is_y_odd().into())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use ecdsa::{RecoveryId, Signature, SigningKey};
+    use k256::Secp256k1;
+
+    use super::{address, eip155_v, eip191_hash, eip712_hash, keccak256, legacy_v};
+    use crate::{curve::RecoverableSignature, k256::ProductionParams112};
+
+    fn hex32(s: &str) -> [u8; 32] {
+        hex::decode(s).unwrap().try_into().unwrap()
+    }
+
+    fn padded_address(s: &str) -> [u8; 32] {
+        let mut bytes = [0u8; 32];
+        bytes[12..].copy_from_slice(&hex::decode(s).unwrap());
+        bytes
+    }
+
+    #[test]
+    fn address_test_vector() {
+        let secret = hex::decode("4c0883a69102937d6231471b5dbb6204fe5129617082792ae468d01a3f362318").unwrap();
+        let signing_key = SigningKey::<Secp256k1>::from_slice(&secret).unwrap();
+        assert_eq!(
+            hex::encode(address(signing_key.verifying_key())),
+            "2c7536e3605d9c16a7a3d7b1898e529396a65c23"
+        );
+    }
+
+    #[test]
+    fn eip191_test_vector() {
+        assert_eq!(
+            hex::encode(eip191_hash(b"Hello World")),
+            "a1de988600a42c4b4ab089b619297c17d53cffae5d5120d82d8a92d0bb3b78f2"
+        );
+    }
+
+    #[test]
+    fn eip712_test_vector() {
+        // The example from EIP-712
+        let domain_type_hash =
+            keccak256(b"EIP712Domain(string name,string version,uint256 chainId,address verifyingContract)");
+        let mut chain_id = [0u8; 32];
+        chain_id[31] = 1;
+        let domain_separator = keccak256(
+            &[
+                domain_type_hash,
+                keccak256(b"Ether Mail"),
+                keccak256(b"1"),
+                chain_id,
+                padded_address("cccccccccccccccccccccccccccccccccccccccc"),
+            ]
+            .concat(),
+        );
+        assert_eq!(
+            domain_separator,
+            hex32("f2cee375fa42b42143804025fc449deafd50cc031ca257e0b194a650a912090f")
+        );
+
+        let person_type_hash = keccak256(b"Person(string name,address wallet)");
+        let mail_type_hash =
+            keccak256(b"Mail(Person from,Person to,string contents)Person(string name,address wallet)");
+        let from = keccak256(
+            &[
+                person_type_hash,
+                keccak256(b"Cow"),
+                padded_address("cd2a3d9f938e13cd947ec05abc7fe734df8dd826"),
+            ]
+            .concat(),
+        );
+        let to = keccak256(
+            &[
+                person_type_hash,
+                keccak256(b"Bob"),
+                padded_address("bbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbb"),
+            ]
+            .concat(),
+        );
+        let struct_hash = keccak256(&[mail_type_hash, from, to, keccak256(b"Hello, Bob!")].concat());
+        assert_eq!(
+            struct_hash,
+            hex32("c52c0ee5d84264471806290a3f2c4cecfc5490626bf912d01f240d7a274b371e")
+        );
+
+        assert_eq!(
+            hex::encode(eip712_hash(&domain_separator, &struct_hash)),
+            "be609aee343fb3c4b28e1df9e632fca64fcfaede20f02e86244efddf30957bd2"
+        );
+    }
+
+    #[test]
+    fn v_values() {
+        let make = |is_y_odd, is_x_reduced| {
+            let signature = Signature::<Secp256k1>::from_scalars([1u8; 32], [2u8; 32]).unwrap();
+            RecoverableSignature::<ProductionParams112>::from_backend(
+                signature,
+                RecoveryId::new(is_y_odd, is_x_reduced),
+            )
+        };
+
+        assert_eq!(legacy_v(&make(false, false)), Some(27));
+        assert_eq!(legacy_v(&make(true, false)), Some(28));
+        assert_eq!(legacy_v(&make(false, true)), None);
+
+        // Ethereum mainnet
+        assert_eq!(eip155_v(&make(false, false), 1), Some(37));
+        assert_eq!(eip155_v(&make(true, false), 1), Some(38));
+        assert_eq!(eip155_v(&make(true, true), 1), None);
+        assert_eq!(eip155_v(&make(false, false), u64::MAX), None);
+    }
+}
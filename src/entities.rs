@@ -2,10 +2,9 @@ mod full;
 mod internal;
 mod threshold;
 
-pub use full::{AuxInfo, KeyShare, KeyShareChange};
-pub use threshold::ThresholdKeyShare;
+pub use full::{AuxInfo, KeyShare, KeyShareChange, PrecomputedAuxInfo};
+pub use threshold::{HierarchicalAccessStructure, ShareIdAssignment, ThresholdKeyShare, ThresholdPublicKeyShares};
 
-pub(crate) use full::{
-    AuxInfoPrecomputed, PublicAuxInfo, PublicAuxInfoPrecomputed, PublicAuxInfos, PublicKeyShares, SecretAuxInfo,
-};
+pub(crate) use full::{PublicAuxInfo, PublicAuxInfoPrecomputed, PublicAuxInfos, PublicKeyShares, SecretAuxInfo};
 pub(crate) use internal::Sid;
+pub(crate) use threshold::PublicShareWithId;
@@ -1,14 +1,20 @@
 use alloc::{
     collections::{BTreeMap, BTreeSet},
     format,
+    sync::Arc,
     vec::Vec,
 };
 use core::fmt::Debug;
 
-use ecdsa::VerifyingKey;
+use ecdsa::{SigningKey, VerifyingKey};
 use manul::{protocol::PartyId, session::LocalError, utils::SerializableMap};
 use rand_core::CryptoRngCore;
 
+#[cfg(feature = "bip32")]
+use bip32::DerivationPath;
+
+#[cfg(feature = "bip32")]
+use crate::curve::{Bip32Tweak, DeriveChildKey, PublicTweakable, SecretTweakable};
 use crate::{
     curve::{secret_split, Point, Scalar},
     paillier::{
@@ -83,6 +89,26 @@ where
     pub(crate) public_aux: BTreeMap<I, PublicAuxInfoPrecomputed<P>>,
 }
 
+/// Auxiliary info with the Paillier keys and the ring-Pedersen parameters prepared for use in the protocols.
+///
+/// Preparing them is expensive, so this object is meant to be created once per [`AuxInfo`]
+/// (that is, once per key refresh) and reused in every signing session. Cloning it is cheap.
+#[derive(Debug, Clone)]
+pub struct PrecomputedAuxInfo<P, I>(Arc<PrecomputedAuxInfoInner<P, I>>)
+where
+    P: SchemeParams,
+    I: PartyId;
+
+#[derive(Debug)]
+struct PrecomputedAuxInfoInner<P, I>
+where
+    P: SchemeParams,
+    I: PartyId,
+{
+    aux_info: AuxInfo<P, I>,
+    precomputed: AuxInfoPrecomputed<P, I>,
+}
+
 #[derive(Debug, Clone)]
 pub(crate) struct SecretAuxInfoPrecomputed<P>
 where
@@ -149,9 +175,29 @@ where
     P: SchemeParams,
     I: PartyId,
 {
+    pub(crate) fn new(public_shares: BTreeMap<I, Point<P>>) -> Self {
+        Self(public_shares.into())
+    }
+
     pub(crate) fn as_map(&self) -> &BTreeMap<I, Point<P>> {
         &self.0
     }
+
+    #[cfg(feature = "bip32")]
+    /// Adds `tweak` to the public share of the designated party (the first one in the order of IDs),
+    /// so that the resulting verifying key is tweaked by it.
+    pub(crate) fn add_tweak(self, tweak: &Scalar<P>) -> Result<Self, LocalError> {
+        let mut public_shares = BTreeMap::from(self.0);
+        let designated_share = public_shares
+            .values_mut()
+            .next()
+            .ok_or_else(|| LocalError::new("The set of public shares is empty"))?;
+        *designated_share = *designated_share + tweak.mul_by_generator();
+        if public_shares.values().sum::<Point<P>>() == Point::identity() {
+            return Err(LocalError::new("Tweaked secret key shares add up to zero"));
+        }
+        Ok(Self(public_shares.into()))
+    }
 }
 
 impl<P, I> KeyShare<P, I>
@@ -214,12 +260,29 @@ where
         })
     }
 
+    #[cfg(feature = "bip32")]
+    /// Adds `tweak` to the secret key, applying it to the share of the designated party
+    /// (the first one in the order of IDs) and to the corresponding public share.
+    pub(crate) fn add_tweak(self, tweak: &Scalar<P>) -> Result<Self, LocalError> {
+        let is_designated = self.public.0.keys().next() == Some(&self.owner);
+        let secret = if is_designated {
+            self.secret + tweak
+        } else {
+            self.secret
+        };
+        Ok(Self {
+            owner: self.owner,
+            secret,
+            public: self.public.add_tweak(tweak)?,
+        })
+    }
+
     /// Creates a set of random self-consistent key shares
     /// (which in a decentralized case would be the output of KeyInit protocol).
     pub fn new_centralized(
         rng: &mut impl CryptoRngCore,
         ids: &BTreeSet<I>,
-        signing_key: Option<&ecdsa::SigningKey<P::Curve>>,
+        signing_key: Option<&SigningKey<P::Curve>>,
     ) -> BTreeMap<I, Self> {
         let secret = Secret::init_with(|| match signing_key {
             None => Scalar::random(rng),
@@ -283,6 +346,42 @@ where
     }
 }
 
+#[cfg(feature = "bip32")]
+impl<P, I> KeyShare<P, I>
+where
+    P: SchemeParams,
+    VerifyingKey<P::Curve>: PublicTweakable,
+    SigningKey<P::Curve>: SecretTweakable,
+    I: PartyId,
+{
+    /// Deterministically derives a child share using BIP-32 standard.
+    ///
+    /// The tweak is applied to the share of the first party in the order of IDs,
+    /// so that the public shares still add up to the child verifying key.
+    pub fn derive_bip32(&self, derivation_path: &DerivationPath) -> Result<Self, bip32::Error> {
+        let tweak = Bip32Tweak::new(&self.verifying_key(), derivation_path)?;
+        // Will fail here if the child key is an identity
+        self.clone()
+            .add_tweak(&tweak.to_scalar())
+            .map_err(|_| bip32::Error::Crypto)
+    }
+}
+
+#[cfg(feature = "bip32")]
+impl<P, I> DeriveChildKey<P::Curve> for KeyShare<P, I>
+where
+    P: SchemeParams,
+    I: PartyId,
+    VerifyingKey<P::Curve>: PublicTweakable,
+{
+    fn derive_verifying_key_bip32(
+        &self,
+        derivation_path: &DerivationPath,
+    ) -> Result<VerifyingKey<P::Curve>, bip32::Error> {
+        self.verifying_key().derive_verifying_key_bip32(derivation_path)
+    }
+}
+
 impl<P, I> AuxInfo<P, I>
 where
     P: SchemeParams,
@@ -350,7 +449,14 @@ where
             .collect()
     }
 
-    pub(crate) fn into_precomputed(self) -> AuxInfoPrecomputed<P, I> {
+    /// Prepares the auxiliary info for use in the protocols.
+    ///
+    /// The result can be cloned cheaply and reused between sessions.
+    pub fn precompute(self) -> PrecomputedAuxInfo<P, I> {
+        PrecomputedAuxInfo::new(self)
+    }
+
+    fn to_precomputed(&self) -> AuxInfoPrecomputed<P, I> {
         AuxInfoPrecomputed {
             secret_aux: SecretAuxInfoPrecomputed {
                 paillier_sk: self.secret.paillier_sk.clone().into_precomputed(),
@@ -374,6 +480,69 @@ where
     }
 }
 
+impl<P, I> PrecomputedAuxInfo<P, I>
+where
+    P: SchemeParams,
+    I: PartyId,
+{
+    fn new(aux_info: AuxInfo<P, I>) -> Self {
+        let precomputed = aux_info.to_precomputed();
+        Self(Arc::new(PrecomputedAuxInfoInner { aux_info, precomputed }))
+    }
+
+    /// Returns the owner of the secret part of the auxiliary info.
+    pub fn owner(&self) -> &I {
+        self.0.aux_info.owner()
+    }
+
+    /// Returns the auxiliary info this object was created from.
+    pub fn aux_info(&self) -> &AuxInfo<P, I> {
+        &self.0.aux_info
+    }
+
+    /// Returns a `PrecomputedAuxInfo` object for the given subset of all parties.
+    ///
+    /// The precomputed values are reused, so this is much cheaper than precomputing the subset of [`AuxInfo`].
+    pub fn subset(&self, parties: &BTreeSet<I>) -> Result<Self, LocalError> {
+        let aux_info = self.0.aux_info.clone().subset(parties)?;
+        let public_aux = self
+            .0
+            .precomputed
+            .public_aux
+            .iter()
+            .filter(|(id, _aux)| parties.contains(id))
+            .map(|(id, aux)| (id.clone(), aux.clone()))
+            .collect();
+        let precomputed = AuxInfoPrecomputed {
+            secret_aux: self.0.precomputed.secret_aux.clone(),
+            public_aux,
+        };
+        Ok(Self(Arc::new(PrecomputedAuxInfoInner { aux_info, precomputed })))
+    }
+
+    pub(crate) fn public(&self) -> &PublicAuxInfos<P, I> {
+        self.0.aux_info.public()
+    }
+
+    pub(crate) fn secret_aux(&self) -> &SecretAuxInfoPrecomputed<P> {
+        &self.0.precomputed.secret_aux
+    }
+
+    pub(crate) fn public_aux(&self) -> &BTreeMap<I, PublicAuxInfoPrecomputed<P>> {
+        &self.0.precomputed.public_aux
+    }
+}
+
+impl<P, I> From<AuxInfo<P, I>> for PrecomputedAuxInfo<P, I>
+where
+    P: SchemeParams,
+    I: PartyId,
+{
+    fn from(aux_info: AuxInfo<P, I>) -> Self {
+        Self::new(aux_info)
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use alloc::collections::BTreeSet;
@@ -401,4 +570,42 @@ mod tests {
             .values()
             .all(|share| &share.verifying_key() == sk.verifying_key()));
     }
+
+    #[cfg(feature = "bip32")]
+    #[test]
+    fn derive_bip32() {
+        use crate::{curve::Point, DeriveChildKey};
+
+        let ids = (0..3)
+            .map(|_| *SigningKey::random(&mut OsRng).verifying_key())
+            .collect::<BTreeSet<_>>();
+        let shares = KeyShare::<TestParams, VerifyingKey<<TestParams as SchemeParams>::Curve>>::new_centralized(
+            &mut OsRng, &ids, None,
+        );
+
+        let path = "m/0/2/1/4/2".parse().unwrap();
+        let child_vkey = shares
+            .values()
+            .next()
+            .unwrap()
+            .derive_verifying_key_bip32(&path)
+            .unwrap();
+        let child_shares = shares
+            .values()
+            .map(|share| share.derive_bip32(&path).unwrap())
+            .collect::<Vec<_>>();
+
+        // The public shares add up to the child key, and agree with the secret shares
+        for share in &child_shares {
+            assert_eq!(share.verifying_key(), child_vkey);
+            assert_eq!(share.secret.mul_by_generator(), share.public_shares()[share.owner()]);
+        }
+        assert_eq!(
+            child_shares
+                .iter()
+                .map(|share| share.secret.mul_by_generator())
+                .sum::<Point<TestParams>>(),
+            Point::from_verifying_key(&child_vkey)
+        );
+    }
 }
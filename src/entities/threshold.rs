@@ -1,22 +1,29 @@
 use alloc::{
     collections::{BTreeMap, BTreeSet},
-    format,
+    format, vec,
+    vec::Vec,
 };
 use core::fmt::Debug;
 
 use ecdsa::{SigningKey, VerifyingKey};
 use manul::{protocol::PartyId, session::LocalError, utils::SerializableMap};
 use rand_core::CryptoRngCore;
+use serde::Serialize;
 
 #[cfg(feature = "bip32")]
 use bip32::{DerivationPath, PrivateKey as _};
 
-use super::full::KeyShare;
+use super::{full::KeyShare, internal::Sid};
 use crate::{
     curve::{Point, Scalar},
     params::SchemeParams,
     tools::{
-        sss::{interpolation_coeff, shamir_evaluation_points, shamir_join_points, shamir_split, ShareId},
+        hashing::{Chain, Hasher},
+        protocol_shortcuts::{MapValues, SafeGet},
+        sss::{
+            assign_weighted_share_ids, birkhoff_coeffs, birkhoff_split, interpolation_coeff, shamir_evaluation_points,
+            shamir_join_points, shamir_split, ShareId, SharePoint,
+        },
         Secret,
     },
 };
@@ -24,8 +31,18 @@ use crate::{
 #[cfg(feature = "bip32")]
 use crate::curve::{apply_tweaks_public, derive_tweaks, DeriveChildKey, PublicTweakable, SecretTweakable};
 
+/// A public share along with the ID of the share.
+pub(crate) type PublicShareWithId<P> = (ShareId<P>, Point<P>);
+
+/// A secret share along with the ID of the share.
+pub(crate) type SecretShareWithId<P> = (ShareId<P>, Secret<Scalar<P>>);
+
 /// A threshold variant of the key share, where any `threshold` shares our of the total number
 /// is enough to perform signing.
+///
+/// A holder can have several shares (that is, a weight greater than 1),
+/// in which case it contributes all of them to the threshold.
+/// Alternatively, the shares can follow a [`HierarchicalAccessStructure`].
 #[derive(Debug, Clone)]
 #[derive_where::derive_where(Serialize, Deserialize)]
 pub struct ThresholdKeyShare<P, I>
@@ -40,6 +57,16 @@ where
     pub(crate) secret_share: Secret<Scalar<P>>,
     pub(crate) share_ids: SerializableMap<I, ShareId<P>>,
     pub(crate) public_shares: SerializableMap<I, Point<P>>,
+    // The shares beyond the first one for the holders with the weight greater than 1
+    // (their share IDs and public shares), and the corresponding secret shares of the owner.
+    // These and the access structure default to empty when missing,
+    // so that the key shares serialized before they were added can still be deserialized.
+    #[serde(default = "empty_map")]
+    pub(crate) extra_shares: SerializableMap<I, Vec<PublicShareWithId<P>>>,
+    #[serde(default)]
+    pub(crate) extra_secret_shares: Vec<Secret<Scalar<P>>>,
+    #[serde(default)]
+    pub(crate) access_structure: Option<HierarchicalAccessStructure<I>>,
 }
 
 impl<P, I> ThresholdKeyShare<P, I>
@@ -47,7 +74,7 @@ where
     P: SchemeParams,
     I: PartyId,
 {
-    /// Threshold share ID.
+    /// Threshold share ID (the first one, if the owner holds several shares).
     pub fn share_id(&self) -> Result<&ShareId<P>, LocalError> {
         self.share_ids.get(&self.owner).ok_or(LocalError::new(format!(
             "owner={:?} is missing in the share_ids",
@@ -60,6 +87,21 @@ where
         self.threshold as usize
     }
 
+    /// The number of shares held by the given party (zero if it is not a holder).
+    pub fn weight(&self, id: &I) -> usize {
+        holder_weight(&self.share_ids, &self.extra_shares, id)
+    }
+
+    /// Returns `true` if some of the holders have more than one share.
+    pub fn is_weighted(&self) -> bool {
+        !self.extra_shares.is_empty()
+    }
+
+    /// The hierarchical access structure of the shares, if they were created with one.
+    pub fn access_structure(&self) -> Option<&HierarchicalAccessStructure<I>> {
+        self.access_structure.as_ref()
+    }
+
     /// Creates a set of threshold key shares for the given IDs.
     pub fn new_centralized(
         rng: &mut impl CryptoRngCore,
@@ -67,10 +109,39 @@ where
         threshold: usize,
         signing_key: Option<&SigningKey<P::Curve>>,
     ) -> Result<BTreeMap<I, Self>, LocalError> {
-        if threshold > ids.len() {
+        Self::new_centralized_with_share_ids(rng, ids, threshold, signing_key, &ShareIdAssignment::Sequential)
+    }
+
+    /// Creates a set of threshold key shares for the given IDs, with the share IDs assigned as specified.
+    ///
+    /// Since there is no session here, [`ShareIdAssignment::FromSessionId`] is not supported.
+    pub fn new_centralized_with_share_ids(
+        rng: &mut impl CryptoRngCore,
+        ids: &BTreeSet<I>,
+        threshold: usize,
+        signing_key: Option<&SigningKey<P::Curve>>,
+        share_id_assignment: &ShareIdAssignment<P, I>,
+    ) -> Result<BTreeMap<I, Self>, LocalError> {
+        let weights = ids.iter().map(|id| (id.clone(), 1)).collect();
+        Self::new_centralized_weighted(rng, &weights, threshold, signing_key, share_id_assignment)
+    }
+
+    /// Creates a set of threshold key shares for the given IDs, where each party holds the number of shares
+    /// given by its weight, with the share IDs assigned as specified.
+    ///
+    /// The threshold is the total weight of the parties required to sign.
+    /// Since there is no session here, [`ShareIdAssignment::FromSessionId`] is not supported.
+    pub fn new_centralized_weighted(
+        rng: &mut impl CryptoRngCore,
+        weights: &BTreeMap<I, usize>,
+        threshold: usize,
+        signing_key: Option<&SigningKey<P::Curve>>,
+        share_id_assignment: &ShareIdAssignment<P, I>,
+    ) -> Result<BTreeMap<I, Self>, LocalError> {
+        let total_weight = weights.values().sum::<usize>();
+        if threshold > total_weight {
             return Err(LocalError::new(format!(
-                "Invalid threshold ({threshold}). Must be smaller than {}",
-                ids.len()
+                "Invalid threshold ({threshold}). Must not be greater than {total_weight}",
             )));
         }
 
@@ -79,57 +150,148 @@ where
             Some(sk) => Scalar::<P>::from(sk.as_nonzero_scalar()),
         });
 
-        let share_ids = shamir_evaluation_points(ids.len());
-        let secret_shares = shamir_split(rng, secret, threshold, &share_ids);
-        let share_ids = ids.iter().cloned().zip(share_ids).collect::<BTreeMap<_, _>>();
+        let share_ids = share_id_assignment.assign_weighted(weights, None)?;
+        let secret_shares = shamir_split(
+            rng,
+            secret,
+            threshold,
+            &share_ids.values().flatten().cloned().collect::<Vec<_>>(),
+        );
+
+        let holders = share_ids
+            .iter()
+            .map(|(id, share_ids)| {
+                let shares = share_ids
+                    .iter()
+                    .map(|share_id| {
+                        let secret_share = secret_shares.safe_get("secret shares", share_id)?;
+                        Ok((*share_id, secret_share.mul_by_generator()))
+                    })
+                    .collect::<Result<Vec<_>, LocalError>>()?;
+                Ok((id.clone(), shares))
+            })
+            .collect::<Result<BTreeMap<_, _>, LocalError>>()?;
+
+        share_ids
+            .iter()
+            .map(|(id, share_ids)| {
+                let own_secret_shares = share_ids
+                    .iter()
+                    .map(|share_id| secret_shares.safe_get("secret shares", share_id).cloned())
+                    .collect::<Result<Vec<_>, _>>()?;
+                let key_share = Self::from_weighted_parts(id.clone(), threshold, own_secret_shares, holders.clone())?;
+                Ok((id.clone(), key_share))
+            })
+            .collect()
+    }
+
+    /// Creates a set of threshold key shares for the holders in the given hierarchical access structure.
+    ///
+    /// The share IDs are assigned sequentially, in the order of the levels.
+    pub fn new_centralized_hierarchical(
+        rng: &mut impl CryptoRngCore,
+        access_structure: &HierarchicalAccessStructure<I>,
+        signing_key: Option<&SigningKey<P::Curve>>,
+    ) -> Result<BTreeMap<I, Self>, LocalError> {
+        let threshold = u32::try_from(access_structure.threshold())
+            .map_err(|_| LocalError::new("The threshold does not fit into `u32`"))?;
+
+        let secret = Secret::init_with(|| match signing_key {
+            None => Scalar::<P>::random(rng),
+            Some(sk) => Scalar::<P>::from(sk.as_nonzero_scalar()),
+        });
+
+        let holder_points = access_structure.holder_points::<P>()?;
+        let share_ids = holder_points.map_values_ref(|(share_id, _order)| *share_id);
+        let points = holder_points.into_values().collect::<Vec<_>>();
+        let secret_shares = birkhoff_split(rng, secret, access_structure.threshold(), &points);
 
         let public_shares = share_ids
             .iter()
             .map(|(id, share_id)| {
-                let secret_share = secret_shares
-                    .get(share_id)
-                    .ok_or_else(|| LocalError::new("share_id={share_id:?} is missing in the secret shares"))?;
+                let secret_share = secret_shares.safe_get("secret shares", share_id)?;
                 Ok((id.clone(), secret_share.mul_by_generator()))
             })
             .collect::<Result<BTreeMap<_, _>, LocalError>>()?;
 
-        ids.iter()
-            .map(|id| {
-                let share_id = share_ids
-                    .get(id)
-                    .ok_or_else(|| LocalError::new("id={id:?} is missing in the share_ids"))?;
-                let secret_share = secret_shares
-                    .get(share_id)
-                    .ok_or_else(|| LocalError::new("share_id={share_id:?} is missing in the secret shares"))?
-                    .clone();
-                Ok((
-                    id.clone(),
-                    Self {
-                        owner: id.clone(),
-                        threshold: threshold as u32,
-                        secret_share,
-                        share_ids: share_ids.clone().into(),
-                        public_shares: public_shares.clone().into(),
-                    },
-                ))
+        share_ids
+            .iter()
+            .map(|(id, share_id)| {
+                let key_share = Self {
+                    owner: id.clone(),
+                    threshold,
+                    secret_share: secret_shares.safe_get("secret shares", share_id)?.clone(),
+                    share_ids: share_ids.clone().into(),
+                    public_shares: public_shares.clone().into(),
+                    extra_shares: BTreeMap::new().into(),
+                    extra_secret_shares: Vec::new(),
+                    access_structure: Some(access_structure.clone()),
+                };
+                Ok((id.clone(), key_share))
             })
             .collect()
     }
 
+    /// Assembles a key share given the secret shares of the owner,
+    /// and the share IDs and the public shares of all the holders
+    /// (in the same order as the secret shares for the owner).
+    pub(crate) fn from_weighted_parts(
+        owner: I,
+        threshold: usize,
+        secret_shares: Vec<Secret<Scalar<P>>>,
+        holders: BTreeMap<I, Vec<PublicShareWithId<P>>>,
+    ) -> Result<Self, LocalError> {
+        let threshold =
+            u32::try_from(threshold).map_err(|_| LocalError::new("The threshold does not fit into `u32`"))?;
+
+        if holders.safe_get("holders", &owner)?.len() != secret_shares.len() {
+            return Err(LocalError::new(
+                "The number of the owner's secret shares does not match the number of its share IDs",
+            ));
+        }
+
+        let mut secret_shares = secret_shares.into_iter();
+        let secret_share = secret_shares
+            .next()
+            .ok_or_else(|| LocalError::new("The owner must have at least one share"))?;
+        let extra_secret_shares = secret_shares.collect();
+
+        let mut share_ids = BTreeMap::new();
+        let mut public_shares = BTreeMap::new();
+        let mut extra_shares = BTreeMap::new();
+        for (id, shares) in holders {
+            let mut shares = shares.into_iter();
+            let (share_id, public_share) = shares
+                .next()
+                .ok_or_else(|| LocalError::new(format!("id={id:?} must have at least one share")))?;
+            share_ids.insert(id.clone(), share_id);
+            public_shares.insert(id.clone(), public_share);
+            let extra = shares.collect::<Vec<_>>();
+            if !extra.is_empty() {
+                extra_shares.insert(id, extra);
+            }
+        }
+
+        Ok(Self {
+            owner,
+            threshold,
+            secret_share,
+            share_ids: share_ids.into(),
+            public_shares: public_shares.into(),
+            extra_shares: extra_shares.into(),
+            extra_secret_shares,
+            access_structure: None,
+        })
+    }
+
     pub(crate) fn verifying_key_as_point(&self) -> Result<Point<P>, LocalError> {
-        Ok(shamir_join_points(
-            &self
-                .share_ids
-                .iter()
-                .map(|(party_idx, share_id)| {
-                    let public_share = self.public_shares.get(party_idx).ok_or(LocalError::new(
-                        "party_idx={party_idx:?} is missing in the public shares",
-                    ))?;
-                    Ok((*share_id, *public_share))
-                })
-                .take(self.threshold as usize)
-                .collect::<Result<_, LocalError>>()?,
-        ))
+        join_public_shares(
+            self.threshold,
+            &self.share_ids,
+            &self.public_shares,
+            &self.extra_shares,
+            self.access_structure.as_ref(),
+        )
     }
 
     /// Return the verifying key to which this set of shares corresponds.
@@ -139,92 +301,724 @@ where
             .ok_or_else(|| LocalError::new("The combined verifying key is an identity"))
     }
 
-    /// Converts a t-of-n key share into a t-of-t key share
-    /// (for the `t` share indices supplied as `share_ids`)
+    /// Returns the public part of this key share, which is the same for all the holders.
+    pub fn public_key_shares(&self) -> ThresholdPublicKeyShares<P, I> {
+        ThresholdPublicKeyShares {
+            threshold: self.threshold,
+            share_ids: self.share_ids.clone(),
+            public_shares: self.public_shares.clone(),
+            extra_shares: self.extra_shares.clone(),
+            access_structure: self.access_structure.clone(),
+        }
+    }
+
+    /// Returns the share IDs and the public shares of all the shares held by the given party.
+    pub(crate) fn holder_shares(&self, id: &I) -> Result<Vec<PublicShareWithId<P>>, LocalError> {
+        holder_shares(&self.share_ids, &self.public_shares, &self.extra_shares, id)
+    }
+
+    /// Returns the points the shares of the given party are evaluated at
+    /// (in the same order as in [`holder_shares`](`Self::holder_shares`)).
+    pub(crate) fn holder_points(&self, id: &I) -> Result<Vec<SharePoint<P>>, LocalError> {
+        holder_points(
+            &self.share_ids,
+            &self.public_shares,
+            &self.extra_shares,
+            self.access_structure.as_ref(),
+            id,
+        )
+    }
+
+    /// Returns the share IDs and the secret shares of all the shares held by the owner.
+    pub(crate) fn owner_secret_shares(&self) -> Result<Vec<SecretShareWithId<P>>, LocalError> {
+        let share_ids = self.holder_shares(&self.owner)?;
+        if share_ids.len() != self.extra_secret_shares.len() + 1 {
+            return Err(LocalError::new(
+                "The number of the owner's secret shares does not match the number of its share IDs",
+            ));
+        }
+        Ok(share_ids
+            .into_iter()
+            .map(|(share_id, _public_share)| share_id)
+            .zip(
+                core::iter::once(&self.secret_share)
+                    .chain(self.extra_secret_shares.iter())
+                    .cloned(),
+            )
+            .collect())
+    }
+
+    /// Converts a t-of-n key share into a t-of-t key share for the holders `ids` (which must include the owner)
     /// that can be used in the presigning/signing protocols.
+    ///
+    /// The holders must have at least `t` shares in total.
+    /// If the holders have several shares, the owner combines all of its shares into one.
+    /// If the shares follow a hierarchical access structure, `ids` must be an authorized set
+    /// of the size equal to the threshold.
     pub fn to_key_share(&self, ids: &BTreeSet<I>) -> Result<KeyShare<P, I>, LocalError> {
-        debug_assert!(ids.len() == self.threshold as usize);
-        debug_assert!(ids.iter().any(|id| id == &self.owner));
+        if !ids.contains(&self.owner) {
+            return Err(LocalError::new(format!(
+                "The owner (id={:?}) must be one of the given holders",
+                self.owner
+            )));
+        }
 
-        let owner_share_id = self
-            .share_ids
-            .get(&self.owner)
-            .ok_or_else(|| LocalError::new("id={id:?} is missing in the share_ids"))?;
+        let coeffs = self.combination_coeffs(ids)?;
+        let secret_share = self
+            .owner_secret_shares()?
+            .into_iter()
+            .zip(coeffs.safe_get("combination coefficients", &self.owner)?)
+            .fold(
+                Secret::init_with(|| Scalar::ZERO),
+                |acc, ((_share_id, secret_share), coeff)| acc + secret_share * coeff,
+            );
+        let public_shares = self.to_public_key_shares(ids)?;
 
-        let share_ids = ids
-            .iter()
-            .map(|id| {
-                let share_id = self
-                    .share_ids
-                    .get(id)
-                    .ok_or_else(|| LocalError::new("id={id:?} is missing in the share_ids"))?;
-                Ok((id.clone(), *share_id))
-            })
-            .collect::<Result<BTreeMap<I, ShareId<P>>, LocalError>>()?;
+        KeyShare::new(self.owner.clone(), secret_share, public_shares)
+    }
 
-        let secret_share = self.secret_share.clone() * interpolation_coeff(share_ids.values(), owner_share_id);
-        let public_shares = ids
-            .iter()
-            .map(|id| {
-                let public_share = self
-                    .public_shares
-                    .get(id)
-                    .ok_or_else(|| LocalError::new("id={id:?} is missing in the public shares"))?;
-                let this_share_id = self
-                    .share_ids
-                    .get(id)
-                    .ok_or_else(|| LocalError::new("id={id:?} is missing in the share_ids"))?;
-                Ok((
-                    id.clone(),
-                    public_share * interpolation_coeff(share_ids.values(), this_share_id),
-                ))
-            })
-            .collect::<Result<_, LocalError>>()?;
+    /// Returns the public shares of the t-of-t key shares for the given `ids`
+    /// (same as the ones in the key shares returned by [`to_key_share`](`Self::to_key_share`)).
+    ///
+    /// Unlike [`to_key_share`](`Self::to_key_share`), does not require the owner to be in `ids`.
+    pub(crate) fn to_public_key_shares(&self, ids: &BTreeSet<I>) -> Result<BTreeMap<I, Point<P>>, LocalError> {
+        combine_public_shares(
+            self.threshold,
+            &self.share_ids,
+            &self.public_shares,
+            &self.extra_shares,
+            self.access_structure.as_ref(),
+            ids,
+        )
+    }
 
-        KeyShare::new(self.owner.clone(), secret_share, public_shares)
+    fn combination_coeffs(&self, ids: &BTreeSet<I>) -> Result<BTreeMap<I, Vec<Scalar<P>>>, LocalError> {
+        combination_coeffs(
+            self.threshold,
+            &self.share_ids,
+            &self.public_shares,
+            &self.extra_shares,
+            self.access_structure.as_ref(),
+            ids,
+        )
     }
 
     /// Creates a t-of-t threshold keyshare that can be used in KeyResharing protocol.
-    pub fn from_key_share(key_share: &KeyShare<P, I>) -> Self {
+    pub fn from_key_share(key_share: &KeyShare<P, I>) -> Result<Self, LocalError> {
+        Self::from_key_share_with_share_ids(key_share, &ShareIdAssignment::Sequential)
+    }
+
+    /// Creates a t-of-t threshold keyshare that can be used in KeyResharing protocol,
+    /// with the share IDs assigned as specified.
+    ///
+    /// Since there is no session here, [`ShareIdAssignment::FromSessionId`] is not supported.
+    pub fn from_key_share_with_share_ids(
+        key_share: &KeyShare<P, I>,
+        share_id_assignment: &ShareIdAssignment<P, I>,
+    ) -> Result<Self, LocalError> {
         let ids = key_share.all_parties();
-        let num_parties: u64 = ids.len().try_into().expect("no more than 2^64-1 shares needed");
-        let share_ids = ids
-            .iter()
-            .cloned()
-            .zip((1..=num_parties).map(ShareId::new))
-            .collect::<BTreeMap<_, _>>();
-
-        let owner_share_id = share_ids
-            .get(key_share.owner())
-            .expect("Just created a ShareId for all parties");
-
-        let secret_share = key_share.secret_share().clone()
-            * interpolation_coeff(share_ids.values(), owner_share_id)
-                .invert()
-                .expect("the interpolation coefficient is a non-zero scalar");
+        let share_ids = share_id_assignment.assign(&ids, None)?;
+
+        // The additive shares are divided by the interpolation coefficients, so that interpolating them
+        // with the same coefficients adds them up.
+        let inverse_coeff = |share_id: &ShareId<P>| {
+            Option::<Scalar<P>>::from(interpolation_coeff(share_ids.values(), share_id).invert())
+                .ok_or_else(|| LocalError::new("The share IDs must be non-zero and distinct"))
+        };
+
+        let owner_share_id = share_ids.safe_get("share IDs", key_share.owner())?;
+        let secret_share = key_share.secret_share().clone() * inverse_coeff(owner_share_id)?;
         let public_shares = ids
             .iter()
             .map(|id| {
-                let share_id = share_ids.get(id).expect("share_ids and ids have identical lengths");
-                let public_share = key_share
-                    .public_shares()
-                    .get(id)
-                    .expect("There is one public share (Point) for each party")
-                    * interpolation_coeff(share_ids.values(), share_id)
-                        .invert()
-                        .expect("the interpolation coefficient is a non-zero scalar");
-                (id.clone(), public_share)
+                let share_id = share_ids.safe_get("share IDs", id)?;
+                let public_share = key_share.public_shares().safe_get("public shares", id)? * inverse_coeff(share_id)?;
+                Ok((id.clone(), public_share))
             })
-            .collect::<BTreeMap<_, _>>();
+            .collect::<Result<BTreeMap<_, _>, LocalError>>()?;
 
-        Self {
+        Ok(Self {
             owner: key_share.owner().clone(),
             threshold: ids.len() as u32,
             share_ids: share_ids.into(),
             secret_share,
             public_shares: public_shares.into(),
+            extra_shares: BTreeMap::new().into(),
+            extra_secret_shares: Vec::new(),
+            access_structure: None,
+        })
+    }
+}
+
+/// The public part of a set of [`ThresholdKeyShare`]s: the threshold,
+/// and the share IDs and the public shares of all the holders.
+///
+/// Can be given to the nodes that do not hold a share themselves.
+#[derive(Debug, Clone)]
+#[derive_where::derive_where(Serialize, Deserialize)]
+pub struct ThresholdPublicKeyShares<P, I>
+where
+    P: SchemeParams,
+    I: PartyId,
+{
+    pub(crate) threshold: u32,
+    pub(crate) share_ids: SerializableMap<I, ShareId<P>>,
+    pub(crate) public_shares: SerializableMap<I, Point<P>>,
+    #[serde(default = "empty_map")]
+    pub(crate) extra_shares: SerializableMap<I, Vec<PublicShareWithId<P>>>,
+    #[serde(default)]
+    pub(crate) access_structure: Option<HierarchicalAccessStructure<I>>,
+}
+
+impl<P, I> ThresholdPublicKeyShares<P, I>
+where
+    P: SchemeParams,
+    I: PartyId,
+{
+    /// The threshold.
+    pub fn threshold(&self) -> usize {
+        self.threshold as usize
+    }
+
+    /// The IDs of all the holders of the shares.
+    pub fn holders(&self) -> BTreeSet<I> {
+        self.share_ids.keys().cloned().collect()
+    }
+
+    /// The share IDs of all the holders of the shares (the first one, if a holder has several shares).
+    ///
+    /// Can be used with [`ShareIdAssignment::Explicit`] to keep the share IDs of the holders
+    /// that remain after resharing.
+    pub fn share_ids(&self) -> &BTreeMap<I, ShareId<P>> {
+        &self.share_ids
+    }
+
+    /// The number of shares held by the given party (zero if it is not a holder).
+    pub fn weight(&self, id: &I) -> usize {
+        holder_weight(&self.share_ids, &self.extra_shares, id)
+    }
+
+    /// Returns `true` if some of the holders have more than one share.
+    pub fn is_weighted(&self) -> bool {
+        !self.extra_shares.is_empty()
+    }
+
+    /// The hierarchical access structure of the shares, if they were created with one.
+    pub fn access_structure(&self) -> Option<&HierarchicalAccessStructure<I>> {
+        self.access_structure.as_ref()
+    }
+
+    /// Returns the share IDs and the public shares of all the shares held by the given party.
+    pub(crate) fn holder_shares(&self, id: &I) -> Result<Vec<PublicShareWithId<P>>, LocalError> {
+        holder_shares(&self.share_ids, &self.public_shares, &self.extra_shares, id)
+    }
+
+    /// Returns the points the shares of the given party are evaluated at.
+    pub(crate) fn holder_points(&self, id: &I) -> Result<Vec<SharePoint<P>>, LocalError> {
+        holder_points(
+            &self.share_ids,
+            &self.public_shares,
+            &self.extra_shares,
+            self.access_structure.as_ref(),
+            id,
+        )
+    }
+
+    /// Returns the first of the given holders whose shares are enough to reconstruct the secret
+    /// (in the order of their IDs, or of their levels if the shares are hierarchical),
+    /// or `None` if all of them together are not enough.
+    pub(crate) fn authorized_subset(&self, ids: &BTreeSet<I>) -> Option<BTreeSet<I>> {
+        if let Some(access_structure) = self.access_structure.as_ref() {
+            let subset = access_structure
+                .ordered_holders()
+                .into_iter()
+                .filter(|id| ids.contains(id))
+                .take(self.threshold())
+                .collect::<BTreeSet<_>>();
+            return (subset.len() == self.threshold() && access_structure.is_authorized(&subset)).then_some(subset);
+        }
+
+        let mut subset = BTreeSet::new();
+        let mut weight = 0;
+        for id in ids {
+            if weight >= self.threshold() {
+                break;
+            }
+            weight += self.weight(id);
+            subset.insert(id.clone());
+        }
+        (weight >= self.threshold()).then_some(subset)
+    }
+
+    /// Returns the coefficients the shares of `ids` are multiplied by to combine them into the shared secret
+    /// (for each holder, one for each of its shares, in the same order as in [`holder_shares`](`Self::holder_shares`)).
+    pub(crate) fn combination_coeffs(&self, ids: &BTreeSet<I>) -> Result<BTreeMap<I, Vec<Scalar<P>>>, LocalError> {
+        combination_coeffs(
+            self.threshold,
+            &self.share_ids,
+            &self.public_shares,
+            &self.extra_shares,
+            self.access_structure.as_ref(),
+            ids,
+        )
+    }
+
+    pub(crate) fn verifying_key_as_point(&self) -> Result<Point<P>, LocalError> {
+        join_public_shares(
+            self.threshold,
+            &self.share_ids,
+            &self.public_shares,
+            &self.extra_shares,
+            self.access_structure.as_ref(),
+        )
+    }
+
+    /// Return the verifying key to which this set of shares corresponds.
+    pub fn verifying_key(&self) -> Result<VerifyingKey<P::Curve>, LocalError> {
+        self.verifying_key_as_point()?
+            .to_verifying_key()
+            .ok_or_else(|| LocalError::new("The combined verifying key is an identity"))
+    }
+}
+
+/// A hierarchical threshold access structure
+/// (see T. Tassa "Hierarchical Threshold Secret Sharing", <https://doi.org/10.1007/s00145-006-0334-8>).
+///
+/// The holders are divided into levels, from the most privileged one, each with a cumulative threshold.
+/// A set of signers is authorized if for every level, the number of signers on this level and the ones above it
+/// is at least the level's threshold. For example, the levels `[(executives, 2), (operators, 5)]`
+/// mean that any 5 holders including at least 2 executives can sign.
+/// The threshold of the last level is the threshold of the key shares.
+#[derive(Debug, Clone)]
+#[derive_where::derive_where(Serialize, Deserialize)]
+pub struct HierarchicalAccessStructure<I: PartyId> {
+    // The level of each holder, starting from 0 for the most privileged one.
+    levels: SerializableMap<I, u32>,
+    // The cumulative threshold of each level.
+    thresholds: Vec<u32>,
+}
+
+impl<I: PartyId> HierarchicalAccessStructure<I> {
+    /// Creates an access structure given the holders on each level and the level's cumulative threshold,
+    /// starting from the most privileged level.
+    ///
+    /// The thresholds must be increasing, and the set of all the holders must be authorized.
+    pub fn new(levels: Vec<(BTreeSet<I>, usize)>) -> Result<Self, LocalError> {
+        if levels.is_empty() {
+            return Err(LocalError::new("The access structure must have at least one level"));
+        }
+
+        let mut holder_levels = BTreeMap::new();
+        let mut thresholds = Vec::with_capacity(levels.len());
+        let mut num_holders = 0;
+        let mut prev_threshold = 0;
+        for (level, (holders, threshold)) in levels.into_iter().enumerate() {
+            if threshold <= prev_threshold {
+                return Err(LocalError::new("The thresholds of the levels must be increasing"));
+            }
+            num_holders += holders.len();
+            if num_holders < threshold {
+                return Err(LocalError::new(format!(
+                    "Level {level} has the threshold {threshold}, but there are only {num_holders} holders on it and above"
+                )));
+            }
+
+            let level =
+                u32::try_from(level).map_err(|_| LocalError::new("The number of levels does not fit into `u32`"))?;
+            for id in holders {
+                if holder_levels.insert(id.clone(), level).is_some() {
+                    return Err(LocalError::new(format!("id={id:?} is on several levels")));
+                }
+            }
+            thresholds
+                .push(u32::try_from(threshold).map_err(|_| LocalError::new("The threshold does not fit into `u32`"))?);
+            prev_threshold = threshold;
+        }
+
+        Ok(Self {
+            levels: holder_levels.into(),
+            thresholds,
+        })
+    }
+
+    /// The threshold of the key shares (that is, the threshold of the last level).
+    pub fn threshold(&self) -> usize {
+        self.thresholds.last().copied().unwrap_or(0) as usize
+    }
+
+    /// The IDs of all the holders.
+    pub fn holders(&self) -> BTreeSet<I> {
+        self.levels.keys().cloned().collect()
+    }
+
+    /// The level of the given holder (starting from 0 for the most privileged one),
+    /// or `None` if it is not a holder.
+    pub fn level(&self, id: &I) -> Option<usize> {
+        self.levels.get(id).map(|level| *level as usize)
+    }
+
+    /// Returns `true` if the given set of signers can sign.
+    pub fn is_authorized(&self, signers: &BTreeSet<I>) -> bool {
+        let mut counts = vec![0usize; self.thresholds.len()];
+        for id in signers {
+            match self.level(id).and_then(|level| counts.get_mut(level)) {
+                Some(count) => *count += 1,
+                None => return false,
+            }
+        }
+
+        let mut num_signers = 0;
+        self.thresholds.iter().zip(counts).all(|(threshold, count)| {
+            num_signers += count;
+            num_signers >= *threshold as usize
+        })
+    }
+
+    /// The order of the derivative of the sharing polynomial the holder's share is evaluated from:
+    /// the threshold of the level above it (zero for the first level).
+    pub(crate) fn derivative_order(&self, id: &I) -> Result<usize, LocalError> {
+        let level = *self.levels.safe_get("holder levels", id)? as usize;
+        if level == 0 {
+            return Ok(0);
+        }
+        self.thresholds
+            .get(level - 1)
+            .map(|threshold| *threshold as usize)
+            .ok_or_else(|| LocalError::new(format!("Invalid level {level} for id={id:?}")))
+    }
+
+    /// The share ID and the derivative order for each holder.
+    ///
+    /// Tassa's scheme requires the share IDs to be monotone in the levels
+    /// for the authorized sets to be able to reconstruct the secret,
+    /// so they are assigned sequentially, in the order of the levels.
+    pub(crate) fn holder_points<P: SchemeParams>(&self) -> Result<BTreeMap<I, SharePoint<P>>, LocalError> {
+        let holders = self.ordered_holders();
+        let share_ids = shamir_evaluation_points(holders.len());
+        holders
+            .into_iter()
+            .zip(share_ids)
+            .map(|(id, share_id)| {
+                let order = self.derivative_order(&id)?;
+                Ok((id, (share_id, order)))
+            })
+            .collect()
+    }
+
+    /// The holders ordered by their levels, starting from the most privileged one.
+    ///
+    /// Since the set of all the holders is authorized, so is any prefix of this list
+    /// with the length at least the threshold.
+    fn ordered_holders(&self) -> Vec<I> {
+        let mut holders = self.levels.iter().collect::<Vec<_>>();
+        holders.sort_by_key(|(id, level)| (**level, *id));
+        holders.into_iter().map(|(id, _level)| id.clone()).collect()
+    }
+}
+
+/// The way the share IDs (the points at which the sharing polynomial is evaluated) are assigned to the holders.
+#[derive(Debug, Clone)]
+pub enum ShareIdAssignment<P: SchemeParams, I> {
+    /// `1, 2, ..., n`, assigned to the holders in the order of their IDs.
+    ///
+    /// A holder's share ID changes when the set of holders changes.
+    Sequential,
+    /// Derived from the hash of the holder's ID.
+    ///
+    /// A holder's share ID does not depend on the set of holders.
+    FromPartyId,
+    /// Derived from the hash of the holder's ID and the session ID.
+    ///
+    /// Only available in protocols. Since the session ID depends on the set of the participants,
+    /// the share IDs change when it changes.
+    FromSessionId,
+    /// Supplied explicitly for every holder. The share IDs must be non-zero and distinct.
+    ///
+    /// Only supports the holders with a single share.
+    Explicit(BTreeMap<I, ShareId<P>>),
+}
+
+impl<P: SchemeParams, I: PartyId> ShareIdAssignment<P, I> {
+    pub(crate) fn assign(&self, ids: &BTreeSet<I>, sid: Option<&Sid>) -> Result<BTreeMap<I, ShareId<P>>, LocalError> {
+        let weights = ids.iter().map(|id| (id.clone(), 1)).collect();
+        self.assign_weighted(&weights, sid)?
+            .into_iter()
+            .map(|(id, share_ids)| {
+                let share_id = share_ids
+                    .first()
+                    .copied()
+                    .ok_or_else(|| LocalError::new(format!("No share IDs assigned to id={id:?}")))?;
+                Ok((id, share_id))
+            })
+            .collect()
+    }
+
+    pub(crate) fn assign_weighted(
+        &self,
+        weights: &BTreeMap<I, usize>,
+        sid: Option<&Sid>,
+    ) -> Result<BTreeMap<I, Vec<ShareId<P>>>, LocalError> {
+        if weights.values().any(|weight| *weight == 0) {
+            return Err(LocalError::new("The weights of the holders must be non-zero"));
+        }
+
+        let share_ids = match self {
+            Self::Sequential => assign_weighted_share_ids(weights),
+            Self::FromPartyId => weights
+                .iter()
+                .map(|(id, weight)| (id.clone(), hash_share_ids(b"ShareId from PartyId", id, *weight)))
+                .collect(),
+            Self::FromSessionId => {
+                let sid = sid.ok_or_else(|| {
+                    LocalError::new("Share IDs derived from the session ID can only be assigned within a session")
+                })?;
+                weights
+                    .iter()
+                    .map(|(id, weight)| (id.clone(), hash_share_ids(b"ShareId from Sid", &(sid, id), *weight)))
+                    .collect()
+            }
+            Self::Explicit(share_ids) => weights
+                .iter()
+                .map(|(id, weight)| {
+                    if *weight != 1 {
+                        return Err(LocalError::new(
+                            "Explicit share IDs are only supported for the holders with a single share",
+                        ));
+                    }
+                    Ok((id.clone(), [*share_ids.safe_get("explicit share IDs", id)?].into()))
+                })
+                .collect::<Result<_, LocalError>>()?,
+        };
+
+        let all_share_ids = share_ids.values().flatten().collect::<Vec<_>>();
+        if all_share_ids.iter().any(|share_id| share_id.is_zero()) {
+            return Err(LocalError::new("Share IDs must be non-zero"));
+        }
+        if all_share_ids.iter().collect::<BTreeSet<_>>().len() != all_share_ids.len() {
+            return Err(LocalError::new("Share IDs must be distinct"));
+        }
+
+        Ok(share_ids)
+    }
+}
+
+/// The default value for the fields that were added after the key shares were first serialized.
+fn empty_map<K: Ord, V>() -> SerializableMap<K, V> {
+    BTreeMap::new().into()
+}
+
+/// Derives `weight` share IDs from the hash of `data`.
+///
+/// The first one only depends on `data`, so it stays the same if the weight changes.
+fn hash_share_ids<P: SchemeParams, T: Serialize>(dst: &[u8], data: &T, weight: usize) -> Vec<ShareId<P>> {
+    (0..weight)
+        .map(|idx| {
+            let digest = Hasher::<P::Digest>::new_with_dst(dst).chain(data);
+            let digest = if idx == 0 { digest } else { digest.chain(&(idx as u64)) };
+            let mut reader = digest.finalize_to_reader();
+            ShareId::from_scalar(Scalar::from_xof_reader(&mut reader))
+        })
+        .collect()
+}
+
+fn holder_weight<P, I>(
+    share_ids: &BTreeMap<I, ShareId<P>>,
+    extra_shares: &BTreeMap<I, Vec<PublicShareWithId<P>>>,
+    id: &I,
+) -> usize
+where
+    P: SchemeParams,
+    I: PartyId,
+{
+    if share_ids.contains_key(id) {
+        1 + extra_shares.get(id).map_or(0, |extra| extra.len())
+    } else {
+        0
+    }
+}
+
+fn holder_shares<P, I>(
+    share_ids: &BTreeMap<I, ShareId<P>>,
+    public_shares: &BTreeMap<I, Point<P>>,
+    extra_shares: &BTreeMap<I, Vec<PublicShareWithId<P>>>,
+    id: &I,
+) -> Result<Vec<PublicShareWithId<P>>, LocalError>
+where
+    P: SchemeParams,
+    I: PartyId,
+{
+    let share_id = share_ids.safe_get("share IDs", id)?;
+    let public_share = public_shares.safe_get("public shares", id)?;
+    let mut shares = Vec::from([(*share_id, *public_share)]);
+    if let Some(extra) = extra_shares.get(id) {
+        shares.extend(extra.iter().cloned());
+    }
+    Ok(shares)
+}
+
+/// Returns the points the shares of the given party are evaluated at: the share IDs, and the orders
+/// of the derivatives of the sharing polynomial (zero unless the shares follow a hierarchical access structure),
+/// in the same order as in [`holder_shares`].
+fn holder_points<P, I>(
+    share_ids: &BTreeMap<I, ShareId<P>>,
+    public_shares: &BTreeMap<I, Point<P>>,
+    extra_shares: &BTreeMap<I, Vec<PublicShareWithId<P>>>,
+    access_structure: Option<&HierarchicalAccessStructure<I>>,
+    id: &I,
+) -> Result<Vec<SharePoint<P>>, LocalError>
+where
+    P: SchemeParams,
+    I: PartyId,
+{
+    let order = match access_structure {
+        Some(access_structure) => access_structure.derivative_order(id)?,
+        None => 0,
+    };
+    Ok(holder_shares(share_ids, public_shares, extra_shares, id)?
+        .into_iter()
+        .map(|(share_id, _public_share)| (share_id, order))
+        .collect())
+}
+
+/// Returns the coefficients the shares of `ids` are multiplied by to combine them into the shared secret
+/// (for each holder, one for each of its shares, in the same order as in [`holder_shares`]).
+fn combination_coeffs<P, I>(
+    threshold: u32,
+    share_ids: &BTreeMap<I, ShareId<P>>,
+    public_shares: &BTreeMap<I, Point<P>>,
+    extra_shares: &BTreeMap<I, Vec<PublicShareWithId<P>>>,
+    access_structure: Option<&HierarchicalAccessStructure<I>>,
+    ids: &BTreeSet<I>,
+) -> Result<BTreeMap<I, Vec<Scalar<P>>>, LocalError>
+where
+    P: SchemeParams,
+    I: PartyId,
+{
+    let holder_share_ids = ids
+        .iter()
+        .map(|id| {
+            let share_ids = holder_shares(share_ids, public_shares, extra_shares, id)?
+                .into_iter()
+                .map(|(share_id, _public_share)| share_id)
+                .collect::<Vec<_>>();
+            Ok((id.clone(), share_ids))
+        })
+        .collect::<Result<BTreeMap<_, _>, LocalError>>()?;
+
+    let access_structure = match access_structure {
+        Some(access_structure) => access_structure,
+        None => {
+            let all_share_ids = holder_share_ids.values().flatten().collect::<Vec<_>>();
+            if all_share_ids.len() < threshold as usize {
+                return Err(LocalError::new(format!(
+                    "The parties must hold at least {threshold} shares in total, got {}",
+                    all_share_ids.len()
+                )));
+            }
+            return Ok(holder_share_ids
+                .iter()
+                .map(|(id, share_ids)| {
+                    let coeffs = share_ids
+                        .iter()
+                        .map(|share_id| interpolation_coeff(all_share_ids.iter().copied(), share_id))
+                        .collect();
+                    (id.clone(), coeffs)
+                })
+                .collect());
         }
+    };
+
+    // Birkhoff interpolation needs exactly as many shares as there are coefficients in the polynomial.
+    if ids.len() != access_structure.threshold() || !access_structure.is_authorized(ids) {
+        return Err(LocalError::new(
+            "The parties must be an authorized set of the size equal to the threshold",
+        ));
+    }
+    let points = holder_share_ids
+        .iter()
+        .map(|(id, share_ids)| {
+            let share_id = share_ids
+                .first()
+                .ok_or_else(|| LocalError::new(format!("id={id:?} must have a share")))?;
+            Ok((*share_id, access_structure.derivative_order(id)?))
+        })
+        .collect::<Result<Vec<_>, LocalError>>()?;
+    let coeffs = birkhoff_coeffs(&points)
+        .ok_or_else(|| LocalError::new("The shares of the given parties do not determine the secret"))?;
+    Ok(ids
+        .iter()
+        .cloned()
+        .zip(coeffs.into_iter().map(|coeff| [coeff].into()))
+        .collect())
+}
+
+/// Returns the shares of `ids` multiplied by the combination coefficients
+/// (so that they add up to the verifying key).
+fn combine_public_shares<P, I>(
+    threshold: u32,
+    share_ids: &BTreeMap<I, ShareId<P>>,
+    public_shares: &BTreeMap<I, Point<P>>,
+    extra_shares: &BTreeMap<I, Vec<PublicShareWithId<P>>>,
+    access_structure: Option<&HierarchicalAccessStructure<I>>,
+    ids: &BTreeSet<I>,
+) -> Result<BTreeMap<I, Point<P>>, LocalError>
+where
+    P: SchemeParams,
+    I: PartyId,
+{
+    let coeffs = combination_coeffs(threshold, share_ids, public_shares, extra_shares, access_structure, ids)?;
+    ids.iter()
+        .map(|id| {
+            let public_share = holder_shares(share_ids, public_shares, extra_shares, id)?
+                .into_iter()
+                .zip(coeffs.safe_get("combination coefficients", id)?)
+                .map(|((_share_id, public_share), coeff)| public_share * coeff)
+                .sum::<Point<P>>();
+            Ok((id.clone(), public_share))
+        })
+        .collect()
+}
+
+fn join_public_shares<P, I>(
+    threshold: u32,
+    share_ids: &BTreeMap<I, ShareId<P>>,
+    public_shares: &BTreeMap<I, Point<P>>,
+    extra_shares: &BTreeMap<I, Vec<PublicShareWithId<P>>>,
+    access_structure: Option<&HierarchicalAccessStructure<I>>,
+) -> Result<Point<P>, LocalError>
+where
+    P: SchemeParams,
+    I: PartyId,
+{
+    if let Some(access_structure) = access_structure {
+        let ids = access_structure
+            .ordered_holders()
+            .into_iter()
+            .take(threshold as usize)
+            .collect::<BTreeSet<_>>();
+        return Ok(combine_public_shares(
+            threshold,
+            share_ids,
+            public_shares,
+            extra_shares,
+            Some(access_structure),
+            &ids,
+        )?
+        .into_values()
+        .sum());
     }
+
+    let shares = share_ids
+        .keys()
+        .map(|id| holder_shares(share_ids, public_shares, extra_shares, id))
+        .collect::<Result<Vec<_>, _>>()?;
+    Ok(shamir_join_points(
+        &shares
+            .into_iter()
+            .flatten()
+            .take(threshold as usize)
+            .collect::<BTreeMap<_, _>>(),
+    ))
 }
 
 #[cfg(feature = "bip32")]
@@ -241,23 +1035,70 @@ where
         let tweakable_pk = pk.tweakable_pk();
         let tweaks = derive_tweaks::<P::Curve>(&tweakable_pk, derivation_path)?;
 
-        // Will fail here if secret share is zero
-        let secret_share = self.secret_share.clone().to_signing_key().ok_or(bip32::Error::Crypto)?;
-        let mut tweakable_sk = secret_share.tweakable_sk();
-        for tweak in &tweaks {
-            tweakable_sk = tweakable_sk.derive_child(*tweak)?;
-        }
-        let sk: SigningKey<P::Curve> = SecretTweakable::key_from_tweakable_sk(&tweakable_sk);
-        let secret_share = Secret::init_with(|| Scalar::new(*sk.as_nonzero_scalar().as_ref()));
+        // Will fail here if a secret share is zero
+        let tweak_secret = |secret_share: &Secret<Scalar<P>>| -> Result<Secret<Scalar<P>>, bip32::Error> {
+            let secret_share = secret_share.clone().to_signing_key().ok_or(bip32::Error::Crypto)?;
+            let mut tweakable_sk = secret_share.tweakable_sk();
+            for tweak in &tweaks {
+                tweakable_sk = tweakable_sk.derive_child(*tweak)?;
+            }
+            let sk: SigningKey<P::Curve> = SecretTweakable::key_from_tweakable_sk(&tweakable_sk);
+            Ok(Secret::init_with(|| Scalar::new(*sk.as_nonzero_scalar().as_ref())))
+        };
 
-        let public_shares = BTreeMap::from(self.public_shares.clone())
-            .into_iter()
-            .map(|(id, point)|
-            // Will fail here if the final or one of the intermediate points is an identity
-            point.to_verifying_key().ok_or(bip32::Error::Crypto)
+        // Will fail here if the final or one of the intermediate points is an identity
+        let tweak_public = |point: &Point<P>| -> Result<Point<P>, bip32::Error> {
+            point
+                .to_verifying_key()
+                .ok_or(bip32::Error::Crypto)
                 .and_then(|vkey| apply_tweaks_public(&vkey, &tweaks))
-                .map(|vkey| (id, Point::from_verifying_key(&vkey))))
-            .collect::<Result<BTreeMap<_, _>, _>>()?;
+                .map(|vkey| Point::from_verifying_key(&vkey))
+        };
+
+        // Adding the tweak to the sharing polynomial does not change its derivatives,
+        // so the shares on the lower levels of a hierarchical access structure stay the same.
+        let is_tweaked = |id: &I| -> Result<bool, bip32::Error> {
+            match self.access_structure.as_ref() {
+                Some(access_structure) => {
+                    let order = access_structure
+                        .derivative_order(id)
+                        .map_err(|_| bip32::Error::Crypto)?;
+                    Ok(order == 0)
+                }
+                None => Ok(true),
+            }
+        };
+
+        let secret_share = if is_tweaked(&self.owner)? {
+            tweak_secret(&self.secret_share)?
+        } else {
+            self.secret_share.clone()
+        };
+        let extra_secret_shares = self
+            .extra_secret_shares
+            .iter()
+            .map(tweak_secret)
+            .collect::<Result<Vec<_>, _>>()?;
+
+        let public_shares = self
+            .public_shares
+            .iter()
+            .map(|(id, point)| {
+                let point = if is_tweaked(id)? { tweak_public(point)? } else { *point };
+                Ok((id.clone(), point))
+            })
+            .collect::<Result<BTreeMap<_, _>, bip32::Error>>()?;
+        let extra_shares = self
+            .extra_shares
+            .iter()
+            .map(|(id, shares)| {
+                let shares = shares
+                    .iter()
+                    .map(|(share_id, point)| Ok((*share_id, tweak_public(point)?)))
+                    .collect::<Result<Vec<_>, bip32::Error>>()?;
+                Ok((id.clone(), shares))
+            })
+            .collect::<Result<BTreeMap<_, _>, bip32::Error>>()?;
 
         Ok(Self {
             owner: self.owner.clone(),
@@ -265,6 +1106,9 @@ where
             share_ids: self.share_ids.clone(),
             secret_share,
             public_shares: public_shares.into(),
+            extra_shares: extra_shares.into(),
+            extra_secret_shares,
+            access_structure: self.access_structure.clone(),
         })
     }
 }
@@ -289,7 +1133,7 @@ where
 
 #[cfg(test)]
 mod tests {
-    use alloc::collections::BTreeSet;
+    use alloc::collections::{BTreeMap, BTreeSet};
 
     use ecdsa::SigningKey;
     use manul::{
@@ -298,7 +1142,7 @@ mod tests {
     };
     use rand_core::OsRng;
 
-    use super::ThresholdKeyShare;
+    use super::{HierarchicalAccessStructure, ShareIdAssignment, ThresholdKeyShare};
     use crate::{curve::Scalar, dev::TestParams};
 
     #[test]
@@ -329,5 +1173,196 @@ mod tests {
         );
         assert_eq!(&nt_share0.verifying_key(), sk_verifying_key);
         assert_eq!(&nt_share1.verifying_key(), sk_verifying_key);
+
+        // The owner must be one of the holders, and the holders must have at least `threshold` shares
+        assert!(shares[&ids[1]].to_key_share(&ids_subset).is_err());
+        assert!(shares[&ids[0]].to_key_share(&BTreeSet::from([ids[0]])).is_err());
+    }
+
+    #[test]
+    fn share_ids_from_party_id() {
+        let sk = SigningKey::random(&mut OsRng);
+
+        let signers = (0..4).map(TestSigner::new).collect::<Vec<_>>();
+        let ids = signers.iter().map(|signer| signer.verifying_key()).collect::<Vec<_>>();
+        let ids_set = ids.iter().take(3).cloned().collect::<BTreeSet<_>>();
+        let ids_superset = ids.iter().cloned().collect::<BTreeSet<_>>();
+
+        let assignment = ShareIdAssignment::FromPartyId;
+        let shares = ThresholdKeyShare::<TestParams, TestVerifier>::new_centralized_with_share_ids(
+            &mut OsRng,
+            &ids_set,
+            2,
+            Some(&sk),
+            &assignment,
+        )
+        .unwrap();
+        let shares_superset = ThresholdKeyShare::<TestParams, TestVerifier>::new_centralized_with_share_ids(
+            &mut OsRng,
+            &ids_superset,
+            2,
+            Some(&sk),
+            &assignment,
+        )
+        .unwrap();
+
+        // The share IDs do not depend on the set of holders
+        for id in ids_set.iter() {
+            assert_eq!(shares[id].share_id().unwrap(), shares_superset[id].share_id().unwrap());
+        }
+
+        let ids_subset = BTreeSet::from([ids[2], ids[0]]);
+        let nt_share0 = shares[&ids[0]].to_key_share(&ids_subset).unwrap();
+        let nt_share1 = shares[&ids[2]].to_key_share(&ids_subset).unwrap();
+        assert_eq!(
+            nt_share0.secret_share().expose_secret() + nt_share1.secret_share().expose_secret(),
+            Scalar::<TestParams>::from(sk.as_nonzero_scalar())
+        );
+
+        // Session ID-derived share IDs are not available outside of a session
+        assert!(
+            ThresholdKeyShare::<TestParams, TestVerifier>::new_centralized_with_share_ids(
+                &mut OsRng,
+                &ids_set,
+                2,
+                Some(&sk),
+                &ShareIdAssignment::FromSessionId,
+            )
+            .is_err()
+        );
+    }
+
+    #[test]
+    fn weighted_key_share_centralized() {
+        let sk = SigningKey::random(&mut OsRng);
+
+        let signers = (0..3).map(TestSigner::new).collect::<Vec<_>>();
+        let ids = signers.iter().map(|signer| signer.verifying_key()).collect::<Vec<_>>();
+        let weights = BTreeMap::from([(ids[0], 3), (ids[1], 1), (ids[2], 2)]);
+
+        let shares = ThresholdKeyShare::<TestParams, TestVerifier>::new_centralized_weighted(
+            &mut OsRng,
+            &weights,
+            4,
+            Some(&sk),
+            &ShareIdAssignment::Sequential,
+        )
+        .unwrap();
+
+        for share in shares.values() {
+            assert_eq!(&share.verifying_key().unwrap(), sk.verifying_key());
+            assert!(share.is_weighted());
+            for (id, weight) in weights.iter() {
+                assert_eq!(share.weight(id), *weight);
+            }
+        }
+
+        // The weights of the signers add up to at least the threshold
+        for ids_subset in [
+            BTreeSet::from([ids[0], ids[1]]),
+            BTreeSet::from([ids[0], ids[2]]),
+            BTreeSet::from([ids[0], ids[1], ids[2]]),
+        ] {
+            let secret = ids_subset
+                .iter()
+                .map(|id| {
+                    *shares[id]
+                        .to_key_share(&ids_subset)
+                        .unwrap()
+                        .secret_share()
+                        .expose_secret()
+                })
+                .sum::<Scalar<TestParams>>();
+            assert_eq!(secret, Scalar::<TestParams>::from(sk.as_nonzero_scalar()));
+        }
+
+        // The first holders with enough shares are picked
+        let public_key_shares = shares[&ids[0]].public_key_shares();
+        assert_eq!(
+            public_key_shares.authorized_subset(&weights.keys().cloned().collect()),
+            Some(BTreeSet::from([ids[0], ids[1]]))
+        );
+        assert_eq!(
+            public_key_shares.authorized_subset(&BTreeSet::from([ids[1], ids[2]])),
+            None
+        );
+
+        // Explicit share IDs only support one share per holder
+        let explicit = ShareIdAssignment::Explicit(shares[&ids[0]].share_ids.clone().into());
+        assert!(ThresholdKeyShare::<TestParams, TestVerifier>::new_centralized_weighted(
+            &mut OsRng,
+            &weights,
+            4,
+            Some(&sk),
+            &explicit,
+        )
+        .is_err());
+    }
+
+    #[test]
+    fn hierarchical_key_share_centralized() {
+        let sk = SigningKey::random(&mut OsRng);
+
+        let signers = (0..5).map(TestSigner::new).collect::<Vec<_>>();
+        let ids = signers.iter().map(|signer| signer.verifying_key()).collect::<Vec<_>>();
+        let executives = BTreeSet::from([ids[0], ids[1]]);
+        let operators = BTreeSet::from([ids[2], ids[3], ids[4]]);
+
+        // Any 4 holders including both of the executives
+        let access_structure = HierarchicalAccessStructure::new([(executives, 2), (operators, 4)].into()).unwrap();
+        assert_eq!(access_structure.threshold(), 4);
+
+        let shares = ThresholdKeyShare::<TestParams, TestVerifier>::new_centralized_hierarchical(
+            &mut OsRng,
+            &access_structure,
+            Some(&sk),
+        )
+        .unwrap();
+
+        for share in shares.values() {
+            assert_eq!(&share.verifying_key().unwrap(), sk.verifying_key());
+            assert_eq!(&share.public_key_shares().verifying_key().unwrap(), sk.verifying_key());
+        }
+
+        for authorized in [
+            BTreeSet::from([ids[0], ids[1], ids[2], ids[3]]),
+            BTreeSet::from([ids[0], ids[1], ids[3], ids[4]]),
+        ] {
+            assert!(access_structure.is_authorized(&authorized));
+            let secret = authorized
+                .iter()
+                .map(|id| {
+                    *shares[id]
+                        .to_key_share(&authorized)
+                        .unwrap()
+                        .secret_share()
+                        .expose_secret()
+                })
+                .sum::<Scalar<TestParams>>();
+            assert_eq!(secret, Scalar::<TestParams>::from(sk.as_nonzero_scalar()));
+        }
+
+        // Not enough executives
+        let unauthorized = BTreeSet::from([ids[0], ids[2], ids[3], ids[4]]);
+        assert!(!access_structure.is_authorized(&unauthorized));
+        assert!(shares[&ids[0]].to_key_share(&unauthorized).is_err());
+
+        // The most privileged holders are picked first
+        let public_key_shares = shares[&ids[0]].public_key_shares();
+        assert_eq!(
+            public_key_shares.authorized_subset(&access_structure.holders()),
+            Some(BTreeSet::from([ids[0], ids[1], ids[2], ids[3]]))
+        );
+        assert_eq!(public_key_shares.authorized_subset(&unauthorized), None);
+
+        // The thresholds must be increasing, and all the holders together must be authorized
+        assert!(HierarchicalAccessStructure::new(
+            [(BTreeSet::from([ids[0]]), 2), (BTreeSet::from([ids[1]]), 3)].into()
+        )
+        .is_err());
+        assert!(HierarchicalAccessStructure::new(
+            [(BTreeSet::from([ids[0], ids[1]]), 2), (BTreeSet::from([ids[2]]), 2)].into()
+        )
+        .is_err());
     }
 }
@@ -17,10 +17,13 @@
 ## Features
 
 `k256`: Secp256k1 parameters using [`k256`](`::k256`) crate. See the [`k256`] module.
+Also enables the threshold BIP-340 Schnorr signing protocol (`SchnorrSigning`).
+
+`ethereum`: Ethereum-specific signing helpers (implies `k256`). See the [`ethereum`] module.
 
 `dev`: Non-secure development parameters using [`tiny-curve`](`::tiny_curve`) crate. See the [`dev`] module.
 
-`bip32`: enables BIP32 support for [`ThresholdKeyShare`].
+`bip32`: enables BIP32 support for [`KeyShare`], [`ThresholdKeyShare`], and [`InteractiveSigning`].
 */
 
 extern crate alloc;
@@ -37,6 +40,9 @@ mod zk;
 #[cfg(feature = "k256")]
 pub use params::k256;
 
+#[cfg(feature = "ethereum")]
+pub use curve::ethereum;
+
 #[cfg(any(test, feature = "dev"))]
 pub use params::dev;
 
@@ -50,18 +56,35 @@ pub use bip32;
 pub use signature;
 
 pub use curve::RecoverableSignature;
-pub use entities::{AuxInfo, KeyShare, KeyShareChange, ThresholdKeyShare};
+pub use entities::{
+    AuxInfo, HierarchicalAccessStructure, KeyShare, KeyShareChange, PrecomputedAuxInfo, ShareIdAssignment,
+    ThresholdKeyShare, ThresholdPublicKeyShares,
+};
 pub use paillier::PaillierParams;
 pub use params::SchemeParams;
 pub use protocols::{
-    AuxGen, AuxGenAssociatedData, AuxGenProtocol, InteractiveSigning, InteractiveSigningAssociatedData,
-    InteractiveSigningProtocol, KeyInit, KeyInitAssociatedData, KeyInitProtocol, KeyRefresh, KeyRefreshAssociatedData,
-    KeyRefreshProtocol, KeyResharing, KeyResharingProtocol, NewHolder, OldHolder, PrehashedMessage,
+    AuxGen, AuxGenAssociatedData, AuxGenProtocol, BatchInteractiveSigning, BatchInteractiveSigningAssociatedData,
+    BatchInteractiveSigningProtocol, BatchPresigning, BatchPresigningAssociatedData, BatchPresigningProtocol,
+    InteractiveSigning, InteractiveSigningAssociatedData, InteractiveSigningProtocol, KeyExport,
+    KeyExportAssociatedData, KeyExportProtocol, KeyGen, KeyGenAssociatedData, KeyGenProtocol, KeyImport,
+    KeyImportAssociatedData, KeyImportProtocol, KeyInit, KeyInitAssociatedData, KeyInitProtocol, KeyRefresh,
+    KeyRefreshAssociatedData, KeyRefreshProtocol, KeyResharing, KeyResharingAssociatedData, KeyResharingProtocol,
+    NewHolder, OldHolder, PrehashedMessage, Presignature, PresignatureTranscript, Presigning, PresigningAssociatedData,
+    PresigningProtocol, RawMessage, ShareRecovery, ShareRecoveryAssociatedData, ShareRecoveryProtocol, Signing,
+    SigningAssociatedData, SigningProtocol, ThresholdKeyInit, ThresholdKeyInitAssociatedData, ThresholdKeyInitProtocol,
+    ThresholdKeyRefresh, ThresholdKeyRefreshProtocol, ThresholdShareUpdate, ThresholdShareUpdateAssociatedData,
+    ThresholdShareUpdateProtocol,
 };
+pub use tools::sss::ShareId;
 pub use uint::{BoxedEncoding, Extendable, MulWide};
 
 #[cfg(feature = "bip32")]
-pub use curve::{DeriveChildKey, PublicTweakable, SecretTweakable};
+pub use curve::{Bip32Tweak, DeriveChildKey, PublicTweakable, SecretTweakable};
+
+#[cfg(feature = "k256")]
+pub use curve::TaprootTweak;
+#[cfg(feature = "k256")]
+pub use protocols::{SchnorrSigning, SchnorrSigningAssociatedData, SchnorrSigningProtocol};
 
 #[cfg(feature = "private-benches")]
 #[allow(missing_docs)]
@@ -8,18 +8,49 @@
 //! refers to the version of the paper published at <https://eprint.iacr.org/2021/060.pdf>
 
 mod aux_gen;
+mod batch;
+mod batch_interactive_signing;
+mod batch_presigning;
 mod interactive_signing;
+mod key_export;
+mod key_gen;
+mod key_import;
 mod key_init;
 mod key_refresh;
 mod key_resharing;
+mod share_recovery;
+mod signing;
+mod threshold_key_init;
+mod threshold_key_refresh;
+
+#[cfg(feature = "k256")]
+mod schnorr_signing;
 
 #[cfg(test)]
 mod misbehavior_tests;
 
 pub use aux_gen::{AuxGen, AuxGenAssociatedData, AuxGenProtocol};
+pub use batch_interactive_signing::{
+    BatchInteractiveSigning, BatchInteractiveSigningAssociatedData, BatchInteractiveSigningProtocol,
+};
+pub use batch_presigning::{BatchPresigning, BatchPresigningAssociatedData, BatchPresigningProtocol};
 pub use interactive_signing::{
-    InteractiveSigning, InteractiveSigningAssociatedData, InteractiveSigningProtocol, PrehashedMessage,
+    InteractiveSigning, InteractiveSigningAssociatedData, InteractiveSigningProtocol, PrehashedMessage, Presignature,
+    PresignatureTranscript, Presigning, PresigningAssociatedData, PresigningProtocol, RawMessage,
 };
+pub use key_export::{KeyExport, KeyExportAssociatedData, KeyExportProtocol};
+pub use key_gen::{KeyGen, KeyGenAssociatedData, KeyGenProtocol};
+pub use key_import::{KeyImport, KeyImportAssociatedData, KeyImportProtocol};
 pub use key_init::{KeyInit, KeyInitAssociatedData, KeyInitProtocol};
 pub use key_refresh::{KeyRefresh, KeyRefreshAssociatedData, KeyRefreshProtocol};
-pub use key_resharing::{KeyResharing, KeyResharingProtocol, NewHolder, OldHolder};
+pub use key_resharing::{KeyResharing, KeyResharingAssociatedData, KeyResharingProtocol, NewHolder, OldHolder};
+pub use share_recovery::{ShareRecovery, ShareRecoveryAssociatedData, ShareRecoveryProtocol};
+pub use signing::{Signing, SigningAssociatedData, SigningProtocol};
+pub use threshold_key_init::{ThresholdKeyInit, ThresholdKeyInitAssociatedData, ThresholdKeyInitProtocol};
+pub use threshold_key_refresh::{
+    ThresholdKeyRefresh, ThresholdKeyRefreshProtocol, ThresholdShareUpdate, ThresholdShareUpdateAssociatedData,
+    ThresholdShareUpdateProtocol,
+};
+
+#[cfg(feature = "k256")]
+pub use schnorr_signing::{SchnorrSigning, SchnorrSigningAssociatedData, SchnorrSigningProtocol};
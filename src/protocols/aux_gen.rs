@@ -337,55 +337,205 @@ where
         shared_randomness: &[u8],
         id: &Id,
     ) -> Result<BoxedRound<Id, Self::Protocol>, LocalError> {
-        if !self.all_ids.contains(id) {
-            return Err(LocalError::new("The given node IDs must contain this node's ID"));
-        }
+        let round = make_round1(rng, shared_randomness, id, &self.all_ids, ReturnAuxInfo)?;
+        Ok(BoxedRound::new_dynamic(round))
+    }
+}
 
-        let other_ids = self.all_ids.clone().without(id);
+/// Determines which protocol the AuxGen rounds are a part of.
+pub(super) trait AuxGenTarget<P: SchemeParams, Id: PartyId>: 'static + Debug + Send + Sync + Sized {
+    /// The protocol the AuxGen rounds are a part of.
+    type Protocol: Protocol<Id, ProtocolError: From<AuxGenError<P, Id>>>;
+}
 
-        let sid = Sid::new::<P, Id>(shared_randomness, &self.all_ids);
+/// An [`AuxGenTarget`] of a protocol that finishes along with the AuxGen rounds.
+pub(super) trait AuxGenResult<P: SchemeParams, Id: PartyId>: AuxGenTarget<P, Id> {
+    /// Wraps the created auxiliary info into the result of the target protocol.
+    fn aux_gen_result(self, aux_info: AuxInfo<P, Id>) -> <Self::Protocol as Protocol<Id>>::Result;
+}
 
-        // Paillier secret key $p_i$, $q_i$
-        let paillier_sk = SecretKeyPaillierWire::<P::Paillier>::random(rng);
-        // Paillier public key $N_i$
-        let paillier_pk = paillier_sk.public_key();
+/// Return the auxiliary info as the result (used in [`AuxGenProtocol`]).
+#[derive(Debug)]
+pub(super) struct ReturnAuxInfo;
 
-        // Ring-Pedersen secret $\lambda$.
-        let rp_secret = RPSecret::random(rng);
-        // Ring-Pedersen parameters ($N$, $s$, $t$) bundled in a single object.
-        let rp_params = RPParams::random_with_secret(rng, &rp_secret);
+impl<P: SchemeParams, Id: PartyId> AuxGenTarget<P, Id> for ReturnAuxInfo {
+    type Protocol = AuxGenProtocol<P, Id>;
+}
 
-        let aux = (&sid, id);
-        let psi = PrmProof::<P>::new(rng, &rp_secret, &rp_params, &aux);
+impl<P: SchemeParams, Id: PartyId> AuxGenResult<P, Id> for ReturnAuxInfo {
+    fn aux_gen_result(self, aux_info: AuxInfo<P, Id>) -> AuxInfo<P, Id> {
+        aux_info
+    }
+}
 
-        let rid = BitVec::random(rng, P::SECURITY_PARAMETER);
-        let u = BitVec::random(rng, P::SECURITY_PARAMETER);
+/// A variant of [`AuxGenProtocol`] that returns a value given to its entry point along with the auxiliary info.
+///
+/// Used as the last protocol of a chain to pass through the results of the previous ones.
+#[derive(Debug)]
+pub struct AuxGenWithProtocol<P: SchemeParams, Id: Debug, T>(PhantomData<(P, Id, T)>);
 
-        // Note: typo in the paper, $V$ hashes in $B_i$ which is not present in the '24 version of the paper.
-        let public_data = PublicData {
-            paillier_pk: paillier_pk.into_precomputed(),
-            rp_params: rp_params.clone(),
-            psi,
-            rid,
-            u,
-        };
+impl<P, Id, T> Protocol<Id> for AuxGenWithProtocol<P, Id, T>
+where
+    P: SchemeParams,
+    Id: PartyId,
+    T: 'static + Debug + Send + Sync,
+{
+    type Result = (T, AuxInfo<P, Id>);
+    type ProtocolError = AuxGenError<P, Id>;
 
-        let context = Context {
-            paillier_sk: paillier_sk.into_precomputed(),
-            rp_params,
-            my_id: id.clone(),
-            other_ids,
-            sid,
-        };
+    fn verify_direct_message_is_invalid(
+        format: &BoxedFormat,
+        round_id: &RoundId,
+        message: &DirectMessage,
+    ) -> Result<(), MessageValidationError> {
+        AuxGenProtocol::<P, Id>::verify_direct_message_is_invalid(format, round_id, message)
+    }
+
+    fn verify_echo_broadcast_is_invalid(
+        format: &BoxedFormat,
+        round_id: &RoundId,
+        message: &EchoBroadcast,
+    ) -> Result<(), MessageValidationError> {
+        AuxGenProtocol::<P, Id>::verify_echo_broadcast_is_invalid(format, round_id, message)
+    }
+
+    fn verify_normal_broadcast_is_invalid(
+        format: &BoxedFormat,
+        round_id: &RoundId,
+        message: &NormalBroadcast,
+    ) -> Result<(), MessageValidationError> {
+        AuxGenProtocol::<P, Id>::verify_normal_broadcast_is_invalid(format, round_id, message)
+    }
+}
+
+/// Return the given value along with the auxiliary info (used in [`AuxGenWithProtocol`]).
+#[derive(Debug)]
+struct ReturnWith<T>(T);
+
+impl<P, Id, T> AuxGenTarget<P, Id> for ReturnWith<T>
+where
+    P: SchemeParams,
+    Id: PartyId,
+    T: 'static + Debug + Send + Sync,
+{
+    type Protocol = AuxGenWithProtocol<P, Id, T>;
+}
+
+impl<P, Id, T> AuxGenResult<P, Id> for ReturnWith<T>
+where
+    P: SchemeParams,
+    Id: PartyId,
+    T: 'static + Debug + Send + Sync,
+{
+    fn aux_gen_result(self, aux_info: AuxInfo<P, Id>) -> (T, AuxInfo<P, Id>) {
+        (self.0, aux_info)
+    }
+}
+
+/// An entry point for the [`AuxGenWithProtocol`].
+#[derive(Debug, Clone)]
+pub struct AuxGenWith<P, Id, T> {
+    all_ids: BTreeSet<Id>,
+    value: T,
+    phantom: PhantomData<P>,
+}
+
+impl<P, Id: PartyId, T> AuxGenWith<P, Id, T> {
+    /// Creates a new entry point given the set of the participants' IDs
+    /// (including this node's), and the value to return along with the auxiliary info.
+    pub fn new(all_ids: BTreeSet<Id>, value: T) -> Self {
+        Self {
+            all_ids,
+            value,
+            phantom: PhantomData,
+        }
+    }
+}
 
-        let round = Round1 { context, public_data };
+impl<P, Id, T> EntryPoint<Id> for AuxGenWith<P, Id, T>
+where
+    P: SchemeParams,
+    Id: PartyId,
+    T: 'static + Debug + Send + Sync,
+{
+    type Protocol = AuxGenWithProtocol<P, Id, T>;
 
+    fn entry_round_id() -> RoundId {
+        1.into()
+    }
+
+    fn make_round(
+        self,
+        rng: &mut dyn CryptoRngCore,
+        shared_randomness: &[u8],
+        id: &Id,
+    ) -> Result<BoxedRound<Id, Self::Protocol>, LocalError> {
+        let round = make_round1(rng, shared_randomness, id, &self.all_ids, ReturnWith(self.value))?;
         Ok(BoxedRound::new_dynamic(round))
     }
 }
 
+/// Creates the first round of AuxGen for the node `id` out of `all_ids`.
+pub(super) fn make_round1<P, Id, T>(
+    rng: &mut dyn CryptoRngCore,
+    shared_randomness: &[u8],
+    id: &Id,
+    all_ids: &BTreeSet<Id>,
+    target: T,
+) -> Result<Round1<P, Id, T>, LocalError>
+where
+    P: SchemeParams,
+    Id: PartyId,
+    T: AuxGenTarget<P, Id>,
+{
+    if !all_ids.contains(id) {
+        return Err(LocalError::new("The given node IDs must contain this node's ID"));
+    }
+
+    let other_ids = all_ids.clone().without(id);
+
+    let sid = Sid::new::<P, Id>(shared_randomness, all_ids);
+
+    // Paillier secret key $p_i$, $q_i$
+    let paillier_sk = SecretKeyPaillierWire::<P::Paillier>::random(rng);
+    // Paillier public key $N_i$
+    let paillier_pk = paillier_sk.public_key();
+
+    // Ring-Pedersen secret $\lambda$.
+    let rp_secret = RPSecret::random(rng);
+    // Ring-Pedersen parameters ($N$, $s$, $t$) bundled in a single object.
+    let rp_params = RPParams::random_with_secret(rng, &rp_secret);
+
+    let aux = (&sid, id);
+    let psi = PrmProof::<P>::new(rng, &rp_secret, &rp_params, &aux);
+
+    let rid = BitVec::random(rng, P::SECURITY_PARAMETER);
+    let u = BitVec::random(rng, P::SECURITY_PARAMETER);
+
+    // Note: typo in the paper, $V$ hashes in $B_i$ which is not present in the '24 version of the paper.
+    let public_data = PublicData {
+        paillier_pk: paillier_pk.into_precomputed(),
+        rp_params: rp_params.clone(),
+        psi,
+        rid,
+        u,
+    };
+
+    let context = Context {
+        target,
+        paillier_sk: paillier_sk.into_precomputed(),
+        rp_params,
+        my_id: id.clone(),
+        other_ids,
+        sid,
+    };
+
+    Ok(Round1 { context, public_data })
+}
+
 #[derive(Debug)]
-pub(super) struct Context<P: SchemeParams, Id> {
+pub(super) struct Context<P: SchemeParams, Id, T> {
+    target: T,
     paillier_sk: SecretKeyPaillier<P::Paillier>,
     rp_params: RPParams<P::Paillier>,
     pub(super) my_id: Id,
@@ -394,8 +544,8 @@ pub(super) struct Context<P: SchemeParams, Id> {
 }
 
 #[derive(Debug)]
-pub(super) struct Round1<P: SchemeParams, Id: PartyId> {
-    pub(super) context: Context<P, Id>,
+pub(super) struct Round1<P: SchemeParams, Id: PartyId, T> {
+    pub(super) context: Context<P, Id, T>,
     pub(super) public_data: PublicData<P>,
 }
 
@@ -408,8 +558,44 @@ struct Round1Payload {
     cap_v: HashOutput,
 }
 
-impl<P: SchemeParams, Id: PartyId> Round<Id> for Round1<P, Id> {
-    type Protocol = AuxGenProtocol<P, Id>;
+impl<P: SchemeParams, Id: PartyId, T: AuxGenTarget<P, Id>> Round1<P, Id, T> {
+    /// Creates the echo broadcast of this round.
+    pub(super) fn echo_broadcast(&self, format: &BoxedFormat) -> Result<EchoBroadcast, LocalError> {
+        let message = Round1EchoBroadcast {
+            cap_v: self.public_data.hash(&self.context.sid, &self.context.my_id),
+        };
+        EchoBroadcast::new(format, message)
+    }
+
+    /// Processes a message of this round.
+    pub(super) fn receive(
+        &self,
+        format: &BoxedFormat,
+        message: ProtocolMessage,
+    ) -> Result<Payload, ReceiveError<Id, T::Protocol>> {
+        message.normal_broadcast.assert_is_none()?;
+        message.direct_message.assert_is_none()?;
+        let echo_broadcast = message.echo_broadcast.deserialize::<Round1EchoBroadcast>(format)?;
+        let payload = Round1Payload {
+            cap_v: echo_broadcast.cap_v,
+        };
+        Ok(Payload::new(payload))
+    }
+
+    /// Creates the next round once all the messages of this round are received.
+    pub(super) fn next_round(self, payloads: BTreeMap<Id, Payload>) -> Result<Round2<P, Id, T>, LocalError> {
+        let payloads = payloads.downcast_all::<Round1Payload>()?;
+        let cap_vs = payloads.map_values(|payload| payload.cap_v);
+        Ok(Round2 {
+            context: self.context,
+            public_data: self.public_data,
+            cap_vs,
+        })
+    }
+}
+
+impl<P: SchemeParams, Id: PartyId, T: AuxGenResult<P, Id>> Round<Id> for Round1<P, Id, T> {
+    type Protocol = T::Protocol;
 
     fn transition_info(&self) -> TransitionInfo {
         TransitionInfo::new_linear(1)
@@ -424,10 +610,7 @@ impl<P: SchemeParams, Id: PartyId> Round<Id> for Round1<P, Id> {
         _rng: &mut dyn CryptoRngCore,
         format: &BoxedFormat,
     ) -> Result<EchoBroadcast, LocalError> {
-        let message = Round1EchoBroadcast {
-            cap_v: self.public_data.hash(&self.context.sid, &self.context.my_id),
-        };
-        EchoBroadcast::new(format, message)
+        self.echo_broadcast(format)
     }
 
     fn receive_message(
@@ -436,13 +619,7 @@ impl<P: SchemeParams, Id: PartyId> Round<Id> for Round1<P, Id> {
         _from: &Id,
         message: ProtocolMessage,
     ) -> Result<Payload, ReceiveError<Id, Self::Protocol>> {
-        message.normal_broadcast.assert_is_none()?;
-        message.direct_message.assert_is_none()?;
-        let echo_broadcast = message.echo_broadcast.deserialize::<Round1EchoBroadcast>(format)?;
-        let payload = Round1Payload {
-            cap_v: echo_broadcast.cap_v,
-        };
-        Ok(Payload::new(payload))
+        self.receive(format, message)
     }
 
     fn finalize(
@@ -451,20 +628,14 @@ impl<P: SchemeParams, Id: PartyId> Round<Id> for Round1<P, Id> {
         payloads: BTreeMap<Id, Payload>,
         _artifacts: BTreeMap<Id, Artifact>,
     ) -> Result<FinalizeOutcome<Id, Self::Protocol>, LocalError> {
-        let payloads = payloads.downcast_all::<Round1Payload>()?;
-        let cap_vs = payloads.map_values(|payload| payload.cap_v);
-        let next_round = Round2 {
-            context: self.context,
-            public_data: self.public_data,
-            cap_vs,
-        };
+        let next_round = self.next_round(payloads)?;
         Ok(FinalizeOutcome::AnotherRound(BoxedRound::new_dynamic(next_round)))
     }
 }
 
 #[derive(Debug)]
-struct Round2<P: SchemeParams, Id: PartyId> {
-    context: Context<P, Id>,
+pub(super) struct Round2<P: SchemeParams, Id: PartyId, T> {
+    context: Context<P, Id, T>,
     public_data: PublicData<P>,
     cap_vs: BTreeMap<Id, HashOutput>,
 }
@@ -490,22 +661,9 @@ struct Round2Payload<P: SchemeParams> {
     rid: BitVec,
 }
 
-impl<P: SchemeParams, Id: PartyId> Round<Id> for Round2<P, Id> {
-    type Protocol = AuxGenProtocol<P, Id>;
-
-    fn transition_info(&self) -> TransitionInfo {
-        TransitionInfo::new_linear(2)
-    }
-
-    fn communication_info(&self) -> CommunicationInfo<Id> {
-        CommunicationInfo::regular(&self.context.other_ids)
-    }
-
-    fn make_normal_broadcast(
-        &self,
-        _rng: &mut dyn CryptoRngCore,
-        format: &BoxedFormat,
-    ) -> Result<NormalBroadcast, LocalError> {
+impl<P: SchemeParams, Id: PartyId, T: AuxGenTarget<P, Id>> Round2<P, Id, T> {
+    /// Creates the normal broadcast of this round.
+    pub(super) fn normal_broadcast(&self, format: &BoxedFormat) -> Result<NormalBroadcast, LocalError> {
         let message = Round2NormalBroadcast {
             paillier_pk: self.public_data.paillier_pk.clone().into_wire(),
             psi: self.public_data.psi.clone(),
@@ -514,11 +672,8 @@ impl<P: SchemeParams, Id: PartyId> Round<Id> for Round2<P, Id> {
         NormalBroadcast::new(format, message)
     }
 
-    fn make_echo_broadcast(
-        &self,
-        _rng: &mut dyn CryptoRngCore,
-        format: &BoxedFormat,
-    ) -> Result<EchoBroadcast, LocalError> {
+    /// Creates the echo broadcast of this round.
+    pub(super) fn echo_broadcast(&self, format: &BoxedFormat) -> Result<EchoBroadcast, LocalError> {
         let message = Round2EchoBroadcast::<P> {
             rid: self.public_data.rid.clone(),
             rp_params: self.public_data.rp_params.to_wire(),
@@ -526,12 +681,13 @@ impl<P: SchemeParams, Id: PartyId> Round<Id> for Round2<P, Id> {
         EchoBroadcast::new(format, message)
     }
 
-    fn receive_message(
+    /// Processes a message of this round from the node `from`.
+    pub(super) fn receive(
         &self,
         format: &BoxedFormat,
         from: &Id,
         message: ProtocolMessage,
-    ) -> Result<Payload, ReceiveError<Id, Self::Protocol>> {
+    ) -> Result<Payload, ReceiveError<Id, T::Protocol>> {
         message.direct_message.assert_is_none()?;
         let echo_broadcast = message.echo_broadcast.deserialize::<Round2EchoBroadcast<P>>(format)?;
         let normal_broadcast = message
@@ -549,20 +705,24 @@ impl<P: SchemeParams, Id: PartyId> Round<Id> for Round2<P, Id> {
         let cap_v = self.cap_vs.safe_get("other nodes' `V`", from)?;
 
         if &data.hash(&self.context.sid, from) != cap_v {
-            return Err(ReceiveError::protocol(Error::R2HashMismatch.into()));
+            return Err(ReceiveError::protocol(AuxGenError::from(Error::R2HashMismatch).into()));
         }
 
         if data.paillier_pk.modulus().bits_vartime() < <P::Paillier as PaillierParams>::MODULUS_BITS - 2 {
-            return Err(ReceiveError::protocol(Error::R2PaillierModulusTooSmall.into()));
+            return Err(ReceiveError::protocol(
+                AuxGenError::from(Error::R2PaillierModulusTooSmall).into(),
+            ));
         }
 
         if data.rp_params.modulus().bits_vartime() < <P::Paillier as PaillierParams>::MODULUS_BITS - 2 {
-            return Err(ReceiveError::protocol(Error::R2RPModulusTooSmall.into()));
+            return Err(ReceiveError::protocol(
+                AuxGenError::from(Error::R2RPModulusTooSmall).into(),
+            ));
         }
 
         let aux = (&self.context.sid, &from);
         if !data.psi.verify(&data.rp_params, &aux) {
-            return Err(ReceiveError::protocol(Error::R2PrmFailed.into()));
+            return Err(ReceiveError::protocol(AuxGenError::from(Error::R2PrmFailed).into()));
         }
 
         let payload = Round2Payload::<P> {
@@ -574,12 +734,12 @@ impl<P: SchemeParams, Id: PartyId> Round<Id> for Round2<P, Id> {
         Ok(Payload::new(payload))
     }
 
-    fn finalize(
-        self: Box<Self>,
+    /// Creates the next round once all the messages of this round are received.
+    pub(super) fn next_round(
+        self,
         rng: &mut dyn CryptoRngCore,
         payloads: BTreeMap<Id, Payload>,
-        _artifacts: BTreeMap<Id, Artifact>,
-    ) -> Result<FinalizeOutcome<Id, Self::Protocol>, LocalError> {
+    ) -> Result<Round3<P, Id, T>, LocalError> {
         let mut payloads = payloads.downcast_all::<Round2Payload<P>>()?;
 
         let mut rid_combined = self.public_data.rid.clone();
@@ -599,20 +759,65 @@ impl<P: SchemeParams, Id: PartyId> Round<Id> for Round2<P, Id> {
         };
         payloads.insert(self.context.my_id.clone(), my_r2_payload);
 
-        let next_round = Round3 {
+        Ok(Round3 {
             context: self.context,
             r2_payloads: payloads,
             rid_combined,
             psi_prime,
-        };
+        })
+    }
+}
+
+impl<P: SchemeParams, Id: PartyId, T: AuxGenResult<P, Id>> Round<Id> for Round2<P, Id, T> {
+    type Protocol = T::Protocol;
+
+    fn transition_info(&self) -> TransitionInfo {
+        TransitionInfo::new_linear(2)
+    }
+
+    fn communication_info(&self) -> CommunicationInfo<Id> {
+        CommunicationInfo::regular(&self.context.other_ids)
+    }
 
+    fn make_normal_broadcast(
+        &self,
+        _rng: &mut dyn CryptoRngCore,
+        format: &BoxedFormat,
+    ) -> Result<NormalBroadcast, LocalError> {
+        self.normal_broadcast(format)
+    }
+
+    fn make_echo_broadcast(
+        &self,
+        _rng: &mut dyn CryptoRngCore,
+        format: &BoxedFormat,
+    ) -> Result<EchoBroadcast, LocalError> {
+        self.echo_broadcast(format)
+    }
+
+    fn receive_message(
+        &self,
+        format: &BoxedFormat,
+        from: &Id,
+        message: ProtocolMessage,
+    ) -> Result<Payload, ReceiveError<Id, Self::Protocol>> {
+        self.receive(format, from, message)
+    }
+
+    fn finalize(
+        self: Box<Self>,
+        rng: &mut dyn CryptoRngCore,
+        payloads: BTreeMap<Id, Payload>,
+        _artifacts: BTreeMap<Id, Artifact>,
+    ) -> Result<FinalizeOutcome<Id, Self::Protocol>, LocalError> {
+        let next_round = self.next_round(rng, payloads)?;
         Ok(FinalizeOutcome::AnotherRound(BoxedRound::new_dynamic(next_round)))
     }
 }
 
 #[derive(Debug)]
-struct Round3<P: SchemeParams, Id> {
-    context: Context<P, Id>,
+pub(super) struct Round3<P: SchemeParams, Id, T> {
+    context: Context<P, Id, T>,
     rid_combined: BitVec,
     r2_payloads: BTreeMap<Id, Round2Payload<P>>,
     psi_prime: ModProof<P>,
@@ -630,29 +835,17 @@ pub(super) struct Round3DirectMessage<P: SchemeParams> {
     pub(super) psi: FacProof<P>,
 }
 
-impl<P: SchemeParams, Id: PartyId> Round<Id> for Round3<P, Id> {
-    type Protocol = AuxGenProtocol<P, Id>;
-
-    fn transition_info(&self) -> TransitionInfo {
-        TransitionInfo::new_linear_terminating(3)
-    }
-
-    fn communication_info(&self) -> CommunicationInfo<Id> {
-        CommunicationInfo::regular(&self.context.other_ids)
-    }
-
-    fn make_normal_broadcast(
-        &self,
-        _rng: &mut dyn CryptoRngCore,
-        format: &BoxedFormat,
-    ) -> Result<NormalBroadcast, LocalError> {
+impl<P: SchemeParams, Id: PartyId, T: AuxGenTarget<P, Id>> Round3<P, Id, T> {
+    /// Creates the normal broadcast of this round.
+    pub(super) fn normal_broadcast(&self, format: &BoxedFormat) -> Result<NormalBroadcast, LocalError> {
         let message = Round3NormalBroadcast {
             psi_prime: self.psi_prime.clone(),
         };
         NormalBroadcast::new(format, message)
     }
 
-    fn make_direct_message(
+    /// Creates the direct message of this round for the node `destination`.
+    pub(super) fn direct_message(
         &self,
         rng: &mut dyn CryptoRngCore,
         format: &BoxedFormat,
@@ -670,12 +863,13 @@ impl<P: SchemeParams, Id: PartyId> Round<Id> for Round3<P, Id> {
         Ok((dm, None))
     }
 
-    fn receive_message(
+    /// Processes a message of this round from the node `from`.
+    pub(super) fn receive(
         &self,
         format: &BoxedFormat,
         from: &Id,
         message: ProtocolMessage,
-    ) -> Result<Payload, ReceiveError<Id, Self::Protocol>> {
+    ) -> Result<Payload, ReceiveError<Id, T::Protocol>> {
         message.echo_broadcast.assert_is_none()?;
         let normal_broadcast = message
             .normal_broadcast
@@ -688,7 +882,7 @@ impl<P: SchemeParams, Id: PartyId> Round<Id> for Round3<P, Id> {
 
         let aux = (&self.context.sid, from, &self.rid_combined);
         if !normal_broadcast.psi_prime.verify(&r2_payload.paillier_pk, &aux) {
-            return Err(ReceiveError::protocol(Error::R3ModFailed.into()));
+            return Err(ReceiveError::protocol(AuxGenError::from(Error::R3ModFailed).into()));
         }
 
         if !direct_message
@@ -696,9 +890,9 @@ impl<P: SchemeParams, Id: PartyId> Round<Id> for Round3<P, Id> {
             .verify(&r2_payload.paillier_pk, &self.context.rp_params, &aux)
         {
             return Err(ReceiveError::protocol(
-                Error::R3FacFailed {
+                AuxGenError::from(Error::R3FacFailed {
                     reported_by: my_id.clone(),
-                }
+                })
                 .into(),
             ));
         }
@@ -706,30 +900,70 @@ impl<P: SchemeParams, Id: PartyId> Round<Id> for Round3<P, Id> {
         Ok(Payload::empty())
     }
 
-    fn finalize(
-        self: Box<Self>,
-        _rng: &mut dyn CryptoRngCore,
-        _payloads: BTreeMap<Id, Payload>,
-        _artifacts: BTreeMap<Id, Artifact>,
-    ) -> Result<FinalizeOutcome<Id, Self::Protocol>, LocalError> {
-        let my_id = &self.context.my_id;
-
-        let public_aux = self.r2_payloads.map_values(|payload| PublicAuxInfo {
-            paillier_pk: payload.paillier_pk.into_wire(),
+    /// Creates the auxiliary info once all the messages of this round are received.
+    pub(super) fn aux_info(&self) -> AuxInfo<P, Id> {
+        let public_aux = self.r2_payloads.map_values_ref(|payload| PublicAuxInfo {
+            paillier_pk: payload.paillier_pk.clone().into_wire(),
             rp_params: payload.rp_params.to_wire(),
         });
 
         let secret_aux = SecretAuxInfo {
-            paillier_sk: self.context.paillier_sk.into_wire(),
+            paillier_sk: self.context.paillier_sk.clone().into_wire(),
         };
 
-        let aux_info = AuxInfo {
-            owner: my_id.clone(),
+        AuxInfo {
+            owner: self.context.my_id.clone(),
             secret: secret_aux,
             public: PublicAuxInfos(public_aux.into()),
-        };
+        }
+    }
+}
+
+impl<P: SchemeParams, Id: PartyId, T: AuxGenResult<P, Id>> Round<Id> for Round3<P, Id, T> {
+    type Protocol = T::Protocol;
 
-        Ok(FinalizeOutcome::Result(aux_info))
+    fn transition_info(&self) -> TransitionInfo {
+        TransitionInfo::new_linear_terminating(3)
+    }
+
+    fn communication_info(&self) -> CommunicationInfo<Id> {
+        CommunicationInfo::regular(&self.context.other_ids)
+    }
+
+    fn make_normal_broadcast(
+        &self,
+        _rng: &mut dyn CryptoRngCore,
+        format: &BoxedFormat,
+    ) -> Result<NormalBroadcast, LocalError> {
+        self.normal_broadcast(format)
+    }
+
+    fn make_direct_message(
+        &self,
+        rng: &mut dyn CryptoRngCore,
+        format: &BoxedFormat,
+        destination: &Id,
+    ) -> Result<(DirectMessage, Option<Artifact>), LocalError> {
+        self.direct_message(rng, format, destination)
+    }
+
+    fn receive_message(
+        &self,
+        format: &BoxedFormat,
+        from: &Id,
+        message: ProtocolMessage,
+    ) -> Result<Payload, ReceiveError<Id, Self::Protocol>> {
+        self.receive(format, from, message)
+    }
+
+    fn finalize(
+        self: Box<Self>,
+        _rng: &mut dyn CryptoRngCore,
+        _payloads: BTreeMap<Id, Payload>,
+        _artifacts: BTreeMap<Id, Artifact>,
+    ) -> Result<FinalizeOutcome<Id, Self::Protocol>, LocalError> {
+        let aux_info = self.aux_info();
+        Ok(FinalizeOutcome::Result(self.context.target.aux_gen_result(aux_info)))
     }
 }
 
@@ -0,0 +1,624 @@
+//! Common machinery for executing several independent instances of the presigning rounds
+//! (and, optionally, the signing round) in a single session.
+//!
+//! The messages of all instances in the regular rounds (1-4) are sent together,
+//! so the number of communication rounds does not depend on the number of instances.
+//! If any of the instances requires an error round (Rounds 5 or 6), only the first such instance proceeds to it,
+//! and the messages of the error round are sent as is.
+//!
+//! Whether an instance requires an error round depends on the values the parties send in normal broadcasts
+//! in Round 3, so a malicious party can make the lawful nodes proceed to the error round with different instances.
+//! The error round messages identify the instance they were created for, so in that case the lawful nodes
+//! report an unprovable error instead of producing evidence against each other.
+
+use alloc::{
+    boxed::Box,
+    collections::{BTreeMap, BTreeSet},
+    format,
+    sync::Arc,
+    vec::Vec,
+};
+use core::fmt::{self, Display};
+
+use manul::protocol::{
+    Artifact, BoxedFormat, BoxedRound, CommunicationInfo, DirectMessage, EchoBroadcast, FinalizeOutcome, LocalError,
+    MessageValidationError, NormalBroadcast, PartyId, Payload, Protocol, ProtocolMessage, ProtocolMessagePart,
+    ProtocolValidationError, ReceiveError, RequiredMessageParts, RequiredMessages, Round, RoundId, TransitionInfo,
+};
+use rand_core::CryptoRngCore;
+use serde::{Deserialize, Serialize};
+
+use super::interactive_signing::{make_round1, Error, PrehashedMessage, PresigningTarget, Round1, Round2, Round3};
+use crate::{
+    entities::{KeyShare, PrecomputedAuxInfo, PublicAuxInfos, PublicKeyShares},
+    params::SchemeParams,
+    tools::protocol_shortcuts::{verify_that, DowncastMap, Without},
+};
+
+/// A [`PresigningTarget`] for a single instance in a batch.
+pub(super) trait BatchTarget<P: SchemeParams, Id: PartyId>:
+    PresigningTarget<P, Id, Protocol: Protocol<Id, ProtocolError: From<BatchError<Id>>>>
+{
+    /// Combines the outcomes of finalizing Round 3 for every instance.
+    fn combine_round3_outcomes(
+        other_ids: BTreeSet<Id>,
+        outcomes: Vec<FinalizeOutcome<Id, Self::Protocol>>,
+    ) -> Result<FinalizeOutcome<Id, Self::Protocol>, LocalError>;
+}
+
+/// A round whose instances can be batched together.
+pub(super) trait BatchedRound<Id: PartyId>:
+    Round<Id, Protocol: Protocol<Id, ProtocolError: From<BatchError<Id>>>>
+{
+    /// Returns the transition info for the batched round.
+    fn batch_transition_info() -> TransitionInfo;
+
+    /// Combines the outcomes of finalizing every instance.
+    fn combine_outcomes(
+        other_ids: BTreeSet<Id>,
+        outcomes: Vec<FinalizeOutcome<Id, Self::Protocol>>,
+    ) -> Result<FinalizeOutcome<Id, Self::Protocol>, LocalError>;
+}
+
+/// Creates the batched Round 1 with an instance for each of the given targets.
+pub(super) fn make_batch_round1<P, Id, T>(
+    rng: &mut dyn CryptoRngCore,
+    shared_randomness: &[u8],
+    id: &Id,
+    key_share: Arc<KeyShare<P, Id>>,
+    aux_info: PrecomputedAuxInfo<P, Id>,
+    targets: Vec<T>,
+) -> Result<BoxedRound<Id, T::Protocol>, LocalError>
+where
+    P: SchemeParams,
+    Id: PartyId,
+    T: BatchTarget<P, Id>,
+{
+    let other_ids = key_share
+        .public_shares()
+        .keys()
+        .cloned()
+        .collect::<BTreeSet<_>>()
+        .without(id);
+
+    let rounds = targets
+        .into_iter()
+        .enumerate()
+        .map(|(index, target)| {
+            make_round1(
+                rng,
+                &instance_shared_randomness(shared_randomness, index),
+                id,
+                key_share.clone(),
+                aux_info.clone(),
+                target,
+            )
+        })
+        .collect::<Result<Vec<_>, _>>()?;
+
+    Ok(new_batch_round(other_ids, rounds))
+}
+
+fn new_batch_round<Id, R>(other_ids: BTreeSet<Id>, rounds: Vec<R>) -> BoxedRound<Id, R::Protocol>
+where
+    Id: PartyId,
+    R: BatchedRound<Id>,
+{
+    BoxedRound::new_dynamic(BatchRound { other_ids, rounds })
+}
+
+/// Downcasts the next rounds of every instance and batches them together.
+///
+/// If one of the instances proceeds to a round of a different type (that is, an error round),
+/// the first such round is returned instead.
+pub(super) fn next_batch<Id, R>(
+    other_ids: BTreeSet<Id>,
+    outcomes: Vec<FinalizeOutcome<Id, R::Protocol>>,
+) -> Result<FinalizeOutcome<Id, R::Protocol>, LocalError>
+where
+    Id: PartyId,
+    R: BatchedRound<Id>,
+{
+    let mut rounds = Vec::with_capacity(outcomes.len());
+    for outcome in outcomes {
+        match outcome {
+            FinalizeOutcome::AnotherRound(round) => {
+                if round.downcast_ref::<R>().is_ok() {
+                    rounds.push(round.downcast::<R>()?);
+                } else {
+                    // Unless a malicious party sent inconsistent values to different nodes,
+                    // all the lawful nodes will choose the same instance to proceed to the error round with.
+                    return Ok(FinalizeOutcome::AnotherRound(round));
+                }
+            }
+            FinalizeOutcome::Result(_) => return Err(LocalError::new("Expected the next round, got a result")),
+        }
+    }
+    Ok(FinalizeOutcome::AnotherRound(new_batch_round(other_ids, rounds)))
+}
+
+/// Concatenates the results of every instance.
+///
+/// If one of the instances proceeds to another round (that is, an error round),
+/// the first such round is returned instead.
+pub(super) fn concat_results<Id, Pr, T>(outcomes: Vec<FinalizeOutcome<Id, Pr>>) -> FinalizeOutcome<Id, Pr>
+where
+    Id: PartyId,
+    Pr: Protocol<Id, Result = Vec<T>>,
+{
+    let mut results = Vec::with_capacity(outcomes.len());
+    for outcome in outcomes {
+        match outcome {
+            FinalizeOutcome::Result(result) => results.extend(result),
+            // Unless a malicious party sent inconsistent values to different nodes,
+            // all the lawful nodes will choose the same instance to proceed to the error round with.
+            FinalizeOutcome::AnotherRound(round) => return FinalizeOutcome::AnotherRound(round),
+        }
+    }
+    FinalizeOutcome::Result(results)
+}
+
+impl<P, Id, T> BatchedRound<Id> for Round1<P, Id, T>
+where
+    P: SchemeParams,
+    Id: PartyId,
+    T: BatchTarget<P, Id>,
+{
+    fn batch_transition_info() -> TransitionInfo {
+        TransitionInfo::new_linear(1)
+    }
+
+    fn combine_outcomes(
+        other_ids: BTreeSet<Id>,
+        outcomes: Vec<FinalizeOutcome<Id, Self::Protocol>>,
+    ) -> Result<FinalizeOutcome<Id, Self::Protocol>, LocalError> {
+        next_batch::<Id, Round2<P, Id, T>>(other_ids, outcomes)
+    }
+}
+
+impl<P, Id, T> BatchedRound<Id> for Round2<P, Id, T>
+where
+    P: SchemeParams,
+    Id: PartyId,
+    T: BatchTarget<P, Id>,
+{
+    fn batch_transition_info() -> TransitionInfo {
+        TransitionInfo::new_linear(2)
+    }
+
+    fn combine_outcomes(
+        other_ids: BTreeSet<Id>,
+        outcomes: Vec<FinalizeOutcome<Id, Self::Protocol>>,
+    ) -> Result<FinalizeOutcome<Id, Self::Protocol>, LocalError> {
+        next_batch::<Id, Round3<P, Id, T>>(other_ids, outcomes)
+    }
+}
+
+impl<P, Id, T> BatchedRound<Id> for Round3<P, Id, T>
+where
+    P: SchemeParams,
+    Id: PartyId,
+    T: BatchTarget<P, Id>,
+{
+    fn batch_transition_info() -> TransitionInfo {
+        T::round3_transition_info()
+    }
+
+    fn combine_outcomes(
+        other_ids: BTreeSet<Id>,
+        outcomes: Vec<FinalizeOutcome<Id, Self::Protocol>>,
+    ) -> Result<FinalizeOutcome<Id, Self::Protocol>, LocalError> {
+        T::combine_round3_outcomes(other_ids, outcomes)
+    }
+}
+
+#[derive(Debug)]
+struct BatchRound<Id, R> {
+    other_ids: BTreeSet<Id>,
+    rounds: Vec<R>,
+}
+
+impl<Id, R> Round<Id> for BatchRound<Id, R>
+where
+    Id: PartyId,
+    R: BatchedRound<Id>,
+{
+    type Protocol = R::Protocol;
+
+    fn transition_info(&self) -> TransitionInfo {
+        R::batch_transition_info()
+    }
+
+    fn communication_info(&self) -> CommunicationInfo<Id> {
+        CommunicationInfo::regular(&self.other_ids)
+    }
+
+    fn make_direct_message(
+        &self,
+        rng: &mut dyn CryptoRngCore,
+        format: &BoxedFormat,
+        destination: &Id,
+    ) -> Result<(DirectMessage, Option<Artifact>), LocalError> {
+        let mut messages = Vec::with_capacity(self.rounds.len());
+        let mut artifacts = Vec::with_capacity(self.rounds.len());
+        for round in self.rounds.iter() {
+            let (message, artifact) = round.make_direct_message(rng, format, destination)?;
+            messages.push(message);
+            artifacts.push(artifact);
+        }
+
+        let artifact = if artifacts.iter().all(Option::is_none) {
+            None
+        } else {
+            Some(Artifact::new(artifacts))
+        };
+
+        Ok((join_parts(format, messages)?, artifact))
+    }
+
+    fn make_echo_broadcast(
+        &self,
+        rng: &mut dyn CryptoRngCore,
+        format: &BoxedFormat,
+    ) -> Result<EchoBroadcast, LocalError> {
+        let messages = self
+            .rounds
+            .iter()
+            .map(|round| round.make_echo_broadcast(rng, format))
+            .collect::<Result<Vec<_>, _>>()?;
+        join_parts(format, messages)
+    }
+
+    fn make_normal_broadcast(
+        &self,
+        rng: &mut dyn CryptoRngCore,
+        format: &BoxedFormat,
+    ) -> Result<NormalBroadcast, LocalError> {
+        let messages = self
+            .rounds
+            .iter()
+            .map(|round| round.make_normal_broadcast(rng, format))
+            .collect::<Result<Vec<_>, _>>()?;
+        join_parts(format, messages)
+    }
+
+    fn receive_message(
+        &self,
+        format: &BoxedFormat,
+        from: &Id,
+        message: ProtocolMessage,
+    ) -> Result<Payload, ReceiveError<Id, Self::Protocol>> {
+        let batch_size = self.rounds.len();
+
+        let direct_messages = split_part(format, &message.direct_message, batch_size)?
+            .ok_or_else(|| ReceiveError::protocol(BatchError::WrongBatchSizeDirectMessage.into()))?;
+        let echo_broadcasts = split_part(format, &message.echo_broadcast, batch_size)?
+            .ok_or_else(|| ReceiveError::protocol(BatchError::WrongBatchSizeEchoBroadcast.into()))?;
+        let normal_broadcasts = split_part(format, &message.normal_broadcast, batch_size)?
+            .ok_or_else(|| ReceiveError::protocol(BatchError::WrongBatchSizeNormalBroadcast.into()))?;
+
+        let payloads = self
+            .rounds
+            .iter()
+            .zip(direct_messages)
+            .zip(echo_broadcasts)
+            .zip(normal_broadcasts)
+            .map(|(((round, direct_message), echo_broadcast), normal_broadcast)| {
+                let message = ProtocolMessage {
+                    direct_message,
+                    echo_broadcast,
+                    normal_broadcast,
+                };
+                round.receive_message(format, from, message)
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+
+        Ok(Payload::new(payloads))
+    }
+
+    fn finalize(
+        self: Box<Self>,
+        rng: &mut dyn CryptoRngCore,
+        payloads: BTreeMap<Id, Payload>,
+        artifacts: BTreeMap<Id, Artifact>,
+    ) -> Result<FinalizeOutcome<Id, Self::Protocol>, LocalError> {
+        let batch_size = self.rounds.len();
+
+        let mut instance_payloads = (0..batch_size).map(|_| BTreeMap::new()).collect::<Vec<_>>();
+        for (id, payloads) in payloads.downcast_all::<Vec<Payload>>()? {
+            if payloads.len() != batch_size {
+                return Err(LocalError::new("Wrong number of payloads in a batch"));
+            }
+            for (instance, payload) in instance_payloads.iter_mut().zip(payloads) {
+                instance.insert(id.clone(), payload);
+            }
+        }
+
+        let mut instance_artifacts = (0..batch_size).map(|_| BTreeMap::new()).collect::<Vec<_>>();
+        for (id, artifacts) in artifacts.downcast_all::<Vec<Option<Artifact>>>()? {
+            if artifacts.len() != batch_size {
+                return Err(LocalError::new("Wrong number of artifacts in a batch"));
+            }
+            for (instance, artifact) in instance_artifacts.iter_mut().zip(artifacts) {
+                if let Some(artifact) = artifact {
+                    instance.insert(id.clone(), artifact);
+                }
+            }
+        }
+
+        let mut outcomes = Vec::with_capacity(batch_size);
+        for ((round, payloads), artifacts) in self.rounds.into_iter().zip(instance_payloads).zip(instance_artifacts) {
+            outcomes.push(Box::new(round).finalize(rng, payloads, artifacts)?);
+        }
+
+        R::combine_outcomes(self.other_ids, outcomes)
+    }
+}
+
+/// Possible verifiable errors of a batched session.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub(super) enum BatchError<Id> {
+    WrongBatchSizeDirectMessage,
+    WrongBatchSizeEchoBroadcast,
+    WrongBatchSizeNormalBroadcast,
+    Instance {
+        /// The index of the instance in the batch.
+        index: usize,
+        error: Error<Id>,
+    },
+}
+
+impl<Id> Display for BatchError<Id> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> Result<(), fmt::Error> {
+        match self {
+            Self::WrongBatchSizeDirectMessage => write!(f, "Wrong number of instances in the direct message."),
+            Self::WrongBatchSizeEchoBroadcast => write!(f, "Wrong number of instances in the echo broadcast."),
+            Self::WrongBatchSizeNormalBroadcast => write!(f, "Wrong number of instances in the normal broadcast."),
+            Self::Instance { index, error } => write!(f, "Instance {}: {}", index, error.description()),
+        }
+    }
+}
+
+impl<Id: PartyId> BatchError<Id> {
+    pub(super) fn required_messages(&self) -> RequiredMessages {
+        match self {
+            Self::WrongBatchSizeDirectMessage => {
+                RequiredMessages::new(RequiredMessageParts::direct_message(), None, None)
+            }
+            Self::WrongBatchSizeEchoBroadcast => {
+                RequiredMessages::new(RequiredMessageParts::echo_broadcast(), None, None)
+            }
+            Self::WrongBatchSizeNormalBroadcast => {
+                RequiredMessages::new(RequiredMessageParts::normal_broadcast(), None, None)
+            }
+            Self::Instance { error, .. } => error.required_messages(),
+        }
+    }
+
+    /// Verifies the error given the public data of the parties, the number of instances in the batch,
+    /// and, if the instances included signing, the messages signed in each instance.
+    #[allow(clippy::too_many_arguments)]
+    pub(super) fn verify_messages_constitute_error<P: SchemeParams>(
+        &self,
+        format: &BoxedFormat,
+        guilty_party: &Id,
+        shared_randomness: &[u8],
+        shares: &PublicKeyShares<P, Id>,
+        aux: &PublicAuxInfos<P, Id>,
+        batch_size: usize,
+        signed_messages: Option<&[PrehashedMessage<P::Curve>]>,
+        message: ProtocolMessage,
+        previous_messages: BTreeMap<RoundId, ProtocolMessage>,
+        combined_echos: BTreeMap<RoundId, BTreeMap<Id, EchoBroadcast>>,
+    ) -> Result<(), ProtocolValidationError> {
+        match self {
+            Self::WrongBatchSizeDirectMessage => {
+                verify_that(split_part(format, &message.direct_message, batch_size)?.is_none())
+            }
+            Self::WrongBatchSizeEchoBroadcast => {
+                verify_that(split_part(format, &message.echo_broadcast, batch_size)?.is_none())
+            }
+            Self::WrongBatchSizeNormalBroadcast => {
+                verify_that(split_part(format, &message.normal_broadcast, batch_size)?.is_none())
+            }
+            Self::Instance { index, error } => {
+                let index = *index;
+                if index >= batch_size {
+                    return Err(ProtocolValidationError::InvalidEvidence(format!(
+                        "Instance {index} is out of bounds"
+                    )));
+                }
+
+                let signed_message = signed_messages
+                    .map(|messages| {
+                        messages.get(index).ok_or_else(|| {
+                            ProtocolValidationError::InvalidEvidence(format!(
+                                "The signed message for instance {index} is not present"
+                            ))
+                        })
+                    })
+                    .transpose()?;
+
+                let message = if is_batched_round(&RoundId::new(error.round_num())) {
+                    instance_message(format, message, index)?
+                } else {
+                    message
+                };
+
+                let previous_messages = previous_messages
+                    .into_iter()
+                    .map(|(round_id, message)| {
+                        let message = if is_batched_round(&round_id) {
+                            instance_message(format, message, index)?
+                        } else {
+                            message
+                        };
+                        Ok((round_id, message))
+                    })
+                    .collect::<Result<BTreeMap<_, _>, ProtocolValidationError>>()?;
+
+                let combined_echos = combined_echos
+                    .into_iter()
+                    .map(|(round_id, echos)| {
+                        let echos = if is_batched_round(&round_id) {
+                            echos
+                                .into_iter()
+                                .map(|(id, echo)| Ok((id, instance_part(format, &echo, index)?)))
+                                .collect::<Result<BTreeMap<_, _>, ProtocolValidationError>>()?
+                        } else {
+                            echos
+                        };
+                        Ok((round_id, echos))
+                    })
+                    .collect::<Result<BTreeMap<_, _>, ProtocolValidationError>>()?;
+
+                error.verify_messages_constitute_error::<P>(
+                    format,
+                    guilty_party,
+                    &instance_shared_randomness(shared_randomness, index),
+                    shares,
+                    aux,
+                    signed_message,
+                    message,
+                    previous_messages,
+                    combined_echos,
+                )
+            }
+        }
+    }
+}
+
+/// Derives the shared randomness for a single instance in the batch,
+/// so that the instances have different EPIDs.
+fn instance_shared_randomness(shared_randomness: &[u8], index: usize) -> Vec<u8> {
+    let mut randomness = shared_randomness.to_vec();
+    randomness.extend((index as u64).to_be_bytes());
+    randomness
+}
+
+/// Returns `true` if the messages of the round contain the messages for every instance in the batch.
+fn is_batched_round(round_id: &RoundId) -> bool {
+    round_id == &1 || round_id == &2 || round_id == &3 || round_id == &4
+}
+
+/// Joins the message parts of all instances into a single message part.
+fn join_parts<T>(format: &BoxedFormat, parts: Vec<T>) -> Result<T, LocalError>
+where
+    T: ProtocolMessagePart + Serialize + 'static,
+{
+    if parts.iter().all(|part| part.is_none()) {
+        Ok(T::none())
+    } else {
+        T::new(format, parts)
+    }
+}
+
+/// Splits a batched message part into the parts for each instance.
+///
+/// Returns `None` if the number of parts does not match the batch size.
+fn split_part<T>(format: &BoxedFormat, part: &T, batch_size: usize) -> Result<Option<Vec<T>>, T::Error>
+where
+    T: ProtocolMessagePart + for<'de> Deserialize<'de>,
+{
+    if part.is_none() {
+        return Ok(Some((0..batch_size).map(|_| T::none()).collect()));
+    }
+    let parts = part.deserialize::<Vec<T>>(format)?;
+    Ok((parts.len() == batch_size).then_some(parts))
+}
+
+/// Extracts the part of the given instance from a batched message part.
+fn instance_part<T>(format: &BoxedFormat, part: &T, index: usize) -> Result<T, ProtocolValidationError>
+where
+    T: ProtocolMessagePart + for<'de> Deserialize<'de>,
+    ProtocolValidationError: From<T::Error>,
+{
+    if part.is_none() {
+        return Ok(T::none());
+    }
+    part.deserialize::<Vec<T>>(format)?
+        .into_iter()
+        .nth(index)
+        .ok_or_else(|| ProtocolValidationError::InvalidEvidence(format!("Instance {index} is not present")))
+}
+
+/// Extracts the message of the given instance from a batched message.
+fn instance_message(
+    format: &BoxedFormat,
+    message: ProtocolMessage,
+    index: usize,
+) -> Result<ProtocolMessage, ProtocolValidationError> {
+    Ok(ProtocolMessage {
+        direct_message: instance_part(format, &message.direct_message, index)?,
+        echo_broadcast: instance_part(format, &message.echo_broadcast, index)?,
+        normal_broadcast: instance_part(format, &message.normal_broadcast, index)?,
+    })
+}
+
+/// A batched message part is invalid if it cannot be deserialized,
+/// or if at least one of the instance parts is invalid according to `verify_part`.
+///
+/// The number of instance parts is not compared with the batch size, since the batch size is a part
+/// of the associated data which is not available here. It does not affect the validity of the evidence:
+/// a lawful node checks the number of parts before the parts themselves and reports a mismatch
+/// as a [`BatchError`] (which is verified against the batch size), and a message containing an invalid instance part
+/// could not have been created by a lawful node whatever the number of parts is.
+fn verify_batched_part_is_invalid<T>(
+    format: &BoxedFormat,
+    round_id: &RoundId,
+    part: &T,
+    verify_part: impl Fn(&T) -> Result<(), MessageValidationError>,
+) -> Result<(), MessageValidationError>
+where
+    T: ProtocolMessagePart + for<'de> Deserialize<'de>,
+{
+    if !is_batched_round(round_id) || part.is_none() {
+        return verify_part(part);
+    }
+    let Ok(parts) = part.deserialize::<Vec<T>>(format) else {
+        return Ok(());
+    };
+    if parts.iter().any(|part| verify_part(part).is_ok()) {
+        Ok(())
+    } else {
+        Err(MessageValidationError::InvalidEvidence(
+            "All the instance message parts are valid".into(),
+        ))
+    }
+}
+
+/// Verifies that a direct message of a batched session is invalid
+/// using the checks of the protocol `Pr` that the instances belong to.
+pub(super) fn verify_batched_direct_message_is_invalid<Id, Pr: Protocol<Id>>(
+    format: &BoxedFormat,
+    round_id: &RoundId,
+    message: &DirectMessage,
+) -> Result<(), MessageValidationError> {
+    verify_batched_part_is_invalid(format, round_id, message, |part| {
+        Pr::verify_direct_message_is_invalid(format, round_id, part)
+    })
+}
+
+/// Verifies that an echo broadcast of a batched session is invalid
+/// using the checks of the protocol `Pr` that the instances belong to.
+pub(super) fn verify_batched_echo_broadcast_is_invalid<Id, Pr: Protocol<Id>>(
+    format: &BoxedFormat,
+    round_id: &RoundId,
+    message: &EchoBroadcast,
+) -> Result<(), MessageValidationError> {
+    verify_batched_part_is_invalid(format, round_id, message, |part| {
+        Pr::verify_echo_broadcast_is_invalid(format, round_id, part)
+    })
+}
+
+/// Verifies that a normal broadcast of a batched session is invalid
+/// using the checks of the protocol `Pr` that the instances belong to.
+pub(super) fn verify_batched_normal_broadcast_is_invalid<Id, Pr: Protocol<Id>>(
+    format: &BoxedFormat,
+    round_id: &RoundId,
+    message: &NormalBroadcast,
+) -> Result<(), MessageValidationError> {
+    verify_batched_part_is_invalid(format, round_id, message, |part| {
+        Pr::verify_normal_broadcast_is_invalid(format, round_id, part)
+    })
+}
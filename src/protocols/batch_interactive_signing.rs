@@ -0,0 +1,357 @@
+//! Batch Interactive Signing: several independent instances of the combined ECDSA Pre-Signing and Signing protocols
+//! (Fig. 8 and 10), each signing its own message, executed in a single session.
+//!
+//! The messages of all instances in Rounds 1-4 are sent together, so the number of communication rounds
+//! does not depend on the number of signed messages.
+//! If any of the instances requires an error round (Rounds 5 or 6), only the first such instance proceeds to it.
+
+use alloc::{
+    collections::{BTreeMap, BTreeSet},
+    sync::Arc,
+    vec,
+    vec::Vec,
+};
+use core::{
+    fmt::{self, Debug, Display},
+    marker::PhantomData,
+};
+
+use manul::protocol::{
+    BoxedFormat, BoxedRound, DirectMessage, EchoBroadcast, EntryPoint, FinalizeOutcome, LocalError,
+    MessageValidationError, NormalBroadcast, PartyId, Protocol, ProtocolError, ProtocolMessage,
+    ProtocolValidationError, RequiredMessages, RoundId, TransitionInfo,
+};
+use rand_core::CryptoRngCore;
+use serde::{Deserialize, Serialize};
+
+use super::{
+    batch::{
+        concat_results, make_batch_round1, next_batch, verify_batched_direct_message_is_invalid,
+        verify_batched_echo_broadcast_is_invalid, verify_batched_normal_broadcast_is_invalid, BatchError, BatchTarget,
+        BatchedRound,
+    },
+    interactive_signing::{
+        check_key_share_and_aux_info, check_public_shares_and_aux_infos, make_round4, Context, Error,
+        InteractiveSigningProtocol, PrehashedMessage, Presignature, PresigningTarget, Round4, SigningTarget,
+    },
+};
+use crate::{
+    curve::{RecoverableSignature, Scalar},
+    entities::{KeyShare, PrecomputedAuxInfo, PublicAuxInfos, PublicKeyShares},
+    params::SchemeParams,
+};
+
+/// A protocol for signing several messages in a single session.
+///
+/// The result is a vector of signatures, in the same order as the messages.
+#[derive(Debug, Clone, Copy)]
+pub struct BatchInteractiveSigningProtocol<P: SchemeParams, Id: Debug>(PhantomData<(P, Id)>);
+
+impl<P: SchemeParams, Id: PartyId> Protocol<Id> for BatchInteractiveSigningProtocol<P, Id> {
+    type Result = Vec<RecoverableSignature<P>>;
+    type ProtocolError = BatchInteractiveSigningError<P, Id>;
+
+    fn verify_direct_message_is_invalid(
+        format: &BoxedFormat,
+        round_id: &RoundId,
+        message: &DirectMessage,
+    ) -> Result<(), MessageValidationError> {
+        verify_batched_direct_message_is_invalid::<Id, InteractiveSigningProtocol<P, Id>>(format, round_id, message)
+    }
+
+    fn verify_echo_broadcast_is_invalid(
+        format: &BoxedFormat,
+        round_id: &RoundId,
+        message: &EchoBroadcast,
+    ) -> Result<(), MessageValidationError> {
+        verify_batched_echo_broadcast_is_invalid::<Id, InteractiveSigningProtocol<P, Id>>(format, round_id, message)
+    }
+
+    fn verify_normal_broadcast_is_invalid(
+        format: &BoxedFormat,
+        round_id: &RoundId,
+        message: &NormalBroadcast,
+    ) -> Result<(), MessageValidationError> {
+        verify_batched_normal_broadcast_is_invalid::<Id, InteractiveSigningProtocol<P, Id>>(format, round_id, message)
+    }
+}
+
+/// Possible verifiable errors of the BatchInteractiveSigning protocol.
+///
+/// The errors of a single signing instance carry the index of the corresponding message.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BatchInteractiveSigningError<P, Id> {
+    error: BatchError<Id>,
+    phantom: PhantomData<P>,
+}
+
+impl<P, Id> Display for BatchInteractiveSigningError<P, Id> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> Result<(), fmt::Error> {
+        write!(f, "{}", self.error)
+    }
+}
+
+impl<P, Id> From<BatchError<Id>> for BatchInteractiveSigningError<P, Id> {
+    fn from(source: BatchError<Id>) -> Self {
+        Self {
+            error: source,
+            phantom: PhantomData,
+        }
+    }
+}
+
+/// Associated data for BatchInteractiveSigning protocol.
+#[derive(Debug, Clone)]
+pub struct BatchInteractiveSigningAssociatedData<P: SchemeParams, Id: PartyId> {
+    /// Public shares of all participating nodes.
+    pub shares: PublicKeyShares<P, Id>,
+    /// Auxiliary data of all participating nodes.
+    pub aux: PublicAuxInfos<P, Id>,
+    /// The messages to be signed.
+    pub messages: Vec<PrehashedMessage<P::Curve>>,
+}
+
+impl<P: SchemeParams, Id: PartyId> BatchInteractiveSigningAssociatedData<P, Id> {
+    /// Creates the associated data for evidence verification of BatchInteractiveSigning.
+    pub fn new(
+        messages: Vec<PrehashedMessage<P::Curve>>,
+        public_key_shares: PublicKeyShares<P, Id>,
+        public_aux_infos: PublicAuxInfos<P, Id>,
+    ) -> Result<Self, LocalError> {
+        check_public_shares_and_aux_infos(public_key_shares.as_map(), &public_aux_infos)?;
+
+        Ok(Self {
+            shares: public_key_shares,
+            aux: public_aux_infos,
+            messages,
+        })
+    }
+}
+
+impl<P: SchemeParams, Id: PartyId> ProtocolError<Id> for BatchInteractiveSigningError<P, Id> {
+    type AssociatedData = BatchInteractiveSigningAssociatedData<P, Id>;
+
+    fn required_messages(&self) -> RequiredMessages {
+        self.error.required_messages()
+    }
+
+    fn verify_messages_constitute_error(
+        &self,
+        format: &BoxedFormat,
+        guilty_party: &Id,
+        shared_randomness: &[u8],
+        associated_data: &Self::AssociatedData,
+        message: ProtocolMessage,
+        previous_messages: BTreeMap<RoundId, ProtocolMessage>,
+        combined_echos: BTreeMap<RoundId, BTreeMap<Id, EchoBroadcast>>,
+    ) -> Result<(), ProtocolValidationError> {
+        self.error.verify_messages_constitute_error::<P>(
+            format,
+            guilty_party,
+            shared_randomness,
+            &associated_data.shares,
+            &associated_data.aux,
+            associated_data.messages.len(),
+            Some(associated_data.messages.as_slice()),
+            message,
+            previous_messages,
+            combined_echos,
+        )
+    }
+}
+
+/// An entry point for the [`BatchInteractiveSigningProtocol`].
+#[derive(Debug, Clone)]
+pub struct BatchInteractiveSigning<P, Id>
+where
+    P: SchemeParams,
+    Id: PartyId,
+{
+    key_share: Arc<KeyShare<P, Id>>,
+    aux_info: PrecomputedAuxInfo<P, Id>,
+    messages: Vec<PrehashedMessage<P::Curve>>,
+}
+
+impl<P: SchemeParams, Id: PartyId> BatchInteractiveSigning<P, Id> {
+    /// Creates a new entry point given a share of the secret key and the messages to sign.
+    ///
+    /// The key share can be given either as [`KeyShare`], or as `Arc<KeyShare>` to share it between sessions,
+    /// and the auxiliary info either as [`AuxInfo`](crate::AuxInfo), or as [`PrecomputedAuxInfo`]
+    /// to avoid repeating the precomputation in every session.
+    pub fn new(
+        messages: Vec<PrehashedMessage<P::Curve>>,
+        key_share: impl Into<Arc<KeyShare<P, Id>>>,
+        aux_info: impl Into<PrecomputedAuxInfo<P, Id>>,
+    ) -> Result<Self, LocalError> {
+        if messages.is_empty() {
+            return Err(LocalError::new("At least one message to sign is required"));
+        }
+        let key_share = key_share.into();
+        let aux_info = aux_info.into();
+        check_key_share_and_aux_info(&key_share, &aux_info)?;
+        Ok(Self {
+            key_share,
+            aux_info,
+            messages,
+        })
+    }
+}
+
+impl<P: SchemeParams, Id: PartyId> EntryPoint<Id> for BatchInteractiveSigning<P, Id> {
+    type Protocol = BatchInteractiveSigningProtocol<P, Id>;
+
+    fn entry_round_id() -> RoundId {
+        1.into()
+    }
+
+    fn make_round(
+        self,
+        rng: &mut dyn CryptoRngCore,
+        shared_randomness: &[u8],
+        id: &Id,
+    ) -> Result<BoxedRound<Id, Self::Protocol>, LocalError> {
+        let targets = self
+            .messages
+            .iter()
+            .enumerate()
+            .map(|(index, message)| SignBatchedMessage {
+                index,
+                scalar_message: Scalar::from_reduced_bytes(message),
+            })
+            .collect();
+        make_batch_round1(rng, shared_randomness, id, self.key_share, self.aux_info, targets)
+    }
+}
+
+/// Sign one of the messages in the batch (used in [`BatchInteractiveSigningProtocol`]).
+#[derive(Debug)]
+pub(super) struct SignBatchedMessage<P: SchemeParams> {
+    index: usize,
+    scalar_message: Scalar<P>,
+}
+
+impl<P: SchemeParams, Id: PartyId> PresigningTarget<P, Id> for SignBatchedMessage<P> {
+    type Protocol = BatchInteractiveSigningProtocol<P, Id>;
+
+    fn round3_transition_info() -> TransitionInfo {
+        TransitionInfo::new_linear(3).with_children([5, 6].into())
+    }
+
+    fn protocol_error(&self, error: Error<Id>) -> BatchInteractiveSigningError<P, Id> {
+        BatchError::Instance {
+            index: self.index,
+            error,
+        }
+        .into()
+    }
+
+    fn finalize_presigning(
+        context: Context<P, Id, Self>,
+        presignature: Presignature<P, Id>,
+    ) -> Result<FinalizeOutcome<Id, Self::Protocol>, LocalError> {
+        Ok(make_round4(context, presignature))
+    }
+}
+
+impl<P: SchemeParams, Id: PartyId> SigningTarget<P, Id> for SignBatchedMessage<P> {
+    fn scalar_message(&self) -> &Scalar<P> {
+        &self.scalar_message
+    }
+
+    fn signing_result(signature: RecoverableSignature<P>) -> Vec<RecoverableSignature<P>> {
+        vec![signature]
+    }
+}
+
+impl<P: SchemeParams, Id: PartyId> BatchTarget<P, Id> for SignBatchedMessage<P> {
+    fn combine_round3_outcomes(
+        other_ids: BTreeSet<Id>,
+        outcomes: Vec<FinalizeOutcome<Id, Self::Protocol>>,
+    ) -> Result<FinalizeOutcome<Id, Self::Protocol>, LocalError> {
+        next_batch::<Id, Round4<P, Id, Self>>(other_ids, outcomes)
+    }
+}
+
+impl<P: SchemeParams, Id: PartyId> BatchedRound<Id> for Round4<P, Id, SignBatchedMessage<P>> {
+    fn batch_transition_info() -> TransitionInfo {
+        TransitionInfo::new_linear_terminating(4).with_siblings([5, 6].into())
+    }
+
+    fn combine_outcomes(
+        _other_ids: BTreeSet<Id>,
+        outcomes: Vec<FinalizeOutcome<Id, Self::Protocol>>,
+    ) -> Result<FinalizeOutcome<Id, Self::Protocol>, LocalError> {
+        Ok(concat_results(outcomes))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use alloc::collections::BTreeSet;
+
+    use ecdsa::{signature::hazmat::PrehashVerifier, VerifyingKey};
+    use elliptic_curve::FieldBytes;
+    use manul::{
+        dev::{run_sync, BinaryFormat, TestSessionParams, TestSigner, TestVerifier},
+        signature::Keypair,
+    };
+    use rand_core::{OsRng, RngCore};
+
+    use super::BatchInteractiveSigning;
+    use crate::{
+        dev::TestParams,
+        entities::{AuxInfo, KeyShare},
+        SchemeParams,
+    };
+    type Curve = <TestParams as SchemeParams>::Curve;
+
+    #[test]
+    fn execute_batch_interactive_signing() {
+        let signers = (0..3).map(TestSigner::new).collect::<Vec<_>>();
+        let ids = signers.iter().map(|signer| signer.verifying_key()).collect::<Vec<_>>();
+        let ids_set = BTreeSet::from_iter(ids.clone());
+
+        let key_shares = KeyShare::<TestParams, TestVerifier>::new_centralized(&mut OsRng, &ids_set, None);
+        let aux_infos = AuxInfo::new_centralized(&mut OsRng, &ids_set);
+
+        let messages = (0..3)
+            .map(|_| {
+                let mut message = FieldBytes::<Curve>::default();
+                OsRng.fill_bytes(&mut message);
+                message
+            })
+            .collect::<Vec<_>>();
+
+        let entry_points = signers
+            .into_iter()
+            .map(|signer| {
+                let id = signer.verifying_key();
+                let entry_point =
+                    BatchInteractiveSigning::new(messages.clone(), key_shares[&id].clone(), aux_infos[&id].clone())
+                        .unwrap();
+                (signer, entry_point)
+            })
+            .collect();
+
+        let mut signatures = run_sync::<_, TestSessionParams<BinaryFormat>>(&mut OsRng, entry_points)
+            .unwrap()
+            .results()
+            .unwrap();
+
+        let vkey = key_shares[&ids[0]].verifying_key();
+
+        while let Some((_, signatures)) = signatures.pop_first() {
+            assert_eq!(signatures.len(), messages.len());
+            for (message, signature) in messages.iter().zip(signatures) {
+                let (sig, rec_id) = signature.to_backend();
+
+                // Check that the signature can be verified
+                vkey.verify_prehash(message, &sig).unwrap();
+
+                // Check that the key can be recovered
+                let recovered_key = VerifyingKey::recover_from_prehash(message.as_ref(), &sig, rec_id).unwrap();
+                assert_eq!(recovered_key, vkey);
+            }
+        }
+    }
+}
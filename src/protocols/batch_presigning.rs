@@ -0,0 +1,343 @@
+//! Batch Presigning: several independent instances of the ECDSA Pre-Signing protocol (Fig. 8)
+//! executed in a single session.
+//!
+//! The messages of all instances in Rounds 1-3 are sent together, so the number of communication rounds
+//! does not depend on the number of presignatures.
+//! If any of the instances requires an error round (Rounds 5 or 6), only the first such instance proceeds to it.
+
+use alloc::{
+    collections::{BTreeMap, BTreeSet},
+    sync::Arc,
+    vec,
+    vec::Vec,
+};
+use core::{
+    fmt::{self, Debug, Display},
+    marker::PhantomData,
+};
+
+use manul::protocol::{
+    BoxedFormat, BoxedRound, DirectMessage, EchoBroadcast, EntryPoint, FinalizeOutcome, LocalError,
+    MessageValidationError, NormalBroadcast, PartyId, Protocol, ProtocolError, ProtocolMessage,
+    ProtocolValidationError, RequiredMessages, RoundId, TransitionInfo,
+};
+use rand_core::CryptoRngCore;
+use serde::{Deserialize, Serialize};
+
+use super::{
+    batch::{
+        concat_results, make_batch_round1, verify_batched_direct_message_is_invalid,
+        verify_batched_echo_broadcast_is_invalid, verify_batched_normal_broadcast_is_invalid, BatchError, BatchTarget,
+    },
+    interactive_signing::{
+        check_key_share_and_aux_info, check_public_shares_and_aux_infos, Context, Error, Presignature,
+        PresigningProtocol, PresigningTarget,
+    },
+};
+use crate::{
+    entities::{KeyShare, PrecomputedAuxInfo, PublicAuxInfos, PublicKeyShares},
+    params::SchemeParams,
+};
+
+/// A protocol for creating several presignatures in a single session.
+///
+/// The result is a vector of [`Presignature`] objects, each of which can be used in
+/// the [`SigningProtocol`](`super::SigningProtocol`) independently.
+#[derive(Debug, Clone, Copy)]
+pub struct BatchPresigningProtocol<P: SchemeParams, Id: Debug>(PhantomData<(P, Id)>);
+
+impl<P: SchemeParams, Id: PartyId> Protocol<Id> for BatchPresigningProtocol<P, Id> {
+    type Result = Vec<Presignature<P, Id>>;
+    type ProtocolError = BatchPresigningError<P, Id>;
+
+    fn verify_direct_message_is_invalid(
+        format: &BoxedFormat,
+        round_id: &RoundId,
+        message: &DirectMessage,
+    ) -> Result<(), MessageValidationError> {
+        verify_batched_direct_message_is_invalid::<Id, PresigningProtocol<P, Id>>(format, round_id, message)
+    }
+
+    fn verify_echo_broadcast_is_invalid(
+        format: &BoxedFormat,
+        round_id: &RoundId,
+        message: &EchoBroadcast,
+    ) -> Result<(), MessageValidationError> {
+        verify_batched_echo_broadcast_is_invalid::<Id, PresigningProtocol<P, Id>>(format, round_id, message)
+    }
+
+    fn verify_normal_broadcast_is_invalid(
+        format: &BoxedFormat,
+        round_id: &RoundId,
+        message: &NormalBroadcast,
+    ) -> Result<(), MessageValidationError> {
+        verify_batched_normal_broadcast_is_invalid::<Id, PresigningProtocol<P, Id>>(format, round_id, message)
+    }
+}
+
+/// Possible verifiable errors of the BatchPresigning protocol.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BatchPresigningError<P, Id> {
+    error: BatchError<Id>,
+    phantom: PhantomData<P>,
+}
+
+impl<P, Id> Display for BatchPresigningError<P, Id> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> Result<(), fmt::Error> {
+        write!(f, "{}", self.error)
+    }
+}
+
+impl<P, Id> From<BatchError<Id>> for BatchPresigningError<P, Id> {
+    fn from(source: BatchError<Id>) -> Self {
+        Self {
+            error: source,
+            phantom: PhantomData,
+        }
+    }
+}
+
+/// Associated data for BatchPresigning protocol.
+#[derive(Debug, Clone)]
+pub struct BatchPresigningAssociatedData<P: SchemeParams, Id: PartyId> {
+    /// Public shares of all participating nodes.
+    pub shares: PublicKeyShares<P, Id>,
+    /// Auxiliary data of all participating nodes.
+    pub aux: PublicAuxInfos<P, Id>,
+    /// The number of presignatures created in the session.
+    pub batch_size: usize,
+}
+
+impl<P: SchemeParams, Id: PartyId> BatchPresigningAssociatedData<P, Id> {
+    /// Creates the associated data for evidence verification of BatchPresigning.
+    pub fn new(
+        public_key_shares: PublicKeyShares<P, Id>,
+        public_aux_infos: PublicAuxInfos<P, Id>,
+        batch_size: usize,
+    ) -> Result<Self, LocalError> {
+        check_public_shares_and_aux_infos(public_key_shares.as_map(), &public_aux_infos)?;
+
+        Ok(Self {
+            shares: public_key_shares,
+            aux: public_aux_infos,
+            batch_size,
+        })
+    }
+}
+
+impl<P: SchemeParams, Id: PartyId> ProtocolError<Id> for BatchPresigningError<P, Id> {
+    type AssociatedData = BatchPresigningAssociatedData<P, Id>;
+
+    fn required_messages(&self) -> RequiredMessages {
+        self.error.required_messages()
+    }
+
+    fn verify_messages_constitute_error(
+        &self,
+        format: &BoxedFormat,
+        guilty_party: &Id,
+        shared_randomness: &[u8],
+        associated_data: &Self::AssociatedData,
+        message: ProtocolMessage,
+        previous_messages: BTreeMap<RoundId, ProtocolMessage>,
+        combined_echos: BTreeMap<RoundId, BTreeMap<Id, EchoBroadcast>>,
+    ) -> Result<(), ProtocolValidationError> {
+        self.error.verify_messages_constitute_error::<P>(
+            format,
+            guilty_party,
+            shared_randomness,
+            &associated_data.shares,
+            &associated_data.aux,
+            associated_data.batch_size,
+            None,
+            message,
+            previous_messages,
+            combined_echos,
+        )
+    }
+}
+
+/// An entry point for the [`BatchPresigningProtocol`].
+#[derive(Debug, Clone)]
+pub struct BatchPresigning<P, Id>
+where
+    P: SchemeParams,
+    Id: PartyId,
+{
+    key_share: Arc<KeyShare<P, Id>>,
+    aux_info: PrecomputedAuxInfo<P, Id>,
+    batch_size: usize,
+}
+
+impl<P: SchemeParams, Id: PartyId> BatchPresigning<P, Id> {
+    /// Creates a new entry point given a share of the secret key and the number of presignatures to create.
+    ///
+    /// The key share can be given either as [`KeyShare`], or as `Arc<KeyShare>` to share it between sessions,
+    /// and the auxiliary info either as [`AuxInfo`](crate::AuxInfo), or as [`PrecomputedAuxInfo`]
+    /// to avoid repeating the precomputation in every session.
+    pub fn new(
+        key_share: impl Into<Arc<KeyShare<P, Id>>>,
+        aux_info: impl Into<PrecomputedAuxInfo<P, Id>>,
+        batch_size: usize,
+    ) -> Result<Self, LocalError> {
+        if batch_size == 0 {
+            return Err(LocalError::new("The batch size must be positive"));
+        }
+        let key_share = key_share.into();
+        let aux_info = aux_info.into();
+        check_key_share_and_aux_info(&key_share, &aux_info)?;
+        Ok(Self {
+            key_share,
+            aux_info,
+            batch_size,
+        })
+    }
+}
+
+impl<P: SchemeParams, Id: PartyId> EntryPoint<Id> for BatchPresigning<P, Id> {
+    type Protocol = BatchPresigningProtocol<P, Id>;
+
+    fn entry_round_id() -> RoundId {
+        1.into()
+    }
+
+    fn make_round(
+        self,
+        rng: &mut dyn CryptoRngCore,
+        shared_randomness: &[u8],
+        id: &Id,
+    ) -> Result<BoxedRound<Id, Self::Protocol>, LocalError> {
+        let targets = (0..self.batch_size)
+            .map(|index| ReturnBatchedPresignature { index })
+            .collect();
+        make_batch_round1(rng, shared_randomness, id, self.key_share, self.aux_info, targets)
+    }
+}
+
+/// Return the presignature as a part of the batch (used in [`BatchPresigningProtocol`]).
+#[derive(Debug)]
+pub(super) struct ReturnBatchedPresignature {
+    index: usize,
+}
+
+impl<P: SchemeParams, Id: PartyId> PresigningTarget<P, Id> for ReturnBatchedPresignature {
+    type Protocol = BatchPresigningProtocol<P, Id>;
+
+    fn round3_transition_info() -> TransitionInfo {
+        TransitionInfo::new_linear_terminating(3).with_children([5, 6].into())
+    }
+
+    fn protocol_error(&self, error: Error<Id>) -> BatchPresigningError<P, Id> {
+        BatchError::Instance {
+            index: self.index,
+            error,
+        }
+        .into()
+    }
+
+    fn finalize_presigning(
+        _context: Context<P, Id, Self>,
+        presignature: Presignature<P, Id>,
+    ) -> Result<FinalizeOutcome<Id, Self::Protocol>, LocalError> {
+        Ok(FinalizeOutcome::Result(vec![presignature]))
+    }
+}
+
+impl<P: SchemeParams, Id: PartyId> BatchTarget<P, Id> for ReturnBatchedPresignature {
+    fn combine_round3_outcomes(
+        _other_ids: BTreeSet<Id>,
+        outcomes: Vec<FinalizeOutcome<Id, Self::Protocol>>,
+    ) -> Result<FinalizeOutcome<Id, Self::Protocol>, LocalError> {
+        Ok(concat_results(outcomes))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use alloc::collections::{BTreeMap, BTreeSet};
+
+    use ecdsa::{signature::hazmat::PrehashVerifier, VerifyingKey};
+    use elliptic_curve::FieldBytes;
+    use manul::{
+        dev::{run_sync, BinaryFormat, TestSessionParams, TestSigner, TestVerifier},
+        signature::Keypair,
+    };
+    use rand_core::{OsRng, RngCore};
+
+    use super::BatchPresigning;
+    use crate::{
+        dev::TestParams,
+        entities::{AuxInfo, KeyShare},
+        protocols::Signing,
+        SchemeParams,
+    };
+    type Curve = <TestParams as SchemeParams>::Curve;
+
+    #[test]
+    fn execute_batch_presigning() {
+        let batch_size = 3;
+        let signers = (0..3).map(TestSigner::new).collect::<Vec<_>>();
+        let ids = signers.iter().map(|signer| signer.verifying_key()).collect::<Vec<_>>();
+        let ids_set = BTreeSet::from_iter(ids.clone());
+
+        let key_shares = KeyShare::<TestParams, TestVerifier>::new_centralized(&mut OsRng, &ids_set, None);
+        let aux_infos = AuxInfo::new_centralized(&mut OsRng, &ids_set);
+
+        let entry_points = signers
+            .iter()
+            .map(|signer| {
+                let id = signer.verifying_key();
+                let entry_point =
+                    BatchPresigning::new(key_shares[&id].clone(), aux_infos[&id].clone(), batch_size).unwrap();
+                (*signer, entry_point)
+            })
+            .collect();
+
+        let presignatures = run_sync::<_, TestSessionParams<BinaryFormat>>(&mut OsRng, entry_points)
+            .unwrap()
+            .results()
+            .unwrap();
+
+        for presignatures in presignatures.values() {
+            assert_eq!(presignatures.len(), batch_size);
+        }
+
+        let vkey = key_shares[&ids[0]].verifying_key();
+
+        let mut presignatures = presignatures
+            .into_iter()
+            .map(|(id, batch)| (id, batch.into_iter()))
+            .collect::<BTreeMap<_, _>>();
+
+        for _ in 0..batch_size {
+            let mut message = FieldBytes::<Curve>::default();
+            OsRng.fill_bytes(&mut message);
+
+            let entry_points = signers
+                .iter()
+                .map(|signer| {
+                    let id = signer.verifying_key();
+                    let presignature = presignatures.get_mut(&id).unwrap().next().unwrap();
+                    let entry_point = Signing::new(message, presignature);
+                    (*signer, entry_point)
+                })
+                .collect();
+
+            let mut signatures = run_sync::<_, TestSessionParams<BinaryFormat>>(&mut OsRng, entry_points)
+                .unwrap()
+                .results()
+                .unwrap();
+
+            while let Some((_, signature)) = signatures.pop_first() {
+                let (sig, rec_id) = signature.to_backend();
+
+                // Check that the signature can be verified
+                vkey.verify_prehash(&message, &sig).unwrap();
+
+                // Check that the key can be recovered
+                let recovered_key = VerifyingKey::recover_from_prehash(message.as_ref(), &sig, rec_id).unwrap();
+                assert_eq!(recovered_key, vkey);
+            }
+        }
+    }
+}
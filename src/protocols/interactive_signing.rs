@@ -3,17 +3,26 @@
 //! - ECDSA Signing (Fig. 10) - Round 4.
 //! - Failed Nonce error round (Fig. 9) - Round 5.
 //! - Failed Chi error round (Section 4.3.1) - Round 6.
+//!
+//! Rounds 1-3, 5 and 6 are also used by the standalone Presigning protocol,
+//! which terminates after Round 3 and returns a [`Presignature`]
+//! (to be used later in the Signing protocol).
 
 use alloc::{
     boxed::Box,
     collections::{BTreeMap, BTreeSet},
+    format,
+    sync::Arc,
+    vec::Vec,
 };
 use core::{
     fmt::{self, Debug, Display},
     marker::PhantomData,
 };
 
-use elliptic_curve::{Curve, FieldBytes};
+use digest::Digest;
+use ecdsa::hazmat::{bits2field, DigestPrimitive};
+use elliptic_curve::{Curve, FieldBytes, PrimeCurve};
 use manul::{
     protocol::{
         Artifact, BoxedFormat, BoxedRound, CommunicationInfo, DirectMessage, EchoBroadcast, EntryPoint,
@@ -26,9 +35,19 @@ use manul::{
 use rand_core::CryptoRngCore;
 use serde::{Deserialize, Serialize};
 
+#[cfg(feature = "bip32")]
+use bip32::DerivationPath;
+#[cfg(feature = "bip32")]
+use ecdsa::{SigningKey, VerifyingKey};
+
+#[cfg(feature = "bip32")]
+use crate::curve::{Bip32Tweak, PublicTweakable, SecretTweakable};
 use crate::{
     curve::{Point, RecoverableSignature, Scalar},
-    entities::{AuxInfo, AuxInfoPrecomputed, KeyShare, PublicAuxInfoPrecomputed, PublicAuxInfos, PublicKeyShares},
+    entities::{
+        AuxInfo, KeyShare, PrecomputedAuxInfo, PublicAuxInfoPrecomputed, PublicAuxInfos, PublicKeyShares,
+        ThresholdKeyShare,
+    },
     paillier::{Ciphertext, CiphertextWire, PaillierParams, Randomizer},
     params::{chain_scheme_params, secret_scalar_from_signed, secret_signed_from_scalar, SchemeParams},
     tools::{
@@ -52,16 +71,139 @@ use crate::{
 #[allow(type_alias_bounds)]
 pub type PrehashedMessage<C: Curve> = FieldBytes<C>;
 
+/// The result of the Presigning protocol: the data necessary to sign a single message.
+///
+/// **Warning:** a presignature must only be used once.
+/// Signing two different messages with the same presignature reveals the secret key.
+#[derive(Debug, Clone)]
+#[derive_where::derive_where(Serialize, Deserialize)]
+pub struct Presignature<P, Id>
+where
+    P: SchemeParams,
+    Id: PartyId,
+{
+    owner: Id,
+    tilde_k: Secret<Scalar<P>>,   // $k / \delta$
+    tilde_chi: Secret<Scalar<P>>, // $\chi / \delta$
+    transcript: PresignatureTranscript<P, Id>,
+}
+
+/// The public part of a [`Presignature`], the same for all the parties of the presigning session.
+///
+/// Contains the values broadcasted during presigning that are needed
+/// to check the signature shares of other parties, and to verify the evidence of an invalid signature share.
+///
+/// When signing, every party commits to the part of the transcript its signature share depends on,
+/// so the evidence of an invalid share can only be verified against the transcript the guilty party itself used.
 #[derive(Debug, Clone)]
-struct PresigningData<P, Id>
+#[derive_where::derive_where(Serialize, Deserialize)]
+pub struct PresignatureTranscript<P, Id>
+where
+    P: SchemeParams,
+    Id: PartyId,
+{
+    epid: Epid, // identifies the presigning session
+    verifying_key: Point<P>,
+    cap_gammas: SerializableMap<Id, Point<P>>, // $\Gamma_j$ for all $j$
+    deltas: SerializableMap<Id, Scalar<P>>,    // $\delta_j$ for all $j$
+    cap_deltas: SerializableMap<Id, Point<P>>, // $\Delta_j$ for all $j$
+    cap_ss: SerializableMap<Id, Point<P>>,     // $S_j$ for all $j$
+}
+
+impl<P, Id> Presignature<P, Id>
 where
     P: SchemeParams,
+    Id: PartyId,
 {
-    cap_gamma_combined: Point<P>,             // $\Gamma$
-    tilde_k: Secret<Scalar<P>>,               // $k / \delta$
-    tilde_chi: Secret<Scalar<P>>,             // $chi / \delta$
-    tilde_cap_deltas: BTreeMap<Id, Point<P>>, // $\Delta_j^{\delta^{-1}}$ for all $j$
-    tilde_cap_ss: BTreeMap<Id, Point<P>>,     // $S_j^{\delta^{-1}}$ for all $j$
+    /// Returns the ID of the party this presignature belongs to.
+    pub fn owner(&self) -> &Id {
+        &self.owner
+    }
+
+    /// Returns the public part of the presignature.
+    pub fn transcript(&self) -> &PresignatureTranscript<P, Id> {
+        &self.transcript
+    }
+
+    /// Returns the signature share of this party for the given message.
+    pub(crate) fn signature_share(&self, scalar_message: &Scalar<P>) -> Scalar<P> {
+        let nonce = self.transcript.cap_gamma_combined().x_coordinate();
+        *(&self.tilde_k * *scalar_message + &self.tilde_chi * nonce).expose_secret()
+    }
+}
+
+impl<P, Id> PresignatureTranscript<P, Id>
+where
+    P: SchemeParams,
+    Id: PartyId,
+{
+    /// Returns the IDs of the parties that participated in the presigning.
+    pub fn ids(&self) -> BTreeSet<Id> {
+        self.cap_gammas.keys().cloned().collect()
+    }
+
+    /// Returns $\Gamma$, the commitment to the signature nonce.
+    pub(crate) fn cap_gamma_combined(&self) -> Point<P> {
+        self.cap_gammas.values().sum()
+    }
+
+    /// Checks that the signature share `sigma` of the party `id` is consistent with the transcript.
+    ///
+    /// Returns `None` if the transcript does not contain the data for `id`, or is otherwise malformed.
+    pub(crate) fn signature_share_is_valid(
+        &self,
+        id: &Id,
+        scalar_message: &Scalar<P>,
+        sigma: &Scalar<P>,
+    ) -> Option<bool> {
+        let delta_combined = self.deltas.values().sum::<Scalar<P>>();
+        let delta_combined_inv = Option::<Scalar<P>>::from(delta_combined.invert())?;
+        let cap_delta = self.cap_deltas.get(id)?;
+        let cap_s = self.cap_ss.get(id)?;
+
+        let cap_gamma = self.cap_gamma_combined();
+        let nonce = cap_gamma.x_coordinate();
+
+        // $\Gamma \sigma_j == \tilde{\Delta}_j m + \tilde{S}_j r$, where $\tilde{X} = X \delta^{-1}$
+        Some(cap_gamma * sigma == (cap_delta * scalar_message + cap_s * nonce) * delta_combined_inv)
+    }
+
+    /// Returns the commitment of the party `id` to the presigning session, the values its signature share
+    /// depends on, and the message being signed.
+    ///
+    /// Returns `None` if the transcript does not contain the data for `id`.
+    pub(crate) fn share_commitment(&self, id: &Id, scalar_message: &Scalar<P>) -> Option<HashOutput> {
+        let cap_delta = self.cap_deltas.get(id)?;
+        let cap_s = self.cap_ss.get(id)?;
+        let digest = Hasher::<P::Digest>::new_with_dst(b"PresignatureShareCommitment");
+        let digest = chain_scheme_params::<P, _>(digest);
+        Some(
+            digest
+                .chain(&self.epid)
+                .chain(id)
+                .chain(&self.verifying_key)
+                .chain(&self.cap_gammas)
+                .chain(&self.deltas)
+                .chain(cap_delta)
+                .chain(cap_s)
+                .chain(scalar_message)
+                .finalize(P::SECURITY_BITS),
+        )
+    }
+
+    /// Assembles the signature given the sum of the signature shares of all the parties.
+    pub(crate) fn assemble_signature(
+        &self,
+        assembled_sigma: Scalar<P>,
+        scalar_message: Scalar<P>,
+    ) -> Option<RecoverableSignature<P>> {
+        RecoverableSignature::from_scalars(
+            self.cap_gamma_combined().x_coordinate(),
+            assembled_sigma,
+            self.verifying_key,
+            scalar_message,
+        )
+    }
 }
 
 /// A protocol for creating all the data necessary for signing
@@ -122,6 +264,64 @@ impl<P: SchemeParams, Id: PartyId> Protocol<Id> for InteractiveSigningProtocol<P
     }
 }
 
+/// A protocol for creating all the data necessary for signing
+/// that doesn't require knowing the actual message being signed.
+///
+/// The result is a [`Presignature`] that can be used in the [`SigningProtocol`](`super::SigningProtocol`)
+/// when the message becomes known.
+#[derive(Debug, Clone, Copy)]
+pub struct PresigningProtocol<P: SchemeParams, Id: Debug>(PhantomData<(P, Id)>);
+
+impl<P: SchemeParams, Id: PartyId> Protocol<Id> for PresigningProtocol<P, Id> {
+    type Result = Presignature<P, Id>;
+    type ProtocolError = PresigningError<P, Id>;
+
+    fn verify_direct_message_is_invalid(
+        format: &BoxedFormat,
+        round_id: &RoundId,
+        message: &DirectMessage,
+    ) -> Result<(), MessageValidationError> {
+        match round_id {
+            r if r == &1 => message.verify_is_not::<Round1DirectMessage<P>>(format),
+            r if r == &2 => message.verify_is_some(),
+            r if r == &3 => message.verify_is_some(),
+            r if r == &5 => message.verify_is_some(),
+            r if r == &6 => message.verify_is_some(),
+            _ => Err(MessageValidationError::InvalidEvidence("Invalid round number".into())),
+        }
+    }
+
+    fn verify_echo_broadcast_is_invalid(
+        format: &BoxedFormat,
+        round_id: &RoundId,
+        message: &EchoBroadcast,
+    ) -> Result<(), MessageValidationError> {
+        match round_id {
+            r if r == &1 => message.verify_is_not::<Round1EchoBroadcast<P>>(format),
+            r if r == &2 => message.verify_is_not::<Round2EchoBroadcast<P, Id>>(format),
+            r if r == &3 => message.verify_is_not::<Round3EchoBroadcast<P>>(format),
+            r if r == &5 => message.verify_is_not::<Round5EchoBroadcast<P, Id>>(format),
+            r if r == &6 => message.verify_is_not::<Round6EchoBroadcast<P, Id>>(format),
+            _ => Err(MessageValidationError::InvalidEvidence("Invalid round number".into())),
+        }
+    }
+
+    fn verify_normal_broadcast_is_invalid(
+        format: &BoxedFormat,
+        round_id: &RoundId,
+        message: &NormalBroadcast,
+    ) -> Result<(), MessageValidationError> {
+        match round_id {
+            r if r == &1 => message.verify_is_some(),
+            r if r == &2 => message.verify_is_not::<Round2NormalBroadcast<P, Id>>(format),
+            r if r == &3 => message.verify_is_not::<Round3NormalBroadcast<P>>(format),
+            r if r == &5 => message.verify_is_some(),
+            r if r == &6 => message.verify_is_some(),
+            _ => Err(MessageValidationError::InvalidEvidence("Invalid round number".into())),
+        }
+    }
+}
+
 /// Possible verifiable errors of the InteractiveSigning protocol.
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct InteractiveSigningError<P, Id> {
@@ -129,37 +329,28 @@ pub struct InteractiveSigningError<P, Id> {
     phantom: PhantomData<P>,
 }
 
-impl<P, Id: Debug> Display for InteractiveSigningError<P, Id> {
+impl<P, Id> Display for InteractiveSigningError<P, Id> {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> Result<(), fmt::Error> {
-        write!(
-            f,
-            "{}",
-            match self.error {
-                Error::R1EncElg0Failed => "Round 1: failed to verify `\\psi^0` (`П^{enc-elg}` proof).",
-                Error::R1EncElg1Failed => "Round 1: failed to verify `\\psi^1` (`П^{enc-elg}` proof).",
-                Error::R2WrongIdsD => "Round 2: wrong IDs in `D` map.",
-                Error::R2WrongIdsF => "Round 2: wrong IDs in `F` map.",
-                Error::R2WrongIdsPsi => "Round 2: wrong IDs in `\\psi` map (`П^{aff-g}` proofs for `D`).",
-                Error::R2AffGPsiFailed { .. } => "Round 2: failed to verify `\\psi` (`П^{aff-g}` proof for `D`).",
-                Error::R2AffGHatPsiFailed { .. } =>
-                    "Round 2: failed to verify `\\hat{psi}` (`П^{aff-g}` proof for `\\hat{D}`).",
-                Error::R2ElogFailed => "Round 2: failed to verify `П^{elog}` proof.",
-                Error::R3ElogFailed => "Round 3: failed to verify `П^{elog}` proof.",
-                Error::R4InvalidSignatureShare => "Round 4: signature share verification failed.",
-                Error::R5DecFailed => "Round 5: `П^{dec}` proof verification failed.",
-                Error::R5WrongIdsPsi => "Round 5: wrong IDs in `П^{aff-g*}` proof map.",
-                Error::R5AffGStarFailed { .. } => "Round 5: `П^{aff-g*}` proof verification failed.",
-                Error::R6DecFailed => "Round 6: `П^{dec}` proof verification failed.",
-                Error::R6WrongIdsPsi => "Round 6: wrong IDs in `П^{aff-g*}` proof map.",
-                Error::R6AffGStarFailed { .. } => "Round 6: `П^{aff-g*}` proof verification failed.",
-            }
-        )
+        write!(f, "{}", self.error.description())
     }
 }
 
-/// Possible verifiable errors of the InteractiveSigning protocol.
+/// Possible verifiable errors of the Presigning protocol.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PresigningError<P, Id> {
+    error: Error<Id>,
+    phantom: PhantomData<P>,
+}
+
+impl<P, Id> Display for PresigningError<P, Id> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> Result<(), fmt::Error> {
+        write!(f, "{}", self.error.description())
+    }
+}
+
+/// Possible verifiable errors of the presigning and signing rounds.
 #[derive(Debug, Clone, Serialize, Deserialize)]
-enum Error<Id> {
+pub(super) enum Error<Id> {
     R1EncElg0Failed,
     R1EncElg1Failed,
     R2WrongIdsD,
@@ -203,68 +394,60 @@ impl<P, Id> From<Error<Id>> for InteractiveSigningError<P, Id> {
     }
 }
 
-/// Associated data for InteractiveSigning protocol.
-#[derive(Debug, Clone)]
-pub struct InteractiveSigningAssociatedData<P: SchemeParams, Id: PartyId> {
-    /// Public shares of all participating nodes.
-    pub shares: PublicKeyShares<P, Id>,
-    /// Auxiliary data of all participating nodes.
-    pub aux: PublicAuxInfos<P, Id>,
-    /// The message to be signed.
-    pub message: PrehashedMessage<P::Curve>,
-}
-
-impl<P: SchemeParams, Id: PartyId> InteractiveSigningAssociatedData<P, Id> {
-    /// Creates the associated data for evidence verification of InteractiveSigning.
-    pub fn new(
-        message: PrehashedMessage<P::Curve>,
-        public_key_shares: PublicKeyShares<P, Id>,
-        public_aux_infos: PublicAuxInfos<P, Id>,
-    ) -> Result<Self, LocalError> {
-        let key_share_keys = public_key_shares.as_map().keys().collect::<BTreeSet<_>>();
-        let aux_info_keys = public_aux_infos.as_map().keys().collect::<BTreeSet<_>>();
-
-        if key_share_keys != aux_info_keys {
-            return Err(LocalError::new(
-                "The key share and the auxiliary info must have information for the same set of parties",
-            ));
+impl<P, Id> From<Error<Id>> for PresigningError<P, Id> {
+    fn from(source: Error<Id>) -> Self {
+        Self {
+            error: source,
+            phantom: PhantomData,
         }
-
-        Ok(Self {
-            shares: public_key_shares,
-            aux: public_aux_infos,
-            message,
-        })
     }
 }
 
-/// The epoch identifier (see Remark 4.1 in the paper).
-///
-/// The epoch identifier is tied to the key-refresh epoch and the auxiliary key material of the parties for that epoch.
-#[derive(Debug, Clone, Serialize, Deserialize)]
-pub(crate) struct Epid(HashOutput);
-
-impl Epid {
-    fn new<P: SchemeParams, Id: PartyId>(
-        shared_randomness: &[u8],
-        associated_data: &InteractiveSigningAssociatedData<P, Id>,
-    ) -> Self {
-        let digest = Hasher::<P::Digest>::new_with_dst(b"EPID");
-        let digest = chain_scheme_params::<P, _>(digest);
-        let digest = digest
-            .chain(&shared_randomness)
-            .chain(&associated_data.shares)
-            .chain(&associated_data.aux);
+impl<Id> Error<Id> {
+    pub(super) fn description(&self) -> &'static str {
+        match self {
+            Error::R1EncElg0Failed => "Round 1: failed to verify `\\psi^0` (`П^{enc-elg}` proof).",
+            Error::R1EncElg1Failed => "Round 1: failed to verify `\\psi^1` (`П^{enc-elg}` proof).",
+            Error::R2WrongIdsD => "Round 2: wrong IDs in `D` map.",
+            Error::R2WrongIdsF => "Round 2: wrong IDs in `F` map.",
+            Error::R2WrongIdsPsi => "Round 2: wrong IDs in `\\psi` map (`П^{aff-g}` proofs for `D`).",
+            Error::R2AffGPsiFailed { .. } => "Round 2: failed to verify `\\psi` (`П^{aff-g}` proof for `D`).",
+            Error::R2AffGHatPsiFailed { .. } => {
+                "Round 2: failed to verify `\\hat{psi}` (`П^{aff-g}` proof for `\\hat{D}`)."
+            }
+            Error::R2ElogFailed => "Round 2: failed to verify `П^{elog}` proof.",
+            Error::R3ElogFailed => "Round 3: failed to verify `П^{elog}` proof.",
+            Error::R4InvalidSignatureShare => "Round 4: signature share verification failed.",
+            Error::R5DecFailed => "Round 5: `П^{dec}` proof verification failed.",
+            Error::R5WrongIdsPsi => "Round 5: wrong IDs in `П^{aff-g*}` proof map.",
+            Error::R5AffGStarFailed { .. } => "Round 5: `П^{aff-g*}` proof verification failed.",
+            Error::R6DecFailed => "Round 6: `П^{dec}` proof verification failed.",
+            Error::R6WrongIdsPsi => "Round 6: wrong IDs in `П^{aff-g*}` proof map.",
+            Error::R6AffGStarFailed { .. } => "Round 6: `П^{aff-g*}` proof verification failed.",
+        }
+    }
 
-        Self(digest.finalize(P::SECURITY_BITS))
+    /// Returns the number of the round in which the error occurred.
+    pub(super) fn round_num(&self) -> u8 {
+        match self {
+            Error::R1EncElg0Failed | Error::R1EncElg1Failed => 1,
+            Error::R2WrongIdsD
+            | Error::R2WrongIdsF
+            | Error::R2WrongIdsPsi
+            | Error::R2AffGPsiFailed { .. }
+            | Error::R2AffGHatPsiFailed { .. }
+            | Error::R2ElogFailed => 2,
+            Error::R3ElogFailed => 3,
+            Error::R4InvalidSignatureShare => 4,
+            Error::R5DecFailed | Error::R5WrongIdsPsi | Error::R5AffGStarFailed { .. } => 5,
+            Error::R6DecFailed | Error::R6WrongIdsPsi | Error::R6AffGStarFailed { .. } => 6,
+        }
     }
 }
 
-impl<P: SchemeParams, Id: PartyId> ProtocolError<Id> for InteractiveSigningError<P, Id> {
-    type AssociatedData = InteractiveSigningAssociatedData<P, Id>;
-
-    fn required_messages(&self) -> RequiredMessages {
-        match self.error {
+impl<Id: PartyId> Error<Id> {
+    pub(super) fn required_messages(&self) -> RequiredMessages {
+        match self {
             Error::R1EncElg0Failed => {
                 RequiredMessages::new(RequiredMessageParts::echo_broadcast().and_direct_message(), None, None)
             }
@@ -350,24 +533,27 @@ impl<P: SchemeParams, Id: PartyId> ProtocolError<Id> for InteractiveSigningError
         }
     }
 
-    fn verify_messages_constitute_error(
+    #[allow(clippy::too_many_arguments)]
+    pub(super) fn verify_messages_constitute_error<P: SchemeParams>(
         &self,
         format: &BoxedFormat,
         guilty_party: &Id,
         shared_randomness: &[u8],
-        associated_data: &Self::AssociatedData,
+        shares: &PublicKeyShares<P, Id>,
+        aux_infos: &PublicAuxInfos<P, Id>,
+        signed_message: Option<&PrehashedMessage<P::Curve>>,
         message: ProtocolMessage,
         previous_messages: BTreeMap<RoundId, ProtocolMessage>,
         combined_echos: BTreeMap<RoundId, BTreeMap<Id, EchoBroadcast>>,
     ) -> Result<(), ProtocolValidationError> {
-        let epid = Epid::new::<P, Id>(shared_randomness, associated_data);
+        let epid = Epid::new::<P, Id>(shared_randomness, shares, aux_infos);
 
-        match &self.error {
+        match self {
             Error::R1EncElg0Failed => {
                 let r1_dm = message.direct_message.deserialize::<Round1DirectMessage<P>>(format)?;
                 let r1_eb = message.echo_broadcast.deserialize::<Round1EchoBroadcast<P>>(format)?;
 
-                let public_aux = &associated_data.aux.as_map().try_get("aux infos", guilty_party)?;
+                let public_aux = &aux_infos.as_map().try_get("aux infos", guilty_party)?;
                 let pk = public_aux.paillier_pk.clone().into_precomputed();
                 let rp = public_aux.rp_params.to_precomputed();
 
@@ -389,7 +575,7 @@ impl<P: SchemeParams, Id: PartyId> ProtocolError<Id> for InteractiveSigningError
                 let r1_dm = message.direct_message.deserialize::<Round1DirectMessage<P>>(format)?;
                 let r1_eb = message.echo_broadcast.deserialize::<Round1EchoBroadcast<P>>(format)?;
 
-                let public_aux = &associated_data.aux.as_map().try_get("aux infos", guilty_party)?;
+                let public_aux = &aux_infos.as_map().try_get("aux infos", guilty_party)?;
                 let pk = public_aux.paillier_pk.clone().into_precomputed();
                 let rp = public_aux.rp_params.to_precomputed();
 
@@ -411,8 +597,7 @@ impl<P: SchemeParams, Id: PartyId> ProtocolError<Id> for InteractiveSigningError
                 let r2_nb = message
                     .normal_broadcast
                     .deserialize::<Round2NormalBroadcast<P, Id>>(format)?;
-                let expected_ids = associated_data
-                    .aux
+                let expected_ids = aux_infos
                     .as_map()
                     .keys()
                     .collect::<BTreeSet<_>>()
@@ -423,8 +608,7 @@ impl<P: SchemeParams, Id: PartyId> ProtocolError<Id> for InteractiveSigningError
                 let r2_eb = message
                     .echo_broadcast
                     .deserialize::<Round2EchoBroadcast<P, Id>>(format)?;
-                let expected_ids = associated_data
-                    .aux
+                let expected_ids = aux_infos
                     .as_map()
                     .keys()
                     .collect::<BTreeSet<_>>()
@@ -435,8 +619,7 @@ impl<P: SchemeParams, Id: PartyId> ProtocolError<Id> for InteractiveSigningError
                 let r2_nb = message
                     .normal_broadcast
                     .deserialize::<Round2NormalBroadcast<P, Id>>(format)?;
-                let expected_ids = associated_data
-                    .aux
+                let expected_ids = aux_infos
                     .as_map()
                     .keys()
                     .collect::<BTreeSet<_>>()
@@ -455,8 +638,8 @@ impl<P: SchemeParams, Id: PartyId> ProtocolError<Id> for InteractiveSigningError
                     .normal_broadcast
                     .deserialize::<Round2NormalBroadcast<P, Id>>(format)?;
 
-                let failed_for_aux = &associated_data.aux.as_map().try_get("aux infos", failed_for)?;
-                let guilty_party_aux = &associated_data.aux.as_map().try_get("aux infos", guilty_party)?;
+                let failed_for_aux = &aux_infos.as_map().try_get("aux infos", failed_for)?;
+                let guilty_party_aux = &aux_infos.as_map().try_get("aux infos", guilty_party)?;
 
                 let rp = failed_for_aux.rp_params.to_precomputed();
                 let aux = (&epid, guilty_party);
@@ -494,10 +677,10 @@ impl<P: SchemeParams, Id: PartyId> ProtocolError<Id> for InteractiveSigningError
                     .normal_broadcast
                     .deserialize::<Round2NormalBroadcast<P, Id>>(format)?;
 
-                let cap_x = associated_data.shares.as_map().try_get("shares", failed_for)?;
+                let cap_x = shares.as_map().try_get("shares", failed_for)?;
 
-                let failed_for_aux = &associated_data.aux.as_map().try_get("aux infos", failed_for)?;
-                let guilty_party_aux = &associated_data.aux.as_map().try_get("aux infos", guilty_party)?;
+                let failed_for_aux = &aux_infos.as_map().try_get("aux infos", failed_for)?;
+                let guilty_party_aux = &aux_infos.as_map().try_get("aux infos", guilty_party)?;
 
                 let rp = failed_for_aux.rp_params.to_precomputed();
                 let aux = (&epid, guilty_party);
@@ -608,7 +791,12 @@ impl<P: SchemeParams, Id: PartyId> ProtocolError<Id> for InteractiveSigningError
                     .ok_or_else(|| ProtocolValidationError::InvalidEvidence("`delta` is not invertible".into()))?;
                 let tilde_cap_delta = r3_nb.cap_delta * delta_inv;
                 let tilde_cap_s = r3_nb.cap_s * delta_inv;
-                let scalar_message = Scalar::from_reduced_bytes(associated_data.message.clone());
+                let signed_message = signed_message.ok_or_else(|| {
+                    ProtocolValidationError::InvalidEvidence(
+                        "This error can only occur during interactive signing".into(),
+                    )
+                })?;
+                let scalar_message = Scalar::from_reduced_bytes(signed_message);
 
                 verify_that(cap_gamma * r4_nb.sigma != tilde_cap_delta * scalar_message + tilde_cap_s * nonce)
             }
@@ -635,6 +823,7 @@ impl<P: SchemeParams, Id: PartyId> ProtocolError<Id> for InteractiveSigningError
                 let r5_eb = message
                     .echo_broadcast
                     .deserialize::<Round5EchoBroadcast<P, Id>>(format)?;
+                check_error_round_epid(&r5_eb.epid, &epid)?;
 
                 // Calculate `D_j` where `j = guilty_party`.
                 // `D_j = sum_{l != j}(D_{l,j} + F_{j,l})
@@ -644,13 +833,12 @@ impl<P: SchemeParams, Id: PartyId> ProtocolError<Id> for InteractiveSigningError
                 // r2_ebs[i], i != j: contains D_{l,i}, F_{l,i} for l != i
                 // => F_{j,l} = r2_ebs[l].cap_fs[j]
 
-                let public_aux = &associated_data.aux.as_map().try_get("aux infos", guilty_party)?;
+                let public_aux = &aux_infos.as_map().try_get("aux infos", guilty_party)?;
                 let pk = public_aux.paillier_pk.clone().into_precomputed();
                 let rp = public_aux.rp_params.to_precomputed();
                 let aux = (&epid, guilty_party);
 
-                let ids = associated_data
-                    .aux
+                let ids = aux_infos
                     .as_map()
                     .keys()
                     .collect::<BTreeSet<_>>()
@@ -681,7 +869,7 @@ impl<P: SchemeParams, Id: PartyId> ProtocolError<Id> for InteractiveSigningError
                         cap_d: &cap_d,
                         cap_s: &r3_nb.cap_delta,
                         cap_g: &Point::generator(),
-                        num_parties: associated_data.aux.num_parties(),
+                        num_parties: aux_infos.num_parties(),
                     },
                     &rp,
                     &aux,
@@ -692,8 +880,8 @@ impl<P: SchemeParams, Id: PartyId> ProtocolError<Id> for InteractiveSigningError
                 let r5_nb = message
                     .normal_broadcast
                     .deserialize::<Round5EchoBroadcast<P, Id>>(format)?;
-                let expected_ids = associated_data
-                    .aux
+                check_error_round_epid(&r5_nb.epid, &epid)?;
+                let expected_ids = aux_infos
                     .as_map()
                     .keys()
                     .collect::<BTreeSet<_>>()
@@ -719,9 +907,10 @@ impl<P: SchemeParams, Id: PartyId> ProtocolError<Id> for InteractiveSigningError
                 let r5_eb = message
                     .echo_broadcast
                     .deserialize::<Round5EchoBroadcast<P, Id>>(format)?;
+                check_error_round_epid(&r5_eb.epid, &epid)?;
 
-                let failed_for_aux = &associated_data.aux.as_map().try_get("aux infos", failed_for)?;
-                let guilty_party_aux = &associated_data.aux.as_map().try_get("aux infos", guilty_party)?;
+                let failed_for_aux = &aux_infos.as_map().try_get("aux infos", failed_for)?;
+                let guilty_party_aux = &aux_infos.as_map().try_get("aux infos", guilty_party)?;
 
                 let failed_for_pk = failed_for_aux.paillier_pk.clone().into_precomputed();
 
@@ -783,6 +972,7 @@ impl<P: SchemeParams, Id: PartyId> ProtocolError<Id> for InteractiveSigningError
                 let r5_eb = message
                     .echo_broadcast
                     .deserialize::<Round5EchoBroadcast<P, Id>>(format)?;
+                check_error_round_epid(&r5_eb.epid, &epid)?;
 
                 // Calculate `\hat{D}_j` where `j = guilty_party`.
                 // `\hat{D}_j = sum_{l != j}(\hat{D}_{l,j} + \hat{F}_{j,l})
@@ -792,13 +982,12 @@ impl<P: SchemeParams, Id: PartyId> ProtocolError<Id> for InteractiveSigningError
                 // r2_ebs[i], i != j: contains \hat{D}_{l,i}, \hat{F}_{l,i} for l != i
                 // => \hat{F}_{j,l} = r2_ebs[l].hat_cap_fs[j]
 
-                let public_aux = &associated_data.aux.as_map().try_get("aux infos", guilty_party)?;
+                let public_aux = &aux_infos.as_map().try_get("aux infos", guilty_party)?;
                 let pk = public_aux.paillier_pk.clone().into_precomputed();
                 let rp = public_aux.rp_params.to_precomputed();
                 let aux = (&epid, guilty_party);
 
-                let ids = associated_data
-                    .aux
+                let ids = aux_infos
                     .as_map()
                     .keys()
                     .collect::<BTreeSet<_>>()
@@ -823,7 +1012,7 @@ impl<P: SchemeParams, Id: PartyId> ProtocolError<Id> for InteractiveSigningError
 
                 let total_cap_gamma = r2_eb.cap_gamma + r2_ebs.values().map(|eb| eb.cap_gamma).sum();
 
-                let cap_x = associated_data.shares.as_map().try_get("`X` map", guilty_party)?;
+                let cap_x = shares.as_map().try_get("`X` map", guilty_party)?;
 
                 verify_that(!r5_eb.psi_star.verify(
                     DecPublicInputs {
@@ -833,7 +1022,7 @@ impl<P: SchemeParams, Id: PartyId> ProtocolError<Id> for InteractiveSigningError
                         cap_d: &hat_cap_d,
                         cap_s: &r3_nb.cap_s,
                         cap_g: &total_cap_gamma,
-                        num_parties: associated_data.aux.num_parties(),
+                        num_parties: aux_infos.num_parties(),
                     },
                     &rp,
                     &aux,
@@ -844,8 +1033,8 @@ impl<P: SchemeParams, Id: PartyId> ProtocolError<Id> for InteractiveSigningError
                 let r6_nb = message
                     .normal_broadcast
                     .deserialize::<Round6EchoBroadcast<P, Id>>(format)?;
-                let expected_ids = associated_data
-                    .aux
+                check_error_round_epid(&r6_nb.epid, &epid)?;
+                let expected_ids = aux_infos
                     .as_map()
                     .keys()
                     .collect::<BTreeSet<_>>()
@@ -867,16 +1056,17 @@ impl<P: SchemeParams, Id: PartyId> ProtocolError<Id> for InteractiveSigningError
                 let r6_eb = message
                     .echo_broadcast
                     .deserialize::<Round6EchoBroadcast<P, Id>>(format)?;
+                check_error_round_epid(&r6_eb.epid, &epid)?;
 
-                let failed_for_aux = &associated_data.aux.as_map().try_get("aux infos", failed_for)?;
-                let guilty_party_aux = &associated_data.aux.as_map().try_get("aux infos", guilty_party)?;
+                let failed_for_aux = &aux_infos.as_map().try_get("aux infos", failed_for)?;
+                let guilty_party_aux = &aux_infos.as_map().try_get("aux infos", guilty_party)?;
 
                 let failed_for_pk = failed_for_aux.paillier_pk.clone().into_precomputed();
 
                 let guilty_party_pk = guilty_party_aux.paillier_pk.clone().into_precomputed();
                 let aux = (&epid, guilty_party);
 
-                let cap_x = associated_data.shares.as_map().try_get("shares", failed_for)?;
+                let cap_x = shares.as_map().try_get("shares", failed_for)?;
 
                 // l = failed_for
                 // j = guilty_party
@@ -914,52 +1104,452 @@ impl<P: SchemeParams, Id: PartyId> ProtocolError<Id> for InteractiveSigningError
     }
 }
 
-/// An entry point for the [`InteractiveSigningProtocol`].
+/// Associated data for InteractiveSigning protocol.
+#[derive(Debug, Clone)]
+pub struct InteractiveSigningAssociatedData<P: SchemeParams, Id: PartyId> {
+    /// Public shares of all participating nodes.
+    pub shares: PublicKeyShares<P, Id>,
+    /// Auxiliary data of all participating nodes.
+    pub aux: PublicAuxInfos<P, Id>,
+    /// The message to be signed.
+    pub message: PrehashedMessage<P::Curve>,
+    /// The original message, if the signing was started with a message that is not prehashed
+    /// (in which case `message` must be its hash, which is checked during evidence verification).
+    pub raw_message: Option<RawMessage<P::Curve>>,
+}
+
+/// A message that is not prehashed, along with the digest it is hashed with.
+#[derive_where::derive_where(Clone)]
+pub struct RawMessage<C: PrimeCurve> {
+    message: Box<[u8]>,
+    hash: fn(&[u8]) -> Result<PrehashedMessage<C>, LocalError>,
+}
+
+impl<C: PrimeCurve> RawMessage<C> {
+    fn new<D: Digest>(message: &[u8]) -> Self {
+        Self {
+            message: message.into(),
+            hash: hash_message::<C, D>,
+        }
+    }
+
+    /// Returns the message.
+    pub fn as_bytes(&self) -> &[u8] {
+        &self.message
+    }
+
+    fn hash(&self) -> Result<PrehashedMessage<C>, LocalError> {
+        (self.hash)(&self.message)
+    }
+}
+
+impl<C: PrimeCurve> Debug for RawMessage<C> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> Result<(), fmt::Error> {
+        f.debug_struct("RawMessage")
+            .field("message", &self.message)
+            .finish_non_exhaustive()
+    }
+}
+
+impl<P: SchemeParams, Id: PartyId> InteractiveSigningAssociatedData<P, Id> {
+    /// Creates the associated data for evidence verification of InteractiveSigning.
+    pub fn new(
+        message: PrehashedMessage<P::Curve>,
+        public_key_shares: PublicKeyShares<P, Id>,
+        public_aux_infos: PublicAuxInfos<P, Id>,
+    ) -> Result<Self, LocalError> {
+        check_public_shares_and_aux_infos(public_key_shares.as_map(), &public_aux_infos)?;
+
+        Ok(Self {
+            shares: public_key_shares,
+            aux: public_aux_infos,
+            message,
+            raw_message: None,
+        })
+    }
+
+    /// Creates the associated data for evidence verification of InteractiveSigning
+    /// started with [`InteractiveSigning::from_message`].
+    pub fn from_message(
+        raw_message: &[u8],
+        public_key_shares: PublicKeyShares<P, Id>,
+        public_aux_infos: PublicAuxInfos<P, Id>,
+    ) -> Result<Self, LocalError> {
+        Self::from_message_with_digest::<<P::Curve as DigestPrimitive>::Digest>(
+            raw_message,
+            public_key_shares,
+            public_aux_infos,
+        )
+    }
+
+    /// Creates the associated data for evidence verification of InteractiveSigning
+    /// started with [`InteractiveSigning::from_message_with_digest`].
+    pub fn from_message_with_digest<D: Digest>(
+        raw_message: &[u8],
+        public_key_shares: PublicKeyShares<P, Id>,
+        public_aux_infos: PublicAuxInfos<P, Id>,
+    ) -> Result<Self, LocalError> {
+        let raw_message = RawMessage::new::<D>(raw_message);
+        let message = raw_message.hash()?;
+        Ok(Self {
+            raw_message: Some(raw_message),
+            ..Self::new(message, public_key_shares, public_aux_infos)?
+        })
+    }
+
+    /// Creates the associated data for evidence verification of InteractiveSigning
+    /// started with [`InteractiveSigning::new_threshold`].
+    ///
+    /// Only the public parts of `key_share` and `aux_info` are used,
+    /// so they do not have to belong to one of the `signers`.
+    pub fn new_threshold(
+        message: PrehashedMessage<P::Curve>,
+        key_share: &ThresholdKeyShare<P, Id>,
+        aux_info: &AuxInfo<P, Id>,
+        signers: &BTreeSet<Id>,
+    ) -> Result<Self, LocalError> {
+        check_threshold_signers(key_share, signers)?;
+        let public_key_shares = PublicKeyShares::new(key_share.to_public_key_shares(signers)?);
+        let public_aux_infos = aux_info.public().clone().subset(signers)?;
+        Self::new(message, public_key_shares, public_aux_infos)
+    }
+}
+
+#[cfg(feature = "bip32")]
+impl<P: SchemeParams, Id: PartyId> InteractiveSigningAssociatedData<P, Id> {
+    /// Applies the tweak to the public shares,
+    /// for evidence verification of InteractiveSigning with [`InteractiveSigning::with_bip32_tweak`] applied.
+    pub fn with_bip32_tweak(self, tweak: &Bip32Tweak<P>) -> Result<Self, LocalError> {
+        Ok(Self {
+            shares: self.shares.add_tweak(&tweak.to_scalar())?,
+            ..self
+        })
+    }
+
+    /// Applies the tweak for the given derivation path to the public shares,
+    /// for evidence verification of InteractiveSigning with [`InteractiveSigning::with_derivation_path`] applied.
+    pub fn with_derivation_path(self, derivation_path: &DerivationPath) -> Result<Self, LocalError>
+    where
+        VerifyingKey<P::Curve>: PublicTweakable,
+        SigningKey<P::Curve>: SecretTweakable,
+    {
+        let verifying_key = self
+            .shares
+            .as_map()
+            .values()
+            .sum::<Point<P>>()
+            .to_verifying_key()
+            .ok_or_else(|| LocalError::new("The public shares add up to an identity"))?;
+        let tweak = Bip32Tweak::new(&verifying_key, derivation_path)
+            .map_err(|err| LocalError::new(format!("Failed to derive the BIP-32 tweak: {err}")))?;
+        self.with_bip32_tweak(&tweak)
+    }
+}
+
+/// Associated data for Presigning protocol.
+#[derive(Debug, Clone)]
+pub struct PresigningAssociatedData<P: SchemeParams, Id: PartyId> {
+    /// Public shares of all participating nodes.
+    pub shares: PublicKeyShares<P, Id>,
+    /// Auxiliary data of all participating nodes.
+    pub aux: PublicAuxInfos<P, Id>,
+}
+
+impl<P: SchemeParams, Id: PartyId> PresigningAssociatedData<P, Id> {
+    /// Creates the associated data for evidence verification of Presigning.
+    pub fn new(
+        public_key_shares: PublicKeyShares<P, Id>,
+        public_aux_infos: PublicAuxInfos<P, Id>,
+    ) -> Result<Self, LocalError> {
+        check_public_shares_and_aux_infos(public_key_shares.as_map(), &public_aux_infos)?;
+
+        Ok(Self {
+            shares: public_key_shares,
+            aux: public_aux_infos,
+        })
+    }
+}
+
+/// The epoch identifier (see Remark 4.1 in the paper).
+///
+/// The epoch identifier is tied to the key-refresh epoch and the auxiliary key material of the parties for that epoch.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub(crate) struct Epid(HashOutput);
+
+impl Epid {
+    fn new<P: SchemeParams, Id: PartyId>(
+        shared_randomness: &[u8],
+        shares: &PublicKeyShares<P, Id>,
+        aux: &PublicAuxInfos<P, Id>,
+    ) -> Self {
+        let digest = Hasher::<P::Digest>::new_with_dst(b"EPID");
+        let digest = chain_scheme_params::<P, _>(digest);
+        let digest = digest.chain(&shared_randomness).chain(shares).chain(aux);
+
+        Self(digest.finalize(P::SECURITY_BITS))
+    }
+}
+
+/// Checks that an error round message was sent for the presigning instance the evidence refers to.
+fn check_error_round_epid(message_epid: &Epid, epid: &Epid) -> Result<(), ProtocolValidationError> {
+    if message_epid != epid {
+        return Err(ProtocolValidationError::InvalidEvidence(
+            "The error round message was sent for a different presigning instance".into(),
+        ));
+    }
+    Ok(())
+}
+
+impl<P: SchemeParams, Id: PartyId> ProtocolError<Id> for InteractiveSigningError<P, Id> {
+    type AssociatedData = InteractiveSigningAssociatedData<P, Id>;
+
+    fn required_messages(&self) -> RequiredMessages {
+        self.error.required_messages()
+    }
+
+    fn verify_messages_constitute_error(
+        &self,
+        format: &BoxedFormat,
+        guilty_party: &Id,
+        shared_randomness: &[u8],
+        associated_data: &Self::AssociatedData,
+        message: ProtocolMessage,
+        previous_messages: BTreeMap<RoundId, ProtocolMessage>,
+        combined_echos: BTreeMap<RoundId, BTreeMap<Id, EchoBroadcast>>,
+    ) -> Result<(), ProtocolValidationError> {
+        if let Some(raw_message) = &associated_data.raw_message {
+            let message = raw_message
+                .hash()
+                .map_err(|_| ProtocolValidationError::InvalidEvidence("Failed to hash the raw message".into()))?;
+            if message != associated_data.message {
+                return Err(ProtocolValidationError::InvalidEvidence(
+                    "The message is not the hash of the raw message".into(),
+                ));
+            }
+        }
+
+        self.error.verify_messages_constitute_error::<P>(
+            format,
+            guilty_party,
+            shared_randomness,
+            &associated_data.shares,
+            &associated_data.aux,
+            Some(&associated_data.message),
+            message,
+            previous_messages,
+            combined_echos,
+        )
+    }
+}
+
+impl<P: SchemeParams, Id: PartyId> ProtocolError<Id> for PresigningError<P, Id> {
+    type AssociatedData = PresigningAssociatedData<P, Id>;
+
+    fn required_messages(&self) -> RequiredMessages {
+        self.error.required_messages()
+    }
+
+    fn verify_messages_constitute_error(
+        &self,
+        format: &BoxedFormat,
+        guilty_party: &Id,
+        shared_randomness: &[u8],
+        associated_data: &Self::AssociatedData,
+        message: ProtocolMessage,
+        previous_messages: BTreeMap<RoundId, ProtocolMessage>,
+        combined_echos: BTreeMap<RoundId, BTreeMap<Id, EchoBroadcast>>,
+    ) -> Result<(), ProtocolValidationError> {
+        self.error.verify_messages_constitute_error::<P>(
+            format,
+            guilty_party,
+            shared_randomness,
+            &associated_data.shares,
+            &associated_data.aux,
+            None,
+            message,
+            previous_messages,
+            combined_echos,
+        )
+    }
+}
+
+/// An entry point for the [`InteractiveSigningProtocol`].
+#[derive(Debug, Clone)]
+pub struct InteractiveSigning<P, Id>
+where
+    P: SchemeParams,
+    Id: PartyId,
+{
+    key_share: Arc<KeyShare<P, Id>>,
+    aux_info: PrecomputedAuxInfo<P, Id>,
+    message: PrehashedMessage<P::Curve>,
+}
+
+impl<P: SchemeParams, Id: PartyId> InteractiveSigning<P, Id> {
+    /// Creates a new entry point given a share of the secret key.
+    ///
+    /// The key share can be given either as [`KeyShare`], or as `Arc<KeyShare>` to share it between sessions,
+    /// and the auxiliary info either as [`AuxInfo`], or as [`PrecomputedAuxInfo`]
+    /// to avoid repeating the precomputation in every session.
+    pub fn new(
+        message: PrehashedMessage<P::Curve>,
+        key_share: impl Into<Arc<KeyShare<P, Id>>>,
+        aux_info: impl Into<PrecomputedAuxInfo<P, Id>>,
+    ) -> Result<Self, LocalError> {
+        let key_share = key_share.into();
+        let aux_info = aux_info.into();
+        check_key_share_and_aux_info(&key_share, &aux_info)?;
+        Ok(Self {
+            key_share,
+            aux_info,
+            message,
+        })
+    }
+
+    /// Creates a new entry point given a share of the secret key and a message that is not prehashed.
+    ///
+    /// The message is hashed with the digest associated with the curve, the same way as in ECDSA.
+    pub fn from_message(
+        raw_message: &[u8],
+        key_share: impl Into<Arc<KeyShare<P, Id>>>,
+        aux_info: impl Into<PrecomputedAuxInfo<P, Id>>,
+    ) -> Result<Self, LocalError> {
+        Self::from_message_with_digest::<<P::Curve as DigestPrimitive>::Digest>(raw_message, key_share, aux_info)
+    }
+
+    /// Creates a new entry point given a share of the secret key and a message that is not prehashed.
+    ///
+    /// The message is hashed with the digest `D`, the output of which is truncated to the size of the curve order
+    /// if necessary, the same way as in ECDSA.
+    pub fn from_message_with_digest<D: Digest>(
+        raw_message: &[u8],
+        key_share: impl Into<Arc<KeyShare<P, Id>>>,
+        aux_info: impl Into<PrecomputedAuxInfo<P, Id>>,
+    ) -> Result<Self, LocalError> {
+        Self::new(hash_message::<P::Curve, D>(raw_message)?, key_share, aux_info)
+    }
+
+    /// Creates a new entry point given a threshold share of the secret key
+    /// and the set of parties performing the signing (which must include the owner of the key share).
+    ///
+    /// The number of `signers` must be equal to the threshold
+    /// (if the holders have several shares, their total weight must reach the threshold with every signer needed).
+    pub fn new_threshold(
+        message: PrehashedMessage<P::Curve>,
+        key_share: &ThresholdKeyShare<P, Id>,
+        aux_info: &AuxInfo<P, Id>,
+        signers: &BTreeSet<Id>,
+    ) -> Result<Self, LocalError> {
+        check_threshold_signers(key_share, signers)?;
+        if !signers.contains(&key_share.owner) {
+            return Err(LocalError::new("The signers must include the owner of the key share"));
+        }
+        Self::new(
+            message,
+            key_share.to_key_share(signers)?,
+            aux_info.clone().subset(signers)?,
+        )
+    }
+
+    /// Creates a new entry point given a threshold share of the secret key,
+    /// the precomputed auxiliary info, and the set of parties performing the signing
+    /// (which must include the owner of the key share).
+    ///
+    /// The same requirements on `signers` apply as in [`Self::new_threshold`].
+    pub fn new_threshold_precomputed(
+        message: PrehashedMessage<P::Curve>,
+        key_share: &ThresholdKeyShare<P, Id>,
+        aux_info: &PrecomputedAuxInfo<P, Id>,
+        signers: &BTreeSet<Id>,
+    ) -> Result<Self, LocalError> {
+        check_threshold_signers(key_share, signers)?;
+        if !signers.contains(&key_share.owner) {
+            return Err(LocalError::new("The signers must include the owner of the key share"));
+        }
+        Self::new(message, key_share.to_key_share(signers)?, aux_info.subset(signers)?)
+    }
+}
+
+#[cfg(feature = "bip32")]
+impl<P: SchemeParams, Id: PartyId> InteractiveSigning<P, Id> {
+    /// Makes the protocol sign under the child key obtained by applying the given tweak.
+    ///
+    /// The tweak is applied to the share of one of the parties and to the public shares,
+    /// so there is no need to derive child key shares.
+    /// Tweaks applied in succession are accumulated.
+    pub fn with_bip32_tweak(self, tweak: &Bip32Tweak<P>) -> Result<Self, LocalError> {
+        Ok(Self {
+            key_share: Arc::new(Arc::unwrap_or_clone(self.key_share).add_tweak(&tweak.to_scalar())?),
+            ..self
+        })
+    }
+
+    /// Makes the protocol sign under the child key for the given derivation path.
+    ///
+    /// If several messages are signed under the same path, it is more efficient to calculate [`Bip32Tweak`] once
+    /// and use [`Self::with_bip32_tweak`].
+    pub fn with_derivation_path(self, derivation_path: &DerivationPath) -> Result<Self, LocalError>
+    where
+        VerifyingKey<P::Curve>: PublicTweakable,
+        SigningKey<P::Curve>: SecretTweakable,
+    {
+        let tweak = Bip32Tweak::new(&self.key_share.verifying_key(), derivation_path)
+            .map_err(|err| LocalError::new(format!("Failed to derive the BIP-32 tweak: {err}")))?;
+        self.with_bip32_tweak(&tweak)
+    }
+}
+
+impl<P: SchemeParams, Id: PartyId> EntryPoint<Id> for InteractiveSigning<P, Id> {
+    type Protocol = InteractiveSigningProtocol<P, Id>;
+
+    fn entry_round_id() -> RoundId {
+        1.into()
+    }
+
+    fn make_round(
+        self,
+        rng: &mut dyn CryptoRngCore,
+        shared_randomness: &[u8],
+        id: &Id,
+    ) -> Result<BoxedRound<Id, Self::Protocol>, LocalError> {
+        let target = SignMessage {
+            scalar_message: Scalar::from_reduced_bytes(self.message),
+        };
+        let round1 = make_round1(rng, shared_randomness, id, self.key_share, self.aux_info, target)?;
+        Ok(BoxedRound::new_dynamic(round1))
+    }
+}
+
+/// An entry point for the [`PresigningProtocol`].
 #[derive(Debug, Clone)]
-pub struct InteractiveSigning<P, Id>
+pub struct Presigning<P, Id>
 where
     P: SchemeParams,
     Id: PartyId,
 {
-    key_share: KeyShare<P, Id>,
-    aux_info: AuxInfo<P, Id>,
-    message: PrehashedMessage<P::Curve>,
+    key_share: Arc<KeyShare<P, Id>>,
+    aux_info: PrecomputedAuxInfo<P, Id>,
 }
 
-impl<P: SchemeParams, Id: PartyId> InteractiveSigning<P, Id> {
+impl<P: SchemeParams, Id: PartyId> Presigning<P, Id> {
     /// Creates a new entry point given a share of the secret key.
+    ///
+    /// The key share can be given either as [`KeyShare`], or as `Arc<KeyShare>` to share it between sessions,
+    /// and the auxiliary info either as [`AuxInfo`], or as [`PrecomputedAuxInfo`]
+    /// to avoid repeating the precomputation in every session.
     pub fn new(
-        message: PrehashedMessage<P::Curve>,
-        key_share: KeyShare<P, Id>,
-        aux_info: AuxInfo<P, Id>,
+        key_share: impl Into<Arc<KeyShare<P, Id>>>,
+        aux_info: impl Into<PrecomputedAuxInfo<P, Id>>,
     ) -> Result<Self, LocalError> {
-        if key_share.owner() != aux_info.owner() {
-            return Err(LocalError::new(
-                "The key share and the auxiliary info must have secret parts belonging to the same party",
-            ));
-        }
-
-        let key_share_keys = key_share.public_shares().keys().collect::<BTreeSet<_>>();
-        let aux_info_keys = aux_info.public().as_map().keys().collect::<BTreeSet<_>>();
-
-        if key_share_keys != aux_info_keys {
-            tracing::info!("{:?}", key_share_keys);
-            tracing::info!("{:?}", aux_info_keys);
-            return Err(LocalError::new(
-                "The key share and the auxiliary info must have information for the same set of parties",
-            ));
-        }
-
-        Ok(Self {
-            key_share,
-            aux_info,
-            message,
-        })
+        let key_share = key_share.into();
+        let aux_info = aux_info.into();
+        check_key_share_and_aux_info(&key_share, &aux_info)?;
+        Ok(Self { key_share, aux_info })
     }
 }
 
-impl<P: SchemeParams, Id: PartyId> EntryPoint<Id> for InteractiveSigning<P, Id> {
-    type Protocol = InteractiveSigningProtocol<P, Id>;
+impl<P: SchemeParams, Id: PartyId> EntryPoint<Id> for Presigning<P, Id> {
+    type Protocol = PresigningProtocol<P, Id>;
 
     fn entry_round_id() -> RoundId {
         1.into()
@@ -971,96 +1561,278 @@ impl<P: SchemeParams, Id: PartyId> EntryPoint<Id> for InteractiveSigning<P, Id>
         shared_randomness: &[u8],
         id: &Id,
     ) -> Result<BoxedRound<Id, Self::Protocol>, LocalError> {
-        let key_share = self.key_share;
-        let aux_info = self.aux_info;
+        let round1 = make_round1(
+            rng,
+            shared_randomness,
+            id,
+            self.key_share,
+            self.aux_info,
+            ReturnPresignature,
+        )?;
+        Ok(BoxedRound::new_dynamic(round1))
+    }
+}
 
-        if id != key_share.owner() || id != aux_info.owner() {
-            return Err(LocalError::new(
-                "ID mismatch between the signer, the key share and the aux info",
-            ));
+pub(super) fn check_key_share_and_aux_info<P: SchemeParams, Id: PartyId>(
+    key_share: &KeyShare<P, Id>,
+    aux_info: &PrecomputedAuxInfo<P, Id>,
+) -> Result<(), LocalError> {
+    if key_share.owner() != aux_info.owner() {
+        return Err(LocalError::new(
+            "The key share and the auxiliary info must have secret parts belonging to the same party",
+        ));
+    }
+
+    check_public_shares_and_aux_infos(key_share.public_shares(), aux_info.public())
+}
+
+/// Checks that the public key shares and the public auxiliary info are given for the same set of parties.
+pub(super) fn check_public_shares_and_aux_infos<P: SchemeParams, Id: PartyId>(
+    public_shares: &BTreeMap<Id, Point<P>>,
+    public_aux_infos: &PublicAuxInfos<P, Id>,
+) -> Result<(), LocalError> {
+    if !public_shares.keys().eq(public_aux_infos.as_map().keys()) {
+        return Err(LocalError::new(
+            "The key share and the auxiliary info must have information for the same set of parties",
+        ));
+    }
+
+    Ok(())
+}
+
+fn hash_message<C: PrimeCurve, D: Digest>(raw_message: &[u8]) -> Result<PrehashedMessage<C>, LocalError> {
+    bits2field::<C>(&D::digest(raw_message))
+        .map_err(|_| LocalError::new("The output of the digest is too short for the curve"))
+}
+
+pub(super) fn check_threshold_signers<P: SchemeParams, Id: PartyId>(
+    key_share: &ThresholdKeyShare<P, Id>,
+    signers: &BTreeSet<Id>,
+) -> Result<(), LocalError> {
+    if let Some(access_structure) = key_share.access_structure() {
+        if signers.len() != key_share.threshold() || !access_structure.is_authorized(signers) {
+            return Err(LocalError::new(format!(
+                "The signers must be an authorized set of the size equal to the threshold ({})",
+                key_share.threshold()
+            )));
         }
+        return Ok(());
+    }
+
+    // If the holders have several shares, there may be no set of signers with the total weight equal to the threshold,
+    // so we require the set to be minimal instead: the total weight must reach the threshold,
+    // but must not reach it without any of the signers. Without weights, this is the same as
+    // the number of signers being equal to the threshold.
+    let weights = signers.iter().map(|id| key_share.weight(id)).collect::<Vec<_>>();
+    if weights.contains(&0) {
+        return Err(LocalError::new("All the signers must be holders of the shares"));
+    }
+    let total_weight = weights.iter().sum::<usize>();
+    let min_weight = weights.iter().copied().min().unwrap_or(0);
+    if total_weight < key_share.threshold() || total_weight - min_weight >= key_share.threshold() {
+        return Err(LocalError::new(format!(
+            "The total weight of signers ({total_weight}) must be equal to the threshold ({}), \
+            or exceed it by less than the smallest weight of a signer",
+            key_share.threshold()
+        )));
+    }
+    Ok(())
+}
 
-        let all_ids = key_share.public_shares().keys().cloned().collect::<BTreeSet<_>>();
-        let other_ids = all_ids.clone().without(id);
+pub(super) fn make_round1<P, Id, T>(
+    rng: &mut dyn CryptoRngCore,
+    shared_randomness: &[u8],
+    id: &Id,
+    key_share: Arc<KeyShare<P, Id>>,
+    aux_info: PrecomputedAuxInfo<P, Id>,
+    target: T,
+) -> Result<Round1<P, Id, T>, LocalError>
+where
+    P: SchemeParams,
+    Id: PartyId,
+    T: PresigningTarget<P, Id>,
+{
+    if id != key_share.owner() || id != aux_info.owner() {
+        return Err(LocalError::new(
+            "ID mismatch between the signer, the key share and the aux info",
+        ));
+    }
 
-        let epid = Epid::new::<P, Id>(
-            shared_randomness,
-            &InteractiveSigningAssociatedData {
-                shares: key_share.public().clone(),
-                aux: aux_info.public().clone(),
-                message: self.message.clone(),
-            },
-        );
+    let all_ids = key_share.public_shares().keys().cloned().collect::<BTreeSet<_>>();
+    let other_ids = all_ids.clone().without(id);
 
-        let aux_info = aux_info.into_precomputed();
+    let epid = Epid::new::<P, Id>(shared_randomness, key_share.public(), aux_info.public());
 
-        // The share of an ephemeral scalar
-        let k = Secret::init_with(|| Scalar::random(rng));
-        // The share of the mask used to generate the inverse of the ephemeral scalar
-        let gamma = Secret::init_with(|| Scalar::random(rng));
+    // The share of an ephemeral scalar
+    let k = Secret::init_with(|| Scalar::random(rng));
+    // The share of the mask used to generate the inverse of the ephemeral scalar
+    let gamma = Secret::init_with(|| Scalar::random(rng));
 
-        let pk = aux_info.secret_aux.paillier_sk.public_key();
+    let pk = aux_info.secret_aux().paillier_sk.public_key();
 
-        let nu = Randomizer::<P::Paillier>::random(rng, pk);
-        let cap_g = Ciphertext::new_with_randomizer(pk, &secret_signed_from_scalar::<P>(&gamma), &nu);
+    let nu = Randomizer::<P::Paillier>::random(rng, pk);
+    let cap_g = Ciphertext::new_with_randomizer(pk, &secret_signed_from_scalar::<P>(&gamma), &nu);
 
-        let rho = Randomizer::<P::Paillier>::random(rng, pk);
-        let cap_k = Ciphertext::new_with_randomizer(pk, &secret_signed_from_scalar::<P>(&k), &rho);
+    let rho = Randomizer::<P::Paillier>::random(rng, pk);
+    let cap_k = Ciphertext::new_with_randomizer(pk, &secret_signed_from_scalar::<P>(&k), &rho);
 
-        let y = Secret::init_with(|| Scalar::random(rng));
-        let cap_y = y.mul_by_generator();
+    let y = Secret::init_with(|| Scalar::random(rng));
+    let cap_y = y.mul_by_generator();
 
-        let a = Secret::init_with(|| Scalar::random(rng));
-        let b = Secret::init_with(|| Scalar::random(rng));
-        let cap_a1 = a.mul_by_generator();
-        let cap_a2 = cap_y * &a + k.mul_by_generator();
-        let cap_b1 = b.mul_by_generator();
-        let cap_b2 = cap_y * &b + gamma.mul_by_generator();
+    let a = Secret::init_with(|| Scalar::random(rng));
+    let b = Secret::init_with(|| Scalar::random(rng));
+    let cap_a1 = a.mul_by_generator();
+    let cap_a2 = cap_y * &a + k.mul_by_generator();
+    let cap_b1 = b.mul_by_generator();
+    let cap_b2 = cap_y * &b + gamma.mul_by_generator();
 
-        let r1_echo_broadcast = Round1EchoBroadcast {
-            cap_k: cap_k.to_wire(),
-            cap_g: cap_g.to_wire(),
-            cap_y,
-            cap_a1,
-            cap_a2,
-            cap_b1,
-            cap_b2,
-        };
+    let r1_echo_broadcast = Round1EchoBroadcast {
+        cap_k: cap_k.to_wire(),
+        cap_g: cap_g.to_wire(),
+        cap_y,
+        cap_a1,
+        cap_a2,
+        cap_b1,
+        cap_b2,
+    };
 
-        Ok(BoxedRound::new_dynamic(Round1 {
-            context: Context {
-                scalar_message: Scalar::from_reduced_bytes(self.message),
-                epid,
-                my_id: id.clone(),
-                other_ids,
-                all_ids,
-                key_share,
-                aux_info,
-                k,
-                gamma,
-                a,
-                b,
-                rho,
-                nu,
-            },
-            r1_echo_broadcast,
-        }))
+    Ok(Round1 {
+        context: Context {
+            target,
+            epid,
+            my_id: id.clone(),
+            other_ids,
+            all_ids,
+            key_share,
+            aux_info,
+            k,
+            gamma,
+            a,
+            b,
+            rho,
+            nu,
+        },
+        r1_echo_broadcast,
+    })
+}
+
+/// Determines what happens after the presigning rounds (1-3) succeed:
+/// the resulting presignature is either returned, or used right away to sign a message.
+pub(super) trait PresigningTarget<P: SchemeParams, Id: PartyId>: 'static + Debug + Send + Sync + Sized {
+    /// The protocol the presigning rounds are a part of.
+    type Protocol: Protocol<Id>;
+
+    /// Returns the transition info for Round 3.
+    fn round3_transition_info() -> TransitionInfo;
+
+    /// Wraps an error of the presigning rounds into the error of the target protocol.
+    fn protocol_error(&self, error: Error<Id>) -> <Self::Protocol as Protocol<Id>>::ProtocolError;
+
+    /// Finalizes Round 3 given the created presignature.
+    fn finalize_presigning(
+        context: Context<P, Id, Self>,
+        presignature: Presignature<P, Id>,
+    ) -> Result<FinalizeOutcome<Id, Self::Protocol>, LocalError>;
+}
+
+/// A [`PresigningTarget`] that uses the presignature right away to sign a message in Round 4.
+pub(super) trait SigningTarget<P: SchemeParams, Id: PartyId>: PresigningTarget<P, Id> {
+    /// Returns the message to sign.
+    fn scalar_message(&self) -> &Scalar<P>;
+
+    /// Wraps the created signature into the result of the target protocol.
+    fn signing_result(signature: RecoverableSignature<P>) -> <Self::Protocol as Protocol<Id>>::Result;
+}
+
+/// Creates Round 4 (signing) given the context of the presigning rounds and the created presignature.
+pub(super) fn make_round4<P, Id, T>(
+    context: Context<P, Id, T>,
+    presignature: Presignature<P, Id>,
+) -> FinalizeOutcome<Id, T::Protocol>
+where
+    P: SchemeParams,
+    Id: PartyId,
+    T: SigningTarget<P, Id>,
+{
+    let sigma = presignature.signature_share(context.target.scalar_message());
+    FinalizeOutcome::AnotherRound(BoxedRound::new_dynamic(Round4 {
+        context,
+        presignature,
+        sigma,
+    }))
+}
+
+/// Sign the given message using the presignature (used in [`InteractiveSigningProtocol`]).
+#[derive(Debug)]
+pub(super) struct SignMessage<P: SchemeParams> {
+    scalar_message: Scalar<P>,
+}
+
+impl<P: SchemeParams, Id: PartyId> PresigningTarget<P, Id> for SignMessage<P> {
+    type Protocol = InteractiveSigningProtocol<P, Id>;
+
+    fn round3_transition_info() -> TransitionInfo {
+        TransitionInfo::new_linear(3).with_children([5, 6].into())
+    }
+
+    fn protocol_error(&self, error: Error<Id>) -> InteractiveSigningError<P, Id> {
+        error.into()
+    }
+
+    fn finalize_presigning(
+        context: Context<P, Id, Self>,
+        presignature: Presignature<P, Id>,
+    ) -> Result<FinalizeOutcome<Id, Self::Protocol>, LocalError> {
+        Ok(make_round4(context, presignature))
+    }
+}
+
+impl<P: SchemeParams, Id: PartyId> SigningTarget<P, Id> for SignMessage<P> {
+    fn scalar_message(&self) -> &Scalar<P> {
+        &self.scalar_message
+    }
+
+    fn signing_result(signature: RecoverableSignature<P>) -> RecoverableSignature<P> {
+        signature
+    }
+}
+
+/// Return the presignature as the result (used in [`PresigningProtocol`]).
+#[derive(Debug)]
+pub(super) struct ReturnPresignature;
+
+impl<P: SchemeParams, Id: PartyId> PresigningTarget<P, Id> for ReturnPresignature {
+    type Protocol = PresigningProtocol<P, Id>;
+
+    fn round3_transition_info() -> TransitionInfo {
+        TransitionInfo::new_linear_terminating(3).with_children([5, 6].into())
+    }
+
+    fn protocol_error(&self, error: Error<Id>) -> PresigningError<P, Id> {
+        error.into()
+    }
+
+    fn finalize_presigning(
+        _context: Context<P, Id, Self>,
+        presignature: Presignature<P, Id>,
+    ) -> Result<FinalizeOutcome<Id, Self::Protocol>, LocalError> {
+        Ok(FinalizeOutcome::Result(presignature))
     }
 }
 
 #[derive(Debug)]
-pub(super) struct Context<P, Id>
+pub(super) struct Context<P, Id, T>
 where
     P: SchemeParams,
     Id: PartyId,
 {
-    scalar_message: Scalar<P>,
+    target: T,
     pub(super) epid: Epid,
     pub(super) my_id: Id,
     other_ids: BTreeSet<Id>,
     all_ids: BTreeSet<Id>,
-    key_share: KeyShare<P, Id>,
-    pub(super) aux_info: AuxInfoPrecomputed<P, Id>,
+    key_share: Arc<KeyShare<P, Id>>,
+    pub(super) aux_info: PrecomputedAuxInfo<P, Id>,
     k: Secret<Scalar<P>>,
     pub(super) gamma: Secret<Scalar<P>>,
     a: Secret<Scalar<P>>,
@@ -1069,7 +1841,7 @@ where
     nu: Randomizer<P::Paillier>,
 }
 
-impl<P, Id> Context<P, Id>
+impl<P, Id, T> Context<P, Id, T>
 where
     P: SchemeParams,
     Id: PartyId,
@@ -1079,17 +1851,17 @@ where
     }
 
     pub fn public_aux(&self, i: &Id) -> Result<&PublicAuxInfoPrecomputed<P>, LocalError> {
-        self.aux_info.public_aux.safe_get("public aux", i)
+        self.aux_info.public_aux().safe_get("public aux", i)
     }
 }
 
 #[derive(Debug)]
-struct Round1<P, Id>
+pub(super) struct Round1<P, Id, T>
 where
     P: SchemeParams,
     Id: PartyId,
 {
-    context: Context<P, Id>,
+    context: Context<P, Id, T>,
     r1_echo_broadcast: Round1EchoBroadcast<P>,
 }
 
@@ -1122,8 +1894,8 @@ pub(super) struct Round1Payload<P: SchemeParams> {
     cap_b2: Point<P>,
 }
 
-impl<P: SchemeParams, Id: PartyId> Round<Id> for Round1<P, Id> {
-    type Protocol = InteractiveSigningProtocol<P, Id>;
+impl<P: SchemeParams, Id: PartyId, T: PresigningTarget<P, Id>> Round<Id> for Round1<P, Id, T> {
+    type Protocol = T::Protocol;
 
     fn transition_info(&self) -> TransitionInfo {
         TransitionInfo::new_linear(1)
@@ -1148,7 +1920,7 @@ impl<P: SchemeParams, Id: PartyId> Round<Id> for Round1<P, Id> {
         destination: &Id,
     ) -> Result<(DirectMessage, Option<Artifact>), LocalError> {
         let aux = (&self.context.epid, &self.context.my_id);
-        let pk = self.context.aux_info.secret_aux.paillier_sk.public_key();
+        let pk = self.context.aux_info.secret_aux().paillier_sk.public_key();
 
         let psi0 = EncElgProof::new(
             rng,
@@ -1169,7 +1941,7 @@ impl<P: SchemeParams, Id: PartyId> Round<Id> for Round1<P, Id> {
             &self
                 .context
                 .aux_info
-                .public_aux
+                .public_aux()
                 .safe_get("public aux", destination)?
                 .rp_params,
             &aux,
@@ -1192,7 +1964,7 @@ impl<P: SchemeParams, Id: PartyId> Round<Id> for Round1<P, Id> {
             &self
                 .context
                 .aux_info
-                .public_aux
+                .public_aux()
                 .safe_get("public aux", destination)?
                 .rp_params,
             &aux,
@@ -1235,7 +2007,9 @@ impl<P: SchemeParams, Id: PartyId> Round<Id> for Round1<P, Id> {
             &public_aux.rp_params,
             &aux,
         ) {
-            return Err(ReceiveError::protocol(Error::R1EncElg0Failed.into()));
+            return Err(ReceiveError::protocol(
+                self.context.target.protocol_error(Error::R1EncElg0Failed),
+            ));
         }
 
         if !direct_message.psi1.verify(
@@ -1249,7 +2023,9 @@ impl<P: SchemeParams, Id: PartyId> Round<Id> for Round1<P, Id> {
             &public_aux.rp_params,
             &aux,
         ) {
-            return Err(ReceiveError::protocol(Error::R1EncElg1Failed.into()));
+            return Err(ReceiveError::protocol(
+                self.context.target.protocol_error(Error::R1EncElg1Failed),
+            ));
         }
 
         Ok(Payload::new(Round1Payload::<P> {
@@ -1270,7 +2046,7 @@ impl<P: SchemeParams, Id: PartyId> Round<Id> for Round1<P, Id> {
     ) -> Result<FinalizeOutcome<Id, Self::Protocol>, LocalError> {
         let mut payloads = payloads.downcast_all::<Round1Payload<P>>()?;
 
-        let pk = self.context.aux_info.secret_aux.paillier_sk.public_key();
+        let pk = self.context.aux_info.secret_aux().paillier_sk.public_key();
         let my_payload = Round1Payload {
             cap_k: self.r1_echo_broadcast.cap_k.to_precomputed(pk),
             cap_a1: self.r1_echo_broadcast.cap_a1,
@@ -1346,7 +2122,7 @@ impl<P: SchemeParams, Id: PartyId> Round<Id> for Round1<P, Id> {
         let cap_x = self.context.public_share(&self.context.my_id)?;
 
         let aux = (&self.context.epid, &self.context.my_id);
-        let pk = self.context.aux_info.secret_aux.paillier_sk.public_key();
+        let pk = self.context.aux_info.secret_aux().paillier_sk.public_key();
 
         let mut cap_ds = BTreeMap::new();
         let mut cap_fs = BTreeMap::new();
@@ -1447,8 +2223,8 @@ impl<P: SchemeParams, Id: PartyId> Round<Id> for Round1<P, Id> {
 }
 
 #[derive(Debug)]
-pub(super) struct Round2<P: SchemeParams, Id: PartyId> {
-    pub(super) context: Context<P, Id>,
+pub(super) struct Round2<P: SchemeParams, Id: PartyId, T> {
+    pub(super) context: Context<P, Id, T>,
     betas: BTreeMap<Id, SecretSigned<<P::Paillier as PaillierParams>::Uint>>,
     rs: BTreeMap<Id, Randomizer<P::Paillier>>,
     ss: BTreeMap<Id, Randomizer<P::Paillier>>,
@@ -1495,8 +2271,8 @@ struct Round2Payload<P: SchemeParams, Id: PartyId> {
     hat_cap_fs: BTreeMap<Id, Ciphertext<P::Paillier>>,
 }
 
-impl<P: SchemeParams, Id: PartyId> Round<Id> for Round2<P, Id> {
-    type Protocol = InteractiveSigningProtocol<P, Id>;
+impl<P: SchemeParams, Id: PartyId, T: PresigningTarget<P, Id>> Round<Id> for Round2<P, Id, T> {
+    type Protocol = T::Protocol;
 
     fn transition_info(&self) -> TransitionInfo {
         TransitionInfo::new_linear(2)
@@ -1558,15 +2334,21 @@ impl<P: SchemeParams, Id: PartyId> Round<Id> for Round2<P, Id> {
         let expected_ids = self.context.all_ids.clone().without(from);
 
         if normal_broadcast.cap_ds.keys().cloned().collect::<BTreeSet<_>>() != expected_ids {
-            return Err(ReceiveError::protocol(Error::R2WrongIdsD.into()));
+            return Err(ReceiveError::protocol(
+                self.context.target.protocol_error(Error::R2WrongIdsD),
+            ));
         }
 
         if echo_broadcast.cap_fs.keys().cloned().collect::<BTreeSet<_>>() != expected_ids {
-            return Err(ReceiveError::protocol(Error::R2WrongIdsF.into()));
+            return Err(ReceiveError::protocol(
+                self.context.target.protocol_error(Error::R2WrongIdsF),
+            ));
         }
 
         if normal_broadcast.psis.keys().cloned().collect::<BTreeSet<_>>() != expected_ids {
-            return Err(ReceiveError::protocol(Error::R2WrongIdsPsi.into()));
+            return Err(ReceiveError::protocol(
+                self.context.target.protocol_error(Error::R2WrongIdsPsi),
+            ));
         }
 
         let sender_payload = self.r1_payloads.safe_get("Round 1 payloads", from)?;
@@ -1593,7 +2375,9 @@ impl<P: SchemeParams, Id: PartyId> Round<Id> for Round2<P, Id> {
                 &aux,
             ) {
                 return Err(ReceiveError::protocol(
-                    Error::R2AffGPsiFailed { failed_for: id.clone() }.into(),
+                    self.context
+                        .target
+                        .protocol_error(Error::R2AffGPsiFailed { failed_for: id.clone() }),
                 ));
             }
 
@@ -1631,7 +2415,9 @@ impl<P: SchemeParams, Id: PartyId> Round<Id> for Round2<P, Id> {
                 &aux,
             ) {
                 return Err(ReceiveError::protocol(
-                    Error::R2AffGHatPsiFailed { failed_for: id.clone() }.into(),
+                    self.context
+                        .target
+                        .protocol_error(Error::R2AffGHatPsiFailed { failed_for: id.clone() }),
                 ));
             }
 
@@ -1649,15 +2435,17 @@ impl<P: SchemeParams, Id: PartyId> Round<Id> for Round2<P, Id> {
             },
             &aux,
         ) {
-            return Err(ReceiveError::protocol(Error::R2ElogFailed.into()));
+            return Err(ReceiveError::protocol(
+                self.context.target.protocol_error(Error::R2ElogFailed),
+            ));
         }
 
         let alpha_uint = cap_ds
             .safe_get("`D` map", &self.context.my_id)?
-            .decrypt(&self.context.aux_info.secret_aux.paillier_sk);
+            .decrypt(&self.context.aux_info.secret_aux().paillier_sk);
         let hat_alpha_uint = hat_cap_ds
             .safe_get("`\\hat{D}` map", &self.context.my_id)?
-            .decrypt(&self.context.aux_info.secret_aux.paillier_sk);
+            .decrypt(&self.context.aux_info.secret_aux().paillier_sk);
 
         let alpha = secret_scalar_from_signed::<P>(&alpha_uint);
         let hat_alpha = secret_scalar_from_signed::<P>(&hat_alpha_uint);
@@ -1800,8 +2588,8 @@ impl<P: SchemeParams, Id: PartyId> Round<Id> for Round2<P, Id> {
 }
 
 #[derive(Debug)]
-pub(super) struct Round3<P: SchemeParams, Id: PartyId> {
-    pub(super) context: Context<P, Id>,
+pub(super) struct Round3<P: SchemeParams, Id: PartyId, T> {
+    pub(super) context: Context<P, Id, T>,
     pub(super) cap_k: Ciphertext<P::Paillier>,
     pub(super) cap_gamma_combined: Point<P>,
     pub(super) chi: Secret<Scalar<P>>,
@@ -1841,11 +2629,11 @@ pub(super) struct Round3Payload<P: SchemeParams> {
     pub(super) cap_s: Point<P>,
 }
 
-impl<P: SchemeParams, Id: PartyId> Round<Id> for Round3<P, Id> {
-    type Protocol = InteractiveSigningProtocol<P, Id>;
+impl<P: SchemeParams, Id: PartyId, T: PresigningTarget<P, Id>> Round<Id> for Round3<P, Id, T> {
+    type Protocol = T::Protocol;
 
     fn transition_info(&self) -> TransitionInfo {
-        TransitionInfo::new_linear(3).with_children([5, 6].into())
+        T::round3_transition_info()
     }
 
     fn communication_info(&self) -> CommunicationInfo<Id> {
@@ -1893,7 +2681,9 @@ impl<P: SchemeParams, Id: PartyId> Round<Id> for Round3<P, Id> {
             },
             &aux,
         ) {
-            return Err(ReceiveError::protocol(Error::R3ElogFailed.into()));
+            return Err(ReceiveError::protocol(
+                self.context.target.protocol_error(Error::R3ElogFailed),
+            ));
         }
 
         Ok(Payload::new(Round3Payload {
@@ -1970,30 +2760,28 @@ impl<P: SchemeParams, Id: PartyId> Round<Id> for Round3<P, Id> {
         let delta_combined_inv = Option::<Scalar<P>>::from(delta_combined.invert())
             .ok_or_else(|| LocalError::new("The combined delta is not invertible"))?;
 
-        let presigning_data = PresigningData {
-            cap_gamma_combined: self.cap_gamma_combined,
+        let presignature = Presignature {
+            owner: self.context.my_id.clone(),
             tilde_k: &self.context.k * delta_combined_inv,
             tilde_chi: self.chi * delta_combined_inv,
-            tilde_cap_deltas: payloads.map_values_ref(|payload| payload.cap_delta * delta_combined_inv),
-            tilde_cap_ss: payloads.map_values_ref(|payload| payload.cap_s * delta_combined_inv),
+            transcript: PresignatureTranscript {
+                epid: self.context.epid.clone(),
+                verifying_key: cap_x,
+                cap_gammas: self.cap_gammas.into(),
+                deltas: deltas.into(),
+                cap_deltas: payloads.map_values_ref(|payload| payload.cap_delta).into(),
+                cap_ss: payloads.map_values_ref(|payload| payload.cap_s).into(),
+            },
         };
 
-        let nonce = presigning_data.cap_gamma_combined.x_coordinate();
-        let sigma = *(&presigning_data.tilde_k * self.context.scalar_message + &presigning_data.tilde_chi * nonce)
-            .expose_secret();
-
-        Ok(FinalizeOutcome::AnotherRound(BoxedRound::new_dynamic(Round4 {
-            context: self.context,
-            presigning_data,
-            sigma,
-        })))
+        T::finalize_presigning(self.context, presignature)
     }
 }
 
 #[derive(Debug)]
-struct Round4<P: SchemeParams, Id: PartyId> {
-    context: Context<P, Id>,
-    presigning_data: PresigningData<P, Id>,
+pub(super) struct Round4<P: SchemeParams, Id: PartyId, T> {
+    context: Context<P, Id, T>,
+    presignature: Presignature<P, Id>,
     sigma: Scalar<P>,
 }
 
@@ -2007,8 +2795,8 @@ struct Round4Payload<P: SchemeParams> {
     sigma: Scalar<P>,
 }
 
-impl<P: SchemeParams, Id: PartyId> Round<Id> for Round4<P, Id> {
-    type Protocol = InteractiveSigningProtocol<P, Id>;
+impl<P: SchemeParams, Id: PartyId, T: SigningTarget<P, Id>> Round<Id> for Round4<P, Id, T> {
+    type Protocol = T::Protocol;
 
     fn transition_info(&self) -> TransitionInfo {
         TransitionInfo::new_linear_terminating(4).with_siblings([5, 6].into())
@@ -2038,16 +2826,15 @@ impl<P: SchemeParams, Id: PartyId> Round<Id> for Round4<P, Id> {
             .normal_broadcast
             .deserialize::<Round4NormalBroadcast<P>>(format)?;
 
-        let nonce = self.presigning_data.cap_gamma_combined.x_coordinate();
-        let tilde_cap_delta = self
-            .presigning_data
-            .tilde_cap_deltas
-            .safe_get("`\\tilde{Delta}` map", from)?;
-        let tilde_cap_s = self.presigning_data.tilde_cap_ss.safe_get("`\\tilde{S}` map", from)?;
-        if self.presigning_data.cap_gamma_combined * normal_broadcast.sigma
-            != tilde_cap_delta * self.context.scalar_message + tilde_cap_s * nonce
-        {
-            return Err(ReceiveError::protocol(Error::R4InvalidSignatureShare.into()));
+        let share_is_valid = self
+            .presignature
+            .transcript
+            .signature_share_is_valid(from, self.context.target.scalar_message(), &normal_broadcast.sigma)
+            .ok_or_else(|| LocalError::new("Malformed presignature transcript"))?;
+        if !share_is_valid {
+            return Err(ReceiveError::protocol(
+                self.context.target.protocol_error(Error::R4InvalidSignatureShare),
+            ));
         }
 
         Ok(Payload::new(Round4Payload {
@@ -2065,15 +2852,13 @@ impl<P: SchemeParams, Id: PartyId> Round<Id> for Round4<P, Id> {
 
         let assembled_sigma = payloads.values().map(|payload| payload.sigma).sum::<Scalar<P>>() + self.sigma;
 
-        let signature = RecoverableSignature::from_scalars(
-            self.presigning_data.cap_gamma_combined.x_coordinate(),
-            assembled_sigma,
-            self.context.key_share.verifying_key_as_point(),
-            self.context.scalar_message,
-        );
+        let signature = self
+            .presignature
+            .transcript
+            .assemble_signature(assembled_sigma, *self.context.target.scalar_message());
 
         if let Some(signature) = signature {
-            return Ok(FinalizeOutcome::Result(signature));
+            return Ok(FinalizeOutcome::Result(T::signing_result(signature)));
         }
 
         Err(LocalError::new("Failed!"))
@@ -2081,8 +2866,8 @@ impl<P: SchemeParams, Id: PartyId> Round<Id> for Round4<P, Id> {
 }
 
 #[derive(Debug)]
-pub(super) struct Round5<P: SchemeParams, Id: PartyId> {
-    pub(super) context: Context<P, Id>,
+pub(super) struct Round5<P: SchemeParams, Id: PartyId, T> {
+    pub(super) context: Context<P, Id, T>,
     pub(super) deltas: BTreeMap<Id, Scalar<P>>,
     pub(super) betas: BTreeMap<Id, SecretSigned<<P::Paillier as PaillierParams>::Uint>>,
     pub(super) ss: BTreeMap<Id, Randomizer<P::Paillier>>,
@@ -2096,12 +2881,13 @@ pub(super) struct Round5<P: SchemeParams, Id: PartyId> {
 #[derive(Debug, Clone)]
 #[derive_where::derive_where(Serialize, Deserialize)]
 pub(super) struct Round5EchoBroadcast<P: SchemeParams, Id: PartyId> {
+    pub(super) epid: Epid, // identifies the instance the sender proceeded to the error round with
     pub(super) psi_star: DecProof<P>,
     pub(super) psis: SerializableMap<Id, AffGStarProof<P>>,
 }
 
-impl<P: SchemeParams, Id: PartyId> Round<Id> for Round5<P, Id> {
-    type Protocol = InteractiveSigningProtocol<P, Id>;
+impl<P: SchemeParams, Id: PartyId, T: PresigningTarget<P, Id>> Round<Id> for Round5<P, Id, T> {
+    type Protocol = T::Protocol;
 
     fn transition_info(&self) -> TransitionInfo {
         TransitionInfo {
@@ -2124,7 +2910,7 @@ impl<P: SchemeParams, Id: PartyId> Round<Id> for Round5<P, Id> {
     ) -> Result<EchoBroadcast, LocalError> {
         let my_id = self.context.my_id.clone();
         let aux = (&self.context.epid, &my_id);
-        let pk = self.context.aux_info.secret_aux.paillier_sk.public_key();
+        let pk = self.context.aux_info.secret_aux().paillier_sk.public_key();
         let rp = &self.context.public_aux(&self.context.my_id)?.rp_params;
 
         let ids = self.context.other_ids.clone();
@@ -2144,12 +2930,12 @@ impl<P: SchemeParams, Id: PartyId> Round<Id> for Round5<P, Id> {
         let gamma = secret_signed_from_scalar::<P>(&self.context.gamma);
         let full_ciphertext = cap_k * &gamma + &cap_d;
 
-        let rho = full_ciphertext.derive_randomizer(&self.context.aux_info.secret_aux.paillier_sk);
+        let rho = full_ciphertext.derive_randomizer(&self.context.aux_info.secret_aux().paillier_sk);
 
         // We could have calculated `\delta_i` as SecretSigned in Round 2, but that would require
         // keeping all the components as SecretSigned instead of Scalar as well.
         // Since it's only needed here it's easier to re-create it inplace.
-        let delta_signed = full_ciphertext.decrypt(&self.context.aux_info.secret_aux.paillier_sk);
+        let delta_signed = full_ciphertext.decrypt(&self.context.aux_info.secret_aux().paillier_sk);
 
         let num_parties = self.context.all_ids.len();
         let ceil_log2_num_parties = (num_parties - 1).ilog2() + 1;
@@ -2206,7 +2992,14 @@ impl<P: SchemeParams, Id: PartyId> Round<Id> for Round5<P, Id> {
             psis.insert(id.clone(), psi);
         }
 
-        EchoBroadcast::new(format, Round5EchoBroadcast::<P, Id> { psi_star, psis })
+        EchoBroadcast::new(
+            format,
+            Round5EchoBroadcast::<P, Id> {
+                epid: self.context.epid.clone(),
+                psi_star,
+                psis,
+            },
+        )
     }
 
     fn receive_message(
@@ -2221,6 +3014,15 @@ impl<P: SchemeParams, Id: PartyId> Round<Id> for Round5<P, Id> {
             .echo_broadcast
             .deserialize::<Round5EchoBroadcast<P, Id>>(format)?;
 
+        // In a batched session a malicious party can make the lawful nodes proceed to the error round
+        // with different instances by sending them inconsistent values in Round 3.
+        // The sender is not necessarily at fault then, so this error is not provable.
+        if echo_broadcast.epid != self.context.epid {
+            return Err(ReceiveError::unprovable(
+                "The sender proceeded to the error round with a different presigning instance",
+            ));
+        }
+
         let my_id = self.context.my_id.clone();
         let aux = (&self.context.epid, from);
 
@@ -2252,12 +3054,16 @@ impl<P: SchemeParams, Id: PartyId> Round<Id> for Round5<P, Id> {
             sender_rp,
             &aux,
         ) {
-            return Err(ReceiveError::protocol(Error::R5DecFailed.into()));
+            return Err(ReceiveError::protocol(
+                self.context.target.protocol_error(Error::R5DecFailed),
+            ));
         }
 
         let expected_ids = self.context.all_ids.clone().without(from);
         if echo_broadcast.psis.keys().cloned().collect::<BTreeSet<_>>() != expected_ids {
-            return Err(ReceiveError::protocol(Error::R5WrongIdsPsi.into()));
+            return Err(ReceiveError::protocol(
+                self.context.target.protocol_error(Error::R5WrongIdsPsi),
+            ));
         }
 
         for (id, psi) in echo_broadcast.psis.iter() {
@@ -2278,7 +3084,9 @@ impl<P: SchemeParams, Id: PartyId> Round<Id> for Round5<P, Id> {
                 &aux,
             ) {
                 return Err(ReceiveError::protocol(
-                    Error::R5AffGStarFailed { failed_for: id.clone() }.into(),
+                    self.context
+                        .target
+                        .protocol_error(Error::R5AffGStarFailed { failed_for: id.clone() }),
                 ));
             }
         }
@@ -2299,8 +3107,8 @@ impl<P: SchemeParams, Id: PartyId> Round<Id> for Round5<P, Id> {
 }
 
 #[derive(Debug)]
-pub(super) struct Round6<P: SchemeParams, Id: PartyId> {
-    pub(super) context: Context<P, Id>,
+pub(super) struct Round6<P: SchemeParams, Id: PartyId, T> {
+    pub(super) context: Context<P, Id, T>,
     pub(super) cap_gamma_combined: Point<P>,
     pub(super) hat_betas: BTreeMap<Id, SecretSigned<<P::Paillier as PaillierParams>::Uint>>,
     pub(super) hat_ss: BTreeMap<Id, Randomizer<P::Paillier>>,
@@ -2314,12 +3122,13 @@ pub(super) struct Round6<P: SchemeParams, Id: PartyId> {
 #[derive(Debug, Clone)]
 #[derive_where::derive_where(Serialize, Deserialize)]
 pub(super) struct Round6EchoBroadcast<P: SchemeParams, Id: PartyId> {
+    pub(super) epid: Epid, // identifies the instance the sender proceeded to the error round with
     pub(super) hat_psi_star: DecProof<P>,
     pub(super) hat_psis: SerializableMap<Id, AffGStarProof<P>>,
 }
 
-impl<P: SchemeParams, Id: PartyId> Round<Id> for Round6<P, Id> {
-    type Protocol = InteractiveSigningProtocol<P, Id>;
+impl<P: SchemeParams, Id: PartyId, T: PresigningTarget<P, Id>> Round<Id> for Round6<P, Id, T> {
+    type Protocol = T::Protocol;
 
     fn transition_info(&self) -> TransitionInfo {
         TransitionInfo {
@@ -2342,7 +3151,7 @@ impl<P: SchemeParams, Id: PartyId> Round<Id> for Round6<P, Id> {
     ) -> Result<EchoBroadcast, LocalError> {
         let my_id = self.context.my_id.clone();
         let aux = (&self.context.epid, &my_id);
-        let pk = self.context.aux_info.secret_aux.paillier_sk.public_key();
+        let pk = self.context.aux_info.secret_aux().paillier_sk.public_key();
         let rp = &self.context.public_aux(&self.context.my_id)?.rp_params;
 
         let ids = self.context.other_ids.clone();
@@ -2364,12 +3173,12 @@ impl<P: SchemeParams, Id: PartyId> Round<Id> for Round6<P, Id> {
         let x = secret_signed_from_scalar::<P>(self.context.key_share.secret_share());
         let full_ciphertext = cap_k * &x + &hat_cap_d;
 
-        let rho = full_ciphertext.derive_randomizer(&self.context.aux_info.secret_aux.paillier_sk);
+        let rho = full_ciphertext.derive_randomizer(&self.context.aux_info.secret_aux().paillier_sk);
 
         // We could have calculated `\chi_i` as SecretSigned in Round 2, but that would require
         // keeping all the components as SecretSigned instead of Scalar as well.
         // Since it's only needed here it's easier to re-create it inplace.
-        let chi_signed = full_ciphertext.decrypt(&self.context.aux_info.secret_aux.paillier_sk);
+        let chi_signed = full_ciphertext.decrypt(&self.context.aux_info.secret_aux().paillier_sk);
 
         let num_parties = self.context.all_ids.len();
         let ceil_log2_num_parties = (num_parties - 1).ilog2() + 1;
@@ -2446,6 +3255,7 @@ impl<P: SchemeParams, Id: PartyId> Round<Id> for Round6<P, Id> {
         EchoBroadcast::new(
             format,
             Round6EchoBroadcast::<P, Id> {
+                epid: self.context.epid.clone(),
                 hat_psi_star,
                 hat_psis: hat_psis.into(),
             },
@@ -2464,6 +3274,15 @@ impl<P: SchemeParams, Id: PartyId> Round<Id> for Round6<P, Id> {
             .echo_broadcast
             .deserialize::<Round6EchoBroadcast<P, Id>>(format)?;
 
+        // In a batched session a malicious party can make the lawful nodes proceed to the error round
+        // with different instances by sending them inconsistent values in Round 3.
+        // The sender is not necessarily at fault then, so this error is not provable.
+        if echo_broadcast.epid != self.context.epid {
+            return Err(ReceiveError::unprovable(
+                "The sender proceeded to the error round with a different presigning instance",
+            ));
+        }
+
         let my_id = self.context.my_id.clone();
         let aux = (&self.context.epid, from);
 
@@ -2497,12 +3316,16 @@ impl<P: SchemeParams, Id: PartyId> Round<Id> for Round6<P, Id> {
             sender_rp,
             &aux,
         ) {
-            return Err(ReceiveError::protocol(Error::R6DecFailed.into()));
+            return Err(ReceiveError::protocol(
+                self.context.target.protocol_error(Error::R6DecFailed),
+            ));
         }
 
         let expected_ids = self.context.all_ids.clone().without(from);
         if echo_broadcast.hat_psis.keys().cloned().collect::<BTreeSet<_>>() != expected_ids {
-            return Err(ReceiveError::protocol(Error::R6WrongIdsPsi.into()));
+            return Err(ReceiveError::protocol(
+                self.context.target.protocol_error(Error::R6WrongIdsPsi),
+            ));
         }
 
         for (id, hat_psi) in echo_broadcast.hat_psis.iter() {
@@ -2523,7 +3346,9 @@ impl<P: SchemeParams, Id: PartyId> Round<Id> for Round6<P, Id> {
                 &aux,
             ) {
                 return Err(ReceiveError::protocol(
-                    Error::R6AffGStarFailed { failed_for: id.clone() }.into(),
+                    self.context
+                        .target
+                        .protocol_error(Error::R6AffGStarFailed { failed_for: id.clone() }),
                 ));
             }
         }
@@ -2545,9 +3370,15 @@ impl<P: SchemeParams, Id: PartyId> Round<Id> for Round6<P, Id> {
 
 #[cfg(test)]
 mod tests {
-    use alloc::collections::BTreeSet;
+    use alloc::{
+        collections::{BTreeMap, BTreeSet},
+        sync::Arc,
+    };
 
-    use ecdsa::{signature::hazmat::PrehashVerifier, VerifyingKey};
+    use ecdsa::{
+        signature::{hazmat::PrehashVerifier, Verifier},
+        VerifyingKey,
+    };
     use elliptic_curve::FieldBytes;
     use manul::{
         dev::{run_sync, BinaryFormat, TestSessionParams, TestSigner, TestVerifier},
@@ -2558,7 +3389,7 @@ mod tests {
     use super::InteractiveSigning;
     use crate::{
         dev::TestParams,
-        entities::{AuxInfo, KeyShare},
+        entities::{AuxInfo, KeyShare, ThresholdKeyShare},
         SchemeParams,
     };
     type Curve = <TestParams as SchemeParams>::Curve;
@@ -2602,4 +3433,233 @@ mod tests {
             assert_eq!(recovered_key, vkey);
         }
     }
+
+    #[test]
+    fn execute_interactive_signing_shared() {
+        let signers = (0..3).map(TestSigner::new).collect::<Vec<_>>();
+        let ids = signers.iter().map(|signer| signer.verifying_key()).collect::<Vec<_>>();
+        let ids_set = BTreeSet::from_iter(ids.clone());
+
+        let key_shares = KeyShare::<TestParams, TestVerifier>::new_centralized(&mut OsRng, &ids_set, None)
+            .into_iter()
+            .map(|(id, key_share)| (id, Arc::new(key_share)))
+            .collect::<BTreeMap<_, _>>();
+        let aux_infos = AuxInfo::new_centralized(&mut OsRng, &ids_set)
+            .into_iter()
+            .map(|(id, aux_info)| (id, aux_info.precompute()))
+            .collect::<BTreeMap<_, _>>();
+
+        let vkey = key_shares[&ids[0]].verifying_key();
+
+        // The same key share and precomputed aux info handles are reused in several sessions
+        for _ in 0..2 {
+            let mut message = FieldBytes::<Curve>::default();
+            OsRng.fill_bytes(&mut message);
+
+            let entry_points = signers
+                .iter()
+                .map(|signer| {
+                    let id = signer.verifying_key();
+                    let entry_point =
+                        InteractiveSigning::new(message, key_shares[&id].clone(), aux_infos[&id].clone()).unwrap();
+                    (*signer, entry_point)
+                })
+                .collect();
+
+            let mut signatures = run_sync::<_, TestSessionParams<BinaryFormat>>(&mut OsRng, entry_points)
+                .unwrap()
+                .results()
+                .unwrap();
+
+            while let Some((_, signature)) = signatures.pop_first() {
+                let (sig, _rec_id) = signature.to_backend();
+                vkey.verify_prehash(&message, &sig).unwrap();
+            }
+        }
+    }
+
+    #[test]
+    fn execute_threshold_signing() {
+        let signers = (0..4).map(TestSigner::new).collect::<Vec<_>>();
+        let ids = signers
+            .iter()
+            .map(|signer| signer.verifying_key())
+            .collect::<BTreeSet<_>>();
+
+        let key_shares =
+            ThresholdKeyShare::<TestParams, TestVerifier>::new_centralized(&mut OsRng, &ids, 3, None).unwrap();
+        let aux_infos = AuxInfo::new_centralized(&mut OsRng, &ids);
+
+        let mut message = FieldBytes::<Curve>::default();
+        OsRng.fill_bytes(&mut message);
+
+        let selected_signers = signers.into_iter().skip(1).collect::<Vec<_>>();
+        let selected_ids = selected_signers
+            .iter()
+            .map(|signer| signer.verifying_key())
+            .collect::<BTreeSet<_>>();
+
+        // The wrong number of signers is rejected
+        let id = selected_signers[0].verifying_key();
+        assert!(InteractiveSigning::new_threshold(message, &key_shares[&id], &aux_infos[&id], &ids).is_err());
+
+        let entry_points = selected_signers
+            .into_iter()
+            .map(|signer| {
+                let id = signer.verifying_key();
+                let entry_point =
+                    InteractiveSigning::new_threshold(message, &key_shares[&id], &aux_infos[&id], &selected_ids)
+                        .unwrap();
+                (signer, entry_point)
+            })
+            .collect();
+
+        let mut signatures = run_sync::<_, TestSessionParams<BinaryFormat>>(&mut OsRng, entry_points)
+            .unwrap()
+            .results()
+            .unwrap();
+
+        let vkey = key_shares.values().next().unwrap().verifying_key().unwrap();
+        while let Some((_, signature)) = signatures.pop_first() {
+            let (sig, _rec_id) = signature.to_backend();
+            vkey.verify_prehash(&message, &sig).unwrap();
+        }
+    }
+
+    #[test]
+    fn execute_threshold_signing_precomputed() {
+        let signers = (0..4).map(TestSigner::new).collect::<Vec<_>>();
+        let ids = signers
+            .iter()
+            .map(|signer| signer.verifying_key())
+            .collect::<BTreeSet<_>>();
+
+        let key_shares =
+            ThresholdKeyShare::<TestParams, TestVerifier>::new_centralized(&mut OsRng, &ids, 3, None).unwrap();
+        let aux_infos = AuxInfo::new_centralized(&mut OsRng, &ids)
+            .into_iter()
+            .map(|(id, aux_info)| (id, aux_info.precompute()))
+            .collect::<BTreeMap<_, _>>();
+
+        let vkey = key_shares.values().next().unwrap().verifying_key().unwrap();
+
+        // The same precomputed aux info is reused for different sets of signers
+        for skip in 0..2 {
+            let mut message = FieldBytes::<Curve>::default();
+            OsRng.fill_bytes(&mut message);
+
+            let selected_signers = (skip..skip + 3).map(TestSigner::new).collect::<Vec<_>>();
+            let selected_ids = selected_signers
+                .iter()
+                .map(|signer| signer.verifying_key())
+                .collect::<BTreeSet<_>>();
+
+            let entry_points = selected_signers
+                .into_iter()
+                .map(|signer| {
+                    let id = signer.verifying_key();
+                    let entry_point = InteractiveSigning::new_threshold_precomputed(
+                        message,
+                        &key_shares[&id],
+                        &aux_infos[&id],
+                        &selected_ids,
+                    )
+                    .unwrap();
+                    (signer, entry_point)
+                })
+                .collect();
+
+            let mut signatures = run_sync::<_, TestSessionParams<BinaryFormat>>(&mut OsRng, entry_points)
+                .unwrap()
+                .results()
+                .unwrap();
+
+            while let Some((_, signature)) = signatures.pop_first() {
+                let (sig, _rec_id) = signature.to_backend();
+                vkey.verify_prehash(&message, &sig).unwrap();
+            }
+        }
+    }
+
+    #[test]
+    fn execute_interactive_signing_raw_message() {
+        let signers = (0..3).map(TestSigner::new).collect::<Vec<_>>();
+        let ids = signers.iter().map(|signer| signer.verifying_key()).collect::<Vec<_>>();
+        let ids_set = BTreeSet::from_iter(ids.clone());
+
+        let key_shares = KeyShare::<TestParams, TestVerifier>::new_centralized(&mut OsRng, &ids_set, None);
+        let aux_infos = AuxInfo::new_centralized(&mut OsRng, &ids_set);
+
+        let message = b"a message that is not prehashed";
+
+        let entry_points = signers
+            .into_iter()
+            .map(|signer| {
+                let id = signer.verifying_key();
+                let entry_point =
+                    InteractiveSigning::from_message(message, key_shares[&id].clone(), aux_infos[&id].clone()).unwrap();
+                (signer, entry_point)
+            })
+            .collect();
+
+        let mut signatures = run_sync::<_, TestSessionParams<BinaryFormat>>(&mut OsRng, entry_points)
+            .unwrap()
+            .results()
+            .unwrap();
+
+        let vkey = key_shares[&ids[0]].verifying_key();
+        while let Some((_, signature)) = signatures.pop_first() {
+            let (sig, _rec_id) = signature.to_backend();
+            // The signature is verified against the message hashed with the curve's default digest
+            vkey.verify(message, &sig).unwrap();
+        }
+    }
+
+    #[cfg(feature = "bip32")]
+    #[test]
+    fn execute_interactive_signing_with_derivation_path() {
+        use crate::{Bip32Tweak, DeriveChildKey};
+
+        let signers = (0..3).map(TestSigner::new).collect::<Vec<_>>();
+        let ids = signers.iter().map(|signer| signer.verifying_key()).collect::<Vec<_>>();
+        let ids_set = BTreeSet::from_iter(ids.clone());
+
+        let key_shares = KeyShare::<TestParams, TestVerifier>::new_centralized(&mut OsRng, &ids_set, None);
+        let aux_infos = AuxInfo::new_centralized(&mut OsRng, &ids_set);
+
+        let mut message = FieldBytes::<Curve>::default();
+        OsRng.fill_bytes(&mut message);
+
+        let path = "m/0/2/1/4/2".parse().unwrap();
+        let vkey = key_shares[&ids[0]].verifying_key();
+        let child_vkey = vkey.derive_verifying_key_bip32(&path).unwrap();
+        let tweak = Bip32Tweak::new(&vkey, &path).unwrap();
+
+        // Use both ways of specifying the derivation for different parties, they must be equivalent
+        let entry_points = signers
+            .into_iter()
+            .enumerate()
+            .map(|(idx, signer)| {
+                let id = signer.verifying_key();
+                let entry_point =
+                    InteractiveSigning::new(message, key_shares[&id].clone(), aux_infos[&id].clone()).unwrap();
+                let entry_point = if idx == 0 {
+                    entry_point.with_derivation_path(&path).unwrap()
+                } else {
+                    entry_point.with_bip32_tweak(&tweak).unwrap()
+                };
+                (signer, entry_point)
+            })
+            .collect();
+
+        let mut signatures = run_sync::<_, TestSessionParams<BinaryFormat>>(&mut OsRng, entry_points)
+            .unwrap()
+            .results()
+            .unwrap();
+
+        while let Some((_, signature)) = signatures.pop_first() {
+            let (sig, _rec_id) = signature.to_backend();
+            child_vkey.verify_prehash(&message, &sig).unwrap();
+        }
+    }
 }
@@ -0,0 +1,626 @@
+//! KeyExport protocol: reconstructs the full secret key at a designated recipient
+//! with the consent of `threshold` holders of a [`ThresholdKeyShare`].
+//!
+//! Each exporting node converts its share to an additive one (see [`ThresholdKeyShare::to_key_share`]),
+//! echo-broadcasts the corresponding public share, and sends the secret share to the recipient,
+//! masked with a Diffie-Hellman shared point derived from an ephemeral key and the recipient's public key.
+//! The exporting nodes check each other's public shares against their own copy of the public data,
+//! and the recipient checks the decrypted shares against the public shares.
+//! If the latter check fails, the recipient publishes the shared point along with a proof of its correctness,
+//! making the fault provable without revealing the recipient's secret key.
+
+use alloc::{
+    boxed::Box,
+    collections::{BTreeMap, BTreeSet},
+};
+use core::{
+    fmt::{self, Debug, Display},
+    marker::PhantomData,
+};
+
+use ecdsa::{SigningKey, VerifyingKey};
+use manul::protocol::{
+    Artifact, BoxedFormat, BoxedRound, CommunicationInfo, DirectMessage, EchoBroadcast, EchoRoundParticipation,
+    EntryPoint, FinalizeOutcome, LocalError, MessageValidationError, NormalBroadcast, PartyId, Payload, Protocol,
+    ProtocolError, ProtocolMessage, ProtocolMessagePart, ProtocolValidationError, ReceiveError, RequiredMessageParts,
+    RequiredMessages, Round, RoundId, TransitionInfo,
+};
+use rand_core::CryptoRngCore;
+
+use super::interactive_signing::check_threshold_signers;
+use crate::{
+    curve::{Point, Scalar},
+    entities::{PublicKeyShares, Sid, ThresholdKeyShare},
+    params::SchemeParams,
+    tools::{
+        dh_mask,
+        protocol_shortcuts::{verify_that, DowncastMap, SafeGet, Without},
+        Secret,
+    },
+    zk::{DleqProof, DleqPublicInputs, DleqSecret},
+};
+
+/// The domain separation tag of the masks of the values sent in KeyExport.
+const MASK_DST: &[u8] = b"KeyExport";
+
+/// A protocol for reconstructing the full secret key from threshold key shares at a designated recipient.
+#[derive(Debug)]
+pub struct KeyExportProtocol<P: SchemeParams, I: Debug>(PhantomData<(P, I)>);
+
+impl<P: SchemeParams, I: PartyId> Protocol<I> for KeyExportProtocol<P, I> {
+    type Result = Option<SigningKey<P::Curve>>;
+    type ProtocolError = KeyExportError<P, I>;
+
+    fn verify_direct_message_is_invalid(
+        format: &BoxedFormat,
+        round_id: &RoundId,
+        message: &DirectMessage,
+    ) -> Result<(), MessageValidationError> {
+        match round_id {
+            r if r == &1 => message.verify_is_not::<Round1DirectMessage<P>>(format),
+            _ => Err(MessageValidationError::InvalidEvidence("Invalid round number".into())),
+        }
+    }
+
+    fn verify_echo_broadcast_is_invalid(
+        format: &BoxedFormat,
+        round_id: &RoundId,
+        message: &EchoBroadcast,
+    ) -> Result<(), MessageValidationError> {
+        match round_id {
+            r if r == &1 => message.verify_is_not::<Round1EchoBroadcast<P>>(format),
+            _ => Err(MessageValidationError::InvalidEvidence("Invalid round number".into())),
+        }
+    }
+
+    fn verify_normal_broadcast_is_invalid(
+        _format: &BoxedFormat,
+        round_id: &RoundId,
+        message: &NormalBroadcast,
+    ) -> Result<(), MessageValidationError> {
+        match round_id {
+            r if r == &1 => message.verify_is_some(),
+            _ => Err(MessageValidationError::InvalidEvidence("Invalid round number".into())),
+        }
+    }
+}
+
+/// Possible verifiable errors of the KeyExport protocol.
+#[derive(Debug, Clone)]
+#[derive_where::derive_where(Serialize, Deserialize)]
+pub struct KeyExportError<P: SchemeParams, I: PartyId> {
+    error: Error<P>,
+    phantom: PhantomData<I>,
+}
+
+impl<P: SchemeParams, I: PartyId> Display for KeyExportError<P, I> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> Result<(), fmt::Error> {
+        write!(
+            f,
+            "{}",
+            match self.error {
+                Error::R1PublicShareMismatch => "Round 1: the public share does not match the public key shares.",
+                Error::R1InvalidShare { .. } => "Round 1: the decrypted share does not match the public share.",
+            }
+        )
+    }
+}
+
+impl<P: SchemeParams, I: PartyId> From<Error<P>> for KeyExportError<P, I> {
+    fn from(source: Error<P>) -> Self {
+        Self {
+            error: source,
+            phantom: PhantomData,
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+#[derive_where::derive_where(Serialize, Deserialize)]
+enum Error<P: SchemeParams> {
+    R1PublicShareMismatch,
+    R1InvalidShare {
+        /// The Diffie-Hellman shared point $D = E * y$,
+        /// where $E$ is the sender's ephemeral public key and $y$ is the recipient's secret key.
+        cap_d: Point<P>,
+        /// The proof that $D$ was calculated correctly.
+        proof: DleqProof<P>,
+    },
+}
+
+/// Associated data for KeyExport protocol.
+#[derive(Debug, Clone)]
+pub struct KeyExportAssociatedData<P: SchemeParams, I: PartyId> {
+    /// Public shares of all the exporting nodes.
+    pub shares: PublicKeyShares<P, I>,
+    /// The ID of the recipient.
+    pub recipient: I,
+    /// The public key of the recipient.
+    pub recipient_key: VerifyingKey<P::Curve>,
+}
+
+impl<P: SchemeParams, I: PartyId> KeyExportAssociatedData<P, I> {
+    /// Creates the associated data for evidence verification of KeyExport.
+    ///
+    /// Only the public part of `key_share` is used, so it does not have to belong to one of the `exporters`.
+    pub fn new(
+        key_share: &ThresholdKeyShare<P, I>,
+        exporters: &BTreeSet<I>,
+        recipient: I,
+        recipient_key: VerifyingKey<P::Curve>,
+    ) -> Result<Self, LocalError> {
+        check_threshold_signers(key_share, exporters)?;
+        Ok(Self {
+            shares: PublicKeyShares::new(key_share.to_public_key_shares(exporters)?),
+            recipient,
+            recipient_key,
+        })
+    }
+}
+
+impl<P: SchemeParams, I: PartyId> ProtocolError<I> for KeyExportError<P, I> {
+    type AssociatedData = KeyExportAssociatedData<P, I>;
+
+    fn required_messages(&self) -> RequiredMessages {
+        match self.error {
+            Error::R1PublicShareMismatch => RequiredMessages::new(RequiredMessageParts::echo_broadcast(), None, None),
+            Error::R1InvalidShare { .. } => {
+                RequiredMessages::new(RequiredMessageParts::echo_broadcast().and_direct_message(), None, None)
+            }
+        }
+    }
+
+    fn verify_messages_constitute_error(
+        &self,
+        format: &BoxedFormat,
+        guilty_party: &I,
+        shared_randomness: &[u8],
+        associated_data: &Self::AssociatedData,
+        message: ProtocolMessage,
+        _previous_messages: BTreeMap<RoundId, ProtocolMessage>,
+        _combined_echos: BTreeMap<RoundId, BTreeMap<I, EchoBroadcast>>,
+    ) -> Result<(), ProtocolValidationError> {
+        let r1_eb = message.echo_broadcast.deserialize::<Round1EchoBroadcast<P>>(format)?;
+
+        match &self.error {
+            Error::R1PublicShareMismatch => {
+                let public_share = associated_data.shares.as_map().try_get("public shares", guilty_party)?;
+                verify_that(&r1_eb.public_share != public_share)
+            }
+            Error::R1InvalidShare { cap_d, proof } => {
+                let r1_dm = message.direct_message.deserialize::<Round1DirectMessage<P>>(format)?;
+
+                let mut ids = associated_data.shares.as_map().keys().cloned().collect::<BTreeSet<_>>();
+                ids.insert(associated_data.recipient.clone());
+                let sid = Sid::new::<P, I>(shared_randomness, &ids);
+
+                let recipient_key = Point::from_verifying_key(&associated_data.recipient_key);
+                let public = DleqPublicInputs {
+                    cap_x: &recipient_key,
+                    cap_e: &r1_dm.cap_e,
+                    cap_d,
+                };
+                if !proof.verify(public, &(&sid, guilty_party)) {
+                    return Err(ProtocolValidationError::InvalidEvidence(
+                        "Failed to verify the shared point".into(),
+                    ));
+                }
+
+                let x = r1_dm.cap_c - dh_mask::<P, I>(MASK_DST, &sid, guilty_party, cap_d, 0);
+                verify_that(x.mul_by_generator() != r1_eb.public_share)
+            }
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+enum Role<P: SchemeParams, I: PartyId> {
+    Exporter {
+        key_share: ThresholdKeyShare<P, I>,
+        recipient: I,
+        recipient_key: VerifyingKey<P::Curve>,
+    },
+    Recipient {
+        secret_key: Secret<Scalar<P>>,
+        verifying_key: VerifyingKey<P::Curve>,
+    },
+}
+
+/// An entry point for the [`KeyExportProtocol`].
+#[derive(Debug, Clone)]
+pub struct KeyExport<P: SchemeParams, I: PartyId> {
+    role: Role<P, I>,
+    exporters: BTreeSet<I>,
+}
+
+impl<P, I> KeyExport<P, I>
+where
+    P: SchemeParams,
+    I: PartyId,
+{
+    /// Creates a new entry point for one of the nodes consenting to the export of the key.
+    ///
+    /// `exporters` are the IDs of the consenting nodes (including this one); there must be exactly `threshold` of them.
+    /// The share will be sent to `recipient`, encrypted with `recipient_key`.
+    pub fn new_exporter(
+        key_share: ThresholdKeyShare<P, I>,
+        exporters: BTreeSet<I>,
+        recipient: I,
+        recipient_key: VerifyingKey<P::Curve>,
+    ) -> Result<Self, LocalError> {
+        check_threshold_signers(&key_share, &exporters)?;
+        if !exporters.contains(&key_share.owner) {
+            return Err(LocalError::new("The exporters must include the owner of the key share"));
+        }
+        if exporters.contains(&recipient) {
+            return Err(LocalError::new("The recipient cannot be one of the exporters"));
+        }
+        Ok(Self {
+            role: Role::Exporter {
+                key_share,
+                recipient,
+                recipient_key,
+            },
+            exporters,
+        })
+    }
+
+    /// Creates a new entry point for the recipient of the key.
+    ///
+    /// `secret_key` is the secret counterpart of the public key the exporters encrypt their shares with,
+    /// and `verifying_key` is the verifying key of the exported key shares.
+    pub fn new_recipient(
+        secret_key: &SigningKey<P::Curve>,
+        verifying_key: VerifyingKey<P::Curve>,
+        exporters: BTreeSet<I>,
+    ) -> Self {
+        Self {
+            role: Role::Recipient {
+                secret_key: Secret::init_with(|| Scalar::from(secret_key.as_nonzero_scalar())),
+                verifying_key,
+            },
+            exporters,
+        }
+    }
+}
+
+impl<P, I> EntryPoint<I> for KeyExport<P, I>
+where
+    P: SchemeParams,
+    I: PartyId,
+{
+    type Protocol = KeyExportProtocol<P, I>;
+
+    fn entry_round_id() -> RoundId {
+        1.into()
+    }
+
+    fn make_round(
+        self,
+        rng: &mut dyn CryptoRngCore,
+        shared_randomness: &[u8],
+        id: &I,
+    ) -> Result<BoxedRound<I, Self::Protocol>, LocalError> {
+        let recipient = match &self.role {
+            Role::Exporter { recipient, .. } => recipient,
+            Role::Recipient { .. } => id,
+        };
+        let mut all_ids = self.exporters.clone();
+        all_ids.insert(recipient.clone());
+        let sid = Sid::new::<P, I>(shared_randomness, &all_ids);
+
+        let round = match self.role {
+            Role::Exporter {
+                key_share,
+                recipient,
+                recipient_key,
+            } => {
+                if &key_share.owner != id {
+                    return Err(LocalError::new("The key share must belong to this node"));
+                }
+
+                let public_shares = key_share.to_public_key_shares(&self.exporters)?;
+                let key_share = key_share.to_key_share(&self.exporters)?;
+
+                let ephemeral_key = Secret::init_with(|| Scalar::random(rng));
+                let cap_d = Point::from_verifying_key(&recipient_key) * &ephemeral_key;
+
+                let exporter = ExporterData {
+                    secret_share: key_share.secret_share().clone(),
+                    public_shares,
+                    cap_e: ephemeral_key.mul_by_generator(),
+                    cap_d,
+                };
+
+                let mut message_destinations = self.exporters.clone().without(id);
+                message_destinations.insert(recipient);
+
+                Round1 {
+                    my_id: id.clone(),
+                    sid,
+                    exporter: Some(exporter),
+                    recipient: None,
+                    communication_info: CommunicationInfo {
+                        message_destinations,
+                        expecting_messages_from: self.exporters.without(id),
+                        echo_round_participation: EchoRoundParticipation::Default,
+                    },
+                }
+            }
+            Role::Recipient {
+                secret_key,
+                verifying_key,
+            } => {
+                if self.exporters.contains(id) {
+                    return Err(LocalError::new("The recipient cannot be one of the exporters"));
+                }
+
+                let dleq_secrets = self
+                    .exporters
+                    .iter()
+                    .map(|id| (id.clone(), DleqSecret::random(rng)))
+                    .collect();
+
+                let recipient = RecipientData {
+                    secret_key,
+                    verifying_key: Point::from_verifying_key(&verifying_key),
+                    dleq_secrets,
+                };
+
+                Round1 {
+                    my_id: id.clone(),
+                    sid,
+                    exporter: None,
+                    recipient: Some(recipient),
+                    communication_info: CommunicationInfo {
+                        message_destinations: BTreeSet::new(),
+                        expecting_messages_from: self.exporters.clone(),
+                        echo_round_participation: EchoRoundParticipation::Receive {
+                            echo_targets: self.exporters,
+                        },
+                    },
+                }
+            }
+        };
+
+        Ok(BoxedRound::new_dynamic(round))
+    }
+}
+
+#[derive(Debug)]
+struct ExporterData<P: SchemeParams, I> {
+    secret_share: Secret<Scalar<P>>,
+    public_shares: BTreeMap<I, Point<P>>,
+    cap_e: Point<P>,
+    cap_d: Point<P>,
+}
+
+#[derive(Debug)]
+struct RecipientData<P: SchemeParams, I> {
+    secret_key: Secret<Scalar<P>>,
+    verifying_key: Point<P>,
+    dleq_secrets: BTreeMap<I, DleqSecret<P>>,
+}
+
+#[derive(Debug)]
+struct Round1<P: SchemeParams, I> {
+    my_id: I,
+    sid: Sid,
+    exporter: Option<ExporterData<P, I>>,
+    recipient: Option<RecipientData<P, I>>,
+    communication_info: CommunicationInfo<I>,
+}
+
+#[derive(Debug, Clone)]
+#[derive_where::derive_where(Serialize, Deserialize)]
+struct Round1EchoBroadcast<P: SchemeParams> {
+    public_share: Point<P>,
+}
+
+#[derive(Debug, Clone)]
+#[derive_where::derive_where(Serialize, Deserialize)]
+struct Round1DirectMessage<P: SchemeParams> {
+    /// The ephemeral public key $E$.
+    cap_e: Point<P>,
+    /// The masked secret share.
+    cap_c: Scalar<P>,
+}
+
+struct Round1Payload<P: SchemeParams> {
+    secret_share: Secret<Scalar<P>>,
+}
+
+impl<P: SchemeParams, I: PartyId> Round<I> for Round1<P, I> {
+    type Protocol = KeyExportProtocol<P, I>;
+
+    fn transition_info(&self) -> TransitionInfo {
+        TransitionInfo::new_linear_terminating(1)
+    }
+
+    fn communication_info(&self) -> CommunicationInfo<I> {
+        self.communication_info.clone()
+    }
+
+    fn make_echo_broadcast(
+        &self,
+        _rng: &mut dyn CryptoRngCore,
+        format: &BoxedFormat,
+    ) -> Result<EchoBroadcast, LocalError> {
+        if let Some(exporter) = self.exporter.as_ref() {
+            let public_share = *exporter.public_shares.safe_get("public shares", &self.my_id)?;
+            EchoBroadcast::new(format, Round1EchoBroadcast { public_share })
+        } else {
+            Ok(EchoBroadcast::none())
+        }
+    }
+
+    fn make_direct_message(
+        &self,
+        _rng: &mut dyn CryptoRngCore,
+        format: &BoxedFormat,
+        destination: &I,
+    ) -> Result<(DirectMessage, Option<Artifact>), LocalError> {
+        // Only the recipient gets a direct message.
+        match self.exporter.as_ref() {
+            Some(exporter) if !exporter.public_shares.contains_key(destination) => {
+                let mask = dh_mask::<P, I>(MASK_DST, &self.sid, &self.my_id, &exporter.cap_d, 0);
+                let cap_c = *(exporter.secret_share.clone() + &mask).expose_secret();
+                let direct_message = DirectMessage::new(
+                    format,
+                    Round1DirectMessage {
+                        cap_e: exporter.cap_e,
+                        cap_c,
+                    },
+                )?;
+                Ok((direct_message, None))
+            }
+            _ => Ok((DirectMessage::none(), None)),
+        }
+    }
+
+    fn receive_message(
+        &self,
+        format: &BoxedFormat,
+        from: &I,
+        message: ProtocolMessage,
+    ) -> Result<Payload, ReceiveError<I, Self::Protocol>> {
+        message.normal_broadcast.assert_is_none()?;
+        let echo_broadcast = message.echo_broadcast.deserialize::<Round1EchoBroadcast<P>>(format)?;
+
+        if let Some(exporter) = self.exporter.as_ref() {
+            message.direct_message.assert_is_none()?;
+            let public_share = exporter.public_shares.safe_get("public shares", from)?;
+            if &echo_broadcast.public_share != public_share {
+                return Err(ReceiveError::protocol(Error::R1PublicShareMismatch.into()));
+            }
+            return Ok(Payload::empty());
+        }
+
+        let recipient = self
+            .recipient
+            .as_ref()
+            .ok_or_else(|| LocalError::new("A node must be either an exporter or the recipient"))?;
+        let direct_message = message.direct_message.deserialize::<Round1DirectMessage<P>>(format)?;
+
+        let cap_d = direct_message.cap_e * &recipient.secret_key;
+        let mask = dh_mask::<P, I>(MASK_DST, &self.sid, from, &cap_d, 0);
+        let secret_share = Secret::init_with(|| direct_message.cap_c - mask);
+
+        if secret_share.mul_by_generator() != echo_broadcast.public_share {
+            let recipient_key = recipient.secret_key.mul_by_generator();
+            let public = DleqPublicInputs {
+                cap_x: &recipient_key,
+                cap_e: &direct_message.cap_e,
+                cap_d: &cap_d,
+            };
+            let dleq_secret = recipient.dleq_secrets.safe_get("DLEQ proof secrets", from)?;
+            let proof = DleqProof::new(dleq_secret, &recipient.secret_key, public, &(&self.sid, from));
+            return Err(ReceiveError::protocol(Error::R1InvalidShare { cap_d, proof }.into()));
+        }
+
+        Ok(Payload::new(Round1Payload { secret_share }))
+    }
+
+    fn finalize(
+        self: Box<Self>,
+        _rng: &mut dyn CryptoRngCore,
+        payloads: BTreeMap<I, Payload>,
+        _artifacts: BTreeMap<I, Artifact>,
+    ) -> Result<FinalizeOutcome<I, Self::Protocol>, LocalError> {
+        // If this party is not the recipient, exit.
+        let recipient = match self.recipient {
+            Some(recipient) => recipient,
+            None => return Ok(FinalizeOutcome::Result(None)),
+        };
+
+        let payloads = payloads.downcast_all::<Round1Payload<P>>()?;
+        let secret = payloads
+            .into_values()
+            .fold(Secret::init_with(|| Scalar::ZERO), |acc, payload| {
+                acc + payload.secret_share
+            });
+
+        // Each share matches the public share echoed to all the exporters,
+        // and each exporter checked the public shares against its own copy of the public data.
+        // So this can only happen if all the exporters are colluding,
+        // or if the recipient was given a wrong verifying key.
+        if secret.mul_by_generator() != recipient.verifying_key {
+            return Err(LocalError::new(
+                "The reconstructed key does not match the verifying key",
+            ));
+        }
+
+        let signing_key = secret
+            .to_signing_key()
+            .ok_or_else(|| LocalError::new("The reconstructed key is zero"))?;
+
+        Ok(FinalizeOutcome::Result(Some(signing_key)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use alloc::collections::BTreeSet;
+
+    use ecdsa::SigningKey;
+    use manul::{
+        dev::{run_sync, BinaryFormat, TestSessionParams, TestSigner, TestVerifier},
+        signature::Keypair,
+    };
+    use rand_core::OsRng;
+
+    use super::KeyExport;
+    use crate::{dev::TestParams, ThresholdKeyShare};
+
+    #[test]
+    fn execute_key_export() {
+        let signers = (0..5).map(TestSigner::new).collect::<Vec<_>>();
+        let ids = signers.iter().map(|signer| signer.verifying_key()).collect::<Vec<_>>();
+
+        let holders = BTreeSet::from([ids[0], ids[1], ids[2], ids[3]]);
+        let exporters = BTreeSet::from([ids[1], ids[3]]);
+        let recipient = ids[4];
+
+        let signing_key = SigningKey::random(&mut OsRng);
+        let key_shares =
+            ThresholdKeyShare::<TestParams, TestVerifier>::new_centralized(&mut OsRng, &holders, 2, Some(&signing_key))
+                .unwrap();
+        let verifying_key = key_shares[&ids[0]].verifying_key().unwrap();
+
+        let recipient_secret_key = SigningKey::random(&mut OsRng);
+        let recipient_key = *recipient_secret_key.verifying_key();
+
+        // The number of exporters must be equal to the threshold
+        assert!(KeyExport::new_exporter(
+            key_shares[&ids[1]].clone(),
+            BTreeSet::from([ids[1]]),
+            recipient,
+            recipient_key
+        )
+        .is_err());
+
+        let entry_points = signers
+            .into_iter()
+            .filter(|signer| exporters.contains(&signer.verifying_key()) || signer.verifying_key() == recipient)
+            .map(|signer| {
+                let id = signer.verifying_key();
+                let entry_point = if id == recipient {
+                    KeyExport::new_recipient(&recipient_secret_key, verifying_key, exporters.clone())
+                } else {
+                    KeyExport::new_exporter(key_shares[&id].clone(), exporters.clone(), recipient, recipient_key)
+                        .unwrap()
+                };
+                (signer, entry_point)
+            })
+            .collect::<Vec<_>>();
+
+        let results = run_sync::<_, TestSessionParams<BinaryFormat>>(&mut OsRng, entry_points)
+            .unwrap()
+            .results()
+            .unwrap();
+
+        assert!(results[&ids[1]].is_none());
+        assert!(results[&ids[3]].is_none());
+        assert_eq!(results[&recipient].as_ref().unwrap(), &signing_key);
+    }
+}
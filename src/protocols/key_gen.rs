@@ -0,0 +1,621 @@
+//! KeyGen protocol: [`KeyInit`](super::KeyInit) and [`AuxGen`](super::AuxGen) executed in a single session,
+//! producing both the key share and the auxiliary info needed for signing.
+//!
+//! Both sub-protocols take three rounds, and neither depends on the results of the other,
+//! so their rounds are executed in parallel: the messages of the KeyInit and AuxGen rounds with the same number
+//! are sent together, and the session takes 3 rounds.
+//! The faults of either sub-protocol are reported with the evidence of that sub-protocol.
+//!
+//! The rounds are composed by hand, since `manul` (as of 0.2.1) only has combinators for chaining protocols
+//! (which would run AuxGen after KeyInit, taking 6 rounds) and for testing malicious behavior,
+//! but not for running them in parallel.
+
+use alloc::{
+    boxed::Box,
+    collections::{BTreeMap, BTreeSet},
+};
+use core::{
+    fmt::{self, Debug, Display},
+    marker::PhantomData,
+};
+
+use manul::protocol::{
+    Artifact, BoxedFormat, BoxedRound, CommunicationInfo, DirectMessage, EchoBroadcast, EntryPoint, FinalizeOutcome,
+    LocalError, MessageValidationError, NormalBroadcast, PartyId, Payload, Protocol, ProtocolError, ProtocolMessage,
+    ProtocolMessagePart, ProtocolValidationError, ReceiveError, RequiredMessages, Round, RoundId, TransitionInfo,
+};
+use rand_core::CryptoRngCore;
+use serde::{Deserialize, Serialize};
+
+use super::{
+    aux_gen::{self, AuxGenAssociatedData, AuxGenError, AuxGenProtocol, AuxGenTarget},
+    key_init::{self, KeyInitAssociatedData, KeyInitError, KeyInitProtocol, KeyInitTarget},
+};
+use crate::{
+    entities::{AuxInfo, KeyShare},
+    params::SchemeParams,
+    tools::protocol_shortcuts::{DowncastMap, Without},
+};
+
+/// A protocol generating a new key share and the corresponding auxiliary info in a single session.
+#[derive(Debug)]
+pub struct KeyGenProtocol<P: SchemeParams, Id: Debug>(PhantomData<(P, Id)>);
+
+impl<P: SchemeParams, Id: PartyId> Protocol<Id> for KeyGenProtocol<P, Id> {
+    type Result = (KeyShare<P, Id>, AuxInfo<P, Id>);
+    type ProtocolError = KeyGenError<P, Id>;
+
+    fn verify_direct_message_is_invalid(
+        format: &BoxedFormat,
+        round_id: &RoundId,
+        message: &DirectMessage,
+    ) -> Result<(), MessageValidationError> {
+        verify_part_is_invalid(
+            format,
+            message,
+            |part| KeyInitProtocol::<P, Id>::verify_direct_message_is_invalid(format, round_id, part),
+            |part| AuxGenProtocol::<P, Id>::verify_direct_message_is_invalid(format, round_id, part),
+        )
+    }
+
+    fn verify_echo_broadcast_is_invalid(
+        format: &BoxedFormat,
+        round_id: &RoundId,
+        message: &EchoBroadcast,
+    ) -> Result<(), MessageValidationError> {
+        verify_part_is_invalid(
+            format,
+            message,
+            |part| KeyInitProtocol::<P, Id>::verify_echo_broadcast_is_invalid(format, round_id, part),
+            |part| AuxGenProtocol::<P, Id>::verify_echo_broadcast_is_invalid(format, round_id, part),
+        )
+    }
+
+    fn verify_normal_broadcast_is_invalid(
+        format: &BoxedFormat,
+        round_id: &RoundId,
+        message: &NormalBroadcast,
+    ) -> Result<(), MessageValidationError> {
+        verify_part_is_invalid(
+            format,
+            message,
+            |part| KeyInitProtocol::<P, Id>::verify_normal_broadcast_is_invalid(format, round_id, part),
+            |part| AuxGenProtocol::<P, Id>::verify_normal_broadcast_is_invalid(format, round_id, part),
+        )
+    }
+}
+
+/// Possible verifiable errors of the KeyGen protocol.
+#[derive_where::derive_where(Debug, Clone, Serialize, Deserialize)]
+pub struct KeyGenError<P: SchemeParams, Id: PartyId> {
+    error: Error<P, Id>,
+}
+
+#[derive_where::derive_where(Debug, Clone, Serialize, Deserialize)]
+enum Error<P: SchemeParams, Id: PartyId> {
+    KeyInit(KeyInitError<P>),
+    AuxGen(AuxGenError<P, Id>),
+}
+
+impl<P: SchemeParams, Id: PartyId> Display for KeyGenError<P, Id> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> Result<(), fmt::Error> {
+        match &self.error {
+            Error::KeyInit(error) => write!(f, "KeyInit: {}", error),
+            Error::AuxGen(error) => write!(f, "AuxGen: {}", error),
+        }
+    }
+}
+
+impl<P: SchemeParams, Id: PartyId> From<KeyInitError<P>> for KeyGenError<P, Id> {
+    fn from(source: KeyInitError<P>) -> Self {
+        Self {
+            error: Error::KeyInit(source),
+        }
+    }
+}
+
+impl<P: SchemeParams, Id: PartyId> From<AuxGenError<P, Id>> for KeyGenError<P, Id> {
+    fn from(source: AuxGenError<P, Id>) -> Self {
+        Self {
+            error: Error::AuxGen(source),
+        }
+    }
+}
+
+/// Associated data for KeyGen protocol.
+#[derive(Debug, Clone)]
+pub struct KeyGenAssociatedData<Id> {
+    /// IDs of all participating nodes.
+    pub ids: BTreeSet<Id>,
+}
+
+impl<P: SchemeParams, Id: PartyId> ProtocolError<Id> for KeyGenError<P, Id> {
+    type AssociatedData = KeyGenAssociatedData<Id>;
+
+    fn required_messages(&self) -> RequiredMessages {
+        // The rounds of the sub-protocols have the same numbers as the KeyGen rounds they are executed in.
+        match &self.error {
+            Error::KeyInit(error) => <KeyInitError<P> as ProtocolError<Id>>::required_messages(error),
+            Error::AuxGen(error) => error.required_messages(),
+        }
+    }
+
+    fn verify_messages_constitute_error(
+        &self,
+        format: &BoxedFormat,
+        guilty_party: &Id,
+        shared_randomness: &[u8],
+        associated_data: &Self::AssociatedData,
+        message: ProtocolMessage,
+        previous_messages: BTreeMap<RoundId, ProtocolMessage>,
+        combined_echos: BTreeMap<RoundId, BTreeMap<Id, EchoBroadcast>>,
+    ) -> Result<(), ProtocolValidationError> {
+        let ids = associated_data.ids.clone();
+        match &self.error {
+            Error::KeyInit(error) => {
+                let (message, previous_messages, combined_echos) =
+                    sub_protocol_messages(format, SubProtocol::KeyInit, message, previous_messages, combined_echos)?;
+                error.verify_messages_constitute_error(
+                    format,
+                    guilty_party,
+                    shared_randomness,
+                    &KeyInitAssociatedData { ids },
+                    message,
+                    previous_messages,
+                    combined_echos,
+                )
+            }
+            Error::AuxGen(error) => {
+                let (message, previous_messages, combined_echos) =
+                    sub_protocol_messages(format, SubProtocol::AuxGen, message, previous_messages, combined_echos)?;
+                error.verify_messages_constitute_error(
+                    format,
+                    guilty_party,
+                    shared_randomness,
+                    &AuxGenAssociatedData { ids },
+                    message,
+                    previous_messages,
+                    combined_echos,
+                )
+            }
+        }
+    }
+}
+
+/// An entry point for the [`KeyGenProtocol`].
+#[derive(Debug, Clone)]
+pub struct KeyGen<P, Id> {
+    all_ids: BTreeSet<Id>,
+    phantom: PhantomData<P>,
+}
+
+impl<P, Id: PartyId> KeyGen<P, Id> {
+    /// Creates a new entry point given the set of the participants' IDs
+    /// (including this node's).
+    pub fn new(all_ids: BTreeSet<Id>) -> Self {
+        Self {
+            all_ids,
+            phantom: PhantomData,
+        }
+    }
+}
+
+impl<P: SchemeParams, Id: PartyId> EntryPoint<Id> for KeyGen<P, Id> {
+    type Protocol = KeyGenProtocol<P, Id>;
+
+    fn entry_round_id() -> RoundId {
+        1.into()
+    }
+
+    fn make_round(
+        self,
+        rng: &mut dyn CryptoRngCore,
+        shared_randomness: &[u8],
+        id: &Id,
+    ) -> Result<BoxedRound<Id, Self::Protocol>, LocalError> {
+        let key_init = key_init::make_round1(rng, shared_randomness, id, &self.all_ids, KeyGenTarget)?;
+        let aux_gen = aux_gen::make_round1(rng, shared_randomness, id, &self.all_ids, KeyGenTarget)?;
+        let round = Round1 {
+            other_ids: self.all_ids.without(id),
+            key_init,
+            aux_gen,
+        };
+        Ok(BoxedRound::new_dynamic(round))
+    }
+}
+
+/// Makes the KeyInit and AuxGen rounds a part of [`KeyGenProtocol`].
+#[derive(Debug)]
+struct KeyGenTarget;
+
+impl<P: SchemeParams, Id: PartyId> KeyInitTarget<P, Id> for KeyGenTarget {
+    type Protocol = KeyGenProtocol<P, Id>;
+}
+
+impl<P: SchemeParams, Id: PartyId> AuxGenTarget<P, Id> for KeyGenTarget {
+    type Protocol = KeyGenProtocol<P, Id>;
+}
+
+#[derive(Debug)]
+struct Round1<P: SchemeParams, Id: PartyId> {
+    other_ids: BTreeSet<Id>,
+    key_init: key_init::Round1<P, Id, KeyGenTarget>,
+    aux_gen: aux_gen::Round1<P, Id, KeyGenTarget>,
+}
+
+impl<P: SchemeParams, Id: PartyId> Round<Id> for Round1<P, Id> {
+    type Protocol = KeyGenProtocol<P, Id>;
+
+    fn transition_info(&self) -> TransitionInfo {
+        TransitionInfo::new_linear(1)
+    }
+
+    fn communication_info(&self) -> CommunicationInfo<Id> {
+        CommunicationInfo::regular(&self.other_ids)
+    }
+
+    fn make_echo_broadcast(
+        &self,
+        _rng: &mut dyn CryptoRngCore,
+        format: &BoxedFormat,
+    ) -> Result<EchoBroadcast, LocalError> {
+        join_parts(
+            format,
+            self.key_init.echo_broadcast(format)?,
+            self.aux_gen.echo_broadcast(format)?,
+        )
+    }
+
+    fn receive_message(
+        &self,
+        format: &BoxedFormat,
+        _from: &Id,
+        message: ProtocolMessage,
+    ) -> Result<Payload, ReceiveError<Id, Self::Protocol>> {
+        let (key_init_message, aux_gen_message) = split_message::<ReceiveError<Id, Self::Protocol>>(format, message)?;
+        let key_init_payload = self.key_init.receive(format, key_init_message)?;
+        let aux_gen_payload = self.aux_gen.receive(format, aux_gen_message)?;
+        Ok(Payload::new((key_init_payload, aux_gen_payload)))
+    }
+
+    fn finalize(
+        self: Box<Self>,
+        _rng: &mut dyn CryptoRngCore,
+        payloads: BTreeMap<Id, Payload>,
+        _artifacts: BTreeMap<Id, Artifact>,
+    ) -> Result<FinalizeOutcome<Id, Self::Protocol>, LocalError> {
+        let (key_init_payloads, aux_gen_payloads) = split_payloads(payloads)?;
+        let next_round = Round2 {
+            other_ids: self.other_ids,
+            key_init: self.key_init.next_round(key_init_payloads)?,
+            aux_gen: self.aux_gen.next_round(aux_gen_payloads)?,
+        };
+        Ok(FinalizeOutcome::AnotherRound(BoxedRound::new_dynamic(next_round)))
+    }
+}
+
+#[derive(Debug)]
+struct Round2<P: SchemeParams, Id: PartyId> {
+    other_ids: BTreeSet<Id>,
+    key_init: key_init::Round2<P, Id, KeyGenTarget>,
+    aux_gen: aux_gen::Round2<P, Id, KeyGenTarget>,
+}
+
+impl<P: SchemeParams, Id: PartyId> Round<Id> for Round2<P, Id> {
+    type Protocol = KeyGenProtocol<P, Id>;
+
+    fn transition_info(&self) -> TransitionInfo {
+        TransitionInfo::new_linear(2)
+    }
+
+    fn communication_info(&self) -> CommunicationInfo<Id> {
+        CommunicationInfo::regular(&self.other_ids)
+    }
+
+    fn make_echo_broadcast(
+        &self,
+        _rng: &mut dyn CryptoRngCore,
+        format: &BoxedFormat,
+    ) -> Result<EchoBroadcast, LocalError> {
+        join_parts(
+            format,
+            self.key_init.echo_broadcast(format)?,
+            self.aux_gen.echo_broadcast(format)?,
+        )
+    }
+
+    fn make_normal_broadcast(
+        &self,
+        _rng: &mut dyn CryptoRngCore,
+        format: &BoxedFormat,
+    ) -> Result<NormalBroadcast, LocalError> {
+        join_parts(
+            format,
+            self.key_init.normal_broadcast(format)?,
+            self.aux_gen.normal_broadcast(format)?,
+        )
+    }
+
+    fn receive_message(
+        &self,
+        format: &BoxedFormat,
+        from: &Id,
+        message: ProtocolMessage,
+    ) -> Result<Payload, ReceiveError<Id, Self::Protocol>> {
+        let (key_init_message, aux_gen_message) = split_message::<ReceiveError<Id, Self::Protocol>>(format, message)?;
+        let key_init_payload = self.key_init.receive(format, from, key_init_message)?;
+        let aux_gen_payload = self.aux_gen.receive(format, from, aux_gen_message)?;
+        Ok(Payload::new((key_init_payload, aux_gen_payload)))
+    }
+
+    fn finalize(
+        self: Box<Self>,
+        rng: &mut dyn CryptoRngCore,
+        payloads: BTreeMap<Id, Payload>,
+        _artifacts: BTreeMap<Id, Artifact>,
+    ) -> Result<FinalizeOutcome<Id, Self::Protocol>, LocalError> {
+        let (key_init_payloads, aux_gen_payloads) = split_payloads(payloads)?;
+        let next_round = Round3 {
+            other_ids: self.other_ids,
+            key_init: self.key_init.next_round(key_init_payloads)?,
+            aux_gen: self.aux_gen.next_round(rng, aux_gen_payloads)?,
+        };
+        Ok(FinalizeOutcome::AnotherRound(BoxedRound::new_dynamic(next_round)))
+    }
+}
+
+#[derive(Debug)]
+struct Round3<P: SchemeParams, Id: PartyId> {
+    other_ids: BTreeSet<Id>,
+    key_init: key_init::Round3<P, Id, KeyGenTarget>,
+    aux_gen: aux_gen::Round3<P, Id, KeyGenTarget>,
+}
+
+impl<P: SchemeParams, Id: PartyId> Round<Id> for Round3<P, Id> {
+    type Protocol = KeyGenProtocol<P, Id>;
+
+    fn transition_info(&self) -> TransitionInfo {
+        TransitionInfo::new_linear_terminating(3)
+    }
+
+    fn communication_info(&self) -> CommunicationInfo<Id> {
+        CommunicationInfo::regular(&self.other_ids)
+    }
+
+    fn make_normal_broadcast(
+        &self,
+        _rng: &mut dyn CryptoRngCore,
+        format: &BoxedFormat,
+    ) -> Result<NormalBroadcast, LocalError> {
+        join_parts(
+            format,
+            self.key_init.normal_broadcast(format)?,
+            self.aux_gen.normal_broadcast(format)?,
+        )
+    }
+
+    fn make_direct_message(
+        &self,
+        rng: &mut dyn CryptoRngCore,
+        format: &BoxedFormat,
+        destination: &Id,
+    ) -> Result<(DirectMessage, Option<Artifact>), LocalError> {
+        // KeyInit does not send direct messages in this round.
+        let (direct_message, artifact) = self.aux_gen.direct_message(rng, format, destination)?;
+        Ok((join_parts(format, DirectMessage::none(), direct_message)?, artifact))
+    }
+
+    fn receive_message(
+        &self,
+        format: &BoxedFormat,
+        from: &Id,
+        message: ProtocolMessage,
+    ) -> Result<Payload, ReceiveError<Id, Self::Protocol>> {
+        let (key_init_message, aux_gen_message) = split_message::<ReceiveError<Id, Self::Protocol>>(format, message)?;
+        self.key_init.receive(format, from, key_init_message)?;
+        self.aux_gen.receive(format, from, aux_gen_message)?;
+        Ok(Payload::empty())
+    }
+
+    fn finalize(
+        self: Box<Self>,
+        _rng: &mut dyn CryptoRngCore,
+        _payloads: BTreeMap<Id, Payload>,
+        _artifacts: BTreeMap<Id, Artifact>,
+    ) -> Result<FinalizeOutcome<Id, Self::Protocol>, LocalError> {
+        let key_share = self.key_init.key_share()?;
+        let aux_info = self.aux_gen.aux_info();
+        Ok(FinalizeOutcome::Result((key_share, aux_info)))
+    }
+}
+
+/// One of the protocols executed in KeyGen.
+#[derive(Debug, Clone, Copy)]
+enum SubProtocol {
+    KeyInit,
+    AuxGen,
+}
+
+impl SubProtocol {
+    /// Selects the part belonging to this sub-protocol out of a pair of (KeyInit, AuxGen) parts.
+    fn select<T>(self, parts: (T, T)) -> T {
+        match self {
+            Self::KeyInit => parts.0,
+            Self::AuxGen => parts.1,
+        }
+    }
+}
+
+/// Joins the message parts of the KeyInit and AuxGen rounds into a single message part.
+fn join_parts<T>(format: &BoxedFormat, key_init: T, aux_gen: T) -> Result<T, LocalError>
+where
+    T: ProtocolMessagePart + Serialize + 'static,
+{
+    if key_init.is_none() && aux_gen.is_none() {
+        Ok(T::none())
+    } else {
+        T::new(format, (key_init, aux_gen))
+    }
+}
+
+/// Splits a message part into the parts of the KeyInit and AuxGen rounds.
+fn split_part<T>(format: &BoxedFormat, part: &T) -> Result<(T, T), T::Error>
+where
+    T: ProtocolMessagePart + for<'de> Deserialize<'de>,
+{
+    if part.is_none() {
+        return Ok((T::none(), T::none()));
+    }
+    part.deserialize::<(T, T)>(format)
+}
+
+/// Splits a message into the messages of the KeyInit and AuxGen rounds.
+fn split_message<E>(format: &BoxedFormat, message: ProtocolMessage) -> Result<(ProtocolMessage, ProtocolMessage), E>
+where
+    E: From<<DirectMessage as ProtocolMessagePart>::Error>
+        + From<<EchoBroadcast as ProtocolMessagePart>::Error>
+        + From<<NormalBroadcast as ProtocolMessagePart>::Error>,
+{
+    let (key_init_direct_message, aux_gen_direct_message) = split_part(format, &message.direct_message)?;
+    let (key_init_echo_broadcast, aux_gen_echo_broadcast) = split_part(format, &message.echo_broadcast)?;
+    let (key_init_normal_broadcast, aux_gen_normal_broadcast) = split_part(format, &message.normal_broadcast)?;
+    let key_init_message = ProtocolMessage {
+        direct_message: key_init_direct_message,
+        echo_broadcast: key_init_echo_broadcast,
+        normal_broadcast: key_init_normal_broadcast,
+    };
+    let aux_gen_message = ProtocolMessage {
+        direct_message: aux_gen_direct_message,
+        echo_broadcast: aux_gen_echo_broadcast,
+        normal_broadcast: aux_gen_normal_broadcast,
+    };
+    Ok((key_init_message, aux_gen_message))
+}
+
+/// Splits the payloads of a KeyGen round into the payloads of the KeyInit and AuxGen rounds.
+#[allow(clippy::type_complexity)]
+fn split_payloads<Id: PartyId>(
+    payloads: BTreeMap<Id, Payload>,
+) -> Result<(BTreeMap<Id, Payload>, BTreeMap<Id, Payload>), LocalError> {
+    let mut key_init_payloads = BTreeMap::new();
+    let mut aux_gen_payloads = BTreeMap::new();
+    for (id, (key_init_payload, aux_gen_payload)) in payloads.downcast_all::<(Payload, Payload)>()? {
+        key_init_payloads.insert(id.clone(), key_init_payload);
+        aux_gen_payloads.insert(id, aux_gen_payload);
+    }
+    Ok((key_init_payloads, aux_gen_payloads))
+}
+
+/// Extracts the messages of a sub-protocol from the messages of KeyGen attached to the evidence.
+#[allow(clippy::type_complexity)]
+fn sub_protocol_messages<Id: PartyId>(
+    format: &BoxedFormat,
+    sub_protocol: SubProtocol,
+    message: ProtocolMessage,
+    previous_messages: BTreeMap<RoundId, ProtocolMessage>,
+    combined_echos: BTreeMap<RoundId, BTreeMap<Id, EchoBroadcast>>,
+) -> Result<
+    (
+        ProtocolMessage,
+        BTreeMap<RoundId, ProtocolMessage>,
+        BTreeMap<RoundId, BTreeMap<Id, EchoBroadcast>>,
+    ),
+    ProtocolValidationError,
+> {
+    let message = sub_protocol.select(split_message::<ProtocolValidationError>(format, message)?);
+
+    let previous_messages = previous_messages
+        .into_iter()
+        .map(|(round_id, message)| {
+            let message = sub_protocol.select(split_message::<ProtocolValidationError>(format, message)?);
+            Ok((round_id, message))
+        })
+        .collect::<Result<BTreeMap<_, _>, ProtocolValidationError>>()?;
+
+    let combined_echos = combined_echos
+        .into_iter()
+        .map(|(round_id, echos)| {
+            let echos = echos
+                .into_iter()
+                .map(|(id, echo)| Ok((id, sub_protocol.select(split_part(format, &echo)?))))
+                .collect::<Result<BTreeMap<_, _>, ProtocolValidationError>>()?;
+            Ok((round_id, echos))
+        })
+        .collect::<Result<BTreeMap<_, _>, ProtocolValidationError>>()?;
+
+    Ok((message, previous_messages, combined_echos))
+}
+
+/// A message part is invalid if it cannot be split into the parts of the sub-protocols,
+/// or if at least one of these parts is invalid according to the corresponding sub-protocol.
+fn verify_part_is_invalid<T>(
+    format: &BoxedFormat,
+    part: &T,
+    verify_key_init_part: impl Fn(&T) -> Result<(), MessageValidationError>,
+    verify_aux_gen_part: impl Fn(&T) -> Result<(), MessageValidationError>,
+) -> Result<(), MessageValidationError>
+where
+    T: ProtocolMessagePart + for<'de> Deserialize<'de>,
+{
+    let Ok((key_init_part, aux_gen_part)) = split_part(format, part) else {
+        return Ok(());
+    };
+    if verify_key_init_part(&key_init_part).is_ok() || verify_aux_gen_part(&aux_gen_part).is_ok() {
+        Ok(())
+    } else {
+        Err(MessageValidationError::InvalidEvidence(
+            "Both KeyInit and AuxGen message parts are valid".into(),
+        ))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use alloc::collections::BTreeSet;
+
+    use manul::{
+        dev::{run_sync, BinaryFormat, TestSessionParams, TestSigner, TestVerifier},
+        signature::Keypair,
+    };
+    use rand_core::OsRng;
+
+    use super::KeyGen;
+    use crate::{dev::TestParams, tools::protocol_shortcuts::MapValues};
+
+    #[test]
+    fn execute_key_gen() {
+        let signers = (0..3).map(TestSigner::new).collect::<Vec<_>>();
+        let id0 = signers[0].verifying_key();
+
+        let all_ids = signers
+            .iter()
+            .map(|signer| signer.verifying_key())
+            .collect::<BTreeSet<_>>();
+        let entry_points = signers
+            .into_iter()
+            .map(|signer| {
+                let entry_point = KeyGen::<TestParams, TestVerifier>::new(all_ids.clone());
+                (signer, entry_point)
+            })
+            .collect::<Vec<_>>();
+
+        let results = run_sync::<_, TestSessionParams<BinaryFormat>>(&mut OsRng, entry_points)
+            .unwrap()
+            .results()
+            .unwrap();
+
+        // Check that both parts of the result belong to the node that created them
+        assert!(results
+            .iter()
+            .all(|(id, (share, aux_info))| share.owner() == id && aux_info.owner() == id));
+
+        // Check that the sets of public keys are the same at each node
+        let public_sets = results.map_values_ref(|(share, _aux_info)| share.public_shares().clone());
+        assert!(public_sets.values().all(|pk| pk == &public_sets[&id0]));
+
+        // Check that the public keys correspond to the secret key shares
+        let public_set = &public_sets[&id0];
+        let public_from_secret = results.map_values_ref(|(share, _aux_info)| share.secret_share().mul_by_generator());
+        assert!(public_set == &public_from_secret);
+    }
+}
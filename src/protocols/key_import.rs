@@ -0,0 +1,798 @@
+//! KeyImport protocol: distributes threshold shares of an existing secret key held by a single party (the dealer).
+//!
+//! The dealer splits the key with Feldman VSS: the public polynomial is echo-broadcasted to all the holders,
+//! and each holder receives its evaluation as a direct message.
+//! The evaluation is masked with a Diffie-Hellman shared point
+//! derived from the dealer's ephemeral key and the holder's public key.
+//! Each holder checks that the polynomial commits to the expected verifying key
+//! and that its share matches the polynomial; a mismatch constitutes a provable fault of the dealer.
+//! In the latter case the holder publishes the shared point along with a proof of its correctness,
+//! making the fault provable without revealing the holder's secret key.
+//!
+//! Note that it is impossible to prove that the dealer erased the original key after the import.
+//! The entry point takes the key by value, and the library does not keep any copies of it after the protocol ends
+//! (if the dealer is not one of the holders, its result is `None`), but the dealer must still be trusted
+//! to erase the key from wherever it was stored before.
+
+use alloc::{
+    boxed::Box,
+    collections::{BTreeMap, BTreeSet},
+    format,
+    vec::Vec,
+};
+use core::{
+    fmt::{self, Debug, Display},
+    marker::PhantomData,
+};
+
+use ecdsa::{SigningKey, VerifyingKey};
+use manul::protocol::{
+    Artifact, BoxedFormat, BoxedRound, CommunicationInfo, DirectMessage, EchoBroadcast, EchoRoundParticipation,
+    EntryPoint, FinalizeOutcome, LocalError, MessageValidationError, NormalBroadcast, PartyId, Payload, Protocol,
+    ProtocolError, ProtocolMessage, ProtocolMessagePart, ProtocolValidationError, ReceiveError, RequiredMessageParts,
+    RequiredMessages, Round, RoundId, TransitionInfo,
+};
+use rand_core::CryptoRngCore;
+
+use crate::{
+    curve::{Point, Scalar},
+    entities::{Sid, ThresholdKeyShare},
+    params::SchemeParams,
+    tools::{
+        dh_mask,
+        protocol_shortcuts::{verify_that, DowncastMap, SafeGet, Without},
+        sss::{assign_share_ids, Polynomial, PublicPolynomial, ShareId},
+        Secret,
+    },
+    zk::{DleqProof, DleqPublicInputs, DleqSecret},
+};
+
+/// The domain separation tag of the masks of the values sent in KeyImport.
+const MASK_DST: &[u8] = b"KeyImport";
+
+/// A protocol for importing an existing secret key as a set of threshold key shares.
+#[derive(Debug)]
+pub struct KeyImportProtocol<P: SchemeParams, I: Debug>(PhantomData<(P, I)>);
+
+impl<P: SchemeParams, I: PartyId> Protocol<I> for KeyImportProtocol<P, I> {
+    type Result = Option<ThresholdKeyShare<P, I>>;
+    type ProtocolError = KeyImportError<P, I>;
+
+    fn verify_direct_message_is_invalid(
+        format: &BoxedFormat,
+        round_id: &RoundId,
+        message: &DirectMessage,
+    ) -> Result<(), MessageValidationError> {
+        match round_id {
+            r if r == &1 => message.verify_is_not::<Round1DirectMessage<P, I>>(format),
+            _ => Err(MessageValidationError::InvalidEvidence("Invalid round number".into())),
+        }
+    }
+
+    fn verify_echo_broadcast_is_invalid(
+        format: &BoxedFormat,
+        round_id: &RoundId,
+        message: &EchoBroadcast,
+    ) -> Result<(), MessageValidationError> {
+        match round_id {
+            r if r == &1 => message.verify_is_not::<Round1EchoBroadcast<P>>(format),
+            _ => Err(MessageValidationError::InvalidEvidence("Invalid round number".into())),
+        }
+    }
+
+    fn verify_normal_broadcast_is_invalid(
+        _format: &BoxedFormat,
+        round_id: &RoundId,
+        message: &NormalBroadcast,
+    ) -> Result<(), MessageValidationError> {
+        match round_id {
+            r if r == &1 => message.verify_is_some(),
+            _ => Err(MessageValidationError::InvalidEvidence("Invalid round number".into())),
+        }
+    }
+}
+
+/// Possible verifiable errors of the KeyImport protocol.
+#[derive(Debug, Clone)]
+#[derive_where::derive_where(Serialize, Deserialize)]
+pub struct KeyImportError<P: SchemeParams, I: PartyId> {
+    error: Error<P, I>,
+}
+
+impl<P: SchemeParams, I: PartyId> Display for KeyImportError<P, I> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> Result<(), fmt::Error> {
+        write!(f, "{}", self.error)
+    }
+}
+
+impl<P: SchemeParams, I: PartyId> From<Error<P, I>> for KeyImportError<P, I> {
+    fn from(source: Error<P, I>) -> Self {
+        Self { error: source }
+    }
+}
+
+// All the faults happen in the only round that receives messages, but the variants are still named after it
+// for consistency with the other protocols.
+#[allow(clippy::enum_variant_names)]
+#[derive(displaydoc::Display, Debug, Clone)]
+#[derive_where::derive_where(Serialize, Deserialize)]
+enum Error<P: SchemeParams, I: PartyId> {
+    /// Round 1: the public polynomial has a wrong number of coefficients.
+    R1WrongPolynomialDegree,
+    /// Round 1: the public polynomial does not commit to the imported verifying key.
+    R1VerifyingKeyMismatch,
+    /// Round 1: the subshare does not match the public polynomial.
+    R1InvalidSubshare {
+        /// The ID of the node that received the subshare.
+        reported_by: I,
+        /// The Diffie-Hellman shared point $D = E * y$,
+        /// where $E$ is the dealer's ephemeral public key and $y$ is the holder's secret key.
+        cap_d: Point<P>,
+        /// The proof that $D$ was calculated correctly.
+        proof: DleqProof<P>,
+    },
+}
+
+/// Associated data for KeyImport protocol.
+#[derive(Debug, Clone)]
+pub struct KeyImportAssociatedData<P: SchemeParams, I> {
+    /// The verifying key of the imported secret key.
+    pub verifying_key: VerifyingKey<P::Curve>,
+    /// IDs of the nodes receiving the shares, mapped to the public keys their shares are encrypted with.
+    pub holder_keys: BTreeMap<I, VerifyingKey<P::Curve>>,
+    /// The threshold of the resulting shares.
+    pub threshold: usize,
+}
+
+impl<P: SchemeParams, I: PartyId> ProtocolError<I> for KeyImportError<P, I> {
+    type AssociatedData = KeyImportAssociatedData<P, I>;
+
+    fn required_messages(&self) -> RequiredMessages {
+        match self.error {
+            Error::R1WrongPolynomialDegree | Error::R1VerifyingKeyMismatch => {
+                RequiredMessages::new(RequiredMessageParts::echo_broadcast(), None, None)
+            }
+            Error::R1InvalidSubshare { .. } => {
+                RequiredMessages::new(RequiredMessageParts::echo_broadcast().and_direct_message(), None, None)
+            }
+        }
+    }
+
+    fn verify_messages_constitute_error(
+        &self,
+        format: &BoxedFormat,
+        guilty_party: &I,
+        shared_randomness: &[u8],
+        associated_data: &Self::AssociatedData,
+        message: ProtocolMessage,
+        _previous_messages: BTreeMap<RoundId, ProtocolMessage>,
+        _combined_echos: BTreeMap<RoundId, BTreeMap<I, EchoBroadcast>>,
+    ) -> Result<(), ProtocolValidationError> {
+        let r1_eb = message.echo_broadcast.deserialize::<Round1EchoBroadcast<P>>(format)?;
+
+        match &self.error {
+            Error::R1WrongPolynomialDegree => {
+                verify_that(r1_eb.public_polynomial.num_coeffs() != associated_data.threshold)
+            }
+            Error::R1VerifyingKeyMismatch => {
+                let coeff0 = r1_eb
+                    .public_polynomial
+                    .coeff0()
+                    .map_err(|_| ProtocolValidationError::InvalidEvidence("Empty public polynomial".into()))?;
+                verify_that(coeff0 != &Point::from_verifying_key(&associated_data.verifying_key))
+            }
+            Error::R1InvalidSubshare {
+                reported_by,
+                cap_d,
+                proof,
+            } => {
+                let r1_dm = message
+                    .direct_message
+                    .deserialize::<Round1DirectMessage<P, I>>(format)?;
+
+                // Make sure the message was actually addressed to the reporting node.
+                if &r1_dm.destination != reported_by {
+                    return Err(ProtocolValidationError::InvalidEvidence(
+                        "The direct message was not addressed to the reporting node".into(),
+                    ));
+                }
+
+                let holders = associated_data.holder_keys.keys().cloned().collect::<BTreeSet<_>>();
+                let mut all_ids = holders.clone();
+                all_ids.insert(guilty_party.clone());
+                let sid = Sid::new::<P, I>(shared_randomness, &all_ids);
+
+                let holder_key = associated_data.holder_keys.try_get("holder keys", reported_by)?;
+                let holder_key = Point::from_verifying_key(holder_key);
+                let public = DleqPublicInputs {
+                    cap_x: &holder_key,
+                    cap_e: &r1_dm.cap_e,
+                    cap_d,
+                };
+                if !proof.verify(public, &(&sid, guilty_party)) {
+                    return Err(ProtocolValidationError::InvalidEvidence(
+                        "Failed to verify the shared point".into(),
+                    ));
+                }
+
+                let share_ids = assign_share_ids::<P, I>(&holders);
+                let share_id = share_ids.try_get("share IDs", reported_by)?;
+                let subshare = r1_dm.cap_c - dh_mask::<P, I>(MASK_DST, &sid, guilty_party, cap_d, 0);
+                verify_that(r1_eb.public_polynomial.evaluate(share_id) != subshare.mul_by_generator())
+            }
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+enum Role<P: SchemeParams, I> {
+    Dealer {
+        secret: Secret<Scalar<P>>,
+        holder_keys: BTreeMap<I, VerifyingKey<P::Curve>>,
+    },
+    Holder {
+        dealer: I,
+        secret_key: Secret<Scalar<P>>,
+    },
+}
+
+/// An entry point for the [`KeyImportProtocol`].
+#[derive(Debug, Clone)]
+pub struct KeyImport<P: SchemeParams, I> {
+    role: Role<P, I>,
+    verifying_key: VerifyingKey<P::Curve>,
+    holders: BTreeSet<I>,
+    threshold: usize,
+}
+
+impl<P, I> KeyImport<P, I>
+where
+    P: SchemeParams,
+    I: PartyId,
+{
+    /// Creates a new entry point for the dealer holding the secret key being imported.
+    ///
+    /// `holder_keys` maps the IDs of the holders of the resulting shares to the public keys
+    /// the shares will be encrypted with.
+    /// The dealer may or may not be one of the holders.
+    pub fn new_dealer(
+        signing_key: SigningKey<P::Curve>,
+        holder_keys: BTreeMap<I, VerifyingKey<P::Curve>>,
+        threshold: usize,
+    ) -> Result<Self, LocalError> {
+        let verifying_key = *signing_key.verifying_key();
+        let secret = Secret::init_with(|| Scalar::<P>::from(signing_key.as_nonzero_scalar()));
+        let holders = holder_keys.keys().cloned().collect();
+        Self::new(Role::Dealer { secret, holder_keys }, verifying_key, holders, threshold)
+    }
+
+    /// Creates a new entry point for a node receiving a share from the given dealer.
+    ///
+    /// `secret_key` is the secret counterpart of the public key the dealer encrypts this node's share with,
+    /// and `verifying_key` is the verifying key of the secret key being imported,
+    /// which the holder must have obtained from a trusted source.
+    pub fn new_holder(
+        dealer: I,
+        secret_key: &SigningKey<P::Curve>,
+        verifying_key: VerifyingKey<P::Curve>,
+        holders: BTreeSet<I>,
+        threshold: usize,
+    ) -> Result<Self, LocalError> {
+        let secret_key = Secret::init_with(|| Scalar::from(secret_key.as_nonzero_scalar()));
+        Self::new(Role::Holder { dealer, secret_key }, verifying_key, holders, threshold)
+    }
+
+    fn new(
+        role: Role<P, I>,
+        verifying_key: VerifyingKey<P::Curve>,
+        holders: BTreeSet<I>,
+        threshold: usize,
+    ) -> Result<Self, LocalError> {
+        if threshold == 0 || threshold > holders.len() {
+            return Err(LocalError::new(format!(
+                "The threshold must be between 1 and the number of holders ({}), got {threshold}",
+                holders.len()
+            )));
+        }
+        Ok(Self {
+            role,
+            verifying_key,
+            holders,
+            threshold,
+        })
+    }
+}
+
+impl<P, I> EntryPoint<I> for KeyImport<P, I>
+where
+    P: SchemeParams,
+    I: PartyId,
+{
+    type Protocol = KeyImportProtocol<P, I>;
+
+    fn entry_round_id() -> RoundId {
+        1.into()
+    }
+
+    fn make_round(
+        self,
+        rng: &mut dyn CryptoRngCore,
+        shared_randomness: &[u8],
+        id: &I,
+    ) -> Result<BoxedRound<I, Self::Protocol>, LocalError> {
+        let share_ids = assign_share_ids(&self.holders);
+
+        let (dealer, dealer_data, holder_data) = match self.role {
+            Role::Dealer { secret, holder_keys } => {
+                let polynomial = Polynomial::random(rng, secret, self.threshold);
+                let public_polynomial = polynomial.public();
+                let ephemeral_key = Secret::init_with(|| Scalar::random(rng));
+                let cap_ds = holder_keys
+                    .iter()
+                    .map(|(id, key)| (id.clone(), Point::from_verifying_key(key) * &ephemeral_key))
+                    .collect();
+                let dealer_data = DealerData {
+                    polynomial,
+                    public_polynomial,
+                    cap_e: ephemeral_key.mul_by_generator(),
+                    cap_ds,
+                };
+                (id.clone(), Some(dealer_data), None)
+            }
+            Role::Holder { dealer, secret_key } => {
+                if !self.holders.contains(id) {
+                    return Err(LocalError::new("The given holder IDs must contain this node's ID"));
+                }
+                if &dealer == id {
+                    return Err(LocalError::new(
+                        "The dealer must be initialized with `KeyImport::new_dealer()`",
+                    ));
+                }
+                let holder_data = HolderData {
+                    secret_key,
+                    dleq_secret: DleqSecret::random(rng),
+                };
+                (dealer, None, Some(holder_data))
+            }
+        };
+
+        let mut all_ids = self.holders.clone();
+        all_ids.insert(dealer.clone());
+        let sid = Sid::new::<P, I>(shared_randomness, &all_ids);
+
+        // The dealer is the only sender, and it does not need to receive its own echo broadcast back,
+        // so it does not take part in the echo round.
+        let (message_destinations, expecting_messages_from, echo_round_participation) = if dealer_data.is_some() {
+            (
+                self.holders.clone().without(id),
+                BTreeSet::new(),
+                EchoRoundParticipation::Send,
+            )
+        } else {
+            let echo_targets = self.holders.clone().without(id).without(&dealer);
+            (
+                BTreeSet::new(),
+                BTreeSet::from([dealer.clone()]),
+                EchoRoundParticipation::Receive { echo_targets },
+            )
+        };
+
+        Ok(BoxedRound::new_dynamic(Round1 {
+            my_id: id.clone(),
+            sid,
+            dealer,
+            dealer_data,
+            holder_data,
+            verifying_key: Point::from_verifying_key(&self.verifying_key),
+            share_ids,
+            threshold: self.threshold,
+            message_destinations,
+            expecting_messages_from,
+            echo_round_participation,
+        }))
+    }
+}
+
+#[derive(Debug)]
+struct DealerData<P: SchemeParams, I> {
+    polynomial: Polynomial<P>,
+    public_polynomial: PublicPolynomial<P>,
+    cap_e: Point<P>,
+    cap_ds: BTreeMap<I, Point<P>>,
+}
+
+#[derive(Debug)]
+struct HolderData<P: SchemeParams> {
+    secret_key: Secret<Scalar<P>>,
+    dleq_secret: DleqSecret<P>,
+}
+
+#[derive(Debug)]
+struct Round1<P: SchemeParams, I> {
+    my_id: I,
+    sid: Sid,
+    dealer: I,
+    dealer_data: Option<DealerData<P, I>>,
+    holder_data: Option<HolderData<P>>,
+    verifying_key: Point<P>,
+    share_ids: BTreeMap<I, ShareId<P>>,
+    threshold: usize,
+    message_destinations: BTreeSet<I>,
+    expecting_messages_from: BTreeSet<I>,
+    echo_round_participation: EchoRoundParticipation<I>,
+}
+
+#[derive(Debug, Clone)]
+#[derive_where::derive_where(Serialize, Deserialize)]
+pub(super) struct Round1EchoBroadcast<P: SchemeParams> {
+    pub(super) public_polynomial: PublicPolynomial<P>,
+}
+
+#[derive(Debug, Clone)]
+#[derive_where::derive_where(Serialize, Deserialize)]
+pub(super) struct Round1DirectMessage<P: SchemeParams, I: PartyId> {
+    // Included so that the message could not be used as evidence by a node it was not addressed to.
+    destination: I,
+    /// The ephemeral public key $E$.
+    cap_e: Point<P>,
+    /// The masked subshare.
+    pub(super) cap_c: Scalar<P>,
+}
+
+struct Round1Payload<P: SchemeParams> {
+    public_polynomial: PublicPolynomial<P>,
+    subshare: Secret<Scalar<P>>,
+}
+
+impl<P: SchemeParams, I: PartyId> Round<I> for Round1<P, I> {
+    type Protocol = KeyImportProtocol<P, I>;
+
+    fn transition_info(&self) -> TransitionInfo {
+        TransitionInfo::new_linear_terminating(1)
+    }
+
+    fn communication_info(&self) -> CommunicationInfo<I> {
+        CommunicationInfo {
+            message_destinations: self.message_destinations.clone(),
+            expecting_messages_from: self.expecting_messages_from.clone(),
+            echo_round_participation: self.echo_round_participation.clone(),
+        }
+    }
+
+    fn make_echo_broadcast(
+        &self,
+        _rng: &mut dyn CryptoRngCore,
+        format: &BoxedFormat,
+    ) -> Result<EchoBroadcast, LocalError> {
+        if let Some(dealer_data) = self.dealer_data.as_ref() {
+            EchoBroadcast::new(
+                format,
+                Round1EchoBroadcast {
+                    public_polynomial: dealer_data.public_polynomial.clone(),
+                },
+            )
+        } else {
+            Ok(EchoBroadcast::none())
+        }
+    }
+
+    fn make_direct_message(
+        &self,
+        _rng: &mut dyn CryptoRngCore,
+        format: &BoxedFormat,
+        destination: &I,
+    ) -> Result<(DirectMessage, Option<Artifact>), LocalError> {
+        if let Some(dealer_data) = self.dealer_data.as_ref() {
+            let their_share_id = self.share_ids.safe_get("share IDs", destination)?;
+            let cap_d = dealer_data.cap_ds.safe_get("shared points", destination)?;
+            let mask = dh_mask::<P, I>(MASK_DST, &self.sid, &self.my_id, cap_d, 0);
+            let cap_c = *(dealer_data.polynomial.evaluate(their_share_id) + &mask).expose_secret();
+            let direct_message = DirectMessage::new(
+                format,
+                Round1DirectMessage {
+                    destination: destination.clone(),
+                    cap_e: dealer_data.cap_e,
+                    cap_c,
+                },
+            )?;
+            Ok((direct_message, None))
+        } else {
+            Ok((DirectMessage::none(), None))
+        }
+    }
+
+    fn receive_message(
+        &self,
+        format: &BoxedFormat,
+        from: &I,
+        message: ProtocolMessage,
+    ) -> Result<Payload, ReceiveError<I, Self::Protocol>> {
+        message.normal_broadcast.assert_is_none()?;
+        let echo_broadcast = message.echo_broadcast.deserialize::<Round1EchoBroadcast<P>>(format)?;
+        let direct_message = message
+            .direct_message
+            .deserialize::<Round1DirectMessage<P, I>>(format)?;
+
+        if echo_broadcast.public_polynomial.num_coeffs() != self.threshold {
+            return Err(ReceiveError::protocol(Error::R1WrongPolynomialDegree.into()));
+        }
+
+        if echo_broadcast.public_polynomial.coeff0()? != &self.verifying_key {
+            return Err(ReceiveError::protocol(Error::R1VerifyingKeyMismatch.into()));
+        }
+
+        if direct_message.destination != self.my_id {
+            return Err(ReceiveError::unprovable(
+                "The direct message is addressed to another node",
+            ));
+        }
+
+        let holder_data = self
+            .holder_data
+            .as_ref()
+            .ok_or_else(|| LocalError::new("Only the holders receive messages"))?;
+        let cap_d = direct_message.cap_e * &holder_data.secret_key;
+        let mask = dh_mask::<P, I>(MASK_DST, &self.sid, from, &cap_d, 0);
+        let subshare = Secret::init_with(|| direct_message.cap_c - mask);
+
+        let my_share_id = self.share_ids.safe_get("share IDs", &self.my_id)?;
+        if subshare.mul_by_generator() != echo_broadcast.public_polynomial.evaluate(my_share_id) {
+            let holder_key = holder_data.secret_key.mul_by_generator();
+            let public = DleqPublicInputs {
+                cap_x: &holder_key,
+                cap_e: &direct_message.cap_e,
+                cap_d: &cap_d,
+            };
+            let proof = DleqProof::new(
+                &holder_data.dleq_secret,
+                &holder_data.secret_key,
+                public,
+                &(&self.sid, from),
+            );
+            return Err(ReceiveError::protocol(
+                Error::R1InvalidSubshare {
+                    reported_by: self.my_id.clone(),
+                    cap_d,
+                    proof,
+                }
+                .into(),
+            ));
+        }
+
+        Ok(Payload::new(Round1Payload {
+            public_polynomial: echo_broadcast.public_polynomial,
+            subshare,
+        }))
+    }
+
+    fn finalize(
+        self: Box<Self>,
+        _rng: &mut dyn CryptoRngCore,
+        payloads: BTreeMap<I, Payload>,
+        _artifacts: BTreeMap<I, Artifact>,
+    ) -> Result<FinalizeOutcome<I, Self::Protocol>, LocalError> {
+        // If this party is not a holder (which means it is the dealer), exit.
+        let my_share_id = match self.share_ids.get(&self.my_id) {
+            Some(share_id) => share_id,
+            None => return Ok(FinalizeOutcome::Result(None)),
+        };
+
+        let (public_polynomial, secret_share) = if let Some(dealer_data) = self.dealer_data {
+            // The dealer is also a holder, it can evaluate its own share.
+            let secret_share = dealer_data.polynomial.evaluate(my_share_id);
+            (dealer_data.public_polynomial, secret_share)
+        } else {
+            let mut payloads = payloads.downcast_all::<Round1Payload<P>>()?;
+            let payload = payloads
+                .remove(&self.dealer)
+                .ok_or_else(|| LocalError::new("The dealer's payload is missing"))?;
+            (payload.public_polynomial, payload.subshare)
+        };
+
+        let public_shares = self
+            .share_ids
+            .iter()
+            .map(|(id, share_id)| (id.clone(), public_polynomial.evaluate(share_id)))
+            .collect::<BTreeMap<_, _>>();
+
+        let threshold =
+            u32::try_from(self.threshold).map_err(|_| LocalError::new("The threshold does not fit into `u32`"))?;
+
+        Ok(FinalizeOutcome::Result(Some(ThresholdKeyShare {
+            owner: self.my_id,
+            threshold,
+            secret_share,
+            share_ids: self.share_ids.into(),
+            public_shares: public_shares.into(),
+            extra_shares: BTreeMap::new().into(),
+            extra_secret_shares: Vec::new(),
+            access_structure: None,
+        })))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use alloc::collections::{BTreeMap, BTreeSet};
+
+    use ecdsa::{SigningKey, VerifyingKey};
+    use manul::{
+        dev::{run_sync, BinaryFormat, TestSessionParams, TestSigner, TestVerifier},
+        signature::Keypair,
+    };
+    use rand_core::OsRng;
+
+    use super::KeyImport;
+    use crate::{dev::TestParams, SchemeParams, ThresholdKeyShare};
+
+    type Curve = <TestParams as SchemeParams>::Curve;
+
+    #[allow(clippy::type_complexity)]
+    fn make_holder_keys(
+        holders: &BTreeSet<TestVerifier>,
+    ) -> (
+        BTreeMap<TestVerifier, SigningKey<Curve>>,
+        BTreeMap<TestVerifier, VerifyingKey<Curve>>,
+    ) {
+        let secret_keys = holders
+            .iter()
+            .map(|id| (*id, SigningKey::random(&mut OsRng)))
+            .collect::<BTreeMap<_, _>>();
+        let public_keys = secret_keys
+            .iter()
+            .map(|(id, secret_key)| (*id, *secret_key.verifying_key()))
+            .collect();
+        (secret_keys, public_keys)
+    }
+
+    fn check_shares(
+        shares: &BTreeMap<TestVerifier, ThresholdKeyShare<TestParams, TestVerifier>>,
+        signing_key: &SigningKey<Curve>,
+        threshold: usize,
+    ) {
+        let holders = shares.keys().cloned().collect::<BTreeSet<_>>();
+        let share0 = shares.values().next().unwrap();
+
+        // Check that the public data is the same at each node
+        assert!(shares.values().all(|share| share.public_shares == share0.public_shares
+            && share.share_ids == share0.share_ids
+            && share.threshold() == threshold));
+
+        // Check that the public keys correspond to the secret key shares
+        for share in shares.values() {
+            assert_eq!(share.secret_share.mul_by_generator(), share.public_shares[&share.owner]);
+        }
+
+        // Check that any `threshold` shares produce the imported verifying key
+        for excluded in &holders {
+            let subset = holders
+                .iter()
+                .filter(|id| id != &excluded)
+                .cloned()
+                .collect::<BTreeSet<_>>();
+            for id in &subset {
+                let key_share = shares[id].to_key_share(&subset).unwrap();
+                assert_eq!(&key_share.verifying_key(), signing_key.verifying_key());
+            }
+        }
+    }
+
+    #[test]
+    fn execute_key_import() {
+        let signers = (0..4).map(TestSigner::new).collect::<Vec<_>>();
+        let ids = signers.iter().map(|signer| signer.verifying_key()).collect::<Vec<_>>();
+        let holders = BTreeSet::from([ids[1], ids[2], ids[3]]);
+        let threshold = 2;
+
+        let signing_key = SigningKey::random(&mut OsRng);
+        let verifying_key = *signing_key.verifying_key();
+
+        let (holder_secret_keys, holder_keys) = make_holder_keys(&holders);
+
+        // Invalid thresholds are rejected
+        let secret_key = &holder_secret_keys[&ids[1]];
+        assert!(KeyImport::<TestParams, TestVerifier>::new_holder(
+            ids[0],
+            secret_key,
+            verifying_key,
+            holders.clone(),
+            0
+        )
+        .is_err());
+        assert!(KeyImport::<TestParams, TestVerifier>::new_holder(
+            ids[0],
+            secret_key,
+            verifying_key,
+            holders.clone(),
+            4
+        )
+        .is_err());
+
+        let entry_points = signers
+            .into_iter()
+            .map(|signer| {
+                let id = signer.verifying_key();
+                let entry_point = if id == ids[0] {
+                    KeyImport::<TestParams, TestVerifier>::new_dealer(
+                        signing_key.clone(),
+                        holder_keys.clone(),
+                        threshold,
+                    )
+                } else {
+                    KeyImport::new_holder(
+                        ids[0],
+                        &holder_secret_keys[&id],
+                        verifying_key,
+                        holders.clone(),
+                        threshold,
+                    )
+                }
+                .unwrap();
+                (signer, entry_point)
+            })
+            .collect::<Vec<_>>();
+
+        let shares = run_sync::<_, TestSessionParams<BinaryFormat>>(&mut OsRng, entry_points)
+            .unwrap()
+            .results()
+            .unwrap();
+
+        // The dealer is not a holder, so it does not keep anything.
+        assert!(shares[&ids[0]].is_none());
+
+        let shares = shares
+            .into_iter()
+            .filter(|(id, _share)| id != &ids[0])
+            .map(|(id, share)| (id, share.unwrap()))
+            .collect::<BTreeMap<_, _>>();
+
+        check_shares(&shares, &signing_key, threshold);
+    }
+
+    #[test]
+    fn execute_key_import_dealer_is_holder() {
+        let signers = (0..3).map(TestSigner::new).collect::<Vec<_>>();
+        let ids = signers.iter().map(|signer| signer.verifying_key()).collect::<Vec<_>>();
+        let holders = ids.iter().cloned().collect::<BTreeSet<_>>();
+        let threshold = 2;
+
+        let signing_key = SigningKey::random(&mut OsRng);
+        let verifying_key = *signing_key.verifying_key();
+
+        let (holder_secret_keys, holder_keys) = make_holder_keys(&holders);
+
+        let entry_points = signers
+            .into_iter()
+            .map(|signer| {
+                let id = signer.verifying_key();
+                let entry_point = if id == ids[0] {
+                    KeyImport::<TestParams, TestVerifier>::new_dealer(
+                        signing_key.clone(),
+                        holder_keys.clone(),
+                        threshold,
+                    )
+                } else {
+                    KeyImport::new_holder(
+                        ids[0],
+                        &holder_secret_keys[&id],
+                        verifying_key,
+                        holders.clone(),
+                        threshold,
+                    )
+                }
+                .unwrap();
+                (signer, entry_point)
+            })
+            .collect::<Vec<_>>();
+
+        let shares = run_sync::<_, TestSessionParams<BinaryFormat>>(&mut OsRng, entry_points)
+            .unwrap()
+            .results()
+            .unwrap()
+            .into_iter()
+            .map(|(id, share)| (id, share.unwrap()))
+            .collect::<BTreeMap<_, _>>();
+
+        check_shares(&shares, &signing_key, threshold);
+    }
+}
@@ -249,41 +249,86 @@ impl<P: SchemeParams, Id: PartyId> EntryPoint<Id> for KeyInit<P, Id> {
         shared_randomness: &[u8],
         id: &Id,
     ) -> Result<BoxedRound<Id, Self::Protocol>, LocalError> {
-        if !self.all_ids.contains(id) {
-            return Err(LocalError::new("The given node IDs must contain this node's ID"));
-        }
-
-        let other_ids = self.all_ids.clone().without(id);
+        let round = make_round1(rng, shared_randomness, id, &self.all_ids, ReturnKeyShare)?;
+        Ok(BoxedRound::new_dynamic(round))
+    }
+}
 
-        let sid = Sid::new::<P, Id>(shared_randomness, &self.all_ids);
+/// Determines which protocol the KeyInit rounds are a part of.
+pub(super) trait KeyInitTarget<P: SchemeParams, Id: PartyId>: 'static + Debug + Send + Sync + Sized {
+    /// The protocol the KeyInit rounds are a part of.
+    type Protocol: Protocol<Id, ProtocolError: From<KeyInitError<P>>>;
+}
 
-        // The secret share
-        let x = Secret::init_with(|| Scalar::random(rng));
-        // The public share
-        let cap_x = x.mul_by_generator();
+/// A [`KeyInitTarget`] of a protocol that finishes along with the KeyInit rounds.
+pub(super) trait KeyInitResult<P: SchemeParams, Id: PartyId>: KeyInitTarget<P, Id> {
+    /// Wraps the created key share into the result of the target protocol.
+    fn key_init_result(self, key_share: KeyShare<P, Id>) -> <Self::Protocol as Protocol<Id>>::Result;
+}
 
-        let rho = BitVec::random(rng, P::SECURITY_PARAMETER);
-        let tau = SchSecret::random(rng);
-        let cap_a = SchCommitment::new(&tau);
-        let u = BitVec::random(rng, P::SECURITY_PARAMETER);
+/// Return the key share as the result (used in [`KeyInitProtocol`]).
+#[derive(Debug)]
+pub(super) struct ReturnKeyShare;
 
-        let public_data = PublicData { cap_x, cap_a, rho, u };
+impl<P: SchemeParams, Id: PartyId> KeyInitTarget<P, Id> for ReturnKeyShare {
+    type Protocol = KeyInitProtocol<P, Id>;
+}
 
-        let context = Context {
-            other_ids,
-            my_id: id.clone(),
-            x,
-            tau,
-            public_data,
-            sid,
-        };
+impl<P: SchemeParams, Id: PartyId> KeyInitResult<P, Id> for ReturnKeyShare {
+    fn key_init_result(self, key_share: KeyShare<P, Id>) -> KeyShare<P, Id> {
+        key_share
+    }
+}
 
-        Ok(BoxedRound::new_dynamic(Round1 { context }))
+/// Creates the first round of KeyInit for the node `id` out of `all_ids`.
+pub(super) fn make_round1<P, Id, T>(
+    rng: &mut dyn CryptoRngCore,
+    shared_randomness: &[u8],
+    id: &Id,
+    all_ids: &BTreeSet<Id>,
+    target: T,
+) -> Result<Round1<P, Id, T>, LocalError>
+where
+    P: SchemeParams,
+    Id: PartyId,
+    T: KeyInitTarget<P, Id>,
+{
+    if !all_ids.contains(id) {
+        return Err(LocalError::new("The given node IDs must contain this node's ID"));
     }
+
+    let other_ids = all_ids.clone().without(id);
+
+    let sid = Sid::new::<P, Id>(shared_randomness, all_ids);
+
+    // The secret share
+    let x = Secret::init_with(|| Scalar::random(rng));
+    // The public share
+    let cap_x = x.mul_by_generator();
+
+    let rho = BitVec::random(rng, P::SECURITY_PARAMETER);
+    let tau = SchSecret::random(rng);
+    let cap_a = SchCommitment::new(&tau);
+    let u = BitVec::random(rng, P::SECURITY_PARAMETER);
+
+    let public_data = PublicData { cap_x, cap_a, rho, u };
+
+    let context = Context {
+        target,
+        other_ids,
+        my_id: id.clone(),
+        x,
+        tau,
+        public_data,
+        sid,
+    };
+
+    Ok(Round1 { context })
 }
 
 #[derive(Debug)]
-pub(super) struct Context<P: SchemeParams, Id> {
+pub(super) struct Context<P: SchemeParams, Id, T> {
+    target: T,
     pub(super) other_ids: BTreeSet<Id>,
     pub(super) my_id: Id,
     pub(super) x: Secret<Scalar<P>>,
@@ -293,8 +338,8 @@ pub(super) struct Context<P: SchemeParams, Id> {
 }
 
 #[derive(Debug)]
-struct Round1<P: SchemeParams, Id> {
-    context: Context<P, Id>,
+pub(super) struct Round1<P: SchemeParams, Id, T> {
+    context: Context<P, Id, T>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -306,12 +351,45 @@ struct Round1Payload {
     cap_v: HashOutput,
 }
 
-impl<P, Id> Round<Id> for Round1<P, Id>
+impl<P: SchemeParams, Id: PartyId, T: KeyInitTarget<P, Id>> Round1<P, Id, T> {
+    /// Creates the echo broadcast of this round.
+    pub(super) fn echo_broadcast(&self, format: &BoxedFormat) -> Result<EchoBroadcast, LocalError> {
+        let cap_v = self.context.public_data.hash(&self.context.sid, &self.context.my_id);
+        EchoBroadcast::new(format, Round1EchoBroadcast { cap_v })
+    }
+
+    /// Processes a message of this round.
+    pub(super) fn receive(
+        &self,
+        format: &BoxedFormat,
+        message: ProtocolMessage,
+    ) -> Result<Payload, ReceiveError<Id, T::Protocol>> {
+        message.normal_broadcast.assert_is_none()?;
+        message.direct_message.assert_is_none()?;
+        let echo_broadcast = message.echo_broadcast.deserialize::<Round1EchoBroadcast>(format)?;
+        Ok(Payload::new(Round1Payload {
+            cap_v: echo_broadcast.cap_v,
+        }))
+    }
+
+    /// Creates the next round once all the messages of this round are received.
+    pub(super) fn next_round(self, payloads: BTreeMap<Id, Payload>) -> Result<Round2<P, Id, T>, LocalError> {
+        let payloads = payloads.downcast_all::<Round1Payload>()?;
+        let cap_vs = payloads.map_values(|payload| payload.cap_v);
+        Ok(Round2 {
+            context: self.context,
+            cap_vs,
+        })
+    }
+}
+
+impl<P, Id, T> Round<Id> for Round1<P, Id, T>
 where
     P: SchemeParams,
     Id: PartyId,
+    T: KeyInitResult<P, Id>,
 {
-    type Protocol = KeyInitProtocol<P, Id>;
+    type Protocol = T::Protocol;
 
     fn transition_info(&self) -> TransitionInfo {
         TransitionInfo::new_linear(1)
@@ -326,8 +404,7 @@ where
         _rng: &mut dyn CryptoRngCore,
         format: &BoxedFormat,
     ) -> Result<EchoBroadcast, LocalError> {
-        let cap_v = self.context.public_data.hash(&self.context.sid, &self.context.my_id);
-        EchoBroadcast::new(format, Round1EchoBroadcast { cap_v })
+        self.echo_broadcast(format)
     }
 
     fn receive_message(
@@ -336,12 +413,7 @@ where
         _from: &Id,
         message: ProtocolMessage,
     ) -> Result<Payload, ReceiveError<Id, Self::Protocol>> {
-        message.normal_broadcast.assert_is_none()?;
-        message.direct_message.assert_is_none()?;
-        let echo_broadcast = message.echo_broadcast.deserialize::<Round1EchoBroadcast>(format)?;
-        Ok(Payload::new(Round1Payload {
-            cap_v: echo_broadcast.cap_v,
-        }))
+        self.receive(format, message)
     }
 
     fn finalize(
@@ -350,19 +422,14 @@ where
         payloads: BTreeMap<Id, Payload>,
         _artifacts: BTreeMap<Id, Artifact>,
     ) -> Result<FinalizeOutcome<Id, Self::Protocol>, LocalError> {
-        let payloads = payloads.downcast_all::<Round1Payload>()?;
-        let cap_vs = payloads.map_values(|payload| payload.cap_v);
-        let next_round = Round2 {
-            context: self.context,
-            cap_vs,
-        };
+        let next_round = self.next_round(payloads)?;
         Ok(FinalizeOutcome::AnotherRound(BoxedRound::new_dynamic(next_round)))
     }
 }
 
 #[derive(Debug)]
-struct Round2<P: SchemeParams, Id> {
-    context: Context<P, Id>,
+pub(super) struct Round2<P: SchemeParams, Id, T> {
+    context: Context<P, Id, T>,
     cap_vs: BTreeMap<Id, HashOutput>,
 }
 
@@ -385,22 +452,9 @@ struct Round2Payload<P: SchemeParams> {
     rho: BitVec,
 }
 
-impl<P: SchemeParams, Id: PartyId> Round<Id> for Round2<P, Id> {
-    type Protocol = KeyInitProtocol<P, Id>;
-
-    fn transition_info(&self) -> TransitionInfo {
-        TransitionInfo::new_linear(2)
-    }
-
-    fn communication_info(&self) -> CommunicationInfo<Id> {
-        CommunicationInfo::regular(&self.context.other_ids)
-    }
-
-    fn make_echo_broadcast(
-        &self,
-        _rng: &mut dyn CryptoRngCore,
-        format: &BoxedFormat,
-    ) -> Result<EchoBroadcast, LocalError> {
+impl<P: SchemeParams, Id: PartyId, T: KeyInitTarget<P, Id>> Round2<P, Id, T> {
+    /// Creates the echo broadcast of this round.
+    pub(super) fn echo_broadcast(&self, format: &BoxedFormat) -> Result<EchoBroadcast, LocalError> {
         EchoBroadcast::new(
             format,
             Round2EchoBroadcast {
@@ -409,11 +463,8 @@ impl<P: SchemeParams, Id: PartyId> Round<Id> for Round2<P, Id> {
         )
     }
 
-    fn make_normal_broadcast(
-        &self,
-        _rng: &mut dyn CryptoRngCore,
-        format: &BoxedFormat,
-    ) -> Result<NormalBroadcast, LocalError> {
+    /// Creates the normal broadcast of this round.
+    pub(super) fn normal_broadcast(&self, format: &BoxedFormat) -> Result<NormalBroadcast, LocalError> {
         NormalBroadcast::new(
             format,
             Round2NormalBroadcast {
@@ -424,12 +475,13 @@ impl<P: SchemeParams, Id: PartyId> Round<Id> for Round2<P, Id> {
         )
     }
 
-    fn receive_message(
+    /// Processes a message of this round from the node `from`.
+    pub(super) fn receive(
         &self,
         format: &BoxedFormat,
         from: &Id,
         message: ProtocolMessage,
-    ) -> Result<Payload, ReceiveError<Id, Self::Protocol>> {
+    ) -> Result<Payload, ReceiveError<Id, T::Protocol>> {
         message.direct_message.assert_is_none()?;
         let normal_broadcast = message
             .normal_broadcast
@@ -445,7 +497,7 @@ impl<P: SchemeParams, Id: PartyId> Round<Id> for Round2<P, Id> {
         };
 
         if &data.hash(&self.context.sid, from) != cap_v {
-            return Err(ReceiveError::protocol(Error::R2HashMismatch.into()));
+            return Err(ReceiveError::protocol(KeyInitError::from(Error::R2HashMismatch).into()));
         }
 
         Ok(Payload::new(Round2Payload {
@@ -455,12 +507,8 @@ impl<P: SchemeParams, Id: PartyId> Round<Id> for Round2<P, Id> {
         }))
     }
 
-    fn finalize(
-        self: Box<Self>,
-        _rng: &mut dyn CryptoRngCore,
-        payloads: BTreeMap<Id, Payload>,
-        _artifacts: BTreeMap<Id, Artifact>,
-    ) -> Result<FinalizeOutcome<Id, Self::Protocol>, LocalError> {
+    /// Creates the next round once all the messages of this round are received.
+    pub(super) fn next_round(self, payloads: BTreeMap<Id, Payload>) -> Result<Round3<P, Id, T>, LocalError> {
         let payloads = payloads.downcast_all::<Round2Payload<P>>()?;
 
         let mut rho_combined = self.context.public_data.rho.clone();
@@ -471,20 +519,65 @@ impl<P: SchemeParams, Id: PartyId> Round<Id> for Round2<P, Id> {
         let cap_xs = payloads.map_values_ref(|payload| payload.cap_x);
         let cap_as = payloads.map_values_ref(|payload| payload.cap_a.clone());
 
-        let next_round = Round3 {
+        Ok(Round3 {
             context: self.context,
             cap_xs,
             cap_as,
             rho_combined,
-        };
+        })
+    }
+}
 
+impl<P: SchemeParams, Id: PartyId, T: KeyInitResult<P, Id>> Round<Id> for Round2<P, Id, T> {
+    type Protocol = T::Protocol;
+
+    fn transition_info(&self) -> TransitionInfo {
+        TransitionInfo::new_linear(2)
+    }
+
+    fn communication_info(&self) -> CommunicationInfo<Id> {
+        CommunicationInfo::regular(&self.context.other_ids)
+    }
+
+    fn make_echo_broadcast(
+        &self,
+        _rng: &mut dyn CryptoRngCore,
+        format: &BoxedFormat,
+    ) -> Result<EchoBroadcast, LocalError> {
+        self.echo_broadcast(format)
+    }
+
+    fn make_normal_broadcast(
+        &self,
+        _rng: &mut dyn CryptoRngCore,
+        format: &BoxedFormat,
+    ) -> Result<NormalBroadcast, LocalError> {
+        self.normal_broadcast(format)
+    }
+
+    fn receive_message(
+        &self,
+        format: &BoxedFormat,
+        from: &Id,
+        message: ProtocolMessage,
+    ) -> Result<Payload, ReceiveError<Id, Self::Protocol>> {
+        self.receive(format, from, message)
+    }
+
+    fn finalize(
+        self: Box<Self>,
+        _rng: &mut dyn CryptoRngCore,
+        payloads: BTreeMap<Id, Payload>,
+        _artifacts: BTreeMap<Id, Artifact>,
+    ) -> Result<FinalizeOutcome<Id, Self::Protocol>, LocalError> {
+        let next_round = self.next_round(payloads)?;
         Ok(FinalizeOutcome::AnotherRound(BoxedRound::new_dynamic(next_round)))
     }
 }
 
 #[derive(Debug)]
-pub(super) struct Round3<P: SchemeParams, Id> {
-    pub(super) context: Context<P, Id>,
+pub(super) struct Round3<P: SchemeParams, Id, T> {
+    pub(super) context: Context<P, Id, T>,
     pub(super) cap_xs: BTreeMap<Id, Point<P>>,
     pub(super) cap_as: BTreeMap<Id, SchCommitment<P>>,
     pub(super) rho_combined: BitVec,
@@ -496,22 +589,9 @@ pub(super) struct Round3NormalBroadcast<P: SchemeParams> {
     pub(super) psi: SchProof<P>,
 }
 
-impl<P: SchemeParams, Id: PartyId> Round<Id> for Round3<P, Id> {
-    type Protocol = KeyInitProtocol<P, Id>;
-
-    fn transition_info(&self) -> TransitionInfo {
-        TransitionInfo::new_linear_terminating(3)
-    }
-
-    fn communication_info(&self) -> CommunicationInfo<Id> {
-        CommunicationInfo::regular(&self.context.other_ids)
-    }
-
-    fn make_normal_broadcast(
-        &self,
-        _rng: &mut dyn CryptoRngCore,
-        format: &BoxedFormat,
-    ) -> Result<NormalBroadcast, LocalError> {
+impl<P: SchemeParams, Id: PartyId, T: KeyInitTarget<P, Id>> Round3<P, Id, T> {
+    /// Creates the normal broadcast of this round.
+    pub(super) fn normal_broadcast(&self, format: &BoxedFormat) -> Result<NormalBroadcast, LocalError> {
         let aux = (&self.context.sid, &self.context.my_id, &self.rho_combined);
         let psi = SchProof::new(
             &self.context.tau,
@@ -523,12 +603,13 @@ impl<P: SchemeParams, Id: PartyId> Round<Id> for Round3<P, Id> {
         NormalBroadcast::new(format, Round3NormalBroadcast { psi })
     }
 
-    fn receive_message(
+    /// Processes a message of this round from the node `from`.
+    pub(super) fn receive(
         &self,
         format: &BoxedFormat,
         from: &Id,
         message: ProtocolMessage,
-    ) -> Result<Payload, ReceiveError<Id, Self::Protocol>> {
+    ) -> Result<Payload, ReceiveError<Id, T::Protocol>> {
         message.echo_broadcast.assert_is_none()?;
         message.direct_message.assert_is_none()?;
         let normal_broadcast = message
@@ -540,27 +621,62 @@ impl<P: SchemeParams, Id: PartyId> Round<Id> for Round3<P, Id> {
 
         let aux = (&self.context.sid, from, &self.rho_combined);
         if !normal_broadcast.psi.verify(cap_a, cap_x, &aux) {
-            return Err(ReceiveError::protocol(Error::R3InvalidSchProof.into()));
+            return Err(ReceiveError::protocol(
+                KeyInitError::from(Error::R3InvalidSchProof).into(),
+            ));
         }
         Ok(Payload::empty())
     }
 
-    fn finalize(
-        self: Box<Self>,
-        _rng: &mut dyn CryptoRngCore,
-        _payloads: BTreeMap<Id, Payload>,
-        _artifacts: BTreeMap<Id, Artifact>,
-    ) -> Result<FinalizeOutcome<Id, Self::Protocol>, LocalError> {
+    /// Creates the key share once all the messages of this round are received.
+    pub(super) fn key_share(&self) -> Result<KeyShare<P, Id>, LocalError> {
         let my_id = self.context.my_id.clone();
-        let mut public_shares = self.cap_xs;
+        let mut public_shares = self.cap_xs.clone();
         public_shares.insert(my_id.clone(), self.context.public_data.cap_x);
 
         // This can fail if the shares add up to zero.
         // Can't really protect from it, and it should be extremely rare.
         // If that happens one can only restart the whole thing.
-        let key_share = KeyShare::<P, Id>::new(my_id, self.context.x, public_shares)?;
+        KeyShare::<P, Id>::new(my_id, self.context.x.clone(), public_shares)
+    }
+}
+
+impl<P: SchemeParams, Id: PartyId, T: KeyInitResult<P, Id>> Round<Id> for Round3<P, Id, T> {
+    type Protocol = T::Protocol;
+
+    fn transition_info(&self) -> TransitionInfo {
+        TransitionInfo::new_linear_terminating(3)
+    }
+
+    fn communication_info(&self) -> CommunicationInfo<Id> {
+        CommunicationInfo::regular(&self.context.other_ids)
+    }
+
+    fn make_normal_broadcast(
+        &self,
+        _rng: &mut dyn CryptoRngCore,
+        format: &BoxedFormat,
+    ) -> Result<NormalBroadcast, LocalError> {
+        self.normal_broadcast(format)
+    }
 
-        Ok(FinalizeOutcome::Result(key_share))
+    fn receive_message(
+        &self,
+        format: &BoxedFormat,
+        from: &Id,
+        message: ProtocolMessage,
+    ) -> Result<Payload, ReceiveError<Id, Self::Protocol>> {
+        self.receive(format, from, message)
+    }
+
+    fn finalize(
+        self: Box<Self>,
+        _rng: &mut dyn CryptoRngCore,
+        _payloads: BTreeMap<Id, Payload>,
+        _artifacts: BTreeMap<Id, Artifact>,
+    ) -> Result<FinalizeOutcome<Id, Self::Protocol>, LocalError> {
+        let key_share = self.key_share()?;
+        Ok(FinalizeOutcome::Result(self.context.target.key_init_result(key_share)))
     }
 }
 
@@ -7,6 +7,37 @@
 //!
 //! This is not a part of the CGGMP proper, but is requried to extend it to operating with threshold key shares,
 //! since the CGGMP paper itself does not contain any threshold functionality.
+//!
+//! The subshares are masked with ephemeral Diffie-Hellman keys:
+//! in Round 1 each new holder echo-broadcasts an ephemeral Diffie-Hellman key for each old holder,
+//! and in Round 2 each old holder echo-broadcasts an ephemeral key for each new holder,
+//! and sends it the subshare masked with a hash of their shared point.
+//! So the protocol does not need confidential channels between the nodes.
+//!
+//! Each old holder reshares each of its old shares with a separate polynomial
+//! (so an old holder with several shares, see [`ThresholdKeyShare::new_centralized_weighted`],
+//! sends several subshares for each share ID of a new holder), and the new holders combine the subshares
+//! with the coefficients that combine the old shares into the secret (see [`ThresholdKeyShare::to_key_share`]).
+//!
+//! Only `old_threshold` of the old shares are needed, so a new holder can finish once the old holders
+//! it received the subshares from (out of [`NewHolder::old_holders`]) are enough to reconstruct the secret.
+//! It uses the first of them whose shares are enough (in the order of their IDs,
+//! or of their levels if the old shares are hierarchical), and returns this set along with the new share.
+//! The new shares are only consistent with each other if all the new holders used the same set
+//! (which is the case if they received the subshares from the same old holders),
+//! so the new holders must compare the returned sets before using the new shares.
+//!
+//! Note that a `manul` session only finalizes a round by itself once the messages from all the nodes it expects
+//! have arrived, so if some of the old holders never respond, a new holder has to finalize the remaining rounds
+//! manually when it stops waiting for them.
+//!
+//! The new holders can be given weights (see [`KeyResharing::with_new_weights`]), in which case an old holder
+//! sends a new holder one subshare for each of its new share IDs.
+//!
+//! The new shares can also be hierarchical (see [`KeyResharing::with_new_access_structure`]),
+//! in which case an old holder sends each new holder the derivative of its polynomial of the holder's order
+//! evaluated at the holder's share ID (as in [`ThresholdKeyShare::new_centralized_hierarchical`]).
+//! The old shares can be hierarchical too, as long as the old holders taking part include an authorized set.
 
 use alloc::{
     boxed::Box,
@@ -14,90 +45,407 @@ use alloc::{
     format,
     vec::Vec,
 };
-use core::{fmt::Debug, marker::PhantomData};
+use core::{
+    fmt::{self, Debug, Display},
+    marker::PhantomData,
+};
 
 use ecdsa::VerifyingKey;
 use manul::protocol::{
     Artifact, BoxedFormat, BoxedRound, CommunicationInfo, DirectMessage, EchoBroadcast, EchoRoundParticipation,
     EntryPoint, FinalizeOutcome, LocalError, MessageValidationError, NormalBroadcast, PartyId, Payload, Protocol,
-    ProtocolError, ProtocolMessage, ProtocolMessagePart, ProtocolValidationError, ReceiveError, RequiredMessages,
-    Round, RoundId, TransitionInfo,
+    ProtocolError, ProtocolMessage, ProtocolMessagePart, ProtocolValidationError, ReceiveError, RequiredMessageParts,
+    RequiredMessages, Round, RoundId, TransitionInfo,
 };
+use manul::utils::SerializableMap;
 use rand_core::CryptoRngCore;
-use serde::{Deserialize, Serialize};
 
 use crate::{
     curve::{Point, Scalar},
-    entities::ThresholdKeyShare,
+    entities::{
+        HierarchicalAccessStructure, PublicShareWithId, ShareIdAssignment, Sid, ThresholdKeyShare,
+        ThresholdPublicKeyShares,
+    },
     params::SchemeParams,
     tools::{
-        protocol_shortcuts::{DowncastMap, Without},
-        sss::{interpolation_coeff, shamir_join_points, shamir_join_scalars, Polynomial, PublicPolynomial, ShareId},
+        dh_mask,
+        protocol_shortcuts::{verify_that, DowncastMap, GetRound, MapValues, SafeGet, Without},
+        sss::{Polynomial, PublicPolynomial, SharePoint},
         Secret,
     },
 };
 
+/// The domain separation tag of the masks of the values sent in KeyResharing.
+const MASK_DST: &[u8] = b"KeyResharing";
+
 /// A protocol for modifying the set of owners of a shared secret key.
+///
+/// The new holders get the new key share along with the set of the old holders whose shares were used to create it
+/// (see the module documentation).
+/// The old holders that are not among the new holders get `None`.
 #[derive(Debug)]
 pub struct KeyResharingProtocol<P: SchemeParams, I: Debug>(PhantomData<(P, I)>);
 
 impl<P: SchemeParams, I: PartyId> Protocol<I> for KeyResharingProtocol<P, I> {
-    type Result = Option<ThresholdKeyShare<P, I>>;
-    type ProtocolError = KeyResharingError;
+    type Result = Option<(ThresholdKeyShare<P, I>, BTreeSet<I>)>;
+    type ProtocolError = KeyResharingError<P, I>;
 
     fn verify_direct_message_is_invalid(
-        _format: &BoxedFormat,
-        _round_id: &RoundId,
-        _message: &DirectMessage,
+        format: &BoxedFormat,
+        round_id: &RoundId,
+        message: &DirectMessage,
     ) -> Result<(), MessageValidationError> {
-        unimplemented!()
+        match round_id {
+            r if r == &1 => message.verify_is_some(),
+            r if r == &2 => message.verify_is_not::<Round2DirectMessage<P, I>>(format),
+            _ => Err(MessageValidationError::InvalidEvidence("Invalid round number".into())),
+        }
     }
 
     fn verify_echo_broadcast_is_invalid(
-        _format: &BoxedFormat,
-        _round_id: &RoundId,
-        _message: &EchoBroadcast,
+        format: &BoxedFormat,
+        round_id: &RoundId,
+        message: &EchoBroadcast,
     ) -> Result<(), MessageValidationError> {
-        unimplemented!()
+        match round_id {
+            r if r == &1 => message.verify_is_not::<Round1EchoBroadcast<P, I>>(format),
+            r if r == &2 => message.verify_is_not::<Round2EchoBroadcast<P, I>>(format),
+            _ => Err(MessageValidationError::InvalidEvidence("Invalid round number".into())),
+        }
     }
 
     fn verify_normal_broadcast_is_invalid(
         _format: &BoxedFormat,
-        _round_id: &RoundId,
-        _message: &NormalBroadcast,
+        round_id: &RoundId,
+        message: &NormalBroadcast,
     ) -> Result<(), MessageValidationError> {
-        unimplemented!()
+        match round_id {
+            r if r == &1 => message.verify_is_some(),
+            r if r == &2 => message.verify_is_some(),
+            _ => Err(MessageValidationError::InvalidEvidence("Invalid round number".into())),
+        }
+    }
+}
+
+/// Possible verifiable errors of the KeyResharing protocol.
+#[derive(Debug, Clone)]
+#[derive_where::derive_where(Serialize, Deserialize)]
+pub struct KeyResharingError<P: SchemeParams, I: PartyId> {
+    error: Error<P, I>,
+    phantom: PhantomData<P>,
+}
+
+impl<P: SchemeParams, I: PartyId> Display for KeyResharingError<P, I> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> Result<(), fmt::Error> {
+        write!(
+            f,
+            "{}",
+            match self.error {
+                Error::R1WrongIdsY => "Round 1: wrong IDs in the ephemeral keys map.",
+                Error::R2WrongIdsY => "Round 2: wrong IDs in the ephemeral keys map.",
+                Error::R2WrongPolynomialDegree => "Round 2: a public polynomial has a wrong number of coefficients.",
+                Error::R2OldShareMismatch => {
+                    "Round 2: the constant coefficients of the public polynomials do not match the sender's old public shares."
+                }
+                Error::R2InvalidSubshare { .. } => "Round 2: the subshares do not match the public polynomials.",
+            }
+        )
+    }
+}
+
+impl<P: SchemeParams, I: PartyId> From<Error<P, I>> for KeyResharingError<P, I> {
+    fn from(source: Error<P, I>) -> Self {
+        Self {
+            error: source,
+            phantom: PhantomData,
+        }
     }
 }
 
-/// Provable faults of KeyResharing
-#[derive(displaydoc::Display, Debug, Clone, Copy, Serialize, Deserialize)]
-pub enum KeyResharingError {
-    /// Unexpected sender of a message (not one of the old holders)
-    UnexpectedSender,
-    /// Mismatch of the subshare
-    SubshareMismatch,
+#[derive(Debug, Clone)]
+#[derive_where::derive_where(Serialize, Deserialize)]
+enum Error<P: SchemeParams, I: PartyId> {
+    R1WrongIdsY,
+    R2WrongIdsY,
+    R2WrongPolynomialDegree,
+    R2OldShareMismatch,
+    R2InvalidSubshare {
+        /// The ID $k$ of the node that received the subshare.
+        reported_by: I,
+        /// The ephemeral secret $y_{k,j}$, where $j$ is the ID of the guilty party.
+        y: Scalar<P>,
+    },
+}
+
+/// Associated data for KeyResharing protocol.
+#[derive(Debug, Clone)]
+pub struct KeyResharingAssociatedData<P: SchemeParams, I: PartyId> {
+    /// The public part of the old key shares.
+    pub old_public_key_shares: ThresholdPublicKeyShares<P, I>,
+    /// The old holders taking part in the resharing.
+    pub old_holders: BTreeSet<I>,
+    /// The new holders of the shares.
+    pub new_holders: BTreeSet<I>,
+    /// The new threshold.
+    pub new_threshold: usize,
+    /// The way the share IDs are assigned to the new holders.
+    pub share_id_assignment: ShareIdAssignment<P, I>,
+    /// The weights of the new holders. The holders missing from the map have the weight 1.
+    pub new_weights: BTreeMap<I, usize>,
+    /// The access structure of the new shares, if they are hierarchical.
+    pub new_access_structure: Option<HierarchicalAccessStructure<I>>,
 }
 
-impl<I> ProtocolError<I> for KeyResharingError {
-    type AssociatedData = ();
+impl<P: SchemeParams, I: PartyId> KeyResharingAssociatedData<P, I> {
+    /// Creates the associated data for evidence verification of KeyResharing
+    /// given the public part of the old key shares.
+    pub fn new(
+        old_public_key_shares: &ThresholdPublicKeyShares<P, I>,
+        old_holders: BTreeSet<I>,
+        new_holders: BTreeSet<I>,
+        new_threshold: usize,
+    ) -> Self {
+        Self {
+            old_public_key_shares: old_public_key_shares.clone(),
+            old_holders,
+            new_holders,
+            new_threshold,
+            share_id_assignment: ShareIdAssignment::Sequential,
+            new_weights: BTreeMap::new(),
+            new_access_structure: None,
+        }
+    }
+
+    /// Sets the way the share IDs are assigned to the new holders,
+    /// for evidence verification of KeyResharing with [`KeyResharing::with_share_id_assignment`] applied.
+    pub fn with_share_id_assignment(self, share_id_assignment: ShareIdAssignment<P, I>) -> Self {
+        Self {
+            share_id_assignment,
+            ..self
+        }
+    }
+
+    /// Sets the weights of the new holders,
+    /// for evidence verification of KeyResharing with [`KeyResharing::with_new_weights`] applied.
+    pub fn with_new_weights(self, new_weights: BTreeMap<I, usize>) -> Self {
+        Self { new_weights, ..self }
+    }
+
+    /// Sets the access structure of the new shares,
+    /// for evidence verification of KeyResharing with [`KeyResharing::with_new_access_structure`] applied.
+    pub fn with_new_access_structure(self, new_access_structure: HierarchicalAccessStructure<I>) -> Self {
+        Self {
+            new_access_structure: Some(new_access_structure),
+            ..self
+        }
+    }
+}
+
+impl<P: SchemeParams, I: PartyId> ProtocolError<I> for KeyResharingError<P, I> {
+    type AssociatedData = KeyResharingAssociatedData<P, I>;
 
     fn required_messages(&self) -> RequiredMessages {
-        unimplemented!()
+        match self.error {
+            Error::R1WrongIdsY | Error::R2WrongIdsY | Error::R2WrongPolynomialDegree | Error::R2OldShareMismatch => {
+                RequiredMessages::new(RequiredMessageParts::echo_broadcast(), None, None)
+            }
+            Error::R2InvalidSubshare { .. } => RequiredMessages::new(
+                RequiredMessageParts::echo_broadcast().and_direct_message(),
+                None,
+                Some([1.into()].into()),
+            ),
+        }
     }
 
     fn verify_messages_constitute_error(
         &self,
-        _format: &BoxedFormat,
-        _guilty_party: &I,
-        _shared_randomness: &[u8],
-        _associated_data: &Self::AssociatedData,
-        _message: ProtocolMessage,
+        format: &BoxedFormat,
+        guilty_party: &I,
+        shared_randomness: &[u8],
+        associated_data: &Self::AssociatedData,
+        message: ProtocolMessage,
         _previous_messages: BTreeMap<RoundId, ProtocolMessage>,
-        _combined_echos: BTreeMap<RoundId, BTreeMap<I, EchoBroadcast>>,
+        combined_echos: BTreeMap<RoundId, BTreeMap<I, EchoBroadcast>>,
     ) -> Result<(), ProtocolValidationError> {
-        unimplemented!()
+        match &self.error {
+            Error::R1WrongIdsY => {
+                let r1_eb = message
+                    .echo_broadcast
+                    .deserialize::<Round1EchoBroadcast<P, I>>(format)?;
+                verify_that(
+                    r1_eb.cap_ys.keys().cloned().collect::<BTreeSet<_>>()
+                        != associated_data.old_holders.clone().without(guilty_party),
+                )
+            }
+            Error::R2WrongIdsY => {
+                let r2_eb = message
+                    .echo_broadcast
+                    .deserialize::<Round2EchoBroadcast<P, I>>(format)?;
+                verify_that(
+                    r2_eb.cap_ys.keys().cloned().collect::<BTreeSet<_>>()
+                        != associated_data.new_holders.clone().without(guilty_party),
+                )
+            }
+            Error::R2WrongPolynomialDegree => {
+                let r2_eb = message
+                    .echo_broadcast
+                    .deserialize::<Round2EchoBroadcast<P, I>>(format)?;
+                verify_that(
+                    r2_eb
+                        .public_polynomials
+                        .iter()
+                        .any(|public_polynomial| public_polynomial.num_coeffs() != associated_data.new_threshold),
+                )
+            }
+            Error::R2OldShareMismatch => {
+                let r2_eb = message
+                    .echo_broadcast
+                    .deserialize::<Round2EchoBroadcast<P, I>>(format)?;
+                let old_public_shares = associated_data
+                    .old_public_key_shares
+                    .holder_shares(guilty_party)
+                    .map_err(|_| {
+                        ProtocolValidationError::InvalidEvidence("The guilty party is not an old holder".into())
+                    })?;
+                verify_that(!reshares_old_shares(&r2_eb.public_polynomials, &old_public_shares))
+            }
+            Error::R2InvalidSubshare { reported_by, y } => {
+                // Check that `y` attached to the evidence is correct
+                // (that is, can be verified against something signed by `guilty_party`).
+                // It is `y_{k,j}` where `k == reported_by` and `j == guilty_party`
+                let r1_eb_k = combined_echos
+                    .get_round(1)?
+                    .try_get("combined echos for Round 1", reported_by)?
+                    .deserialize::<Round1EchoBroadcast<P, I>>(format)?;
+                let cap_y_kj = r1_eb_k.cap_ys.try_get("ephemeral keys", guilty_party)?;
+                if &y.mul_by_generator() != cap_y_kj {
+                    return Err(ProtocolValidationError::InvalidEvidence(
+                        "The provided `y` is invalid".into(),
+                    ));
+                }
+
+                let r2_eb = message
+                    .echo_broadcast
+                    .deserialize::<Round2EchoBroadcast<P, I>>(format)?;
+                let r2_dm = message
+                    .direct_message
+                    .deserialize::<Round2DirectMessage<P, I>>(format)?;
+
+                // Make sure the message was actually addressed to the reporting node.
+                if &r2_dm.destination != reported_by {
+                    return Err(ProtocolValidationError::InvalidEvidence(
+                        "The direct message was not addressed to the reporting node".into(),
+                    ));
+                }
+
+                let all_ids = associated_data
+                    .old_holders
+                    .union(&associated_data.new_holders)
+                    .cloned()
+                    .collect::<BTreeSet<_>>();
+                let sid = Sid::new::<P, I>(shared_randomness, &all_ids);
+
+                let new_points = new_holder_points(
+                    &associated_data.new_holders,
+                    &associated_data.new_weights,
+                    &associated_data.share_id_assignment,
+                    associated_data.new_access_structure.as_ref(),
+                    &sid,
+                )
+                .map_err(|_| ProtocolValidationError::InvalidEvidence("Failed to assign the new share IDs".into()))?;
+                let points = new_points.try_get("new share IDs", reported_by)?;
+
+                let cap_y_jk = r2_eb.cap_ys.try_get("ephemeral keys", reported_by)?;
+                let cap_d = cap_y_jk * y;
+                verify_that(
+                    r2_dm.cap_cs.len() != r2_eb.public_polynomials.len() * points.len()
+                        || r2_dm
+                            .cap_cs
+                            .iter()
+                            .zip(subshare_points(&r2_eb.public_polynomials, points))
+                            .enumerate()
+                            .any(|(idx, (cap_c, (public_polynomial, (share_id, order))))| {
+                                let subshare = *cap_c - dh_mask::<P, I>(MASK_DST, &sid, guilty_party, &cap_d, idx);
+                                subshare.mul_by_generator() != public_polynomial.evaluate_derivative(share_id, *order)
+                            }),
+                )
+            }
+        }
+    }
+}
+
+/// Returns the weights of all the new holders, given the weights set explicitly for some of them.
+fn new_holder_weights<I: PartyId>(
+    new_holders: &BTreeSet<I>,
+    new_weights: &BTreeMap<I, usize>,
+) -> Result<BTreeMap<I, usize>, LocalError> {
+    if let Some(id) = new_weights.keys().find(|id| !new_holders.contains(id)) {
+        return Err(LocalError::new(format!(
+            "id={id:?} has a weight, but is not one of the new holders"
+        )));
     }
+    Ok(new_holders
+        .iter()
+        .map(|id| (id.clone(), new_weights.get(id).copied().unwrap_or(1)))
+        .collect())
+}
+
+/// Returns the share IDs of all the new holders, each along with the order of the derivative
+/// of the sharing polynomial the share is evaluated from (zero unless the new shares are hierarchical).
+fn new_holder_points<P: SchemeParams, I: PartyId>(
+    new_holders: &BTreeSet<I>,
+    new_weights: &BTreeMap<I, usize>,
+    share_id_assignment: &ShareIdAssignment<P, I>,
+    new_access_structure: Option<&HierarchicalAccessStructure<I>>,
+    sid: &Sid,
+) -> Result<BTreeMap<I, Vec<SharePoint<P>>>, LocalError> {
+    let access_structure = match new_access_structure {
+        Some(access_structure) => access_structure,
+        None => {
+            let weights = new_holder_weights(new_holders, new_weights)?;
+            let share_ids = share_id_assignment.assign_weighted(&weights, Some(sid))?;
+            return Ok(share_ids.map_values(|share_ids| share_ids.into_iter().map(|share_id| (share_id, 0)).collect()));
+        }
+    };
+
+    if !new_weights.is_empty() {
+        return Err(LocalError::new("Hierarchical new shares cannot have weights"));
+    }
+    // The share IDs must be monotone in the levels, so they cannot be assigned in any other way.
+    if !matches!(share_id_assignment, ShareIdAssignment::Sequential) {
+        return Err(LocalError::new(
+            "The share IDs of hierarchical new shares can only be assigned sequentially",
+        ));
+    }
+    if &access_structure.holders() != new_holders {
+        return Err(LocalError::new(
+            "The new holders must be the same as the holders in the new access structure",
+        ));
+    }
+    Ok(access_structure.holder_points()?.map_values(|point| Vec::from([point])))
+}
+
+/// Returns `true` if the public polynomials reshare the given old public shares, one polynomial for each share.
+fn reshares_old_shares<P: SchemeParams>(
+    public_polynomials: &[PublicPolynomial<P>],
+    old_public_shares: &[PublicShareWithId<P>],
+) -> bool {
+    public_polynomials.len() == old_public_shares.len()
+        && public_polynomials
+            .iter()
+            .zip(old_public_shares)
+            .all(|(public_polynomial, (_share_id, public_share))| public_polynomial.coeff0().ok() == Some(public_share))
+}
+
+/// Pairs each of the polynomials of an old holder (one for each of its old shares)
+/// with each of the points of a new holder, in the order the subshares are sent in.
+fn subshare_points<'a, T, P: SchemeParams>(
+    polynomials: &'a [T],
+    points: &'a [SharePoint<P>],
+) -> impl Iterator<Item = (&'a T, &'a SharePoint<P>)> {
+    polynomials
+        .iter()
+        .flat_map(move |polynomial| points.iter().map(move |point| (polynomial, point)))
 }
 
 /// Old share data.
@@ -105,6 +453,10 @@ impl<I> ProtocolError<I> for KeyResharingError {
 pub struct OldHolder<P: SchemeParams, I: PartyId> {
     /// The threshold key share.
     pub key_share: ThresholdKeyShare<P, I>,
+    /// The holders of the old shares taking part in the resharing (including this node).
+    ///
+    /// Must be the same as [`NewHolder::old_holders`].
+    pub old_holders: BTreeSet<I>,
 }
 
 /// New share data.
@@ -114,8 +466,16 @@ pub struct NewHolder<P: SchemeParams, I: PartyId> {
     pub verifying_key: VerifyingKey<P::Curve>,
     /// The old threshold.
     pub old_threshold: usize,
-    /// Some of the holders of the old shares (at least `old_threshold` of them).
+    /// The holders of the old shares taking part in the resharing (with the total weight of at least `old_threshold`;
+    /// if the old shares are hierarchical, including an authorized set).
+    ///
+    /// Only the first of the ones that sent their subshares whose shares are enough to reconstruct the secret
+    /// are used to create the new share (see [`KeyResharingProtocol`]).
     pub old_holders: BTreeSet<I>,
+    /// The public part of the old shares (see [`ThresholdKeyShare::public_key_shares`]).
+    ///
+    /// The public polynomials sent by the old holders are checked against it.
+    pub old_public_key_shares: ThresholdPublicKeyShares<P, I>,
 }
 
 /// An entry point for the [`KeyResharingProtocol`].
@@ -129,6 +489,12 @@ pub struct KeyResharing<P: SchemeParams, I: PartyId> {
     new_holders: BTreeSet<I>,
     /// The new threshold.
     new_threshold: usize,
+    /// The way the share IDs are assigned to the new holders.
+    share_id_assignment: ShareIdAssignment<P, I>,
+    /// The weights of the new holders.
+    new_weights: BTreeMap<I, usize>,
+    /// The access structure of the new shares, if they are hierarchical.
+    new_access_structure: Option<HierarchicalAccessStructure<I>>,
 }
 
 impl<P, I> KeyResharing<P, I>
@@ -148,6 +514,42 @@ where
             new_holder,
             new_holders,
             new_threshold,
+            share_id_assignment: ShareIdAssignment::Sequential,
+            new_weights: BTreeMap::new(),
+            new_access_structure: None,
+        }
+    }
+
+    /// Sets the way the share IDs are assigned to the new holders
+    /// (by default, [`ShareIdAssignment::Sequential`]).
+    ///
+    /// All the nodes must use the same assignment.
+    pub fn with_share_id_assignment(self, share_id_assignment: ShareIdAssignment<P, I>) -> Self {
+        Self {
+            share_id_assignment,
+            ..self
+        }
+    }
+
+    /// Sets the weights of the new holders, that is, the number of shares each of them will hold
+    /// (by default, every new holder has one share). The holders missing from the map have the weight 1.
+    ///
+    /// The new threshold is the total weight of the holders required to sign.
+    /// All the nodes must use the same weights.
+    pub fn with_new_weights(self, new_weights: BTreeMap<I, usize>) -> Self {
+        Self { new_weights, ..self }
+    }
+
+    /// Makes the new shares hierarchical, with the given access structure
+    /// (see [`ThresholdKeyShare::new_centralized_hierarchical`]).
+    ///
+    /// The holders in the access structure must be the new holders, and its threshold must be the new threshold.
+    /// Cannot be combined with weights or a share ID assignment other than [`ShareIdAssignment::Sequential`].
+    /// All the nodes must use the same access structure.
+    pub fn with_new_access_structure(self, new_access_structure: HierarchicalAccessStructure<I>) -> Self {
+        Self {
+            new_access_structure: Some(new_access_structure),
+            ..self
         }
     }
 }
@@ -166,148 +568,361 @@ where
     fn make_round(
         self,
         rng: &mut dyn CryptoRngCore,
-        _shared_randomness: &[u8],
+        shared_randomness: &[u8],
         id: &I,
     ) -> Result<BoxedRound<I, Self::Protocol>, LocalError> {
-        // Start new share indices from 1.
-        let new_share_ids = self
-            .new_holders
-            .iter()
-            .enumerate()
-            .map(|(idx, id)| {
-                let idx: u64 = idx.try_into().expect("no more than 2^64-1 shares needed");
-                (id.clone(), ShareId::new(idx + 1))
-            })
-            .collect();
-
-        if self.old_holder.is_none() && self.new_holder.is_none() {
-            return Err(LocalError::new("Either old holder or new holder data must be provided"));
+        let old_holders = match (self.old_holder.as_ref(), self.new_holder.as_ref()) {
+            (Some(old_holder), Some(new_holder)) => {
+                if old_holder.old_holders != new_holder.old_holders {
+                    return Err(LocalError::new(
+                        "The old holders must be the same in the old holder and the new holder data",
+                    ));
+                }
+                old_holder.old_holders.clone()
+            }
+            (Some(old_holder), None) => old_holder.old_holders.clone(),
+            (None, Some(new_holder)) => new_holder.old_holders.clone(),
+            (None, None) => {
+                return Err(LocalError::new("Either old holder or new holder data must be provided"));
+            }
         };
 
-        let message_destinations = if self.old_holder.is_some() {
-            // It is possible that a party is both an old holder and a new holder.
-            // This will be processed separately.
-            self.new_holders.clone().without(id)
-        } else {
-            BTreeSet::new()
-        };
+        if self.old_holder.is_some() && !old_holders.contains(id) {
+            return Err(LocalError::new("This node must be one of the old holders"));
+        }
 
-        let expecting_messages_from = if let Some(new_holder) = self.new_holder.as_ref() {
-            // TODO: we only need `old_threshold` of them, but it is not supported yet in `manul`.
-            new_holder.old_holders.clone().without(id)
-        } else {
-            BTreeSet::new()
-        };
+        if let Some(new_holder) = self.new_holder.as_ref() {
+            if !self.new_holders.contains(id) {
+                return Err(LocalError::new("This node must be one of the new holders"));
+            }
+            let old_public_key_shares = &new_holder.old_public_key_shares;
+            if old_public_key_shares.threshold() != new_holder.old_threshold {
+                return Err(LocalError::new(
+                    "The old threshold does not match the threshold of the old public key shares",
+                ));
+            }
+            if !new_holder.old_holders.is_subset(&old_public_key_shares.holders()) {
+                return Err(LocalError::new(
+                    "All the old holders must be present in the old public key shares",
+                ));
+            }
+            if old_public_key_shares
+                .authorized_subset(&new_holder.old_holders)
+                .is_none()
+            {
+                return Err(LocalError::new(
+                    "The shares of the old holders must be enough to reconstruct the secret",
+                ));
+            }
+            if old_public_key_shares.verifying_key_as_point()? != Point::from_verifying_key(&new_holder.verifying_key) {
+                return Err(LocalError::new(
+                    "The old public key shares do not correspond to the verifying key",
+                ));
+            }
+        }
 
-        let echo_round_participation = if self.old_holder.is_some() && self.new_holder.is_none() {
-            EchoRoundParticipation::Send
-        } else if self.new_holder.is_some() && self.old_holder.is_none() {
-            EchoRoundParticipation::Receive {
-                echo_targets: self.new_holders.without(id),
+        let all_ids = old_holders.union(&self.new_holders).cloned().collect::<BTreeSet<_>>();
+        let sid = Sid::new::<P, I>(shared_randomness, &all_ids);
+
+        let new_points = new_holder_points(
+            &self.new_holders,
+            &self.new_weights,
+            &self.share_id_assignment,
+            self.new_access_structure.as_ref(),
+            &sid,
+        )?;
+        if let Some(access_structure) = self.new_access_structure.as_ref() {
+            if self.new_threshold != access_structure.threshold() {
+                return Err(LocalError::new(
+                    "The new threshold must be the threshold of the new access structure",
+                ));
             }
-        } else {
-            EchoRoundParticipation::Default
-        };
+        }
+        if self.new_threshold > new_points.values().map(|points| points.len()).sum() {
+            return Err(LocalError::new(
+                "The new threshold cannot be greater than the total weight of the new holders",
+            ));
+        }
+
+        // It is possible that a party is both an old holder and a new holder.
+        // The messages it would send to itself are processed separately.
+        let other_old_holders = old_holders.clone().without(id);
+        let other_new_holders = self.new_holders.clone().without(id);
 
         let old_holder = self
             .old_holder
             .map(|old_holder| {
-                let polynomial = Polynomial::random(rng, old_holder.key_share.secret_share.clone(), self.new_threshold);
-                let public_polynomial = polynomial.public();
+                // Each of our old shares is reshared with its own polynomial.
+                let polynomials = old_holder
+                    .key_share
+                    .owner_secret_shares()?
+                    .into_iter()
+                    .map(|(_share_id, secret_share)| Polynomial::random(rng, secret_share, self.new_threshold))
+                    .collect::<Vec<_>>();
+                let public_polynomials = polynomials.iter().map(|polynomial| polynomial.public()).collect();
+
+                // Ephemeral DH keys $y_{j,k}$ where $j$ is this party's index, and $k$ is a new holder.
+                let ys = other_new_holders
+                    .iter()
+                    .cloned()
+                    .map(|other_id| (other_id, Secret::init_with(|| Scalar::random(rng))))
+                    .collect::<BTreeMap<_, _>>();
 
-                Ok(OldHolderData {
-                    polynomial,
-                    share_id: *old_holder.key_share.share_id()?,
-                    public_polynomial,
+                Ok::<_, LocalError>(OldHolderData {
+                    polynomials,
+                    public_polynomials,
+                    ys,
                 })
             })
             .transpose()?;
 
-        let new_holder = self.new_holder.map(|new_holder| NewHolderData { inputs: new_holder });
+        let new_holder = self.new_holder.map(|new_holder| {
+            // Ephemeral DH keys $y_{k,j}$ where $k$ is this party's index, and $j$ is an old holder.
+            let ys = other_old_holders
+                .iter()
+                .cloned()
+                .map(|other_id| (other_id, Secret::init_with(|| Scalar::random(rng))))
+                .collect::<BTreeMap<_, _>>();
+            NewHolderData { inputs: new_holder, ys }
+        });
+
+        // In Round 1 the new holders send their ephemeral keys to the old holders,
+        // and in Round 2 the old holders send the subshares to the new holders.
+        let round1_communication = communication_info(
+            new_holder.is_some(),
+            old_holder.is_some(),
+            &other_new_holders,
+            &other_old_holders,
+        );
 
-        Ok(BoxedRound::new_dynamic(Round1 {
+        let round2_communication = communication_info(
+            old_holder.is_some(),
+            new_holder.is_some(),
+            &other_old_holders,
+            &other_new_holders,
+        );
+
+        let context = Context {
             old_holder,
             new_holder,
-            new_share_ids,
+            new_points,
             new_threshold: self.new_threshold,
+            new_access_structure: self.new_access_structure,
+            old_holders,
+            new_holders: self.new_holders,
             my_id: id.clone(),
-            message_destinations,
-            expecting_messages_from,
-            echo_round_participation,
-            phantom: PhantomData,
+            sid,
+            round2_communication,
+        };
+
+        Ok(BoxedRound::new_dynamic(Round1 {
+            context,
+            communication: round1_communication,
         }))
     }
 }
 
+/// Returns the communication info for a round where `senders` send messages to `receivers`
+/// (given the other nodes from each group), and the receivers echo the broadcasts between themselves.
+fn communication_info<I: PartyId>(
+    is_sender: bool,
+    is_receiver: bool,
+    other_senders: &BTreeSet<I>,
+    other_receivers: &BTreeSet<I>,
+) -> CommunicationInfo<I> {
+    let message_destinations = if is_sender {
+        other_receivers.clone()
+    } else {
+        BTreeSet::new()
+    };
+
+    let expecting_messages_from = if is_receiver {
+        other_senders.clone()
+    } else {
+        BTreeSet::new()
+    };
+
+    let echo_round_participation = if is_sender && !is_receiver {
+        EchoRoundParticipation::Send
+    } else if is_receiver && !is_sender {
+        EchoRoundParticipation::Receive {
+            echo_targets: other_receivers.clone(),
+        }
+    } else {
+        EchoRoundParticipation::Default
+    };
+
+    CommunicationInfo {
+        message_destinations,
+        expecting_messages_from,
+        echo_round_participation,
+    }
+}
+
 #[derive(Debug)]
-struct OldHolderData<P: SchemeParams> {
-    share_id: ShareId<P>,
-    polynomial: Polynomial<P>,
-    public_polynomial: PublicPolynomial<P>,
+struct OldHolderData<P: SchemeParams, I> {
+    // One for each of the old shares of this node.
+    polynomials: Vec<Polynomial<P>>,
+    public_polynomials: Vec<PublicPolynomial<P>>,
+    ys: BTreeMap<I, Secret<Scalar<P>>>, // $y_{j,k}$ where $j$ is this party's index
 }
 
 #[derive(Debug)]
 struct NewHolderData<P: SchemeParams, I: PartyId> {
     inputs: NewHolder<P, I>,
+    ys: BTreeMap<I, Secret<Scalar<P>>>, // $y_{k,j}$ where $k$ is this party's index
 }
 
 #[derive(Debug)]
-struct Round1<P: SchemeParams, I: PartyId> {
-    old_holder: Option<OldHolderData<P>>,
+struct Context<P: SchemeParams, I: PartyId> {
+    old_holder: Option<OldHolderData<P, I>>,
     new_holder: Option<NewHolderData<P, I>>,
-    new_share_ids: BTreeMap<I, ShareId<P>>,
+    // The share IDs of the new holders along with the derivative orders.
+    new_points: BTreeMap<I, Vec<SharePoint<P>>>,
     new_threshold: usize,
+    new_access_structure: Option<HierarchicalAccessStructure<I>>,
+    old_holders: BTreeSet<I>,
+    new_holders: BTreeSet<I>,
     my_id: I,
-    message_destinations: BTreeSet<I>,
-    expecting_messages_from: BTreeSet<I>,
-    echo_round_participation: EchoRoundParticipation<I>,
-    phantom: PhantomData<P>,
+    sid: Sid,
+    round2_communication: CommunicationInfo<I>,
 }
 
-#[derive(Debug, Clone)]
-#[derive_where::derive_where(Serialize, Deserialize)]
-struct Round1BroadcastMessage<P: SchemeParams> {
-    public_polynomial: PublicPolynomial<P>,
-    old_share_id: ShareId<P>,
+#[derive(Debug)]
+struct Round1<P: SchemeParams, I: PartyId> {
+    context: Context<P, I>,
+    communication: CommunicationInfo<I>,
 }
 
 #[derive(Debug, Clone)]
 #[derive_where::derive_where(Serialize, Deserialize)]
-struct Round1DirectMessage<P: SchemeParams> {
-    subshare: Secret<Scalar<P>>,
+pub(super) struct Round1EchoBroadcast<P: SchemeParams, I: PartyId> {
+    pub(super) cap_ys: SerializableMap<I, Point<P>>, // $Y_{k,j}$ where $k$ is this party's index
 }
+
 struct Round1Payload<P: SchemeParams> {
-    subshare: Secret<Scalar<P>>,
-    public_polynomial: PublicPolynomial<P>,
-    old_share_id: ShareId<P>,
+    cap_y: Point<P>, // $Y_{k,j}$ where $j$ is this party's index
 }
 
 impl<P: SchemeParams, I: PartyId> Round<I> for Round1<P, I> {
     type Protocol = KeyResharingProtocol<P, I>;
 
     fn transition_info(&self) -> TransitionInfo {
-        TransitionInfo::new_linear_terminating(1)
+        TransitionInfo::new_linear(1)
     }
 
     fn communication_info(&self) -> CommunicationInfo<I> {
-        CommunicationInfo {
-            message_destinations: self.message_destinations.clone(),
-            expecting_messages_from: self.expecting_messages_from.clone(),
-            echo_round_participation: self.echo_round_participation.clone(),
+        self.communication.clone()
+    }
+
+    fn make_echo_broadcast(
+        &self,
+        _rng: &mut dyn CryptoRngCore,
+        format: &BoxedFormat,
+    ) -> Result<EchoBroadcast, LocalError> {
+        if let Some(new_holder) = self.context.new_holder.as_ref() {
+            let cap_ys = new_holder.ys.map_values_ref(|y| y.mul_by_generator());
+            EchoBroadcast::new(format, Round1EchoBroadcast { cap_ys: cap_ys.into() })
+        } else {
+            Ok(EchoBroadcast::none())
         }
     }
 
+    fn receive_message(
+        &self,
+        format: &BoxedFormat,
+        from: &I,
+        message: ProtocolMessage,
+    ) -> Result<Payload, ReceiveError<I, Self::Protocol>> {
+        message.normal_broadcast.assert_is_none()?;
+        message.direct_message.assert_is_none()?;
+        let echo_broadcast = message
+            .echo_broadcast
+            .deserialize::<Round1EchoBroadcast<P, I>>(format)?;
+
+        if self.context.old_holder.is_none() {
+            return Err(ReceiveError::unprovable("This node is not an old holder"));
+        }
+        if !self.context.new_holders.contains(from) {
+            return Err(ReceiveError::unprovable("The sender is not one of the new holders"));
+        }
+
+        if echo_broadcast.cap_ys.keys().cloned().collect::<BTreeSet<_>>()
+            != self.context.old_holders.clone().without(from)
+        {
+            return Err(ReceiveError::protocol(Error::R1WrongIdsY.into()));
+        }
+
+        let cap_y = *echo_broadcast.cap_ys.safe_get("ephemeral keys", &self.context.my_id)?;
+        Ok(Payload::new(Round1Payload { cap_y }))
+    }
+
+    fn finalize(
+        self: Box<Self>,
+        _rng: &mut dyn CryptoRngCore,
+        payloads: BTreeMap<I, Payload>,
+        _artifacts: BTreeMap<I, Artifact>,
+    ) -> Result<FinalizeOutcome<I, Self::Protocol>, LocalError> {
+        let payloads = payloads.downcast_all::<Round1Payload<P>>()?;
+        let cap_ys = payloads.map_values(|payload| payload.cap_y);
+        Ok(FinalizeOutcome::AnotherRound(BoxedRound::new_dynamic(Round2 {
+            context: self.context,
+            cap_ys,
+        })))
+    }
+}
+
+#[derive(Debug)]
+struct Round2<P: SchemeParams, I: PartyId> {
+    context: Context<P, I>,
+    cap_ys: BTreeMap<I, Point<P>>, // $Y_{k,j}$ where $j$ is this party's index
+}
+
+#[derive(Debug, Clone)]
+#[derive_where::derive_where(Serialize, Deserialize)]
+pub(super) struct Round2EchoBroadcast<P: SchemeParams, I: PartyId> {
+    // One for each of the old shares of this party.
+    pub(super) public_polynomials: Vec<PublicPolynomial<P>>,
+    pub(super) cap_ys: SerializableMap<I, Point<P>>, // $Y_{j,k}$ where $j$ is this party's index
+}
+
+#[derive(Debug, Clone)]
+#[derive_where::derive_where(Serialize, Deserialize)]
+pub(super) struct Round2DirectMessage<P: SchemeParams, I: PartyId> {
+    // Included so that the message could not be used as evidence by a node it was not addressed to.
+    pub(super) destination: I,
+    // One for each old share of this party and each share ID of the destination (see `subshare_points()`).
+    pub(super) cap_cs: Vec<Scalar<P>>,
+}
+
+struct Round2Payload<P: SchemeParams> {
+    subshares: Vec<Secret<Scalar<P>>>,
+    public_polynomials: Vec<PublicPolynomial<P>>,
+}
+
+impl<P: SchemeParams, I: PartyId> Round<I> for Round2<P, I> {
+    type Protocol = KeyResharingProtocol<P, I>;
+
+    fn transition_info(&self) -> TransitionInfo {
+        TransitionInfo::new_linear_terminating(2)
+    }
+
+    fn communication_info(&self) -> CommunicationInfo<I> {
+        self.context.round2_communication.clone()
+    }
+
     fn make_echo_broadcast(
         &self,
         _rng: &mut dyn CryptoRngCore,
         format: &BoxedFormat,
     ) -> Result<EchoBroadcast, LocalError> {
-        if let Some(old_holder) = self.old_holder.as_ref() {
+        if let Some(old_holder) = self.context.old_holder.as_ref() {
+            let cap_ys = old_holder.ys.map_values_ref(|y| y.mul_by_generator());
             EchoBroadcast::new(
                 format,
-                Round1BroadcastMessage {
-                    public_polynomial: old_holder.public_polynomial.clone(),
-                    old_share_id: old_holder.share_id,
+                Round2EchoBroadcast {
+                    public_polynomials: old_holder.public_polynomials.clone(),
+                    cap_ys: cap_ys.into(),
                 },
             )
         } else {
@@ -321,13 +936,30 @@ impl<P: SchemeParams, I: PartyId> Round<I> for Round1<P, I> {
         format: &BoxedFormat,
         destination: &I,
     ) -> Result<(DirectMessage, Option<Artifact>), LocalError> {
-        if let Some(old_holder) = self.old_holder.as_ref() {
-            let their_share_id = self.new_share_ids.get(destination).ok_or(LocalError::new(format!(
-                "destination={destination:?} is missing from the new_share_ids",
+        if let Some(old_holder) = self.context.old_holder.as_ref() {
+            let their_points = self.context.new_points.get(destination).ok_or(LocalError::new(format!(
+                "destination={destination:?} is missing from the new_points",
             )))?;
 
-            let subshare: Secret<Scalar<P>> = old_holder.polynomial.evaluate(their_share_id);
-            let dm = DirectMessage::new(format, Round1DirectMessage { subshare })?;
+            let cap_y = self.cap_ys.safe_get("ephemeral public keys", destination)?;
+            let y = old_holder.ys.safe_get("ephemeral secrets", destination)?;
+            let cap_d = cap_y * y;
+            let cap_cs = subshare_points(&old_holder.polynomials, their_points)
+                .enumerate()
+                .map(|(idx, (polynomial, (share_id, order)))| {
+                    let subshare = polynomial.evaluate_derivative(share_id, *order);
+                    let rho = dh_mask::<P, I>(MASK_DST, &self.context.sid, &self.context.my_id, &cap_d, idx);
+                    *(subshare + &rho).expose_secret()
+                })
+                .collect();
+
+            let dm = DirectMessage::new(
+                format,
+                Round2DirectMessage {
+                    destination: destination.clone(),
+                    cap_cs,
+                },
+            )?;
             Ok((dm, None))
         } else {
             Ok((DirectMessage::none(), None))
@@ -343,34 +975,84 @@ impl<P: SchemeParams, I: PartyId> Round<I> for Round1<P, I> {
         message.normal_broadcast.assert_is_none()?;
         let echo_broadcast = message
             .echo_broadcast
-            .deserialize::<Round1BroadcastMessage<P>>(format)?;
-        let direct_message = message.direct_message.deserialize::<Round1DirectMessage<P>>(format)?;
+            .deserialize::<Round2EchoBroadcast<P, I>>(format)?;
+        let direct_message = message
+            .direct_message
+            .deserialize::<Round2DirectMessage<P, I>>(format)?;
 
-        if let Some(new_holder) = self.new_holder.as_ref() {
-            if new_holder.inputs.old_holders.contains(from) {
-                let my_share_id = self.new_share_ids.get(&self.my_id).ok_or(LocalError::new(format!(
-                    "my_id={:?} is missing from the new_share_ids",
-                    &self.my_id
-                )))?;
-                let public_subshare_from_poly = echo_broadcast.public_polynomial.evaluate(my_share_id);
-                let public_subshare_from_private = Secret::mul_by_generator(&direct_message.subshare);
-
-                // Check that the public polynomial sent in the broadcast corresponds to the secret share
-                // sent in the direct message.
-                if public_subshare_from_poly != public_subshare_from_private {
-                    return Err(ReceiveError::protocol(KeyResharingError::SubshareMismatch));
-                }
+        let new_holder = match self.context.new_holder.as_ref() {
+            Some(new_holder) => new_holder,
+            None => return Err(ReceiveError::unprovable("This node is not a new holder")),
+        };
+        if !self.context.old_holders.contains(from) {
+            return Err(ReceiveError::unprovable("The sender is not one of the old holders"));
+        }
 
-                return Ok(Payload::new(Round1Payload {
-                    subshare: direct_message.subshare,
-                    public_polynomial: echo_broadcast.public_polynomial,
-                    old_share_id: echo_broadcast.old_share_id,
-                }));
-            }
+        if echo_broadcast.cap_ys.keys().cloned().collect::<BTreeSet<_>>()
+            != self.context.new_holders.clone().without(from)
+        {
+            return Err(ReceiveError::protocol(Error::R2WrongIdsY.into()));
+        }
+
+        if echo_broadcast
+            .public_polynomials
+            .iter()
+            .any(|public_polynomial| public_polynomial.num_coeffs() != self.context.new_threshold)
+        {
+            return Err(ReceiveError::protocol(Error::R2WrongPolynomialDegree.into()));
+        }
+
+        // Check that the public polynomials reshare the sender's old shares.
+        let old_public_shares = new_holder.inputs.old_public_key_shares.holder_shares(from)?;
+        if !reshares_old_shares(&echo_broadcast.public_polynomials, &old_public_shares) {
+            return Err(ReceiveError::protocol(Error::R2OldShareMismatch.into()));
         }
-        Err(ReceiveError::protocol(KeyResharingError::UnexpectedSender))
-    }
 
+        if direct_message.destination != self.context.my_id {
+            return Err(ReceiveError::unprovable(
+                "The direct message is addressed to another node",
+            ));
+        }
+
+        let my_id = &self.context.my_id;
+        let cap_y = echo_broadcast.cap_ys.safe_get("ephemeral public keys", my_id)?;
+        let y = new_holder.ys.safe_get("ephemeral secrets", from)?;
+        let cap_d = cap_y * y;
+        let subshares = direct_message
+            .cap_cs
+            .iter()
+            .enumerate()
+            .map(|(idx, cap_c)| {
+                let rho = dh_mask::<P, I>(MASK_DST, &self.context.sid, from, &cap_d, idx);
+                Secret::init_with(|| *cap_c - rho)
+            })
+            .collect::<Vec<_>>();
+
+        // Check that the public polynomials sent in the broadcast correspond to the secret shares
+        // sent in the direct message.
+        let my_points = self.context.new_points.safe_get("new share IDs", my_id)?;
+        if subshares.len() != echo_broadcast.public_polynomials.len() * my_points.len()
+            || subshares
+                .iter()
+                .zip(subshare_points(&echo_broadcast.public_polynomials, my_points))
+                .any(|(subshare, (public_polynomial, (share_id, order)))| {
+                    subshare.mul_by_generator() != public_polynomial.evaluate_derivative(share_id, *order)
+                })
+        {
+            return Err(ReceiveError::protocol(
+                Error::R2InvalidSubshare {
+                    reported_by: my_id.clone(),
+                    y: *y.expose_secret(),
+                }
+                .into(),
+            ));
+        }
+
+        Ok(Payload::new(Round2Payload {
+            subshares,
+            public_polynomials: echo_broadcast.public_polynomials,
+        }))
+    }
     fn finalize(
         self: Box<Self>,
         _rng: &mut dyn CryptoRngCore,
@@ -378,106 +1060,265 @@ impl<P: SchemeParams, I: PartyId> Round<I> for Round1<P, I> {
         _artifacts: BTreeMap<I, Artifact>,
     ) -> Result<FinalizeOutcome<I, Self::Protocol>, LocalError> {
         // If this party is not a new holder, exit.
-        let new_holder = match self.new_holder.as_ref() {
+        let new_holder = match self.context.new_holder.as_ref() {
             Some(new_holder) => new_holder,
             None => return Ok(FinalizeOutcome::Result(None)),
         };
 
-        let mut payloads = payloads.downcast_all::<Round1Payload<P>>()?;
+        let mut payloads = payloads.downcast_all::<Round2Payload<P>>()?;
 
-        let share_id = self
-            .new_share_ids
-            .get(&self.my_id)
-            .ok_or_else(|| LocalError::new(format!("my_id={:?} is missing from new_share_ids", &self.my_id)))?;
+        let my_points =
+            self.context.new_points.get(&self.context.my_id).ok_or_else(|| {
+                LocalError::new(format!("my_id={:?} is missing from new_points", &self.context.my_id))
+            })?;
 
         // If this node is both an old and a new holder,
         // add a simulated payload to the mapping, as if it sent a message to itself.
-        if let Some(old_holder) = self.old_holder.as_ref() {
-            if self.new_holder.as_ref().is_some() {
-                let subshare = old_holder.polynomial.evaluate(share_id);
-                let my_payload = Round1Payload {
-                    subshare,
-                    public_polynomial: old_holder.public_polynomial.clone(),
-                    old_share_id: old_holder.share_id,
+        if let Some(old_holder) = self.context.old_holder.as_ref() {
+            if self.context.new_holder.as_ref().is_some() {
+                let subshares = subshare_points(&old_holder.polynomials, my_points)
+                    .map(|(polynomial, (share_id, order))| polynomial.evaluate_derivative(share_id, *order))
+                    .collect();
+                let my_payload = Round2Payload {
+                    subshares,
+                    public_polynomials: old_holder.public_polynomials.clone(),
                 };
-                payloads.insert(self.my_id.clone(), my_payload);
+                payloads.insert(self.context.my_id.clone(), my_payload);
             }
         }
 
-        // Check that the 0-th coefficients of public polynomials (that is, the old shares)
-        // add up to the expected verifying key.
-
-        let old_share_ids = payloads
-            .values()
-            .map(|payload| payload.old_share_id)
-            .collect::<Vec<_>>();
-
-        let vkey = payloads
-            .values()
-            .map(|payload| {
+        // Use the first of the old holders that sent their subshares whose shares are enough
+        // to reconstruct the secret. The new shares are only consistent with each other
+        // if all the new holders use the same set, so it is returned along with the new share.
+        let old_public_key_shares = &new_holder.inputs.old_public_key_shares;
+        let used_old_holders = old_public_key_shares
+            .authorized_subset(&payloads.keys().cloned().collect())
+            .ok_or_else(|| LocalError::new("The old holders that sent their subshares are not an authorized set"))?;
+        let coeffs = old_public_key_shares.combination_coeffs(&used_old_holders)?;
+
+        // For each of the old shares used, its public polynomial, the subshares for our share IDs,
+        // and its combination coefficient.
+        let num_points = my_points.len();
+        let used_shares = used_old_holders
+            .iter()
+            .map(|id| {
+                let payload = payloads.safe_get("payloads", id)?;
+                let coeffs = coeffs.safe_get("combination coefficients", id)?;
+                if payload.public_polynomials.len() != coeffs.len() {
+                    return Err(LocalError::new(format!(
+                        "The number of public polynomials from id={id:?} does not match the number of its old shares"
+                    )));
+                }
                 payload
-                    .public_polynomial
-                    .coeff0()
-                    .map(|coeff0| coeff0 * interpolation_coeff(old_share_ids.iter(), &payload.old_share_id))
+                    .public_polynomials
+                    .iter()
+                    .zip(coeffs)
+                    .enumerate()
+                    .map(|(share_idx, (public_polynomial, coeff))| {
+                        let subshares = payload
+                            .subshares
+                            .get(share_idx * num_points..(share_idx + 1) * num_points)
+                            .ok_or_else(|| {
+                                LocalError::new("The number of subshares does not match the number of share IDs")
+                            })?;
+                        Ok((public_polynomial, subshares, coeff))
+                    })
+                    .collect::<Result<Vec<_>, LocalError>>()
             })
-            .sum::<Result<_, _>>()?;
+            .collect::<Result<Vec<_>, LocalError>>()?
+            .into_iter()
+            .flatten()
+            .collect::<Vec<_>>();
+
+        // Check that the 0-th coefficients of public polynomials (that is, the old shares)
+        // combine into the expected verifying key.
+        // Each of them was already checked against the corresponding old public share,
+        // so a mismatch here means the old public key shares given to this node are inconsistent.
+        let vkey = used_shares
+            .iter()
+            .map(|(public_polynomial, _subshares, coeff)| Ok(*public_polynomial.coeff0()? * *coeff))
+            .sum::<Result<Point<P>, LocalError>>()?;
         if Point::from_verifying_key(&new_holder.inputs.verifying_key) != vkey {
-            // TODO (#113): this is unattributable.
-            // Should we add an enum variant to `FinalizeError`?
-            // or take the public shares as an input (assuming the nodes published those previously)
-            panic!("Invalid shares");
+            return Err(LocalError::new(
+                "The old public key shares do not add up to the verifying key",
+            ));
         }
 
-        // Assemble the new share.
-        let subshares = new_holder
-            .inputs
-            .old_holders
-            .iter()
-            .map(|id| {
-                let payload = payloads
-                    .get(id)
-                    .ok_or_else(|| LocalError::new("id={id:?} is missing from the payloads"))?;
-                Ok((payload.old_share_id, payload.subshare.clone()))
+        // Assemble the new shares (one for each of our share IDs).
+        let secret_shares = (0..num_points)
+            .map(|idx| {
+                used_shares.iter().try_fold(
+                    Secret::init_with(|| Scalar::ZERO),
+                    |acc, (_public_polynomial, subshares, coeff)| {
+                        let subshare = subshares.get(idx).ok_or_else(|| {
+                            LocalError::new("The number of subshares does not match the number of share IDs")
+                        })?;
+                        Ok(acc + subshare.clone() * *coeff)
+                    },
+                )
             })
-            .collect::<Result<BTreeMap<_, _>, _>>()?;
-        let secret_share = shamir_join_scalars(subshares);
+            .collect::<Result<Vec<_>, LocalError>>()?;
 
         // Generate the public shares of all the new holders.
-        let public_shares = self
-            .new_share_ids
+        let holders = self
+            .context
+            .new_points
             .iter()
-            .map(|(id, share_id)| {
-                let public_subshares = payloads
-                    .values()
-                    .map(|p| (p.old_share_id, p.public_polynomial.evaluate(share_id)))
-                    .collect::<BTreeMap<_, _>>();
-                let public_share = shamir_join_points(&public_subshares);
-                (id.clone(), public_share)
+            .map(|(id, points)| {
+                let shares = points
+                    .iter()
+                    .map(|(share_id, order)| {
+                        let public_share = used_shares
+                            .iter()
+                            .map(|(public_polynomial, _subshares, coeff)| {
+                                public_polynomial.evaluate_derivative(share_id, *order) * *coeff
+                            })
+                            .sum::<Point<P>>();
+                        (*share_id, public_share)
+                    })
+                    .collect();
+                (id.clone(), shares)
             })
             .collect::<BTreeMap<_, _>>();
 
-        Ok(FinalizeOutcome::Result(Some(ThresholdKeyShare {
-            owner: self.my_id.clone(),
-            threshold: self.new_threshold as u32,
-            secret_share,
-            share_ids: self.new_share_ids.into(),
-            public_shares: public_shares.into(),
-        })))
+        let key_share = ThresholdKeyShare::from_weighted_parts(
+            self.context.my_id.clone(),
+            self.context.new_threshold,
+            secret_shares,
+            holders,
+        )?;
+        let key_share = ThresholdKeyShare {
+            access_structure: self.context.new_access_structure.clone(),
+            ..key_share
+        };
+
+        Ok(FinalizeOutcome::Result(Some((key_share, used_old_holders))))
     }
 }
 
 #[cfg(test)]
 mod tests {
-    use alloc::collections::{BTreeMap, BTreeSet};
+    use alloc::{
+        collections::{BTreeMap, BTreeSet, VecDeque},
+        vec,
+        vec::Vec,
+    };
 
     use manul::{
         dev::{run_sync, BinaryFormat, TestSessionParams, TestSigner, TestVerifier},
+        session::{
+            CanFinalize, Message, RoundAccumulator, RoundOutcome, Session, SessionId, SessionReport, VerifiedMessage,
+        },
         signature::Keypair,
     };
     use rand_core::OsRng;
 
-    use super::{KeyResharing, NewHolder, OldHolder, ThresholdKeyShare};
-    use crate::dev::TestParams;
+    use super::{KeyResharing, KeyResharingProtocol, NewHolder, OldHolder, ThresholdKeyShare};
+    use crate::{curve::Scalar, dev::TestParams, HierarchicalAccessStructure, ShareIdAssignment};
+
+    type SP = TestSessionParams<BinaryFormat>;
+    type TestSession = Session<KeyResharingProtocol<TestParams, TestVerifier>, SP>;
+    type TestAccum = RoundAccumulator<KeyResharingProtocol<TestParams, TestVerifier>, SP>;
+    type TestReport = SessionReport<KeyResharingProtocol<TestParams, TestVerifier>, SP>;
+    type MessageQueue = VecDeque<(TestVerifier, TestVerifier, Message<TestVerifier>)>;
+
+    /// Queues the messages of the current round of the session, and returns the accumulator for it
+    /// with the cached messages for the round added.
+    fn start_round(
+        session: &TestSession,
+        cached_messages: Vec<VerifiedMessage<TestVerifier>>,
+        messages: &mut MessageQueue,
+    ) -> TestAccum {
+        let mut accum = session.make_accumulator();
+        for destination in session.message_destinations() {
+            let (message, artifact) = session.make_message(&mut OsRng, destination).unwrap();
+            messages.push_back((session.verifier(), *destination, message));
+            session.add_artifact(&mut accum, artifact).unwrap();
+        }
+        for message in cached_messages {
+            let processed = session.process_message(message);
+            session.add_processed_message(&mut accum, processed).unwrap();
+        }
+        accum
+    }
+
+    /// Finalizes the current round of the node's session, and then the following rounds as long as they can be finalized.
+    fn finalize(
+        id: &TestVerifier,
+        sessions: &mut BTreeMap<TestVerifier, (TestSession, TestAccum)>,
+        reports: &mut BTreeMap<TestVerifier, TestReport>,
+        messages: &mut MessageQueue,
+    ) {
+        let (mut session, mut accum) = sessions.remove(id).unwrap();
+        loop {
+            match session.finalize_round(&mut OsRng, accum).unwrap() {
+                RoundOutcome::Finished(report) => {
+                    reports.insert(*id, report);
+                    return;
+                }
+                RoundOutcome::AnotherRound {
+                    session: new_session,
+                    cached_messages,
+                } => {
+                    accum = start_round(&new_session, cached_messages, messages);
+                    session = new_session;
+                }
+            }
+            if session.can_finalize(&accum) != CanFinalize::Yes {
+                sessions.insert(*id, (session, accum));
+                return;
+            }
+        }
+    }
+
+    /// Runs the sessions like `run_sync()`, but once there are no messages left to deliver,
+    /// the node in the earliest round stops waiting and finalizes it with the messages it has
+    /// (`run_sync()` requires all the expected messages to arrive).
+    fn run_with_timeouts(
+        entry_points: Vec<(TestSigner, KeyResharing<TestParams, TestVerifier>)>,
+    ) -> BTreeMap<TestVerifier, TestReport> {
+        let session_id = SessionId::random::<SP>(&mut OsRng);
+        let mut messages = MessageQueue::new();
+        let mut sessions = BTreeMap::new();
+        let mut reports = BTreeMap::new();
+
+        for (signer, entry_point) in entry_points {
+            let session = TestSession::new(&mut OsRng, session_id.clone(), signer, entry_point).unwrap();
+            let accum = start_round(&session, vec![], &mut messages);
+            sessions.insert(signer.verifying_key(), (session, accum));
+        }
+
+        loop {
+            let id = match messages.pop_front() {
+                Some((from, to, message)) => {
+                    let (session, accum) = match sessions.get_mut(&to) {
+                        Some(entry) => entry,
+                        None => continue,
+                    };
+                    let preprocessed = session.preprocess_message(accum, &from, message).unwrap();
+                    if let Some(verified) = preprocessed.ok() {
+                        let processed = session.process_message(verified);
+                        session.add_processed_message(accum, processed).unwrap();
+                    }
+                    if session.can_finalize(accum) != CanFinalize::Yes {
+                        continue;
+                    }
+                    to
+                }
+                None => match sessions
+                    .iter()
+                    .min_by_key(|(_id, (session, _accum))| session.round_id())
+                {
+                    Some((id, _)) => *id,
+                    None => break,
+                },
+            };
+
+            finalize(&id, &mut sessions, &mut reports, &mut messages);
+        }
+
+        reports
+    }
 
     #[test]
     fn execute_key_reshare() {
@@ -490,11 +1331,13 @@ mod tests {
         let old_key_shares =
             ThresholdKeyShare::<TestParams, TestVerifier>::new_centralized(&mut OsRng, &old_holders, 2, None).unwrap();
         let old_vkey = old_key_shares[&ids[0]].verifying_key().unwrap();
+        let old_public_key_shares = old_key_shares[&ids[0]].public_key_shares();
         let new_threshold = 2;
 
         let party0 = KeyResharing::new(
             Some(OldHolder {
                 key_share: old_key_shares[&ids[0]].clone(),
+                old_holders: old_holders.clone(),
             }),
             None,
             new_holders.clone(),
@@ -504,11 +1347,13 @@ mod tests {
         let party1 = KeyResharing::new(
             Some(OldHolder {
                 key_share: old_key_shares[&ids[1]].clone(),
+                old_holders: old_holders.clone(),
             }),
             Some(NewHolder {
                 verifying_key: old_vkey,
                 old_threshold: 2,
                 old_holders: old_holders.clone(),
+                old_public_key_shares: old_public_key_shares.clone(),
             }),
             new_holders.clone(),
             new_threshold,
@@ -517,11 +1362,13 @@ mod tests {
         let party2 = KeyResharing::new(
             Some(OldHolder {
                 key_share: old_key_shares[&ids[2]].clone(),
+                old_holders: old_holders.clone(),
             }),
             Some(NewHolder {
                 verifying_key: old_vkey,
                 old_threshold: 2,
                 old_holders: old_holders.clone(),
+                old_public_key_shares: old_public_key_shares.clone(),
             }),
             new_holders.clone(),
             new_threshold,
@@ -533,6 +1380,7 @@ mod tests {
                 verifying_key: old_vkey,
                 old_threshold: 2,
                 old_holders: old_holders.clone(),
+                old_public_key_shares: old_public_key_shares.clone(),
             }),
             new_holders.clone(),
             new_threshold,
@@ -552,10 +1400,21 @@ mod tests {
         assert!(shares[&ids[0]].is_none());
 
         // Unwrap the results of the new holders
-        let shares = shares
+        let results = shares
             .into_iter()
-            .filter(|(id, _share)| id != &ids[0])
-            .map(|(id, share)| (id, share.unwrap()))
+            .filter(|(id, _result)| id != &ids[0])
+            .map(|(id, result)| (id, result.unwrap()))
+            .collect::<BTreeMap<_, _>>();
+
+        // Check that all the new holders used the shares of the first `old_threshold` old holders
+        let expected_old_holders = BTreeSet::from([ids[0], ids[1]]);
+        assert!(results
+            .values()
+            .all(|(_share, used_old_holders)| used_old_holders == &expected_old_holders));
+
+        let shares = results
+            .into_iter()
+            .map(|(id, (share, _used_old_holders))| (id, share))
             .collect::<BTreeMap<_, _>>();
 
         // Check that all public information is the same between the shares
@@ -571,4 +1430,378 @@ mod tests {
             assert_eq!(public, share.public_shares[&share.owner]);
         }
     }
+
+    #[test]
+    fn execute_key_reshare_with_missing_old_holder() {
+        let signers = (0..4).map(TestSigner::new).collect::<Vec<_>>();
+        let ids = signers.iter().map(|signer| signer.verifying_key()).collect::<Vec<_>>();
+
+        let all_old_holders = BTreeSet::from([ids[0], ids[1], ids[2]]);
+        let old_key_shares =
+            ThresholdKeyShare::<TestParams, TestVerifier>::new_centralized(&mut OsRng, &all_old_holders, 2, None)
+                .unwrap();
+        let old_vkey = old_key_shares[&ids[1]].verifying_key().unwrap();
+        let old_public_key_shares = old_key_shares[&ids[1]].public_key_shares();
+
+        // The first old holder is offline, so it is left out of the session.
+        let old_holders = BTreeSet::from([ids[1], ids[2]]);
+        let new_holders = BTreeSet::from([ids[1], ids[2], ids[3]]);
+
+        let entry_points = signers
+            .into_iter()
+            .filter(|signer| new_holders.contains(&signer.verifying_key()))
+            .map(|signer| {
+                let id = signer.verifying_key();
+                let old_holder = old_holders.contains(&id).then(|| OldHolder {
+                    key_share: old_key_shares[&id].clone(),
+                    old_holders: old_holders.clone(),
+                });
+                let new_holder = NewHolder {
+                    verifying_key: old_vkey,
+                    old_threshold: 2,
+                    old_holders: old_holders.clone(),
+                    old_public_key_shares: old_public_key_shares.clone(),
+                };
+                let entry_point = KeyResharing::new(old_holder, Some(new_holder), new_holders.clone(), 2);
+                (signer, entry_point)
+            })
+            .collect::<Vec<_>>();
+
+        let results = run_sync::<_, TestSessionParams<BinaryFormat>>(&mut OsRng, entry_points)
+            .unwrap()
+            .results()
+            .unwrap()
+            .into_iter()
+            .map(|(id, result)| (id, result.unwrap()))
+            .collect::<BTreeMap<_, _>>();
+
+        // All the new holders used the same old holders, the ones taking part in the session
+        assert!(results
+            .values()
+            .all(|(_share, used_old_holders)| used_old_holders == &old_holders));
+
+        let shares = results
+            .into_iter()
+            .map(|(id, (share, _used_old_holders))| (id, share))
+            .collect::<BTreeMap<_, _>>();
+
+        for share in shares.values() {
+            assert_eq!(share.verifying_key().unwrap(), old_vkey);
+            assert_eq!(share.public_shares, shares[&ids[1]].public_shares);
+            assert_eq!(share.secret_share.mul_by_generator(), share.public_shares[&share.owner]);
+        }
+    }
+
+    #[test]
+    fn execute_key_reshare_with_silent_old_holder() {
+        let signers = (0..4).map(TestSigner::new).collect::<Vec<_>>();
+        let ids = signers.iter().map(|signer| signer.verifying_key()).collect::<Vec<_>>();
+
+        let old_holders = BTreeSet::from([ids[0], ids[1], ids[2]]);
+        let new_holders = BTreeSet::from([ids[1], ids[2], ids[3]]);
+        let old_key_shares =
+            ThresholdKeyShare::<TestParams, TestVerifier>::new_centralized(&mut OsRng, &old_holders, 2, None).unwrap();
+        let old_vkey = old_key_shares[&ids[1]].verifying_key().unwrap();
+        let old_public_key_shares = old_key_shares[&ids[1]].public_key_shares();
+
+        // The first old holder takes part in the session, but never sends anything.
+        let entry_points = signers
+            .into_iter()
+            .filter(|signer| signer.verifying_key() != ids[0])
+            .map(|signer| {
+                let id = signer.verifying_key();
+                let old_holder = old_holders.contains(&id).then(|| OldHolder {
+                    key_share: old_key_shares[&id].clone(),
+                    old_holders: old_holders.clone(),
+                });
+                let new_holder = NewHolder {
+                    verifying_key: old_vkey,
+                    old_threshold: 2,
+                    old_holders: old_holders.clone(),
+                    old_public_key_shares: old_public_key_shares.clone(),
+                };
+                let entry_point = KeyResharing::new(old_holder, Some(new_holder), new_holders.clone(), 2);
+                (signer, entry_point)
+            })
+            .collect::<Vec<_>>();
+
+        let results = run_with_timeouts(entry_points)
+            .into_iter()
+            .map(|(id, report)| (id, report.result().unwrap().unwrap()))
+            .collect::<BTreeMap<_, _>>();
+        assert_eq!(results.keys().cloned().collect::<BTreeSet<_>>(), new_holders);
+
+        // All the new holders used the old holders that responded
+        let responded_old_holders = BTreeSet::from([ids[1], ids[2]]);
+        assert!(results
+            .values()
+            .all(|(_share, used_old_holders)| used_old_holders == &responded_old_holders));
+
+        let shares = results
+            .into_iter()
+            .map(|(id, (share, _used_old_holders))| (id, share))
+            .collect::<BTreeMap<_, _>>();
+
+        for share in shares.values() {
+            assert_eq!(share.verifying_key().unwrap(), old_vkey);
+            assert_eq!(share.public_shares, shares[&ids[1]].public_shares);
+            assert_eq!(share.secret_share.mul_by_generator(), share.public_shares[&share.owner]);
+        }
+    }
+
+    #[test]
+    fn execute_weighted_key_reshare() {
+        let signers = (0..3).map(TestSigner::new).collect::<Vec<_>>();
+        let ids = signers.iter().map(|signer| signer.verifying_key()).collect::<Vec<_>>();
+        let holders = ids.iter().cloned().collect::<BTreeSet<_>>();
+
+        let old_key_shares =
+            ThresholdKeyShare::<TestParams, TestVerifier>::new_centralized(&mut OsRng, &holders, 2, None).unwrap();
+        let old_vkey = old_key_shares[&ids[0]].verifying_key().unwrap();
+        let old_public_key_shares = old_key_shares[&ids[0]].public_key_shares();
+
+        // The first node gets two shares, so it can sign with any other node.
+        let new_weights = BTreeMap::from([(ids[0], 2)]);
+        let new_threshold = 3;
+
+        let entry_points = signers
+            .into_iter()
+            .map(|signer| {
+                let id = signer.verifying_key();
+                let entry_point = KeyResharing::new(
+                    Some(OldHolder {
+                        key_share: old_key_shares[&id].clone(),
+                        old_holders: holders.clone(),
+                    }),
+                    Some(NewHolder {
+                        verifying_key: old_vkey,
+                        old_threshold: 2,
+                        old_holders: holders.clone(),
+                        old_public_key_shares: old_public_key_shares.clone(),
+                    }),
+                    holders.clone(),
+                    new_threshold,
+                )
+                .with_new_weights(new_weights.clone());
+                (signer, entry_point)
+            })
+            .collect::<Vec<_>>();
+
+        let shares = run_sync::<_, TestSessionParams<BinaryFormat>>(&mut OsRng, entry_points)
+            .unwrap()
+            .results()
+            .unwrap()
+            .into_iter()
+            .map(|(id, result)| (id, result.unwrap().0))
+            .collect::<BTreeMap<_, _>>();
+
+        assert!(shares.values().all(|share| share.weight(&ids[0]) == 2
+            && share.weight(&ids[1]) == 1
+            && share.verifying_key().unwrap() == old_vkey));
+
+        // Check that the public keys correspond to the secret key shares
+        for share in shares.values() {
+            for ((_share_id, public_share), (_, secret_share)) in share
+                .holder_shares(&share.owner)
+                .unwrap()
+                .into_iter()
+                .zip(share.owner_secret_shares().unwrap())
+            {
+                assert_eq!(secret_share.mul_by_generator(), public_share);
+            }
+        }
+
+        // The node with two shares can sign with any other node
+        for other in [ids[1], ids[2]] {
+            let signers = BTreeSet::from([ids[0], other]);
+            let key_share0 = shares[&ids[0]].to_key_share(&signers).unwrap();
+            let key_share1 = shares[&other].to_key_share(&signers).unwrap();
+            let secret = key_share0.secret_share().expose_secret() + key_share1.secret_share().expose_secret();
+            assert_eq!(secret.mul_by_generator().to_verifying_key().unwrap(), old_vkey);
+        }
+    }
+
+    #[test]
+    fn execute_key_reshare_from_weighted_shares() {
+        let signers = (0..4).map(TestSigner::new).collect::<Vec<_>>();
+        let ids = signers.iter().map(|signer| signer.verifying_key()).collect::<Vec<_>>();
+
+        // The first node has two old shares, so it can sign with any other old holder.
+        let old_weights = BTreeMap::from([(ids[0], 2), (ids[1], 1), (ids[2], 1)]);
+        let old_key_shares = ThresholdKeyShare::<TestParams, TestVerifier>::new_centralized_weighted(
+            &mut OsRng,
+            &old_weights,
+            3,
+            None,
+            &ShareIdAssignment::Sequential,
+        )
+        .unwrap();
+        let old_vkey = old_key_shares[&ids[0]].verifying_key().unwrap();
+        let old_public_key_shares = old_key_shares[&ids[0]].public_key_shares();
+
+        let old_holders = BTreeSet::from([ids[0], ids[1]]);
+        let new_holders = BTreeSet::from([ids[1], ids[2], ids[3]]);
+
+        let entry_points = signers
+            .into_iter()
+            .map(|signer| {
+                let id = signer.verifying_key();
+                let old_holder = old_holders.contains(&id).then(|| OldHolder {
+                    key_share: old_key_shares[&id].clone(),
+                    old_holders: old_holders.clone(),
+                });
+                let new_holder = new_holders.contains(&id).then(|| NewHolder {
+                    verifying_key: old_vkey,
+                    old_threshold: 3,
+                    old_holders: old_holders.clone(),
+                    old_public_key_shares: old_public_key_shares.clone(),
+                });
+                let entry_point = KeyResharing::new(old_holder, new_holder, new_holders.clone(), 2);
+                (signer, entry_point)
+            })
+            .collect::<Vec<_>>();
+
+        let shares = run_sync::<_, TestSessionParams<BinaryFormat>>(&mut OsRng, entry_points)
+            .unwrap()
+            .results()
+            .unwrap()
+            .into_iter()
+            .filter_map(|(id, result)| result.map(|(share, _used_old_holders)| (id, share)))
+            .collect::<BTreeMap<_, _>>();
+
+        assert_eq!(shares.keys().cloned().collect::<BTreeSet<_>>(), new_holders);
+
+        for share in shares.values() {
+            assert_eq!(share.verifying_key().unwrap(), old_vkey);
+            assert!(!share.is_weighted());
+            assert_eq!(share.secret_share.mul_by_generator(), share.public_shares[&share.owner]);
+        }
+
+        // Any two of the new holders can sign
+        for signers in [
+            BTreeSet::from([ids[1], ids[2]]),
+            BTreeSet::from([ids[1], ids[3]]),
+            BTreeSet::from([ids[2], ids[3]]),
+        ] {
+            let secret = signers
+                .iter()
+                .map(|id| {
+                    *shares[id]
+                        .to_key_share(&signers)
+                        .unwrap()
+                        .secret_share()
+                        .expose_secret()
+                })
+                .sum::<Scalar<TestParams>>();
+            assert_eq!(secret.mul_by_generator().to_verifying_key().unwrap(), old_vkey);
+        }
+    }
+
+    #[test]
+    fn execute_key_reshare_to_and_from_hierarchical_shares() {
+        let signers = (0..5).map(TestSigner::new).collect::<Vec<_>>();
+        let ids = signers.iter().map(|signer| signer.verifying_key()).collect::<Vec<_>>();
+
+        let old_holders = BTreeSet::from([ids[0], ids[1], ids[2]]);
+        let old_key_shares =
+            ThresholdKeyShare::<TestParams, TestVerifier>::new_centralized(&mut OsRng, &old_holders, 2, None).unwrap();
+        let old_vkey = old_key_shares[&ids[0]].verifying_key().unwrap();
+
+        // Any 4 holders including both of the executives
+        let executives = BTreeSet::from([ids[0], ids[1]]);
+        let operators = BTreeSet::from([ids[2], ids[3], ids[4]]);
+        let access_structure = HierarchicalAccessStructure::new([(executives, 2), (operators, 4)].into()).unwrap();
+        let new_holders = access_structure.holders();
+
+        let run = |old_key_shares: &BTreeMap<TestVerifier, ThresholdKeyShare<TestParams, TestVerifier>>,
+                   old_holders: &BTreeSet<TestVerifier>,
+                   new_holders: &BTreeSet<TestVerifier>,
+                   new_threshold: usize,
+                   new_access_structure: Option<&HierarchicalAccessStructure<TestVerifier>>| {
+            let old_public_key_shares = old_key_shares.values().next().unwrap().public_key_shares();
+            let entry_points = signers
+                .iter()
+                .filter(|signer| {
+                    let id = signer.verifying_key();
+                    old_holders.contains(&id) || new_holders.contains(&id)
+                })
+                .map(|signer| {
+                    let id = signer.verifying_key();
+                    let old_holder = old_holders.contains(&id).then(|| OldHolder {
+                        key_share: old_key_shares[&id].clone(),
+                        old_holders: old_holders.clone(),
+                    });
+                    let new_holder = new_holders.contains(&id).then(|| NewHolder {
+                        verifying_key: old_vkey,
+                        old_threshold: old_public_key_shares.threshold(),
+                        old_holders: old_holders.clone(),
+                        old_public_key_shares: old_public_key_shares.clone(),
+                    });
+                    let entry_point = KeyResharing::new(old_holder, new_holder, new_holders.clone(), new_threshold);
+                    let entry_point = match new_access_structure {
+                        Some(access_structure) => entry_point.with_new_access_structure(access_structure.clone()),
+                        None => entry_point,
+                    };
+                    (*signer, entry_point)
+                })
+                .collect::<Vec<_>>();
+
+            run_sync::<_, TestSessionParams<BinaryFormat>>(&mut OsRng, entry_points)
+                .unwrap()
+                .results()
+                .unwrap()
+                .into_iter()
+                .filter_map(|(id, result)| result.map(|(share, _used_old_holders)| (id, share)))
+                .collect::<BTreeMap<_, _>>()
+        };
+
+        let joined_vkey = |shares: &BTreeMap<TestVerifier, ThresholdKeyShare<TestParams, TestVerifier>>,
+                           signers: &BTreeSet<TestVerifier>| {
+            signers
+                .iter()
+                .map(|id| *shares[id].to_key_share(signers).unwrap().secret_share().expose_secret())
+                .sum::<Scalar<TestParams>>()
+                .mul_by_generator()
+                .to_verifying_key()
+                .unwrap()
+        };
+
+        // Reshare plain shares into hierarchical ones.
+        let hierarchical_shares = run(&old_key_shares, &old_holders, &new_holders, 4, Some(&access_structure));
+        assert_eq!(
+            hierarchical_shares.keys().cloned().collect::<BTreeSet<_>>(),
+            new_holders
+        );
+
+        for share in hierarchical_shares.values() {
+            assert_eq!(share.verifying_key().unwrap(), old_vkey);
+            assert_eq!(share.public_shares, hierarchical_shares[&ids[0]].public_shares);
+            assert_eq!(share.secret_share.mul_by_generator(), share.public_shares[&share.owner]);
+            assert!(share.access_structure().is_some());
+        }
+
+        for authorized in [
+            BTreeSet::from([ids[0], ids[1], ids[2], ids[3]]),
+            BTreeSet::from([ids[0], ids[1], ids[3], ids[4]]),
+        ] {
+            assert_eq!(joined_vkey(&hierarchical_shares, &authorized), old_vkey);
+        }
+
+        // Not enough executives
+        let unauthorized = BTreeSet::from([ids[0], ids[2], ids[3], ids[4]]);
+        assert!(hierarchical_shares[&ids[0]].to_key_share(&unauthorized).is_err());
+
+        // Reshare the hierarchical shares back into plain ones,
+        // using an authorized set of the old holders.
+        let old_holders = BTreeSet::from([ids[0], ids[1], ids[2], ids[4]]);
+        let new_holders = BTreeSet::from([ids[2], ids[3], ids[4]]);
+        let plain_shares = run(&hierarchical_shares, &old_holders, &new_holders, 2, None);
+        assert_eq!(plain_shares.keys().cloned().collect::<BTreeSet<_>>(), new_holders);
+
+        for share in plain_shares.values() {
+            assert_eq!(share.verifying_key().unwrap(), old_vkey);
+            assert!(share.access_structure().is_none());
+        }
+        assert_eq!(joined_vkey(&plain_shares, &BTreeSet::from([ids[3], ids[4]])), old_vkey);
+    }
 }
@@ -1,4 +1,16 @@
 mod aux_gen;
+mod batch_interactive_signing;
+mod batch_presigning;
 mod interactive_signing;
+mod key_gen;
+mod key_import;
 mod key_init;
 mod key_refresh;
+mod key_resharing;
+mod share_recovery;
+mod signing;
+mod threshold_key_init;
+mod threshold_key_refresh;
+
+#[cfg(feature = "k256")]
+mod schnorr_signing;
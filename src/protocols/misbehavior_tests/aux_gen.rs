@@ -13,8 +13,8 @@ use rand_chacha::ChaCha8Rng;
 use rand_core::{CryptoRngCore, OsRng, SeedableRng};
 
 use super::super::aux_gen::{
-    AuxGen, AuxGenAssociatedData, Round1, Round1EchoBroadcast, Round2EchoBroadcast, Round2NormalBroadcast,
-    Round3DirectMessage, Round3NormalBroadcast,
+    AuxGen, AuxGenAssociatedData, ReturnAuxInfo, Round1, Round1EchoBroadcast, Round2EchoBroadcast,
+    Round2NormalBroadcast, Round3DirectMessage, Round3NormalBroadcast,
 };
 use crate::{
     dev::TestParams,
@@ -253,7 +253,7 @@ fn r2_paillier_modulus_too_small() {
             echo_broadcast: EchoBroadcast,
         ) -> Result<EchoBroadcast, LocalError> {
             if round.id() == 1 {
-                let round1 = round.downcast_ref::<Round1<P, Id>>()?;
+                let round1 = round.downcast_ref::<Round1<P, Id, ReturnAuxInfo>>()?;
                 let mut data = round1.public_data.clone();
                 data.paillier_pk = make_small_modulus_pk::<<P as SchemeParams>::Paillier>().into_precomputed();
                 let message = Round1EchoBroadcast {
@@ -292,7 +292,7 @@ fn r2_rp_modulus_too_small() {
             if round.id() == 1 {
                 // Technically we only need to modify `X`, but we need to substitute the hash in Round 1 too,
                 // so that in Round 2 the hash check could pass and the execution reaches the IDs check.
-                let round1 = round.downcast_ref::<Round1<P, Id>>()?;
+                let round1 = round.downcast_ref::<Round1<P, Id, ReturnAuxInfo>>()?;
 
                 let mut data = round1.public_data.clone();
                 data.rp_params = make_small_modulus_rp_params::<<P as SchemeParams>::Paillier>().to_precomputed();
@@ -333,7 +333,7 @@ fn r2_prm_failed() {
             echo_broadcast: EchoBroadcast,
         ) -> Result<EchoBroadcast, LocalError> {
             if round.id() == 1 {
-                let round1 = round.downcast_ref::<Round1<P, Id>>()?;
+                let round1 = round.downcast_ref::<Round1<P, Id, ReturnAuxInfo>>()?;
                 let mut data = round1.public_data.clone();
 
                 let mut rng = ChaCha8Rng::seed_from_u64(123);
@@ -0,0 +1,140 @@
+use alloc::{collections::BTreeSet, vec::Vec};
+
+use elliptic_curve::FieldBytes;
+use manul::{
+    combinators::misbehave::Misbehaving,
+    dev::{BinaryFormat, TestSessionParams, TestSigner, TestVerifier},
+    protocol::{BoxedFormat, BoxedRound, EntryPoint, LocalError, NormalBroadcast, ProtocolMessagePart},
+    signature::Keypair,
+};
+use rand_core::{CryptoRngCore, OsRng, RngCore};
+
+use super::super::{
+    batch_interactive_signing::{BatchInteractiveSigning, BatchInteractiveSigningAssociatedData},
+    interactive_signing::Round4NormalBroadcast,
+};
+use crate::{
+    curve::Scalar,
+    dev::TestParams,
+    entities::{AuxInfo, KeyShare},
+    params::SchemeParams,
+    tools::protocol_shortcuts_dev::{check_evidence_with_behavior, run_with_one_malicious_party},
+};
+
+type Id = TestVerifier;
+type P = TestParams;
+type SP = TestSessionParams<BinaryFormat>;
+type Curve = <TestParams as SchemeParams>::Curve;
+
+const BATCH_SIZE: usize = 3;
+
+/// The instance the malicious party sends an invalid signature share for.
+const FAILING_INDEX: usize = 1;
+
+#[allow(clippy::type_complexity)]
+fn make_entry_points() -> (
+    BatchInteractiveSigningAssociatedData<P, Id>,
+    Vec<(TestSigner, BatchInteractiveSigning<P, Id>)>,
+) {
+    let signers = (0..3).map(TestSigner::new).collect::<Vec<_>>();
+    let all_ids = signers.iter().map(TestSigner::verifying_key).collect::<BTreeSet<_>>();
+
+    let key_shares = KeyShare::<TestParams, TestVerifier>::new_centralized(&mut OsRng, &all_ids, None);
+    let aux_infos = AuxInfo::new_centralized(&mut OsRng, &all_ids);
+
+    let messages = (0..BATCH_SIZE)
+        .map(|_| {
+            let mut message = FieldBytes::<Curve>::default();
+            OsRng.fill_bytes(&mut message);
+            message
+        })
+        .collect::<Vec<_>>();
+
+    let entry_points = signers
+        .into_iter()
+        .map(|signer| {
+            let id = signer.verifying_key();
+            let entry_point =
+                BatchInteractiveSigning::new(messages.clone(), key_shares[&id].clone(), aux_infos[&id].clone())
+                    .unwrap();
+            (signer, entry_point)
+        })
+        .collect();
+
+    let id = all_ids.first().unwrap();
+    let associated_data = BatchInteractiveSigningAssociatedData::new(
+        messages,
+        key_shares[id].public().clone(),
+        aux_infos[id].public().clone(),
+    )
+    .unwrap();
+
+    (associated_data, entry_points)
+}
+
+struct InvalidSignatureShare;
+
+impl Misbehaving<Id, ()> for InvalidSignatureShare {
+    type EntryPoint = BatchInteractiveSigning<P, Id>;
+
+    fn modify_normal_broadcast(
+        rng: &mut dyn CryptoRngCore,
+        round: &BoxedRound<Id, <Self::EntryPoint as EntryPoint<Id>>::Protocol>,
+        _behavior: &(),
+        format: &BoxedFormat,
+        normal_broadcast: NormalBroadcast,
+    ) -> Result<NormalBroadcast, LocalError> {
+        if round.id() == 4 {
+            let mut parts = normal_broadcast.deserialize::<Vec<NormalBroadcast>>(format).unwrap();
+            let mut message = parts[FAILING_INDEX]
+                .deserialize::<Round4NormalBroadcast<P>>(format)
+                .unwrap();
+            message.sigma = Scalar::random(rng);
+            parts[FAILING_INDEX] = NormalBroadcast::new(format, message)?;
+            return NormalBroadcast::new(format, parts);
+        }
+
+        Ok(normal_broadcast)
+    }
+}
+
+#[test]
+fn instance_r4_invalid_signature_share() {
+    let (associated_data, entry_points) = make_entry_points();
+    check_evidence_with_behavior::<SP, InvalidSignatureShare, _>(
+        &mut OsRng,
+        entry_points,
+        &(),
+        &associated_data,
+        "Protocol error: Instance 1: Round 4: signature share verification failed.",
+    )
+    .unwrap();
+}
+
+#[test]
+fn instance_evidence_requires_the_instance_message() {
+    let (associated_data, entry_points) = make_entry_points();
+    let misbehaving_id = entry_points
+        .iter()
+        .map(|(signer, _entry_point)| signer.verifying_key())
+        .min()
+        .unwrap();
+
+    let execution_result =
+        run_with_one_malicious_party::<SP, InvalidSignatureShare, _>(&mut OsRng, entry_points, &()).unwrap();
+
+    // The messages signed in the batch, without the one for the failing instance
+    let truncated_messages = BatchInteractiveSigningAssociatedData {
+        messages: associated_data.messages[..FAILING_INDEX].to_vec(),
+        ..associated_data.clone()
+    };
+
+    for (id, report) in execution_result.reports {
+        if id == misbehaving_id {
+            continue;
+        }
+        let evidence = &report.provable_errors[&misbehaving_id];
+        assert!(evidence.verify(&associated_data).is_ok());
+        assert!(evidence.verify(&truncated_messages).is_err());
+    }
+}
@@ -0,0 +1,191 @@
+use alloc::{collections::BTreeSet, vec::Vec};
+
+use manul::{
+    combinators::misbehave::Misbehaving,
+    dev::{BinaryFormat, TestSessionParams, TestSigner, TestVerifier},
+    protocol::{BoxedFormat, BoxedRound, EchoBroadcast, EntryPoint, LocalError, NormalBroadcast, ProtocolMessagePart},
+    signature::Keypair,
+};
+use rand_core::{CryptoRngCore, OsRng};
+
+use super::super::{
+    batch_presigning::{BatchPresigning, BatchPresigningAssociatedData},
+    interactive_signing::Round3NormalBroadcast,
+};
+use crate::{
+    curve::Scalar,
+    dev::TestParams,
+    entities::{AuxInfo, KeyShare},
+    tools::protocol_shortcuts_dev::{check_evidence_with_behavior, run_with_one_malicious_party},
+};
+
+type Id = TestVerifier;
+type P = TestParams;
+type SP = TestSessionParams<BinaryFormat>;
+
+const BATCH_SIZE: usize = 3;
+
+/// The instance the malicious party sends an invalid message for.
+const FAILING_INDEX: usize = 1;
+
+#[allow(clippy::type_complexity)]
+fn make_entry_points() -> (
+    BatchPresigningAssociatedData<P, Id>,
+    Vec<(TestSigner, BatchPresigning<P, Id>)>,
+) {
+    let signers = (0..3).map(TestSigner::new).collect::<Vec<_>>();
+    let all_ids = signers.iter().map(TestSigner::verifying_key).collect::<BTreeSet<_>>();
+
+    let key_shares = KeyShare::<TestParams, TestVerifier>::new_centralized(&mut OsRng, &all_ids, None);
+    let aux_infos = AuxInfo::new_centralized(&mut OsRng, &all_ids);
+
+    let entry_points = signers
+        .into_iter()
+        .map(|signer| {
+            let id = signer.verifying_key();
+            let entry_point =
+                BatchPresigning::new(key_shares[&id].clone(), aux_infos[&id].clone(), BATCH_SIZE).unwrap();
+            (signer, entry_point)
+        })
+        .collect();
+
+    let id = all_ids.first().unwrap();
+    let associated_data = BatchPresigningAssociatedData::new(
+        key_shares[id].public().clone(),
+        aux_infos[id].public().clone(),
+        BATCH_SIZE,
+    )
+    .unwrap();
+
+    (associated_data, entry_points)
+}
+
+struct InvalidInstance;
+
+impl Misbehaving<Id, ()> for InvalidInstance {
+    type EntryPoint = BatchPresigning<P, Id>;
+
+    fn modify_normal_broadcast(
+        rng: &mut dyn CryptoRngCore,
+        round: &BoxedRound<Id, <Self::EntryPoint as EntryPoint<Id>>::Protocol>,
+        _behavior: &(),
+        format: &BoxedFormat,
+        normal_broadcast: NormalBroadcast,
+    ) -> Result<NormalBroadcast, LocalError> {
+        if round.id() == 3 {
+            let mut parts = normal_broadcast.deserialize::<Vec<NormalBroadcast>>(format).unwrap();
+            let mut message = parts[FAILING_INDEX]
+                .deserialize::<Round3NormalBroadcast<P>>(format)
+                .unwrap();
+            message.cap_delta = Scalar::random(rng).mul_by_generator();
+            parts[FAILING_INDEX] = NormalBroadcast::new(format, message)?;
+            return NormalBroadcast::new(format, parts);
+        }
+
+        Ok(normal_broadcast)
+    }
+}
+
+#[test]
+fn instance_r3_elog_failed() {
+    let (associated_data, entry_points) = make_entry_points();
+    check_evidence_with_behavior::<SP, InvalidInstance, _>(
+        &mut OsRng,
+        entry_points,
+        &(),
+        &associated_data,
+        "Protocol error: Instance 1: Round 3: failed to verify `П^{elog}` proof.",
+    )
+    .unwrap();
+}
+
+#[test]
+fn instance_evidence_is_checked_against_batch_size() {
+    let (associated_data, entry_points) = make_entry_points();
+    let misbehaving_id = entry_points
+        .iter()
+        .map(|(signer, _entry_point)| signer.verifying_key())
+        .min()
+        .unwrap();
+
+    let execution_result =
+        run_with_one_malicious_party::<SP, InvalidInstance, _>(&mut OsRng, entry_points, &()).unwrap();
+
+    // A batch in which the failing instance does not exist
+    let smaller_batch = BatchPresigningAssociatedData {
+        batch_size: FAILING_INDEX,
+        ..associated_data.clone()
+    };
+
+    for (id, report) in execution_result.reports {
+        if id == misbehaving_id {
+            continue;
+        }
+        let evidence = &report.provable_errors[&misbehaving_id];
+        assert!(evidence.verify(&associated_data).is_ok());
+        assert!(evidence.verify(&smaller_batch).is_err());
+    }
+}
+
+struct WrongBatchSize;
+
+impl Misbehaving<Id, ()> for WrongBatchSize {
+    type EntryPoint = BatchPresigning<P, Id>;
+
+    fn modify_echo_broadcast(
+        _rng: &mut dyn CryptoRngCore,
+        round: &BoxedRound<Id, <Self::EntryPoint as EntryPoint<Id>>::Protocol>,
+        _behavior: &(),
+        format: &BoxedFormat,
+        echo_broadcast: EchoBroadcast,
+    ) -> Result<EchoBroadcast, LocalError> {
+        if round.id() == 1 {
+            let mut parts = echo_broadcast.deserialize::<Vec<EchoBroadcast>>(format).unwrap();
+            parts.pop();
+            return EchoBroadcast::new(format, parts);
+        }
+
+        Ok(echo_broadcast)
+    }
+}
+
+#[test]
+fn wrong_batch_size() {
+    let (associated_data, entry_points) = make_entry_points();
+    check_evidence_with_behavior::<SP, WrongBatchSize, _>(
+        &mut OsRng,
+        entry_points,
+        &(),
+        &associated_data,
+        "Protocol error: Wrong number of instances in the echo broadcast.",
+    )
+    .unwrap();
+}
+
+#[test]
+fn wrong_batch_size_evidence_is_checked_against_batch_size() {
+    let (associated_data, entry_points) = make_entry_points();
+    let misbehaving_id = entry_points
+        .iter()
+        .map(|(signer, _entry_point)| signer.verifying_key())
+        .min()
+        .unwrap();
+
+    let execution_result =
+        run_with_one_malicious_party::<SP, WrongBatchSize, _>(&mut OsRng, entry_points, &()).unwrap();
+
+    // The batch size matching the number of instances the malicious party sent
+    let smaller_batch = BatchPresigningAssociatedData {
+        batch_size: BATCH_SIZE - 1,
+        ..associated_data.clone()
+    };
+
+    for (id, report) in execution_result.reports {
+        if id == misbehaving_id {
+            continue;
+        }
+        let evidence = &report.provable_errors[&misbehaving_id];
+        assert!(evidence.verify(&associated_data).is_ok());
+        assert!(evidence.verify(&smaller_batch).is_err());
+    }
+}
@@ -15,7 +15,7 @@ use rand_core::{CryptoRngCore, OsRng, RngCore};
 use super::super::interactive_signing::{
     InteractiveSigning, InteractiveSigningAssociatedData, InteractiveSigningProtocol, Round1EchoBroadcast, Round2,
     Round2EchoBroadcast, Round2NormalBroadcast, Round3, Round3EchoBroadcast, Round3NormalBroadcast, Round3Payload,
-    Round4NormalBroadcast, Round5, Round6,
+    Round4NormalBroadcast, Round5, Round6, SignMessage,
 };
 use crate::{
     curve::{Point, RecoverableSignature, Scalar},
@@ -24,7 +24,9 @@ use crate::{
     params::SchemeParams,
     tools::{
         protocol_shortcuts::{DowncastMap, MapValues},
-        protocol_shortcuts_dev::{check_evidence_with_behavior, check_invalid_message_evidence, CheckPart},
+        protocol_shortcuts_dev::{
+            check_evidence_with_behavior, check_invalid_message_evidence, run_with_one_malicious_party, CheckPart,
+        },
     },
     zk::{ElogProof, ElogPublicInputs, ElogSecretInputs},
 };
@@ -63,6 +65,7 @@ fn make_entry_points() -> (
         shares: key_shares[id].public().clone(),
         aux: aux_infos[id].public().clone(),
         message,
+        raw_message: None,
     };
 
     (associated_data, entry_points)
@@ -360,7 +363,7 @@ fn r2_elog_failed() {
             normal_broadcast: NormalBroadcast,
         ) -> Result<NormalBroadcast, LocalError> {
             if round.id() == 2 {
-                let round2 = round.downcast_ref::<Round2<P, Id>>()?;
+                let round2 = round.downcast_ref::<Round2<P, Id, SignMessage<P>>>()?;
                 let aux = (&round2.context.epid, &round2.context.my_id);
 
                 // An invalid `y`
@@ -464,6 +467,83 @@ fn r4_invalid_signature_share() {
     check_evidence::<Override>("Protocol error: Round 4: signature share verification failed.").unwrap();
 }
 
+#[test]
+fn r4_invalid_signature_share_for_raw_message() {
+    struct Override;
+
+    impl Misbehaving<Id, ()> for Override {
+        type EntryPoint = InteractiveSigning<P, Id>;
+
+        fn modify_normal_broadcast(
+            rng: &mut dyn CryptoRngCore,
+            round: &BoxedRound<Id, <Self::EntryPoint as EntryPoint<Id>>::Protocol>,
+            _behavior: &(),
+            format: &BoxedFormat,
+
+            normal_broadcast: NormalBroadcast,
+        ) -> Result<NormalBroadcast, LocalError> {
+            if round.id() == 4 {
+                let mut message = normal_broadcast
+                    .deserialize::<Round4NormalBroadcast<P>>(format)
+                    .unwrap();
+                message.sigma = Scalar::random(rng);
+                return NormalBroadcast::new(format, message);
+            }
+
+            Ok(normal_broadcast)
+        }
+    }
+
+    let signers = (0..3).map(TestSigner::new).collect::<Vec<_>>();
+    let all_ids = signers.iter().map(TestSigner::verifying_key).collect::<BTreeSet<_>>();
+    let misbehaving_id = *all_ids.first().unwrap();
+
+    let key_shares = KeyShare::<TestParams, TestVerifier>::new_centralized(&mut OsRng, &all_ids, None);
+    let aux_infos = AuxInfo::new_centralized(&mut OsRng, &all_ids);
+
+    let raw_message = b"a message that is not prehashed";
+
+    let entry_points = signers
+        .into_iter()
+        .map(|signer| {
+            let id = signer.verifying_key();
+            let entry_point =
+                InteractiveSigning::from_message(raw_message, key_shares[&id].clone(), aux_infos[&id].clone()).unwrap();
+            (signer, entry_point)
+        })
+        .collect();
+
+    let execution_result = run_with_one_malicious_party::<SP, Override, _>(&mut OsRng, entry_points, &()).unwrap();
+
+    let associated_data = InteractiveSigningAssociatedData::from_message(
+        raw_message,
+        key_shares[&misbehaving_id].public().clone(),
+        aux_infos[&misbehaving_id].public().clone(),
+    )
+    .unwrap();
+
+    // The same prehashed message, but a raw message that does not hash to it
+    let other_associated_data = InteractiveSigningAssociatedData::from_message(
+        b"another message",
+        key_shares[&misbehaving_id].public().clone(),
+        aux_infos[&misbehaving_id].public().clone(),
+    )
+    .unwrap();
+    let mismatched_raw_message = InteractiveSigningAssociatedData {
+        raw_message: other_associated_data.raw_message,
+        ..associated_data.clone()
+    };
+
+    for (id, report) in execution_result.reports {
+        if id == misbehaving_id {
+            continue;
+        }
+        let evidence = &report.provable_errors[&misbehaving_id];
+        assert!(evidence.verify(&associated_data).is_ok());
+        assert!(evidence.verify(&mismatched_raw_message).is_err());
+    }
+}
+
 fn force_round5_on_malicious_node(
     rng: &mut dyn CryptoRngCore,
     round: BoxedRound<Id, InteractiveSigningProtocol<P, Id>>,
@@ -473,7 +553,7 @@ fn force_round5_on_malicious_node(
     if round.id() == 3 {
         // Manually start the error round in the malicious node
 
-        let round3 = round.downcast::<Round3<P, Id>>()?;
+        let round3 = round.downcast::<Round3<P, Id, SignMessage<P>>>()?;
         let payloads = payloads.downcast_all::<Round3Payload<P>>()?;
 
         let mut deltas = payloads.map_values(|payload| payload.delta);
@@ -679,7 +759,7 @@ fn force_round6_on_malicious_node(
     if round.id() == 3 {
         // Manually start the error round in the malicious node
 
-        let round3 = round.downcast::<Round3<P, Id>>()?;
+        let round3 = round.downcast::<Round3<P, Id, SignMessage<P>>>()?;
         let payloads = payloads.downcast_all::<Round3Payload<P>>()?;
 
         let mut cap_ks = round3.r1_payloads.map_values_ref(|payload| payload.cap_k.clone());
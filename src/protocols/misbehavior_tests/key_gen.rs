@@ -0,0 +1,155 @@
+use alloc::{collections::BTreeSet, vec::Vec};
+
+use manul::{
+    combinators::misbehave::Misbehaving,
+    dev::{BinaryFormat, TestSessionParams, TestSigner, TestVerifier},
+    protocol::{BoxedFormat, BoxedRound, EchoBroadcast, EntryPoint, LocalError, NormalBroadcast, ProtocolMessagePart},
+    signature::Keypair,
+};
+use rand_core::{CryptoRngCore, OsRng};
+
+use super::super::{
+    aux_gen::Round1EchoBroadcast,
+    key_gen::{KeyGen, KeyGenAssociatedData},
+    key_init::Round2NormalBroadcast,
+};
+use crate::{
+    dev::TestParams,
+    params::SchemeParams,
+    tools::{
+        bitvec::BitVec,
+        hashing::Hasher,
+        protocol_shortcuts_dev::{check_evidence_with_behavior, check_invalid_message_evidence, CheckPart},
+    },
+};
+
+type Id = TestVerifier;
+type P = TestParams;
+type SP = TestSessionParams<BinaryFormat>;
+
+#[allow(clippy::type_complexity)]
+fn make_entry_points() -> (KeyGenAssociatedData<Id>, Vec<(TestSigner, KeyGen<P, Id>)>) {
+    let signers = (0..3).map(TestSigner::new).collect::<Vec<_>>();
+    let all_ids = signers
+        .iter()
+        .map(|signer| signer.verifying_key())
+        .collect::<BTreeSet<_>>();
+
+    let entry_points = signers
+        .into_iter()
+        .map(|signer| (signer, KeyGen::new(all_ids.clone())))
+        .collect();
+
+    (KeyGenAssociatedData { ids: all_ids }, entry_points)
+}
+
+fn check_evidence<M>(expected_description: &str) -> Result<(), LocalError>
+where
+    M: Misbehaving<Id, (), EntryPoint = KeyGen<P, Id>>,
+{
+    let (associated_data, entry_points) = make_entry_points();
+    check_evidence_with_behavior::<SP, M, _>(&mut OsRng, entry_points, &(), &associated_data, expected_description)
+}
+
+#[test]
+fn invalid_messages() {
+    let (associated_data, entry_points) = make_entry_points();
+
+    // Every message part is expected to contain a pair of sub-protocol parts,
+    // so a malformed part fails to deserialize even where both sub-protocols send nothing.
+    for (round_id, part) in [
+        (1, CheckPart::EchoBroadcast),
+        (2, CheckPart::EchoBroadcast),
+        (3, CheckPart::EchoBroadcast),
+        (1, CheckPart::NormalBroadcast),
+        (2, CheckPart::NormalBroadcast),
+        (3, CheckPart::NormalBroadcast),
+        (1, CheckPart::DirectMessage),
+        (2, CheckPart::DirectMessage),
+        (3, CheckPart::DirectMessage),
+    ] {
+        check_invalid_message_evidence::<SP, _>(
+            &mut OsRng,
+            entry_points.clone(),
+            round_id,
+            part,
+            &associated_data,
+            true,
+        )
+        .unwrap();
+    }
+}
+
+#[test]
+fn key_init_r2_hash_mismatch() {
+    struct Override;
+
+    impl Misbehaving<Id, ()> for Override {
+        type EntryPoint = KeyGen<P, Id>;
+
+        fn modify_normal_broadcast(
+            rng: &mut dyn CryptoRngCore,
+            round: &BoxedRound<Id, <Self::EntryPoint as EntryPoint<Id>>::Protocol>,
+            _behavior: &(),
+            format: &BoxedFormat,
+            normal_broadcast: NormalBroadcast,
+        ) -> Result<NormalBroadcast, LocalError> {
+            if round.id() == 2 {
+                let (key_init_part, aux_gen_part) = normal_broadcast
+                    .deserialize::<(NormalBroadcast, NormalBroadcast)>(format)
+                    .unwrap();
+                let mut message = key_init_part.deserialize::<Round2NormalBroadcast<P>>(format).unwrap();
+
+                // Replace `u` with something other than we committed to when hashing it in Round 1.
+                message.u = BitVec::random(rng, message.u.bits().len());
+
+                let key_init_part = NormalBroadcast::new(format, message)?;
+                return NormalBroadcast::new(format, (key_init_part, aux_gen_part));
+            }
+
+            Ok(normal_broadcast)
+        }
+    }
+
+    check_evidence::<Override>(
+        "Protocol error: KeyInit: Round 2: the previously sent hash does not match the public data.",
+    )
+    .unwrap();
+}
+
+#[test]
+fn aux_gen_r2_hash_mismatch() {
+    struct Override;
+
+    impl Misbehaving<Id, ()> for Override {
+        type EntryPoint = KeyGen<P, Id>;
+
+        fn modify_echo_broadcast(
+            _rng: &mut dyn CryptoRngCore,
+            round: &BoxedRound<Id, <Self::EntryPoint as EntryPoint<Id>>::Protocol>,
+            _behavior: &(),
+            format: &BoxedFormat,
+            echo_broadcast: EchoBroadcast,
+        ) -> Result<EchoBroadcast, LocalError> {
+            if round.id() == 1 {
+                let (key_init_part, _aux_gen_part) = echo_broadcast
+                    .deserialize::<(EchoBroadcast, EchoBroadcast)>(format)
+                    .unwrap();
+
+                // Send a wrong hash in the AuxGen Round 1 message
+                let message = Round1EchoBroadcast {
+                    cap_v: Hasher::<<P as SchemeParams>::Digest>::new_with_dst(b"bad hash").finalize(P::SECURITY_BITS),
+                };
+                let aux_gen_part = EchoBroadcast::new(format, message)?;
+                return EchoBroadcast::new(format, (key_init_part, aux_gen_part));
+            }
+
+            Ok(echo_broadcast)
+        }
+    }
+
+    check_evidence::<Override>(
+        "Protocol error: AuxGen: Round 2: the previously sent hash does not match the public data.",
+    )
+    .unwrap();
+}
@@ -0,0 +1,228 @@
+use alloc::{
+    collections::{BTreeMap, BTreeSet},
+    vec::Vec,
+};
+
+use ecdsa::SigningKey;
+use manul::{
+    combinators::misbehave::Misbehaving,
+    dev::{BinaryFormat, TestSessionParams, TestSigner, TestVerifier},
+    protocol::{
+        Artifact, BoxedFormat, BoxedRound, DirectMessage, EchoBroadcast, EntryPoint, LocalError, ProtocolMessagePart,
+    },
+    signature::Keypair,
+};
+use rand_core::{CryptoRngCore, OsRng};
+
+use super::super::key_import::{KeyImport, KeyImportAssociatedData, Round1DirectMessage, Round1EchoBroadcast};
+use crate::{
+    curve::Scalar,
+    dev::TestParams,
+    params::SchemeParams,
+    tools::{
+        protocol_shortcuts_dev::{check_evidence_with_behavior, check_invalid_message_evidence, CheckPart},
+        sss::Polynomial,
+        Secret,
+    },
+};
+
+type Id = TestVerifier;
+type P = TestParams;
+type SP = TestSessionParams<BinaryFormat>;
+type Curve = <P as SchemeParams>::Curve;
+
+const THRESHOLD: usize = 2;
+
+#[allow(clippy::type_complexity)]
+fn make_entry_points() -> (KeyImportAssociatedData<P, Id>, Vec<(TestSigner, KeyImport<P, Id>)>) {
+    let signers = (0..4).map(TestSigner::new).collect::<Vec<_>>();
+    let all_ids = signers
+        .iter()
+        .map(|signer| signer.verifying_key())
+        .collect::<BTreeSet<_>>();
+
+    // The malicious node is the one with the smallest ID, so it has to be the dealer.
+    let dealer = *all_ids.first().unwrap();
+    let holders = all_ids
+        .iter()
+        .filter(|id| *id != &dealer)
+        .cloned()
+        .collect::<BTreeSet<_>>();
+
+    let signing_key = SigningKey::<Curve>::random(&mut OsRng);
+    let verifying_key = *signing_key.verifying_key();
+
+    let holder_secret_keys = holders
+        .iter()
+        .map(|id| (*id, SigningKey::<Curve>::random(&mut OsRng)))
+        .collect::<BTreeMap<_, _>>();
+    let holder_keys = holder_secret_keys
+        .iter()
+        .map(|(id, secret_key)| (*id, *secret_key.verifying_key()))
+        .collect::<BTreeMap<_, _>>();
+
+    let entry_points = signers
+        .into_iter()
+        .map(|signer| {
+            let id = signer.verifying_key();
+            let entry_point = if id == dealer {
+                KeyImport::new_dealer(signing_key.clone(), holder_keys.clone(), THRESHOLD)
+            } else {
+                KeyImport::new_holder(
+                    dealer,
+                    &holder_secret_keys[&id],
+                    verifying_key,
+                    holders.clone(),
+                    THRESHOLD,
+                )
+            }
+            .unwrap();
+            (signer, entry_point)
+        })
+        .collect();
+
+    let associated_data = KeyImportAssociatedData {
+        verifying_key,
+        holder_keys,
+        threshold: THRESHOLD,
+    };
+
+    (associated_data, entry_points)
+}
+
+fn check_evidence<M>(expected_description: &str) -> Result<(), LocalError>
+where
+    M: Misbehaving<Id, (), EntryPoint = KeyImport<P, Id>>,
+{
+    let (associated_data, entry_points) = make_entry_points();
+    check_evidence_with_behavior::<SP, M, _>(&mut OsRng, entry_points, &(), &associated_data, expected_description)
+}
+
+#[test]
+fn invalid_messages() {
+    let (associated_data, entry_points) = make_entry_points();
+
+    check_invalid_message_evidence::<SP, _>(
+        &mut OsRng,
+        entry_points.clone(),
+        1,
+        CheckPart::EchoBroadcast,
+        &associated_data,
+        true,
+    )
+    .unwrap();
+    check_invalid_message_evidence::<SP, _>(
+        &mut OsRng,
+        entry_points.clone(),
+        1,
+        CheckPart::NormalBroadcast,
+        &associated_data,
+        false,
+    )
+    .unwrap();
+    check_invalid_message_evidence::<SP, _>(
+        &mut OsRng,
+        entry_points.clone(),
+        1,
+        CheckPart::DirectMessage,
+        &associated_data,
+        true,
+    )
+    .unwrap();
+}
+
+#[test]
+fn r1_wrong_polynomial_degree() {
+    struct Override;
+
+    impl Misbehaving<Id, ()> for Override {
+        type EntryPoint = KeyImport<P, Id>;
+
+        fn modify_echo_broadcast(
+            rng: &mut dyn CryptoRngCore,
+            round: &BoxedRound<Id, <Self::EntryPoint as EntryPoint<Id>>::Protocol>,
+            _behavior: &(),
+            format: &BoxedFormat,
+            echo_broadcast: EchoBroadcast,
+        ) -> Result<EchoBroadcast, LocalError> {
+            if round.id() == 1 {
+                let secret = Secret::init_with(|| Scalar::random(rng));
+                let message = Round1EchoBroadcast {
+                    public_polynomial: Polynomial::<P>::random(rng, secret, THRESHOLD + 1).public(),
+                };
+                return EchoBroadcast::new(format, message);
+            }
+
+            Ok(echo_broadcast)
+        }
+    }
+
+    check_evidence::<Override>("Protocol error: Round 1: the public polynomial has a wrong number of coefficients.")
+        .unwrap();
+}
+
+#[test]
+fn r1_verifying_key_mismatch() {
+    struct Override;
+
+    impl Misbehaving<Id, ()> for Override {
+        type EntryPoint = KeyImport<P, Id>;
+
+        fn modify_echo_broadcast(
+            rng: &mut dyn CryptoRngCore,
+            round: &BoxedRound<Id, <Self::EntryPoint as EntryPoint<Id>>::Protocol>,
+            _behavior: &(),
+            format: &BoxedFormat,
+            echo_broadcast: EchoBroadcast,
+        ) -> Result<EchoBroadcast, LocalError> {
+            if round.id() == 1 {
+                // Share a different secret than the one being imported.
+                let secret = Secret::init_with(|| Scalar::random(rng));
+                let message = Round1EchoBroadcast {
+                    public_polynomial: Polynomial::<P>::random(rng, secret, THRESHOLD).public(),
+                };
+                return EchoBroadcast::new(format, message);
+            }
+
+            Ok(echo_broadcast)
+        }
+    }
+
+    check_evidence::<Override>(
+        "Protocol error: Round 1: the public polynomial does not commit to the imported verifying key.",
+    )
+    .unwrap();
+}
+
+#[test]
+fn r1_invalid_subshare() {
+    struct Override;
+
+    impl Misbehaving<Id, ()> for Override {
+        type EntryPoint = KeyImport<P, Id>;
+
+        fn modify_direct_message(
+            rng: &mut dyn CryptoRngCore,
+            round: &BoxedRound<Id, <Self::EntryPoint as EntryPoint<Id>>::Protocol>,
+            _behavior: &(),
+            format: &BoxedFormat,
+            _destination: &Id,
+            direct_message: DirectMessage,
+            artifact: Option<Artifact>,
+        ) -> Result<(DirectMessage, Option<Artifact>), LocalError> {
+            if round.id() == 1 {
+                // Send a masked subshare that does not lie on the broadcasted polynomial.
+                let mut message = direct_message
+                    .deserialize::<Round1DirectMessage<P, Id>>(format)
+                    .unwrap();
+                message.cap_c = Scalar::random(rng);
+                let direct_message = DirectMessage::new(format, message)?;
+                return Ok((direct_message, artifact));
+            }
+
+            Ok((direct_message, artifact))
+        }
+    }
+
+    check_evidence::<Override>("Protocol error: Round 1: the subshare does not match the public polynomial.").unwrap();
+}
@@ -8,7 +8,9 @@ use manul::{
 };
 use rand_core::{CryptoRngCore, OsRng};
 
-use super::super::key_init::{KeyInit, KeyInitAssociatedData, Round2NormalBroadcast, Round3, Round3NormalBroadcast};
+use super::super::key_init::{
+    KeyInit, KeyInitAssociatedData, ReturnKeyShare, Round2NormalBroadcast, Round3, Round3NormalBroadcast,
+};
 use crate::{
     curve::Scalar,
     dev::TestParams,
@@ -191,7 +193,7 @@ fn r3_invalid_sch_proof() {
             normal_broadcast: NormalBroadcast,
         ) -> Result<NormalBroadcast, LocalError> {
             if round.id() == 3 {
-                let round3 = round.downcast_ref::<Round3<P, Id>>()?;
+                let round3 = round.downcast_ref::<Round3<P, Id, ReturnKeyShare>>()?;
                 let context = &round3.context;
                 let aux = (&context.sid, &context.my_id, &round3.rho_combined);
 
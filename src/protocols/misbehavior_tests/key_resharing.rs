@@ -0,0 +1,370 @@
+use alloc::{
+    collections::{BTreeMap, BTreeSet},
+    vec,
+};
+
+use manul::{
+    combinators::misbehave::Misbehaving,
+    dev::{BinaryFormat, TestSessionParams, TestSigner, TestVerifier},
+    protocol::{
+        Artifact, BoxedFormat, BoxedRound, DirectMessage, EchoBroadcast, EntryPoint, LocalError, ProtocolMessagePart,
+    },
+    signature::Keypair,
+};
+use rand_core::{CryptoRngCore, OsRng};
+
+use super::super::key_resharing::{
+    KeyResharing, KeyResharingAssociatedData, NewHolder, OldHolder, Round1EchoBroadcast, Round2DirectMessage,
+    Round2EchoBroadcast,
+};
+use crate::{
+    curve::Scalar,
+    dev::TestParams,
+    entities::ThresholdKeyShare,
+    tools::{
+        protocol_shortcuts_dev::{check_evidence_with_behavior, check_invalid_message_evidence, CheckPart},
+        sss::Polynomial,
+        Secret,
+    },
+};
+
+type Id = TestVerifier;
+type P = TestParams;
+type SP = TestSessionParams<BinaryFormat>;
+
+const THRESHOLD: usize = 2;
+
+/// The new threshold when the first node gets two new shares (four in total).
+const WEIGHTED_THRESHOLD: usize = 3;
+
+#[allow(clippy::type_complexity)]
+fn make_entry_points(
+    new_weights: &BTreeMap<Id, usize>,
+    new_threshold: usize,
+) -> (
+    KeyResharingAssociatedData<P, Id>,
+    Vec<(TestSigner, KeyResharing<P, Id>)>,
+) {
+    let signers = (0..3).map(TestSigner::new).collect::<Vec<_>>();
+    let all_ids = signers
+        .iter()
+        .map(|signer| signer.verifying_key())
+        .collect::<BTreeSet<_>>();
+
+    // All the nodes are both old and new holders, so that every lawful node receives messages
+    // from the malicious one.
+    let old_key_shares = ThresholdKeyShare::<P, Id>::new_centralized(&mut OsRng, &all_ids, THRESHOLD, None).unwrap();
+    let key_share = old_key_shares.values().next().unwrap();
+    let old_public_key_shares = key_share.public_key_shares();
+    let associated_data =
+        KeyResharingAssociatedData::new(&old_public_key_shares, all_ids.clone(), all_ids.clone(), new_threshold)
+            .with_new_weights(new_weights.clone());
+    let verifying_key = key_share.verifying_key().unwrap();
+
+    let entry_points = signers
+        .into_iter()
+        .map(|signer| {
+            let id = signer.verifying_key();
+            let entry_point = KeyResharing::new(
+                Some(OldHolder {
+                    key_share: old_key_shares[&id].clone(),
+                    old_holders: all_ids.clone(),
+                }),
+                Some(NewHolder {
+                    verifying_key,
+                    old_threshold: THRESHOLD,
+                    old_holders: all_ids.clone(),
+                    old_public_key_shares: old_public_key_shares.clone(),
+                }),
+                all_ids.clone(),
+                new_threshold,
+            )
+            .with_new_weights(new_weights.clone());
+            (signer, entry_point)
+        })
+        .collect();
+
+    (associated_data, entry_points)
+}
+
+fn check_evidence<M>(expected_description: &str) -> Result<(), LocalError>
+where
+    M: Misbehaving<Id, (), EntryPoint = KeyResharing<P, Id>>,
+{
+    let (associated_data, entry_points) = make_entry_points(&BTreeMap::new(), THRESHOLD);
+    check_evidence_with_behavior::<SP, M, _>(&mut OsRng, entry_points, &(), &associated_data, expected_description)
+}
+
+fn check_evidence_weighted<M>(expected_description: &str) -> Result<(), LocalError>
+where
+    M: Misbehaving<Id, (), EntryPoint = KeyResharing<P, Id>>,
+{
+    let new_weights = BTreeMap::from([(TestSigner::new(0).verifying_key(), 2)]);
+    let (associated_data, entry_points) = make_entry_points(&new_weights, WEIGHTED_THRESHOLD);
+    check_evidence_with_behavior::<SP, M, _>(&mut OsRng, entry_points, &(), &associated_data, expected_description)
+}
+
+#[test]
+fn invalid_messages() {
+    let (associated_data, entry_points) = make_entry_points(&BTreeMap::new(), THRESHOLD);
+
+    check_invalid_message_evidence::<SP, _>(
+        &mut OsRng,
+        entry_points.clone(),
+        1,
+        CheckPart::EchoBroadcast,
+        &associated_data,
+        true,
+    )
+    .unwrap();
+    check_invalid_message_evidence::<SP, _>(
+        &mut OsRng,
+        entry_points.clone(),
+        1,
+        CheckPart::NormalBroadcast,
+        &associated_data,
+        false,
+    )
+    .unwrap();
+    check_invalid_message_evidence::<SP, _>(
+        &mut OsRng,
+        entry_points.clone(),
+        1,
+        CheckPart::DirectMessage,
+        &associated_data,
+        false,
+    )
+    .unwrap();
+
+    check_invalid_message_evidence::<SP, _>(
+        &mut OsRng,
+        entry_points.clone(),
+        2,
+        CheckPart::EchoBroadcast,
+        &associated_data,
+        true,
+    )
+    .unwrap();
+    check_invalid_message_evidence::<SP, _>(
+        &mut OsRng,
+        entry_points.clone(),
+        2,
+        CheckPart::NormalBroadcast,
+        &associated_data,
+        false,
+    )
+    .unwrap();
+    check_invalid_message_evidence::<SP, _>(
+        &mut OsRng,
+        entry_points.clone(),
+        2,
+        CheckPart::DirectMessage,
+        &associated_data,
+        true,
+    )
+    .unwrap();
+}
+
+#[test]
+fn r1_wrong_ids_y() {
+    struct Override;
+
+    impl Misbehaving<Id, ()> for Override {
+        type EntryPoint = KeyResharing<P, Id>;
+
+        fn modify_echo_broadcast(
+            _rng: &mut dyn CryptoRngCore,
+            round: &BoxedRound<Id, <Self::EntryPoint as EntryPoint<Id>>::Protocol>,
+            _behavior: &(),
+            format: &BoxedFormat,
+            echo_broadcast: EchoBroadcast,
+        ) -> Result<EchoBroadcast, LocalError> {
+            if round.id() == 1 {
+                let mut message = echo_broadcast
+                    .deserialize::<Round1EchoBroadcast<P, Id>>(format)
+                    .unwrap();
+
+                // Drop one of the ephemeral keys.
+                message.cap_ys.pop_first();
+
+                return EchoBroadcast::new(format, message);
+            }
+
+            Ok(echo_broadcast)
+        }
+    }
+
+    check_evidence::<Override>("Protocol error: Round 1: wrong IDs in the ephemeral keys map.").unwrap();
+}
+
+#[test]
+fn r2_wrong_ids_y() {
+    struct Override;
+
+    impl Misbehaving<Id, ()> for Override {
+        type EntryPoint = KeyResharing<P, Id>;
+
+        fn modify_echo_broadcast(
+            _rng: &mut dyn CryptoRngCore,
+            round: &BoxedRound<Id, <Self::EntryPoint as EntryPoint<Id>>::Protocol>,
+            _behavior: &(),
+            format: &BoxedFormat,
+            echo_broadcast: EchoBroadcast,
+        ) -> Result<EchoBroadcast, LocalError> {
+            if round.id() == 2 {
+                let mut message = echo_broadcast
+                    .deserialize::<Round2EchoBroadcast<P, Id>>(format)
+                    .unwrap();
+
+                // Drop one of the ephemeral keys.
+                message.cap_ys.pop_first();
+
+                return EchoBroadcast::new(format, message);
+            }
+
+            Ok(echo_broadcast)
+        }
+    }
+
+    check_evidence::<Override>("Protocol error: Round 2: wrong IDs in the ephemeral keys map.").unwrap();
+}
+
+#[test]
+fn r2_wrong_polynomial_degree() {
+    struct Override;
+
+    impl Misbehaving<Id, ()> for Override {
+        type EntryPoint = KeyResharing<P, Id>;
+
+        fn modify_echo_broadcast(
+            rng: &mut dyn CryptoRngCore,
+            round: &BoxedRound<Id, <Self::EntryPoint as EntryPoint<Id>>::Protocol>,
+            _behavior: &(),
+            format: &BoxedFormat,
+            echo_broadcast: EchoBroadcast,
+        ) -> Result<EchoBroadcast, LocalError> {
+            if round.id() == 2 {
+                let mut message = echo_broadcast
+                    .deserialize::<Round2EchoBroadcast<P, Id>>(format)
+                    .unwrap();
+
+                // Send a polynomial with more coefficients than the new threshold.
+                let secret = Secret::init_with(|| Scalar::random(rng));
+                message.public_polynomials = vec![Polynomial::random(rng, secret, THRESHOLD + 1).public()];
+
+                return EchoBroadcast::new(format, message);
+            }
+
+            Ok(echo_broadcast)
+        }
+    }
+
+    check_evidence::<Override>("Protocol error: Round 2: a public polynomial has a wrong number of coefficients.")
+        .unwrap();
+}
+
+#[test]
+fn r2_wrong_polynomial_degree_weighted() {
+    struct Override;
+
+    impl Misbehaving<Id, ()> for Override {
+        type EntryPoint = KeyResharing<P, Id>;
+
+        fn modify_echo_broadcast(
+            rng: &mut dyn CryptoRngCore,
+            round: &BoxedRound<Id, <Self::EntryPoint as EntryPoint<Id>>::Protocol>,
+            _behavior: &(),
+            format: &BoxedFormat,
+            echo_broadcast: EchoBroadcast,
+        ) -> Result<EchoBroadcast, LocalError> {
+            if round.id() == 2 {
+                let mut message = echo_broadcast
+                    .deserialize::<Round2EchoBroadcast<P, Id>>(format)
+                    .unwrap();
+
+                // With four new shares in total, subshares from a polynomial with four coefficients
+                // would be consistent with it, so the degree has to be checked on its own.
+                let secret = Secret::init_with(|| Scalar::random(rng));
+                message.public_polynomials = vec![Polynomial::random(rng, secret, WEIGHTED_THRESHOLD + 1).public()];
+
+                return EchoBroadcast::new(format, message);
+            }
+
+            Ok(echo_broadcast)
+        }
+    }
+
+    check_evidence_weighted::<Override>(
+        "Protocol error: Round 2: a public polynomial has a wrong number of coefficients.",
+    )
+    .unwrap();
+}
+
+#[test]
+fn r2_old_share_mismatch() {
+    struct Override;
+
+    impl Misbehaving<Id, ()> for Override {
+        type EntryPoint = KeyResharing<P, Id>;
+
+        fn modify_echo_broadcast(
+            rng: &mut dyn CryptoRngCore,
+            round: &BoxedRound<Id, <Self::EntryPoint as EntryPoint<Id>>::Protocol>,
+            _behavior: &(),
+            format: &BoxedFormat,
+            echo_broadcast: EchoBroadcast,
+        ) -> Result<EchoBroadcast, LocalError> {
+            if round.id() == 2 {
+                let mut message = echo_broadcast
+                    .deserialize::<Round2EchoBroadcast<P, Id>>(format)
+                    .unwrap();
+
+                // Reshare a random secret instead of the old share.
+                let secret = Secret::init_with(|| Scalar::random(rng));
+                message.public_polynomials = vec![Polynomial::random(rng, secret, THRESHOLD).public()];
+
+                return EchoBroadcast::new(format, message);
+            }
+
+            Ok(echo_broadcast)
+        }
+    }
+
+    check_evidence::<Override>(
+        "Protocol error: Round 2: the constant coefficients of the public polynomials do not match the sender's old public shares.",
+    )
+    .unwrap();
+}
+
+#[test]
+fn r2_invalid_subshare() {
+    struct Override;
+
+    impl Misbehaving<Id, ()> for Override {
+        type EntryPoint = KeyResharing<P, Id>;
+
+        fn modify_direct_message(
+            rng: &mut dyn CryptoRngCore,
+            round: &BoxedRound<Id, <Self::EntryPoint as EntryPoint<Id>>::Protocol>,
+            _behavior: &(),
+            format: &BoxedFormat,
+            destination: &Id,
+            direct_message: DirectMessage,
+            artifact: Option<Artifact>,
+        ) -> Result<(DirectMessage, Option<Artifact>), LocalError> {
+            if round.id() == 2 {
+                // Send a masked subshare that does not lie on the broadcasted polynomial.
+                let message = Round2DirectMessage::<P, Id> {
+                    destination: *destination,
+                    cap_cs: vec![Scalar::random(rng)],
+                };
+                let direct_message = DirectMessage::new(format, message)?;
+                return Ok((direct_message, artifact));
+            }
+
+            Ok((direct_message, artifact))
+        }
+    }
+
+    check_evidence::<Override>("Protocol error: Round 2: the subshares do not match the public polynomials.").unwrap();
+}
@@ -0,0 +1,105 @@
+use alloc::{collections::BTreeSet, vec::Vec};
+
+use manul::{
+    combinators::misbehave::Misbehaving,
+    dev::{BinaryFormat, TestSessionParams, TestSigner, TestVerifier},
+    protocol::{BoxedFormat, BoxedRound, EntryPoint, LocalError, NormalBroadcast, ProtocolMessagePart},
+    signature::Keypair,
+};
+use rand_core::{CryptoRngCore, OsRng, RngCore};
+
+use super::super::schnorr_signing::{Round2NormalBroadcast, SchnorrSigning, SchnorrSigningAssociatedData};
+use crate::{
+    curve::{Scalar, TaprootTweak},
+    entities::ThresholdKeyShare,
+    k256::ProductionParams112,
+    tools::protocol_shortcuts_dev::check_evidence_with_behavior,
+};
+
+type Id = TestVerifier;
+type P = ProductionParams112;
+type SP = TestSessionParams<BinaryFormat>;
+
+#[allow(clippy::type_complexity)]
+fn make_entry_points(
+    tweak: TaprootTweak,
+) -> (
+    SchnorrSigningAssociatedData<P, Id>,
+    Vec<(TestSigner, SchnorrSigning<P, Id>)>,
+) {
+    let signers = (0..4).map(TestSigner::new).collect::<Vec<_>>();
+    let all_ids = signers.iter().map(TestSigner::verifying_key).collect::<BTreeSet<_>>();
+
+    let key_shares = ThresholdKeyShare::<P, Id>::new_centralized(&mut OsRng, &all_ids, 3, None).unwrap();
+
+    // Sign with a subset of the parties, so that the public shares have to be interpolated.
+    let selected_signers = signers.into_iter().skip(1).collect::<Vec<_>>();
+    let selected_ids = selected_signers
+        .iter()
+        .map(TestSigner::verifying_key)
+        .collect::<BTreeSet<_>>();
+
+    let mut message = [0u8; 32];
+    OsRng.fill_bytes(&mut message);
+
+    let entry_points = selected_signers
+        .into_iter()
+        .map(|signer| {
+            let id = signer.verifying_key();
+            let entry_point = SchnorrSigning::new_threshold(message, &key_shares[&id], &selected_ids, tweak).unwrap();
+            (signer, entry_point)
+        })
+        .collect();
+
+    let key_share = key_shares.values().next().unwrap();
+    let associated_data =
+        SchnorrSigningAssociatedData::new_threshold(message, key_share, &selected_ids, tweak).unwrap();
+
+    (associated_data, entry_points)
+}
+
+struct InvalidSignatureShare;
+
+impl Misbehaving<Id, ()> for InvalidSignatureShare {
+    type EntryPoint = SchnorrSigning<P, Id>;
+
+    fn modify_normal_broadcast(
+        rng: &mut dyn CryptoRngCore,
+        round: &BoxedRound<Id, <Self::EntryPoint as EntryPoint<Id>>::Protocol>,
+        _behavior: &(),
+        format: &BoxedFormat,
+        normal_broadcast: NormalBroadcast,
+    ) -> Result<NormalBroadcast, LocalError> {
+        if round.id() == 2 {
+            let mut message = normal_broadcast
+                .deserialize::<Round2NormalBroadcast<P>>(format)
+                .unwrap();
+            message.z = Scalar::random(rng);
+            return NormalBroadcast::new(format, message);
+        }
+
+        Ok(normal_broadcast)
+    }
+}
+
+#[test]
+fn r2_invalid_signature_share() {
+    let mut merkle_root = [0u8; 32];
+    OsRng.fill_bytes(&mut merkle_root);
+
+    for tweak in [
+        TaprootTweak::None,
+        TaprootTweak::KeyPathOnly,
+        TaprootTweak::ScriptTree(merkle_root),
+    ] {
+        let (associated_data, entry_points) = make_entry_points(tweak);
+        check_evidence_with_behavior::<SP, InvalidSignatureShare, _>(
+            &mut OsRng,
+            entry_points,
+            &(),
+            &associated_data,
+            "Protocol error: Round 2: signature share verification failed.",
+        )
+        .unwrap();
+    }
+}
@@ -0,0 +1,348 @@
+use alloc::{
+    collections::{BTreeMap, BTreeSet},
+    format, vec,
+    vec::Vec,
+};
+
+use manul::{
+    combinators::misbehave::Misbehaving,
+    dev::{BinaryFormat, TestSessionParams, TestSigner, TestVerifier},
+    protocol::{
+        Artifact, BoxedFormat, BoxedRound, DirectMessage, EchoBroadcast, EntryPoint, LocalError, ProtocolMessagePart,
+    },
+    signature::Keypair,
+};
+use rand_core::{CryptoRngCore, OsRng};
+
+use super::super::share_recovery::{
+    Round1EchoBroadcast, Round2DirectMessage, Round2EchoBroadcast, Round3DirectMessage, ShareRecovery,
+    ShareRecoveryAssociatedData,
+};
+use crate::{
+    curve::Scalar,
+    dev::TestParams,
+    entities::ThresholdKeyShare,
+    tools::protocol_shortcuts_dev::{
+        check_evidence_with_behavior, check_invalid_message_evidence, run_with_one_malicious_party, CheckPart,
+    },
+};
+
+type Id = TestVerifier;
+type P = TestParams;
+type SP = TestSessionParams<BinaryFormat>;
+
+const THRESHOLD: usize = 3;
+
+#[allow(clippy::type_complexity)]
+fn make_entry_points() -> (
+    ShareRecoveryAssociatedData<P, Id>,
+    Vec<(TestSigner, ShareRecovery<P, Id>)>,
+) {
+    let signers = (0..4).map(TestSigner::new).collect::<Vec<_>>();
+    let all_ids = signers
+        .iter()
+        .map(|signer| signer.verifying_key())
+        .collect::<BTreeSet<_>>();
+
+    // The malicious node is the first one in the order of IDs, so it is always one of the helpers.
+    let lost_id = *all_ids.last().unwrap();
+    let helpers = all_ids.iter().copied().take(THRESHOLD).collect::<BTreeSet<_>>();
+
+    let key_shares = ThresholdKeyShare::<P, Id>::new_centralized(&mut OsRng, &all_ids, THRESHOLD, None).unwrap();
+    let key_share = key_shares.values().next().unwrap();
+    let verifying_key = key_share.verifying_key().unwrap();
+    let associated_data = ShareRecoveryAssociatedData::new(key_share, helpers.clone(), lost_id).unwrap();
+
+    let entry_points = signers
+        .into_iter()
+        .map(|signer| {
+            let id = signer.verifying_key();
+            let entry_point = if id == lost_id {
+                ShareRecovery::new_recovering(verifying_key, helpers.clone())
+            } else {
+                ShareRecovery::new_helper(key_shares[&id].clone(), helpers.clone(), lost_id).unwrap()
+            };
+            (signer, entry_point)
+        })
+        .collect();
+
+    (associated_data, entry_points)
+}
+
+fn check_evidence<M>(expected_description: &str) -> Result<(), LocalError>
+where
+    M: Misbehaving<Id, (), EntryPoint = ShareRecovery<P, Id>>,
+{
+    let (associated_data, entry_points) = make_entry_points();
+    check_evidence_with_behavior::<SP, M, _>(&mut OsRng, entry_points, &(), &associated_data, expected_description)
+}
+
+/// The lawful nodes that receive the malicious message.
+#[derive(Debug, Clone, Copy)]
+enum Receivers {
+    Helpers,
+    RecoveringNode,
+}
+
+/// Same as [`check_evidence`], but for the messages that are only sent to some of the lawful nodes;
+/// the other lawful nodes are expected to report no provable errors.
+fn check_evidence_from<M>(receivers: Receivers, expected_description: &str) -> Result<(), LocalError>
+where
+    M: Misbehaving<Id, (), EntryPoint = ShareRecovery<P, Id>>,
+{
+    let (associated_data, entry_points) = make_entry_points();
+    let misbehaving_id = *associated_data.helpers.first().unwrap();
+    let execution_result = run_with_one_malicious_party::<SP, M, _>(&mut OsRng, entry_points, &())?;
+
+    for (id, report) in execution_result.reports {
+        let is_receiver = match receivers {
+            Receivers::Helpers => associated_data.helpers.contains(&id),
+            Receivers::RecoveringNode => id == associated_data.lost_id,
+        };
+        if id == misbehaving_id || !is_receiver {
+            if !report.provable_errors.is_empty() {
+                return Err(LocalError::new(format!(
+                    "Node {id:?} was not expected to report provable errors"
+                )));
+            }
+            continue;
+        }
+
+        if report.provable_errors.len() != 1 {
+            return Err(LocalError::new(format!(
+                "Node {id:?} reported more than one provable errors"
+            )));
+        }
+
+        let evidence = report
+            .provable_errors
+            .get(&misbehaving_id)
+            .ok_or_else(|| LocalError::new("A lawful node did not generate a provable error report"))?;
+        let description = evidence.description();
+        if !description.starts_with(expected_description) {
+            return Err(LocalError::new(format!(
+                "Got {description}, expected {expected_description}"
+            )));
+        }
+
+        let verification_result = evidence.verify(&associated_data);
+        if verification_result.is_err() {
+            return Err(LocalError::new(format!("Failed to verify: {verification_result:?}")));
+        }
+    }
+
+    Ok(())
+}
+
+#[test]
+fn invalid_messages() {
+    let (associated_data, entry_points) = make_entry_points();
+
+    // In Round 2 the recovering node does not expect a direct message while the helpers do,
+    // and in Round 3 only the recovering node receives messages, so these are not covered here.
+    for (round_id, part, expecting_a_message) in [
+        (1, CheckPart::EchoBroadcast, true),
+        (2, CheckPart::EchoBroadcast, true),
+        (1, CheckPart::NormalBroadcast, false),
+        (2, CheckPart::NormalBroadcast, false),
+        (1, CheckPart::DirectMessage, false),
+    ] {
+        check_invalid_message_evidence::<SP, _>(
+            &mut OsRng,
+            entry_points.clone(),
+            round_id,
+            part,
+            &associated_data,
+            expecting_a_message,
+        )
+        .unwrap();
+    }
+}
+
+#[test]
+fn r1_wrong_ids_y() {
+    struct Override;
+
+    impl Misbehaving<Id, ()> for Override {
+        type EntryPoint = ShareRecovery<P, Id>;
+
+        fn modify_echo_broadcast(
+            _rng: &mut dyn CryptoRngCore,
+            round: &BoxedRound<Id, <Self::EntryPoint as EntryPoint<Id>>::Protocol>,
+            _behavior: &(),
+            format: &BoxedFormat,
+            echo_broadcast: EchoBroadcast,
+        ) -> Result<EchoBroadcast, LocalError> {
+            if round.id() == 1 {
+                let mut message = echo_broadcast
+                    .deserialize::<Round1EchoBroadcast<P, Id>>(format)
+                    .unwrap();
+
+                // Drop one of the ephemeral keys.
+                message.cap_ys.pop_first();
+
+                return EchoBroadcast::new(format, message);
+            }
+
+            Ok(echo_broadcast)
+        }
+    }
+
+    check_evidence::<Override>("Protocol error: Round 1: wrong IDs in the ephemeral keys map.").unwrap();
+}
+
+#[test]
+fn r2_public_data_mismatch() {
+    struct Override;
+
+    impl Misbehaving<Id, ()> for Override {
+        type EntryPoint = ShareRecovery<P, Id>;
+
+        fn modify_echo_broadcast(
+            _rng: &mut dyn CryptoRngCore,
+            round: &BoxedRound<Id, <Self::EntryPoint as EntryPoint<Id>>::Protocol>,
+            _behavior: &(),
+            format: &BoxedFormat,
+            echo_broadcast: EchoBroadcast,
+        ) -> Result<EchoBroadcast, LocalError> {
+            if round.id() == 2 {
+                let mut message = echo_broadcast
+                    .deserialize::<Round2EchoBroadcast<P, Id>>(format)
+                    .unwrap();
+                message.public_data.threshold += 1;
+                return EchoBroadcast::new(format, message);
+            }
+
+            Ok(echo_broadcast)
+        }
+    }
+
+    // The recovering node only compares the public data after receiving all the messages,
+    // and the lawful helpers abort before that.
+    check_evidence_from::<Override>(
+        Receivers::Helpers,
+        "Protocol error: Round 2: the public data of the key share does not match the one in the associated data.",
+    )
+    .unwrap();
+}
+
+#[test]
+fn r2_invalid_commitments() {
+    struct Override;
+
+    impl Misbehaving<Id, ()> for Override {
+        type EntryPoint = ShareRecovery<P, Id>;
+
+        fn modify_echo_broadcast(
+            rng: &mut dyn CryptoRngCore,
+            round: &BoxedRound<Id, <Self::EntryPoint as EntryPoint<Id>>::Protocol>,
+            _behavior: &(),
+            format: &BoxedFormat,
+            echo_broadcast: EchoBroadcast,
+        ) -> Result<EchoBroadcast, LocalError> {
+            if round.id() == 2 {
+                let mut message = echo_broadcast
+                    .deserialize::<Round2EchoBroadcast<P, Id>>(format)
+                    .unwrap();
+
+                // Commit to pieces that do not add up to the weighted shares.
+                message.cap_deltas = message
+                    .cap_deltas
+                    .iter()
+                    .map(|(id, cap_deltas)| {
+                        let cap_deltas = cap_deltas
+                            .iter()
+                            .map(|_| Scalar::random(rng).mul_by_generator())
+                            .collect();
+                        (*id, cap_deltas)
+                    })
+                    .collect::<BTreeMap<_, _>>()
+                    .into();
+
+                return EchoBroadcast::new(format, message);
+            }
+
+            Ok(echo_broadcast)
+        }
+    }
+
+    check_evidence_from::<Override>(
+        Receivers::Helpers,
+        "Protocol error: Round 2: the commitments to the pieces do not add up to the weighted public share.",
+    )
+    .unwrap();
+}
+
+#[test]
+fn r2_invalid_piece() {
+    struct Override;
+
+    impl Misbehaving<Id, ()> for Override {
+        type EntryPoint = ShareRecovery<P, Id>;
+
+        fn modify_direct_message(
+            rng: &mut dyn CryptoRngCore,
+            round: &BoxedRound<Id, <Self::EntryPoint as EntryPoint<Id>>::Protocol>,
+            _behavior: &(),
+            format: &BoxedFormat,
+            destination: &Id,
+            direct_message: DirectMessage,
+            artifact: Option<Artifact>,
+        ) -> Result<(DirectMessage, Option<Artifact>), LocalError> {
+            // The recovering node does not get a piece.
+            if round.id() == 2 && !direct_message.is_none() {
+                // Send a masked piece that does not match the commitment.
+                let message = Round2DirectMessage::<P, Id> {
+                    destination: *destination,
+                    cap_cs: vec![Scalar::random(rng)],
+                };
+                let direct_message = DirectMessage::new(format, message)?;
+                return Ok((direct_message, artifact));
+            }
+
+            Ok((direct_message, artifact))
+        }
+    }
+
+    check_evidence_from::<Override>(
+        Receivers::Helpers,
+        "Protocol error: Round 2: the piece does not match its commitment.",
+    )
+    .unwrap();
+}
+
+#[test]
+fn r3_invalid_sum() {
+    struct Override;
+
+    impl Misbehaving<Id, ()> for Override {
+        type EntryPoint = ShareRecovery<P, Id>;
+
+        fn modify_direct_message(
+            rng: &mut dyn CryptoRngCore,
+            round: &BoxedRound<Id, <Self::EntryPoint as EntryPoint<Id>>::Protocol>,
+            _behavior: &(),
+            format: &BoxedFormat,
+            _destination: &Id,
+            direct_message: DirectMessage,
+            artifact: Option<Artifact>,
+        ) -> Result<(DirectMessage, Option<Artifact>), LocalError> {
+            if round.id() == 3 {
+                // Send a masked sum that does not match the commitments.
+                let message = Round3DirectMessage::<P> {
+                    cap_cs: vec![Scalar::random(rng)],
+                };
+                let direct_message = DirectMessage::new(format, message)?;
+                return Ok((direct_message, artifact));
+            }
+
+            Ok((direct_message, artifact))
+        }
+    }
+
+    check_evidence_from::<Override>(
+        Receivers::RecoveringNode,
+        "Protocol error: Round 3: the sum of the pieces does not match the commitments.",
+    )
+    .unwrap();
+}
@@ -0,0 +1,196 @@
+use alloc::collections::{BTreeMap, BTreeSet};
+
+use elliptic_curve::FieldBytes;
+use manul::{
+    combinators::misbehave::Misbehaving,
+    dev::{run_sync, BinaryFormat, TestSessionParams, TestSigner, TestVerifier},
+    protocol::{BoxedFormat, BoxedRound, EntryPoint, LocalError, NormalBroadcast, ProtocolMessagePart},
+    signature::Keypair,
+};
+use rand_core::{CryptoRngCore, OsRng, RngCore};
+
+use super::super::{
+    interactive_signing::{Presignature, Presigning},
+    signing::{Round1NormalBroadcast, Signing, SigningAssociatedData},
+};
+use crate::{
+    curve::Scalar,
+    dev::TestParams,
+    entities::{AuxInfo, KeyShare},
+    params::SchemeParams,
+    tools::protocol_shortcuts_dev::{
+        check_evidence_with_behavior, check_invalid_message_evidence, run_with_one_malicious_party, CheckPart,
+    },
+};
+
+type Id = TestVerifier;
+type P = TestParams;
+type SP = TestSessionParams<BinaryFormat>;
+type Curve = <TestParams as SchemeParams>::Curve;
+
+fn make_presignatures(signers: &[TestSigner]) -> BTreeMap<Id, Presignature<P, Id>> {
+    let all_ids = signers.iter().map(TestSigner::verifying_key).collect::<BTreeSet<_>>();
+
+    let key_shares = KeyShare::<TestParams, TestVerifier>::new_centralized(&mut OsRng, &all_ids, None);
+    let aux_infos = AuxInfo::new_centralized(&mut OsRng, &all_ids);
+
+    let entry_points = signers
+        .iter()
+        .map(|signer| {
+            let id = signer.verifying_key();
+            let entry_point = Presigning::new(key_shares[&id].clone(), aux_infos[&id].clone()).unwrap();
+            (*signer, entry_point)
+        })
+        .collect();
+
+    run_sync::<_, SP>(&mut OsRng, entry_points).unwrap().results().unwrap()
+}
+
+#[allow(clippy::type_complexity)]
+fn make_entry_points() -> (SigningAssociatedData<P, Id>, Vec<(TestSigner, Signing<P, Id>)>) {
+    let signers = (0..3).map(TestSigner::new).collect::<Vec<_>>();
+    let presignatures = make_presignatures(&signers);
+
+    let mut message = FieldBytes::<Curve>::default();
+    OsRng.fill_bytes(&mut message);
+
+    let entry_points = signers
+        .into_iter()
+        .map(|signer| {
+            let id = signer.verifying_key();
+            let entry_point = Signing::new(message, presignatures[&id].clone());
+            (signer, entry_point)
+        })
+        .collect();
+
+    let presignature = presignatures.values().next().unwrap();
+    let associated_data = SigningAssociatedData {
+        transcript: presignature.transcript().clone(),
+        message,
+    };
+
+    (associated_data, entry_points)
+}
+
+mod invalid_messages {
+    use super::*;
+
+    #[test]
+    fn echo() {
+        let (associated_data, entry_points) = make_entry_points();
+        check_invalid_message_evidence::<SP, _>(
+            &mut OsRng,
+            entry_points,
+            1,
+            CheckPart::EchoBroadcast,
+            &associated_data,
+            false,
+        )
+        .unwrap();
+    }
+
+    #[test]
+    fn normal() {
+        let (associated_data, entry_points) = make_entry_points();
+        check_invalid_message_evidence::<SP, _>(
+            &mut OsRng,
+            entry_points,
+            1,
+            CheckPart::NormalBroadcast,
+            &associated_data,
+            true,
+        )
+        .unwrap();
+    }
+
+    #[test]
+    fn direct_message() {
+        let (associated_data, entry_points) = make_entry_points();
+        check_invalid_message_evidence::<SP, _>(
+            &mut OsRng,
+            entry_points,
+            1,
+            CheckPart::DirectMessage,
+            &associated_data,
+            false,
+        )
+        .unwrap();
+    }
+}
+
+struct InvalidSignatureShare;
+
+impl Misbehaving<Id, ()> for InvalidSignatureShare {
+    type EntryPoint = Signing<P, Id>;
+
+    fn modify_normal_broadcast(
+        rng: &mut dyn CryptoRngCore,
+        round: &BoxedRound<Id, <Self::EntryPoint as EntryPoint<Id>>::Protocol>,
+        _behavior: &(),
+        format: &BoxedFormat,
+        normal_broadcast: NormalBroadcast,
+    ) -> Result<NormalBroadcast, LocalError> {
+        if round.id() == 1 {
+            // Keep the commitment, so that the lawful nodes check the share itself.
+            let mut message = normal_broadcast
+                .deserialize::<Round1NormalBroadcast<P>>(format)
+                .unwrap();
+            message.sigma = Scalar::random(rng);
+            return NormalBroadcast::new(format, message);
+        }
+
+        Ok(normal_broadcast)
+    }
+}
+
+#[test]
+fn r1_invalid_signature_share() {
+    let (associated_data, entry_points) = make_entry_points();
+    check_evidence_with_behavior::<SP, InvalidSignatureShare, _>(
+        &mut OsRng,
+        entry_points,
+        &(),
+        &associated_data,
+        "Protocol error: Round 1: signature share verification failed.",
+    )
+    .unwrap();
+}
+
+#[test]
+fn r1_invalid_signature_share_with_forged_transcript() {
+    let (associated_data, entry_points) = make_entry_points();
+    let misbehaving_id = entry_points
+        .iter()
+        .map(|(signer, _entry_point)| signer.verifying_key())
+        .min()
+        .unwrap();
+
+    let execution_result =
+        run_with_one_malicious_party::<SP, InvalidSignatureShare, _>(&mut OsRng, entry_points, &()).unwrap();
+
+    // The transcript of a different presigning session (with the same parties)
+    let (other_associated_data, _entry_points) = make_entry_points();
+    let forged_transcript = SigningAssociatedData {
+        transcript: other_associated_data.transcript,
+        message: associated_data.message,
+    };
+
+    // A different message
+    let mut other_message = FieldBytes::<Curve>::default();
+    OsRng.fill_bytes(&mut other_message);
+    let forged_message = SigningAssociatedData {
+        transcript: associated_data.transcript.clone(),
+        message: other_message,
+    };
+
+    for (id, report) in execution_result.reports {
+        if id == misbehaving_id {
+            continue;
+        }
+        let evidence = &report.provable_errors[&misbehaving_id];
+        assert!(evidence.verify(&associated_data).is_ok());
+        // The evidence can only be verified against the transcript and the message the guilty party committed to.
+        assert!(evidence.verify(&forged_transcript).is_err());
+        assert!(evidence.verify(&forged_message).is_err());
+    }
+}
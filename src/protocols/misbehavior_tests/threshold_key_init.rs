@@ -0,0 +1,299 @@
+use alloc::{collections::BTreeSet, vec, vec::Vec};
+
+use manul::{
+    combinators::misbehave::Misbehaving,
+    dev::{BinaryFormat, TestSessionParams, TestSigner, TestVerifier},
+    protocol::{
+        Artifact, BoxedFormat, BoxedRound, DirectMessage, EchoBroadcast, EntryPoint, LocalError, NormalBroadcast,
+        ProtocolMessagePart,
+    },
+    signature::Keypair,
+};
+use rand_chacha::ChaCha8Rng;
+use rand_core::{CryptoRngCore, OsRng, SeedableRng};
+
+use super::super::threshold_key_init::{
+    Context, Round1, Round1EchoBroadcast, Round2, Round2DirectMessage, Round2NormalBroadcast, Round3,
+    Round3NormalBroadcast, ThresholdKeyInit, ThresholdKeyInitAssociatedData,
+};
+use crate::{
+    curve::Scalar,
+    dev::TestParams,
+    tools::{
+        bitvec::BitVec,
+        protocol_shortcuts_dev::{check_evidence_with_behavior, check_invalid_message_evidence, CheckPart},
+        sss::{Polynomial, PublicPolynomial},
+        Secret,
+    },
+    zk::SchProof,
+};
+
+type Id = TestVerifier;
+type P = TestParams;
+type SP = TestSessionParams<BinaryFormat>;
+
+const THRESHOLD: usize = 2;
+
+#[allow(clippy::type_complexity)]
+fn make_entry_points() -> (
+    ThresholdKeyInitAssociatedData<Id>,
+    Vec<(TestSigner, ThresholdKeyInit<P, Id>)>,
+) {
+    let signers = (0..3).map(TestSigner::new).collect::<Vec<_>>();
+    let all_ids = signers
+        .iter()
+        .map(|signer| signer.verifying_key())
+        .collect::<BTreeSet<_>>();
+
+    let entry_points = signers
+        .into_iter()
+        .map(|signer| (signer, ThresholdKeyInit::new(all_ids.clone(), THRESHOLD).unwrap()))
+        .collect();
+
+    let associated_data = ThresholdKeyInitAssociatedData {
+        ids: all_ids,
+        threshold: THRESHOLD,
+        weights: Default::default(),
+        access_structure: None,
+    };
+
+    (associated_data, entry_points)
+}
+
+fn check_evidence<M>(expected_description: &str) -> Result<(), LocalError>
+where
+    M: Misbehaving<Id, (), EntryPoint = ThresholdKeyInit<P, Id>>,
+{
+    let (associated_data, entry_points) = make_entry_points();
+    check_evidence_with_behavior::<SP, M, _>(&mut OsRng, entry_points, &(), &associated_data, expected_description)
+}
+
+#[test]
+fn invalid_messages() {
+    let (associated_data, entry_points) = make_entry_points();
+
+    for (round_id, part, expecting_a_message) in [
+        (1, CheckPart::EchoBroadcast, true),
+        (2, CheckPart::EchoBroadcast, true),
+        (3, CheckPart::EchoBroadcast, false),
+        (1, CheckPart::NormalBroadcast, false),
+        (2, CheckPart::NormalBroadcast, true),
+        (3, CheckPart::NormalBroadcast, true),
+        (1, CheckPart::DirectMessage, false),
+        (2, CheckPart::DirectMessage, true),
+        (3, CheckPart::DirectMessage, false),
+    ] {
+        check_invalid_message_evidence::<SP, _>(
+            &mut OsRng,
+            entry_points.clone(),
+            round_id,
+            part,
+            &associated_data,
+            expecting_a_message,
+        )
+        .unwrap();
+    }
+}
+
+#[test]
+fn r1_wrong_ids_y() {
+    struct Override;
+
+    impl Misbehaving<Id, ()> for Override {
+        type EntryPoint = ThresholdKeyInit<P, Id>;
+
+        fn modify_echo_broadcast(
+            _rng: &mut dyn CryptoRngCore,
+            round: &BoxedRound<Id, <Self::EntryPoint as EntryPoint<Id>>::Protocol>,
+            _behavior: &(),
+            format: &BoxedFormat,
+            echo_broadcast: EchoBroadcast,
+        ) -> Result<EchoBroadcast, LocalError> {
+            if round.id() == 1 {
+                let mut message = echo_broadcast
+                    .deserialize::<Round1EchoBroadcast<P, Id>>(format)
+                    .unwrap();
+
+                // Drop one of the ephemeral keys.
+                message.cap_ys.pop_first();
+
+                return EchoBroadcast::new(format, message);
+            }
+
+            Ok(echo_broadcast)
+        }
+    }
+
+    check_evidence::<Override>("Protocol error: Round 1: wrong IDs in the ephemeral keys map.").unwrap();
+}
+
+#[test]
+fn r2_hash_mismatch() {
+    struct Override;
+
+    impl Misbehaving<Id, ()> for Override {
+        type EntryPoint = ThresholdKeyInit<P, Id>;
+
+        fn modify_normal_broadcast(
+            rng: &mut dyn CryptoRngCore,
+            round: &BoxedRound<Id, <Self::EntryPoint as EntryPoint<Id>>::Protocol>,
+            _behavior: &(),
+            format: &BoxedFormat,
+            normal_broadcast: NormalBroadcast,
+        ) -> Result<NormalBroadcast, LocalError> {
+            if round.id() == 2 {
+                let mut message = normal_broadcast
+                    .deserialize::<Round2NormalBroadcast<P>>(format)
+                    .unwrap();
+
+                // Replace `u` with something other than we committed to when hashing it in Round 1.
+                message.u = BitVec::random(rng, message.u.bits().len());
+
+                return NormalBroadcast::new(format, message);
+            }
+
+            Ok(normal_broadcast)
+        }
+    }
+
+    check_evidence::<Override>("Protocol error: Round 2: the previously sent hash does not match the public data.")
+        .unwrap();
+}
+
+#[test]
+fn r2_wrong_polynomial_degree() {
+    struct Override;
+
+    /// A public polynomial with one coefficient too many.
+    /// It has to be the same in both rounds, so it is generated from a fixed seed.
+    fn wrong_polynomial(context: &Context<P, Id>) -> PublicPolynomial<P> {
+        let mut rng = ChaCha8Rng::seed_from_u64(0);
+        let secret = Secret::init_with(|| Scalar::random(&mut rng));
+        Polynomial::<P>::random(&mut rng, secret, context.threshold + 1).public()
+    }
+
+    impl Misbehaving<Id, ()> for Override {
+        type EntryPoint = ThresholdKeyInit<P, Id>;
+
+        fn modify_echo_broadcast(
+            _rng: &mut dyn CryptoRngCore,
+            round: &BoxedRound<Id, <Self::EntryPoint as EntryPoint<Id>>::Protocol>,
+            _behavior: &(),
+            format: &BoxedFormat,
+            echo_broadcast: EchoBroadcast,
+        ) -> Result<EchoBroadcast, LocalError> {
+            if round.id() == 1 {
+                // Commit to the wrong polynomial, so that in Round 2 the hash check could pass
+                // and the execution reaches the degree check.
+                let context = &round.downcast_ref::<Round1<P, Id>>()?.context;
+                let mut data = context.public_data.clone();
+                data.public_polynomial = wrong_polynomial(context);
+
+                let mut message = echo_broadcast
+                    .deserialize::<Round1EchoBroadcast<P, Id>>(format)
+                    .unwrap();
+                message.cap_v = data.hash(&context.sid, &context.my_id);
+
+                return EchoBroadcast::new(format, message);
+            }
+
+            Ok(echo_broadcast)
+        }
+
+        fn modify_normal_broadcast(
+            _rng: &mut dyn CryptoRngCore,
+            round: &BoxedRound<Id, <Self::EntryPoint as EntryPoint<Id>>::Protocol>,
+            _behavior: &(),
+            format: &BoxedFormat,
+            normal_broadcast: NormalBroadcast,
+        ) -> Result<NormalBroadcast, LocalError> {
+            if round.id() == 2 {
+                let mut message = normal_broadcast
+                    .deserialize::<Round2NormalBroadcast<P>>(format)
+                    .unwrap();
+                let context = &round.downcast_ref::<Round2<P, Id>>()?.context;
+                message.public_polynomial = wrong_polynomial(context);
+
+                return NormalBroadcast::new(format, message);
+            }
+
+            Ok(normal_broadcast)
+        }
+    }
+
+    check_evidence::<Override>("Protocol error: Round 2: the public polynomial has a wrong number of coefficients.")
+        .unwrap();
+}
+
+#[test]
+fn r2_invalid_subshare() {
+    struct Override;
+
+    impl Misbehaving<Id, ()> for Override {
+        type EntryPoint = ThresholdKeyInit<P, Id>;
+
+        fn modify_direct_message(
+            rng: &mut dyn CryptoRngCore,
+            round: &BoxedRound<Id, <Self::EntryPoint as EntryPoint<Id>>::Protocol>,
+            _behavior: &(),
+            format: &BoxedFormat,
+            destination: &Id,
+            direct_message: DirectMessage,
+            artifact: Option<Artifact>,
+        ) -> Result<(DirectMessage, Option<Artifact>), LocalError> {
+            if round.id() == 2 {
+                // Send a masked subshare that does not lie on the broadcasted polynomial.
+                let message = Round2DirectMessage::<P, Id> {
+                    destination: *destination,
+                    cap_cs: vec![Scalar::random(rng)],
+                };
+                let direct_message = DirectMessage::new(format, message)?;
+                return Ok((direct_message, artifact));
+            }
+
+            Ok((direct_message, artifact))
+        }
+    }
+
+    check_evidence::<Override>("Protocol error: Round 2: the subshare does not match the public polynomial.").unwrap();
+}
+
+#[test]
+fn r3_invalid_sch_proof() {
+    struct Override;
+
+    impl Misbehaving<Id, ()> for Override {
+        type EntryPoint = ThresholdKeyInit<P, Id>;
+
+        fn modify_normal_broadcast(
+            rng: &mut dyn CryptoRngCore,
+            round: &BoxedRound<Id, <Self::EntryPoint as EntryPoint<Id>>::Protocol>,
+            _behavior: &(),
+            format: &BoxedFormat,
+            normal_broadcast: NormalBroadcast,
+        ) -> Result<NormalBroadcast, LocalError> {
+            if round.id() == 3 {
+                let round3 = round.downcast_ref::<Round3<P, Id>>()?;
+                let context = &round3.context;
+                let aux = (&context.sid, &context.my_id, &round3.rho_combined);
+
+                // Make a proof for a random secret. This won't pass verification.
+                let x = Secret::init_with(|| Scalar::random(rng));
+                let psi = SchProof::new(
+                    &context.tau,
+                    &x,
+                    &context.public_data.cap_a,
+                    &x.mul_by_generator(),
+                    &aux,
+                );
+
+                let message = Round3NormalBroadcast { psi };
+                return NormalBroadcast::new(format, message);
+            }
+
+            Ok(normal_broadcast)
+        }
+    }
+
+    check_evidence::<Override>("Protocol error: Round 3: failed to verify `П^{sch}`").unwrap();
+}
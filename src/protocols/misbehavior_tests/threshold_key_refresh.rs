@@ -0,0 +1,221 @@
+use alloc::{collections::BTreeSet, vec, vec::Vec};
+
+use manul::{
+    combinators::misbehave::Misbehaving,
+    dev::{BinaryFormat, TestSessionParams, TestSigner, TestVerifier},
+    protocol::{
+        Artifact, BoxedFormat, BoxedRound, DirectMessage, EchoBroadcast, EntryPoint, LocalError, ProtocolMessagePart,
+    },
+    signature::Keypair,
+};
+use rand_core::{CryptoRngCore, OsRng};
+
+use super::super::threshold_key_refresh::{
+    Round1EchoBroadcast, Round2DirectMessage, ThresholdShareUpdate, ThresholdShareUpdateAssociatedData,
+};
+use crate::{
+    curve::Scalar,
+    dev::TestParams,
+    entities::ThresholdKeyShare,
+    tools::{
+        protocol_shortcuts_dev::{check_evidence_with_behavior, check_invalid_message_evidence, CheckPart},
+        sss::Polynomial,
+        Secret,
+    },
+};
+
+type Id = TestVerifier;
+type P = TestParams;
+type SP = TestSessionParams<BinaryFormat>;
+
+const THRESHOLD: usize = 2;
+
+#[allow(clippy::type_complexity)]
+fn make_entry_points() -> (
+    ThresholdShareUpdateAssociatedData<P, Id>,
+    Vec<(TestSigner, ThresholdShareUpdate<P, Id>)>,
+) {
+    let signers = (0..3).map(TestSigner::new).collect::<Vec<_>>();
+    let all_ids = signers
+        .iter()
+        .map(|signer| signer.verifying_key())
+        .collect::<BTreeSet<_>>();
+
+    let key_shares = ThresholdKeyShare::<P, Id>::new_centralized(&mut OsRng, &all_ids, THRESHOLD, None).unwrap();
+    let associated_data = ThresholdShareUpdateAssociatedData::new(key_shares.values().next().unwrap());
+
+    let entry_points = signers
+        .into_iter()
+        .map(|signer| {
+            let entry_point = ThresholdShareUpdate::new(key_shares[&signer.verifying_key()].clone());
+            (signer, entry_point)
+        })
+        .collect();
+
+    (associated_data, entry_points)
+}
+
+fn check_evidence<M>(expected_description: &str) -> Result<(), LocalError>
+where
+    M: Misbehaving<Id, (), EntryPoint = ThresholdShareUpdate<P, Id>>,
+{
+    let (associated_data, entry_points) = make_entry_points();
+    check_evidence_with_behavior::<SP, M, _>(&mut OsRng, entry_points, &(), &associated_data, expected_description)
+}
+
+/// Replaces the public polynomial in the Round 1 message, keeping the ephemeral keys.
+fn replace_polynomial(
+    round: &BoxedRound<Id, <ThresholdShareUpdate<P, Id> as EntryPoint<Id>>::Protocol>,
+    format: &BoxedFormat,
+    echo_broadcast: EchoBroadcast,
+    polynomial: Polynomial<P>,
+) -> Result<EchoBroadcast, LocalError> {
+    if round.id() == 1 {
+        let mut message = echo_broadcast
+            .deserialize::<Round1EchoBroadcast<P, Id>>(format)
+            .unwrap();
+        message.public_polynomial = polynomial.public();
+        return EchoBroadcast::new(format, message);
+    }
+
+    Ok(echo_broadcast)
+}
+
+#[test]
+fn invalid_messages() {
+    let (associated_data, entry_points) = make_entry_points();
+
+    for (round_id, part, expecting_a_message) in [
+        (1, CheckPart::EchoBroadcast, true),
+        (2, CheckPart::EchoBroadcast, false),
+        (1, CheckPart::NormalBroadcast, false),
+        (2, CheckPart::NormalBroadcast, false),
+        (1, CheckPart::DirectMessage, false),
+        (2, CheckPart::DirectMessage, true),
+    ] {
+        check_invalid_message_evidence::<SP, _>(
+            &mut OsRng,
+            entry_points.clone(),
+            round_id,
+            part,
+            &associated_data,
+            expecting_a_message,
+        )
+        .unwrap();
+    }
+}
+
+#[test]
+fn r1_wrong_polynomial_degree() {
+    struct Override;
+
+    impl Misbehaving<Id, ()> for Override {
+        type EntryPoint = ThresholdShareUpdate<P, Id>;
+
+        fn modify_echo_broadcast(
+            rng: &mut dyn CryptoRngCore,
+            round: &BoxedRound<Id, <Self::EntryPoint as EntryPoint<Id>>::Protocol>,
+            _behavior: &(),
+            format: &BoxedFormat,
+            echo_broadcast: EchoBroadcast,
+        ) -> Result<EchoBroadcast, LocalError> {
+            let polynomial = Polynomial::random(rng, Secret::init_with(|| Scalar::ZERO), THRESHOLD + 1);
+            replace_polynomial(round, format, echo_broadcast, polynomial)
+        }
+    }
+
+    check_evidence::<Override>("Protocol error: Round 1: the public polynomial has a wrong number of coefficients.")
+        .unwrap();
+}
+
+#[test]
+fn r1_non_zero_constant() {
+    struct Override;
+
+    impl Misbehaving<Id, ()> for Override {
+        type EntryPoint = ThresholdShareUpdate<P, Id>;
+
+        fn modify_echo_broadcast(
+            rng: &mut dyn CryptoRngCore,
+            round: &BoxedRound<Id, <Self::EntryPoint as EntryPoint<Id>>::Protocol>,
+            _behavior: &(),
+            format: &BoxedFormat,
+            echo_broadcast: EchoBroadcast,
+        ) -> Result<EchoBroadcast, LocalError> {
+            // Deal a non-zero secret, which would change the shared key.
+            let secret = Secret::init_with(|| Scalar::random(rng));
+            let polynomial = Polynomial::random(rng, secret, THRESHOLD);
+            replace_polynomial(round, format, echo_broadcast, polynomial)
+        }
+    }
+
+    check_evidence::<Override>(
+        "Protocol error: Round 1: the constant coefficient of the public polynomial is not zero.",
+    )
+    .unwrap();
+}
+
+#[test]
+fn r1_wrong_ids_y() {
+    struct Override;
+
+    impl Misbehaving<Id, ()> for Override {
+        type EntryPoint = ThresholdShareUpdate<P, Id>;
+
+        fn modify_echo_broadcast(
+            _rng: &mut dyn CryptoRngCore,
+            round: &BoxedRound<Id, <Self::EntryPoint as EntryPoint<Id>>::Protocol>,
+            _behavior: &(),
+            format: &BoxedFormat,
+            echo_broadcast: EchoBroadcast,
+        ) -> Result<EchoBroadcast, LocalError> {
+            if round.id() == 1 {
+                let mut message = echo_broadcast
+                    .deserialize::<Round1EchoBroadcast<P, Id>>(format)
+                    .unwrap();
+
+                // Drop one of the ephemeral keys.
+                message.cap_ys.pop_first();
+
+                return EchoBroadcast::new(format, message);
+            }
+
+            Ok(echo_broadcast)
+        }
+    }
+
+    check_evidence::<Override>("Protocol error: Round 1: wrong IDs in the ephemeral keys map.").unwrap();
+}
+
+#[test]
+fn r2_invalid_subshare() {
+    struct Override;
+
+    impl Misbehaving<Id, ()> for Override {
+        type EntryPoint = ThresholdShareUpdate<P, Id>;
+
+        fn modify_direct_message(
+            rng: &mut dyn CryptoRngCore,
+            round: &BoxedRound<Id, <Self::EntryPoint as EntryPoint<Id>>::Protocol>,
+            _behavior: &(),
+            format: &BoxedFormat,
+            destination: &Id,
+            direct_message: DirectMessage,
+            artifact: Option<Artifact>,
+        ) -> Result<(DirectMessage, Option<Artifact>), LocalError> {
+            if round.id() == 2 {
+                // Send a masked subshare that does not lie on the broadcasted polynomial.
+                let message = Round2DirectMessage::<P, Id> {
+                    destination: *destination,
+                    cap_cs: vec![Scalar::random(rng)],
+                };
+                let direct_message = DirectMessage::new(format, message)?;
+                return Ok((direct_message, artifact));
+            }
+
+            Ok((direct_message, artifact))
+        }
+    }
+
+    check_evidence::<Override>("Protocol error: Round 2: the subshare does not match the public polynomial.").unwrap();
+}
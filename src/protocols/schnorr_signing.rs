@@ -0,0 +1,639 @@
+//! Threshold Schnorr signing producing BIP-340 signatures, with an optional Taproot (BIP-341) tweak of the key.
+//!
+//! The protocol is FROST (C. Komlo, I. Goldberg, "FROST: Flexible Round-Optimized Schnorr Threshold Signatures",
+//! <https://eprint.iacr.org/2020/852.pdf>), executed on the same key shares as the ECDSA protocols:
+//! - Round 1: nonce commitments,
+//! - Round 2: signature shares.
+//!
+//! To satisfy BIP-340, the group key (and, after tweaking, the output key) and the combined nonce
+//! are negated when their Y coordinates are odd.
+
+use alloc::{
+    boxed::Box,
+    collections::{BTreeMap, BTreeSet},
+};
+use core::{
+    fmt::{self, Debug, Display},
+    marker::PhantomData,
+};
+
+use k256::{schnorr::Signature, Secp256k1};
+use manul::protocol::{
+    Artifact, BoxedFormat, BoxedRound, CommunicationInfo, DirectMessage, EchoBroadcast, EntryPoint, FinalizeOutcome,
+    LocalError, MessageValidationError, NormalBroadcast, PartyId, Payload, Protocol, ProtocolError, ProtocolMessage,
+    ProtocolMessagePart, ProtocolValidationError, ReceiveError, RequiredMessageParts, RequiredMessages, Round, RoundId,
+    TransitionInfo,
+};
+use rand_core::CryptoRngCore;
+use serde::{Deserialize, Serialize};
+
+use super::interactive_signing::check_threshold_signers;
+use crate::{
+    curve::{assemble_bip340_signature, bip340_challenge, parity_factor, Point, Scalar, TaprootTweak, TweakedKey},
+    entities::{KeyShare, PublicKeyShares, ThresholdKeyShare},
+    params::SchemeParams,
+    tools::{
+        hashing::{Chain, Hasher},
+        protocol_shortcuts::{verify_that, DeserializeAll, DowncastMap, GetRound, SafeGet, Without},
+        Secret,
+    },
+};
+
+/// A protocol for creating a BIP-340 Schnorr signature.
+#[derive(Debug, Clone, Copy)]
+pub struct SchnorrSigningProtocol<P: SchemeParams, Id: Debug>(PhantomData<(P, Id)>);
+
+impl<P: SchemeParams<Curve = Secp256k1>, Id: PartyId> Protocol<Id> for SchnorrSigningProtocol<P, Id> {
+    type Result = Signature;
+    type ProtocolError = SchnorrSigningError<P, Id>;
+
+    fn verify_direct_message_is_invalid(
+        _format: &BoxedFormat,
+        round_id: &RoundId,
+        message: &DirectMessage,
+    ) -> Result<(), MessageValidationError> {
+        match round_id {
+            r if r == &1 => message.verify_is_some(),
+            r if r == &2 => message.verify_is_some(),
+            _ => Err(MessageValidationError::InvalidEvidence("Invalid round number".into())),
+        }
+    }
+
+    fn verify_echo_broadcast_is_invalid(
+        format: &BoxedFormat,
+        round_id: &RoundId,
+        message: &EchoBroadcast,
+    ) -> Result<(), MessageValidationError> {
+        match round_id {
+            r if r == &1 => message.verify_is_not::<Round1EchoBroadcast<P>>(format),
+            r if r == &2 => message.verify_is_some(),
+            _ => Err(MessageValidationError::InvalidEvidence("Invalid round number".into())),
+        }
+    }
+
+    fn verify_normal_broadcast_is_invalid(
+        format: &BoxedFormat,
+        round_id: &RoundId,
+        message: &NormalBroadcast,
+    ) -> Result<(), MessageValidationError> {
+        match round_id {
+            r if r == &1 => message.verify_is_some(),
+            r if r == &2 => message.verify_is_not::<Round2NormalBroadcast<P>>(format),
+            _ => Err(MessageValidationError::InvalidEvidence("Invalid round number".into())),
+        }
+    }
+}
+
+/// Possible verifiable errors of the SchnorrSigning protocol.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+pub struct SchnorrSigningError<P, Id> {
+    error: Error,
+    phantom: PhantomData<(P, Id)>,
+}
+
+impl<P, Id> Display for SchnorrSigningError<P, Id> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> Result<(), fmt::Error> {
+        write!(f, "{}", self.error)
+    }
+}
+
+impl<P, Id> From<Error> for SchnorrSigningError<P, Id> {
+    fn from(source: Error) -> Self {
+        Self {
+            error: source,
+            phantom: PhantomData,
+        }
+    }
+}
+
+#[derive(displaydoc::Display, Debug, Clone, Copy, Serialize, Deserialize)]
+enum Error {
+    /// Round 2: signature share verification failed.
+    R2InvalidSignatureShare,
+}
+
+/// Associated data for SchnorrSigning protocol.
+#[derive(Debug, Clone)]
+pub struct SchnorrSigningAssociatedData<P: SchemeParams, Id: PartyId> {
+    /// Public shares of all participating nodes.
+    pub shares: PublicKeyShares<P, Id>,
+    /// The message that was signed.
+    pub message: [u8; 32],
+    /// The Taproot tweak applied to the key.
+    pub tweak: TaprootTweak,
+}
+
+impl<P: SchemeParams<Curve = Secp256k1>, Id: PartyId> SchnorrSigningAssociatedData<P, Id> {
+    /// Creates the associated data for evidence verification of SchnorrSigning.
+    pub fn new(message: [u8; 32], public_key_shares: PublicKeyShares<P, Id>, tweak: TaprootTweak) -> Self {
+        Self {
+            shares: public_key_shares,
+            message,
+            tweak,
+        }
+    }
+
+    /// Creates the associated data for evidence verification of SchnorrSigning
+    /// started with [`SchnorrSigning::new_threshold`].
+    ///
+    /// Only the public part of `key_share` is used, so it does not have to belong to one of the `signers`.
+    pub fn new_threshold(
+        message: [u8; 32],
+        key_share: &ThresholdKeyShare<P, Id>,
+        signers: &BTreeSet<Id>,
+        tweak: TaprootTweak,
+    ) -> Result<Self, LocalError> {
+        check_threshold_signers(key_share, signers)?;
+        let public_key_shares = PublicKeyShares::new(key_share.to_public_key_shares(signers)?);
+        Ok(Self::new(message, public_key_shares, tweak))
+    }
+}
+
+impl<P: SchemeParams<Curve = Secp256k1>, Id: PartyId> ProtocolError<Id> for SchnorrSigningError<P, Id> {
+    type AssociatedData = SchnorrSigningAssociatedData<P, Id>;
+
+    fn required_messages(&self) -> RequiredMessages {
+        match self.error {
+            Error::R2InvalidSignatureShare => RequiredMessages::new(
+                RequiredMessageParts::normal_broadcast(),
+                Some([(1.into(), RequiredMessageParts::echo_broadcast())].into()),
+                Some([1.into()].into()),
+            ),
+        }
+    }
+
+    fn verify_messages_constitute_error(
+        &self,
+        format: &BoxedFormat,
+        guilty_party: &Id,
+        shared_randomness: &[u8],
+        associated_data: &Self::AssociatedData,
+        message: ProtocolMessage,
+        previous_messages: BTreeMap<RoundId, ProtocolMessage>,
+        combined_echos: BTreeMap<RoundId, BTreeMap<Id, EchoBroadcast>>,
+    ) -> Result<(), ProtocolValidationError> {
+        match self.error {
+            Error::R2InvalidSignatureShare => {
+                let r1_eb = previous_messages
+                    .get_round(1)?
+                    .echo_broadcast
+                    .deserialize::<Round1EchoBroadcast<P>>(format)?;
+                let r1_ebs = combined_echos
+                    .get_round(1)?
+                    .deserialize_all::<Round1EchoBroadcast<P>>(format)?;
+                let r2_nb = message
+                    .normal_broadcast
+                    .deserialize::<Round2NormalBroadcast<P>>(format)?;
+
+                let mut commitments = r1_ebs;
+                commitments.insert(guilty_party.clone(), r1_eb);
+
+                let shares = associated_data.shares.as_map();
+                let ids = shares.keys().cloned().collect::<BTreeSet<_>>();
+                if commitments.keys().cloned().collect::<BTreeSet<_>>() != ids {
+                    return Err(ProtocolValidationError::InvalidEvidence(
+                        "The nonce commitments do not match the set of parties".into(),
+                    ));
+                }
+
+                let verifying_key = shares.values().sum::<Point<P>>();
+                let tweaked_key = TweakedKey::new(&verifying_key, &associated_data.tweak)
+                    .map_err(ProtocolValidationError::InvalidEvidence)?;
+                let signing_data =
+                    SigningData::new(shared_randomness, &tweaked_key, &associated_data.message, &commitments)
+                        .ok_or_else(|| ProtocolValidationError::InvalidEvidence("Invalid nonce commitments".into()))?;
+
+                let public_share = shares
+                    .get(guilty_party)
+                    .ok_or_else(|| ProtocolValidationError::InvalidEvidence("Missing public share".into()))?;
+                let share_is_valid = signing_data
+                    .signature_share_is_valid(guilty_party, public_share, &r2_nb.z)
+                    .ok_or_else(|| ProtocolValidationError::InvalidEvidence("Missing nonce commitment".into()))?;
+                verify_that(!share_is_valid)
+            }
+        }
+    }
+}
+
+/// An entry point for the [`SchnorrSigningProtocol`].
+#[derive(Debug, Clone)]
+pub struct SchnorrSigning<P, Id>
+where
+    P: SchemeParams,
+    Id: PartyId,
+{
+    key_share: KeyShare<P, Id>,
+    message: [u8; 32],
+    tweak: TaprootTweak,
+}
+
+impl<P: SchemeParams<Curve = Secp256k1>, Id: PartyId> SchnorrSigning<P, Id> {
+    /// Creates a new entry point given a share of the secret key.
+    ///
+    /// The signature will be valid for the x-only public key obtained by applying `tweak`
+    /// to the verifying key of the shares.
+    pub fn new(message: [u8; 32], key_share: KeyShare<P, Id>, tweak: TaprootTweak) -> Self {
+        Self {
+            key_share,
+            message,
+            tweak,
+        }
+    }
+
+    /// Creates a new entry point given a threshold share of the secret key and the set of parties
+    /// (of size equal to the threshold, and including the owner of the key share) participating in signing.
+    pub fn new_threshold(
+        message: [u8; 32],
+        key_share: &ThresholdKeyShare<P, Id>,
+        signers: &BTreeSet<Id>,
+        tweak: TaprootTweak,
+    ) -> Result<Self, LocalError> {
+        check_threshold_signers(key_share, signers)?;
+        if !signers.contains(&key_share.owner) {
+            return Err(LocalError::new("The signers must include the owner of the key share"));
+        }
+        Ok(Self::new(message, key_share.to_key_share(signers)?, tweak))
+    }
+}
+
+impl<P: SchemeParams<Curve = Secp256k1>, Id: PartyId> EntryPoint<Id> for SchnorrSigning<P, Id> {
+    type Protocol = SchnorrSigningProtocol<P, Id>;
+
+    fn entry_round_id() -> RoundId {
+        1.into()
+    }
+
+    fn make_round(
+        self,
+        rng: &mut dyn CryptoRngCore,
+        shared_randomness: &[u8],
+        id: &Id,
+    ) -> Result<BoxedRound<Id, Self::Protocol>, LocalError> {
+        if id != self.key_share.owner() {
+            return Err(LocalError::new("ID mismatch between the signer and the key share"));
+        }
+
+        let other_ids = self.key_share.all_parties().without(id);
+        let tweaked_key =
+            TweakedKey::new(&self.key_share.verifying_key_as_point(), &self.tweak).map_err(LocalError::new)?;
+
+        let d = Secret::init_with(|| Scalar::random(rng));
+        let e = Secret::init_with(|| Scalar::random(rng));
+
+        let r1_echo_broadcast = Round1EchoBroadcast {
+            cap_d: d.mul_by_generator(),
+            cap_e: e.mul_by_generator(),
+        };
+
+        Ok(BoxedRound::new_dynamic(Round1 {
+            context: Context {
+                shared_randomness: shared_randomness.into(),
+                my_id: id.clone(),
+                other_ids,
+                key_share: self.key_share,
+                tweaked_key,
+                message: self.message,
+                d,
+                e,
+            },
+            r1_echo_broadcast,
+        }))
+    }
+}
+
+#[derive(Debug)]
+struct Context<P: SchemeParams, Id: PartyId> {
+    shared_randomness: Box<[u8]>,
+    my_id: Id,
+    other_ids: BTreeSet<Id>,
+    key_share: KeyShare<P, Id>,
+    tweaked_key: TweakedKey<P>,
+    message: [u8; 32],
+    d: Secret<Scalar<P>>,
+    e: Secret<Scalar<P>>,
+}
+
+/// The values derived from the nonce commitments of all the parties.
+#[derive(Debug)]
+struct SigningData<P: SchemeParams, Id> {
+    public_key: Point<P>,
+    /// The combined nonce point (with an even Y coordinate).
+    nonce: Point<P>,
+    /// `1` or `-1`, depending on whether the combined nonce had to be negated.
+    nonce_factor: Scalar<P>,
+    /// The BIP-340 challenge multiplied by the factor of the secret key.
+    share_challenge: Scalar<P>,
+    /// The BIP-340 challenge multiplied by the offset of the secret key.
+    offset_challenge: Scalar<P>,
+    /// The nonce points of each party ($D_i + \rho_i E_i$).
+    nonce_shares: BTreeMap<Id, Point<P>>,
+    binding_factors: BTreeMap<Id, Scalar<P>>,
+}
+
+impl<P: SchemeParams<Curve = Secp256k1>, Id: PartyId> SigningData<P, Id> {
+    /// Returns `None` if the combined nonce is the point at infinity.
+    fn new(
+        shared_randomness: &[u8],
+        tweaked_key: &TweakedKey<P>,
+        message: &[u8; 32],
+        commitments: &BTreeMap<Id, Round1EchoBroadcast<P>>,
+    ) -> Option<Self> {
+        let binding_factors = commitments
+            .keys()
+            .map(|id| {
+                let mut reader = Hasher::<P::Digest>::new_with_dst(b"SchnorrBindingFactor")
+                    .chain_bytes(shared_randomness)
+                    .chain(&tweaked_key.public_key)
+                    .chain_bytes(message)
+                    .chain(commitments)
+                    .chain(id)
+                    .finalize_to_reader();
+                (id.clone(), Scalar::from_xof_reader(&mut reader))
+            })
+            .collect::<BTreeMap<_, _>>();
+
+        let nonce_shares = commitments
+            .iter()
+            .zip(binding_factors.values())
+            .map(|((id, commitment), rho)| (id.clone(), commitment.cap_d + commitment.cap_e * rho))
+            .collect::<BTreeMap<_, _>>();
+
+        let nonce = nonce_shares.values().sum::<Point<P>>();
+        if nonce == Point::identity() {
+            return None;
+        }
+        let nonce_factor = parity_factor(&nonce);
+        let nonce = nonce * nonce_factor;
+
+        let challenge = bip340_challenge(&nonce, &tweaked_key.public_key, message);
+
+        Some(Self {
+            public_key: tweaked_key.public_key,
+            nonce,
+            nonce_factor,
+            share_challenge: challenge * tweaked_key.factor,
+            offset_challenge: challenge * tweaked_key.offset,
+            nonce_shares,
+            binding_factors,
+        })
+    }
+
+    /// Checks that $z_i G = \pm (D_i + \rho_i E_i) + c' X_i$.
+    /// Returns `None` if `id` is not among the parties that sent the nonce commitments.
+    fn signature_share_is_valid(&self, id: &Id, public_share: &Point<P>, z: &Scalar<P>) -> Option<bool> {
+        let nonce_share = self.nonce_shares.get(id)?;
+        Some(z.mul_by_generator() == *nonce_share * self.nonce_factor + *public_share * self.share_challenge)
+    }
+}
+
+#[derive(Debug)]
+struct Round1<P: SchemeParams, Id: PartyId> {
+    context: Context<P, Id>,
+    r1_echo_broadcast: Round1EchoBroadcast<P>,
+}
+
+#[derive(Debug, Clone)]
+#[derive_where::derive_where(Serialize, Deserialize)]
+struct Round1EchoBroadcast<P: SchemeParams> {
+    cap_d: Point<P>,
+    cap_e: Point<P>,
+}
+
+struct Round1Payload<P: SchemeParams> {
+    commitment: Round1EchoBroadcast<P>,
+}
+
+impl<P: SchemeParams<Curve = Secp256k1>, Id: PartyId> Round<Id> for Round1<P, Id> {
+    type Protocol = SchnorrSigningProtocol<P, Id>;
+
+    fn transition_info(&self) -> TransitionInfo {
+        TransitionInfo::new_linear(1)
+    }
+
+    fn communication_info(&self) -> CommunicationInfo<Id> {
+        CommunicationInfo::regular(&self.context.other_ids)
+    }
+
+    fn make_echo_broadcast(
+        &self,
+        _rng: &mut dyn CryptoRngCore,
+        format: &BoxedFormat,
+    ) -> Result<EchoBroadcast, LocalError> {
+        EchoBroadcast::new(format, self.r1_echo_broadcast.clone())
+    }
+
+    fn receive_message(
+        &self,
+        format: &BoxedFormat,
+        _from: &Id,
+        message: ProtocolMessage,
+    ) -> Result<Payload, ReceiveError<Id, Self::Protocol>> {
+        message.normal_broadcast.assert_is_none()?;
+        message.direct_message.assert_is_none()?;
+        let echo_broadcast = message.echo_broadcast.deserialize::<Round1EchoBroadcast<P>>(format)?;
+        Ok(Payload::new(Round1Payload {
+            commitment: echo_broadcast,
+        }))
+    }
+
+    fn finalize(
+        self: Box<Self>,
+        _rng: &mut dyn CryptoRngCore,
+        payloads: BTreeMap<Id, Payload>,
+        _artifacts: BTreeMap<Id, Artifact>,
+    ) -> Result<FinalizeOutcome<Id, Self::Protocol>, LocalError> {
+        let payloads = payloads.downcast_all::<Round1Payload<P>>()?;
+
+        let mut commitments = payloads
+            .into_iter()
+            .map(|(id, payload)| (id, payload.commitment))
+            .collect::<BTreeMap<_, _>>();
+        commitments.insert(self.context.my_id.clone(), self.r1_echo_broadcast);
+
+        let signing_data = SigningData::new(
+            &self.context.shared_randomness,
+            &self.context.tweaked_key,
+            &self.context.message,
+            &commitments,
+        )
+        .ok_or_else(|| LocalError::new("The combined nonce is the point at infinity"))?;
+
+        let rho = signing_data
+            .binding_factors
+            .safe_get("binding factors", &self.context.my_id)?;
+        let k = (&self.context.d + &(&self.context.e * rho)) * signing_data.nonce_factor;
+        let z = k + self.context.key_share.secret_share() * signing_data.share_challenge;
+
+        let r2_normal_broadcast = Round2NormalBroadcast { z: *z.expose_secret() };
+
+        Ok(FinalizeOutcome::AnotherRound(BoxedRound::new_dynamic(Round2 {
+            context: self.context,
+            signing_data,
+            r2_normal_broadcast,
+        })))
+    }
+}
+
+#[derive(Debug)]
+struct Round2<P: SchemeParams, Id: PartyId> {
+    context: Context<P, Id>,
+    signing_data: SigningData<P, Id>,
+    r2_normal_broadcast: Round2NormalBroadcast<P>,
+}
+
+#[derive(Debug, Clone)]
+#[derive_where::derive_where(Serialize, Deserialize)]
+pub(super) struct Round2NormalBroadcast<P: SchemeParams> {
+    pub(super) z: Scalar<P>,
+}
+
+struct Round2Payload<P: SchemeParams> {
+    z: Scalar<P>,
+}
+
+impl<P: SchemeParams<Curve = Secp256k1>, Id: PartyId> Round<Id> for Round2<P, Id> {
+    type Protocol = SchnorrSigningProtocol<P, Id>;
+
+    fn transition_info(&self) -> TransitionInfo {
+        TransitionInfo::new_linear_terminating(2)
+    }
+
+    fn communication_info(&self) -> CommunicationInfo<Id> {
+        CommunicationInfo::regular(&self.context.other_ids)
+    }
+
+    fn make_normal_broadcast(
+        &self,
+        _rng: &mut dyn CryptoRngCore,
+        format: &BoxedFormat,
+    ) -> Result<NormalBroadcast, LocalError> {
+        NormalBroadcast::new(format, self.r2_normal_broadcast.clone())
+    }
+
+    fn receive_message(
+        &self,
+        format: &BoxedFormat,
+        from: &Id,
+        message: ProtocolMessage,
+    ) -> Result<Payload, ReceiveError<Id, Self::Protocol>> {
+        message.echo_broadcast.assert_is_none()?;
+        message.direct_message.assert_is_none()?;
+        let normal_broadcast = message
+            .normal_broadcast
+            .deserialize::<Round2NormalBroadcast<P>>(format)?;
+
+        let public_share = self.context.key_share.public_shares().safe_get("public shares", from)?;
+        let share_is_valid = self
+            .signing_data
+            .signature_share_is_valid(from, public_share, &normal_broadcast.z)
+            .ok_or_else(|| LocalError::new("Missing nonce commitment"))?;
+        if !share_is_valid {
+            return Err(ReceiveError::protocol(Error::R2InvalidSignatureShare.into()));
+        }
+
+        Ok(Payload::new(Round2Payload { z: normal_broadcast.z }))
+    }
+
+    fn finalize(
+        self: Box<Self>,
+        _rng: &mut dyn CryptoRngCore,
+        payloads: BTreeMap<Id, Payload>,
+        _artifacts: BTreeMap<Id, Artifact>,
+    ) -> Result<FinalizeOutcome<Id, Self::Protocol>, LocalError> {
+        let payloads = payloads.downcast_all::<Round2Payload<P>>()?;
+
+        let s = payloads.values().map(|payload| payload.z).sum::<Scalar<P>>()
+            + self.r2_normal_broadcast.z
+            + self.signing_data.offset_challenge;
+
+        assemble_bip340_signature(
+            &self.signing_data.nonce,
+            &s,
+            &self.signing_data.public_key,
+            &self.context.message,
+        )
+        .map(FinalizeOutcome::Result)
+        .ok_or_else(|| LocalError::new("Failed to assemble the signature"))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use alloc::collections::BTreeSet;
+
+    use k256::schnorr::VerifyingKey;
+    use manul::{
+        dev::{run_sync, BinaryFormat, TestSessionParams, TestSigner, TestVerifier},
+        signature::{hazmat::PrehashVerifier, Keypair},
+    };
+    use rand_core::{OsRng, RngCore};
+
+    use super::SchnorrSigning;
+    use crate::{
+        curve::{Point, TaprootTweak, TweakedKey},
+        entities::ThresholdKeyShare,
+        k256::ProductionParams112,
+    };
+
+    #[test]
+    fn execute_threshold_schnorr_signing() {
+        let signers = (0..4).map(TestSigner::new).collect::<Vec<_>>();
+        let ids = signers
+            .iter()
+            .map(|signer| signer.verifying_key())
+            .collect::<BTreeSet<_>>();
+
+        let key_shares =
+            ThresholdKeyShare::<ProductionParams112, TestVerifier>::new_centralized(&mut OsRng, &ids, 3, None).unwrap();
+        let verifying_key = key_shares.values().next().unwrap().verifying_key().unwrap();
+
+        let selected_signers = signers.into_iter().skip(1).collect::<Vec<_>>();
+        let selected_ids = selected_signers
+            .iter()
+            .map(|signer| signer.verifying_key())
+            .collect::<BTreeSet<_>>();
+
+        let mut merkle_root = [0u8; 32];
+        OsRng.fill_bytes(&mut merkle_root);
+
+        for tweak in [
+            TaprootTweak::None,
+            TaprootTweak::KeyPathOnly,
+            TaprootTweak::ScriptTree(merkle_root),
+        ] {
+            let mut message = [0u8; 32];
+            OsRng.fill_bytes(&mut message);
+
+            let entry_points = selected_signers
+                .iter()
+                .map(|signer| {
+                    let id = signer.verifying_key();
+                    let entry_point =
+                        SchnorrSigning::new_threshold(message, &key_shares[&id], &selected_ids, tweak).unwrap();
+                    (*signer, entry_point)
+                })
+                .collect();
+
+            let signatures = run_sync::<_, TestSessionParams<BinaryFormat>>(&mut OsRng, entry_points)
+                .unwrap()
+                .results()
+                .unwrap();
+
+            let tweaked_key =
+                TweakedKey::<ProductionParams112>::new(&Point::from_verifying_key(&verifying_key), &tweak).unwrap();
+            let output_key = VerifyingKey::from_bytes(
+                &tweaked_key
+                    .public_key
+                    .to_verifying_key()
+                    .unwrap()
+                    .to_encoded_point(true)
+                    .as_bytes()[1..],
+            )
+            .unwrap();
+
+            for signature in signatures.values() {
+                output_key.verify_prehash(&message, signature).unwrap();
+            }
+        }
+    }
+}
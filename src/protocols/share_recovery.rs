@@ -0,0 +1,1376 @@
+//! ShareRecovery protocol: recomputes the lost share of one of the holders of a [`ThresholdKeyShare`]
+//! at its existing share ID, with the help of `threshold` other holders.
+//!
+//! Each helper $i$ multiplies its share by the Lagrange coefficient $\lambda_i$ for the interpolation
+//! at the lost share ID, splits the result into random additive pieces $\delta_{i,j}$, one for each helper $j$,
+//! and echo-broadcasts the commitments $\Delta_{i,j} = g * \delta_{i,j}$ along with the public data of the key share.
+//! The helpers check that the commitments add up to $X_i * \lambda_i$, where $X_i$ is the public share of $i$.
+//! Each helper then sends the sum of the pieces it received to the recovering node,
+//! which checks the sums against the commitments and adds them up to obtain the lost share.
+//! Since each helper only sees random pieces of the others' weighted shares, the helpers learn nothing
+//! about the lost share or each other's shares.
+//!
+//! The pieces and the sums are masked with pairwise Diffie-Hellman shared points:
+//! in Round 1 each participant (including the recovering node) echo-broadcasts an ephemeral Diffie-Hellman key
+//! for each other participant, and the pieces (Round 2) and the sums (Round 3) are sent masked with a hash
+//! of the shared point of the sender and the receiver.
+//! A node reporting an invalid piece or sum reveals its ephemeral secret for the sender, so that the value
+//! can be unmasked during evidence verification.
+//!
+//! If the holders have several shares (see [`ThresholdKeyShare::new_centralized_weighted`]),
+//! the helpers use all of their shares for the interpolation, so the number of helpers can be below the threshold
+//! as long as their total weight reaches it, and all of the shares of the recovering node are recovered,
+//! each with its own set of pieces. Hierarchical key shares are not supported.
+//!
+//! The recovered shares are checked against the public shares of the recovering node,
+//! and the public data received from the helpers is checked against the verifying key
+//! the recovering node has to supply. The shares of the other holders do not change.
+
+use alloc::{
+    boxed::Box,
+    collections::{BTreeMap, BTreeSet},
+    format,
+    vec::Vec,
+};
+use core::{
+    fmt::{self, Debug, Display},
+    marker::PhantomData,
+};
+
+use ecdsa::VerifyingKey;
+use manul::{
+    protocol::{
+        Artifact, BoxedFormat, BoxedRound, CommunicationInfo, DirectMessage, EchoBroadcast, EchoRoundParticipation,
+        EntryPoint, FinalizeOutcome, LocalError, MessageValidationError, NormalBroadcast, PartyId, Payload, Protocol,
+        ProtocolError, ProtocolMessage, ProtocolMessagePart, ProtocolValidationError, ReceiveError,
+        RequiredMessageParts, RequiredMessages, Round, RoundId, TransitionInfo,
+    },
+    utils::SerializableMap,
+};
+use rand_core::CryptoRngCore;
+
+use super::interactive_signing::check_threshold_signers;
+use crate::{
+    curve::{Point, Scalar},
+    entities::{PublicShareWithId, Sid, ThresholdKeyShare, ThresholdPublicKeyShares},
+    params::SchemeParams,
+    tools::{
+        dh_mask,
+        protocol_shortcuts::{verify_that, DeserializeAll, DowncastMap, GetRound, MapValues, SafeGet, Without},
+        sss::{interpolation_coeff_at, ShareId},
+        Secret,
+    },
+};
+
+/// The domain separation tag of the masks of the values sent in ShareRecovery.
+const MASK_DST: &[u8] = b"ShareRecovery mask";
+
+/// A protocol for recovering the lost share of a [`ThresholdKeyShare`] holder.
+#[derive(Debug)]
+pub struct ShareRecoveryProtocol<P: SchemeParams, I: Debug>(PhantomData<(P, I)>);
+
+impl<P: SchemeParams, I: PartyId> Protocol<I> for ShareRecoveryProtocol<P, I> {
+    type Result = Option<ThresholdKeyShare<P, I>>;
+    type ProtocolError = ShareRecoveryError<P, I>;
+
+    fn verify_direct_message_is_invalid(
+        format: &BoxedFormat,
+        round_id: &RoundId,
+        message: &DirectMessage,
+    ) -> Result<(), MessageValidationError> {
+        match round_id {
+            r if r == &1 => message.verify_is_some(),
+            r if r == &2 => message.verify_is_not::<Round2DirectMessage<P, I>>(format),
+            r if r == &3 => message.verify_is_not::<Round3DirectMessage<P>>(format),
+            _ => Err(MessageValidationError::InvalidEvidence("Invalid round number".into())),
+        }
+    }
+
+    fn verify_echo_broadcast_is_invalid(
+        format: &BoxedFormat,
+        round_id: &RoundId,
+        message: &EchoBroadcast,
+    ) -> Result<(), MessageValidationError> {
+        match round_id {
+            r if r == &1 => message.verify_is_not::<Round1EchoBroadcast<P, I>>(format),
+            r if r == &2 => message.verify_is_not::<Round2EchoBroadcast<P, I>>(format),
+            r if r == &3 => message.verify_is_some(),
+            _ => Err(MessageValidationError::InvalidEvidence("Invalid round number".into())),
+        }
+    }
+
+    fn verify_normal_broadcast_is_invalid(
+        _format: &BoxedFormat,
+        round_id: &RoundId,
+        message: &NormalBroadcast,
+    ) -> Result<(), MessageValidationError> {
+        match round_id {
+            r if r == &1 => message.verify_is_some(),
+            r if r == &2 => message.verify_is_some(),
+            r if r == &3 => message.verify_is_some(),
+            _ => Err(MessageValidationError::InvalidEvidence("Invalid round number".into())),
+        }
+    }
+}
+
+/// Possible verifiable errors of the ShareRecovery protocol.
+#[derive(Debug, Clone)]
+#[derive_where::derive_where(Serialize, Deserialize)]
+pub struct ShareRecoveryError<P: SchemeParams, I: PartyId> {
+    error: Error<P, I>,
+}
+
+impl<P: SchemeParams, I: PartyId> Display for ShareRecoveryError<P, I> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> Result<(), fmt::Error> {
+        write!(f, "{}", self.error)
+    }
+}
+
+impl<P: SchemeParams, I: PartyId> From<Error<P, I>> for ShareRecoveryError<P, I> {
+    fn from(source: Error<P, I>) -> Self {
+        Self { error: source }
+    }
+}
+
+#[derive(displaydoc::Display, Debug, Clone)]
+#[derive_where::derive_where(Serialize, Deserialize)]
+enum Error<P: SchemeParams, I: PartyId> {
+    /// Round 1: wrong IDs in the ephemeral keys map.
+    R1WrongIdsY,
+    /// Round 2: the public data of the key share does not match the one in the associated data.
+    R2PublicDataMismatch,
+    /// Round 2: the commitments to the pieces do not add up to the weighted public shares.
+    R2InvalidCommitments,
+    /// Round 2: the piece does not match its commitment.
+    R2InvalidPiece {
+        /// The ID $k$ of the node that received the piece.
+        reported_by: I,
+        /// The ephemeral secret $y_{k,j}$, where $j$ is the ID of the guilty party.
+        y: Scalar<P>,
+    },
+    /// Round 3: the sum of the pieces does not match the commitments.
+    R3InvalidSum {
+        /// The ephemeral secret $y_{k,j}$, where $k$ is the ID of the recovering node
+        /// and $j$ is the ID of the guilty party.
+        y: Scalar<P>,
+    },
+}
+
+/// Associated data for ShareRecovery protocol.
+#[derive(Debug, Clone)]
+pub struct ShareRecoveryAssociatedData<P: SchemeParams, I: PartyId> {
+    /// The public part of the key shares.
+    pub public_key_shares: ThresholdPublicKeyShares<P, I>,
+    /// The IDs of the helping nodes.
+    pub helpers: BTreeSet<I>,
+    /// The ID of the node recovering its share.
+    pub lost_id: I,
+}
+
+impl<P: SchemeParams, I: PartyId> ShareRecoveryAssociatedData<P, I> {
+    /// Creates the associated data for evidence verification of ShareRecovery.
+    ///
+    /// Only the public part of `key_share` is used, so it does not have to belong to one of the `helpers`.
+    pub fn new(key_share: &ThresholdKeyShare<P, I>, helpers: BTreeSet<I>, lost_id: I) -> Result<Self, LocalError> {
+        ensure_not_hierarchical(key_share)?;
+        check_threshold_signers(key_share, &helpers)?;
+        Ok(Self {
+            public_key_shares: key_share.public_key_shares(),
+            helpers,
+            lost_id,
+        })
+    }
+
+    fn public_data(&self) -> PublicData<P, I> {
+        PublicData {
+            threshold: self.public_key_shares.threshold,
+            share_ids: self.public_key_shares.share_ids.clone(),
+            public_shares: self.public_key_shares.public_shares.clone(),
+            extra_shares: self.public_key_shares.extra_shares.clone(),
+        }
+    }
+
+    fn participants(&self) -> BTreeSet<I> {
+        let mut participants = self.helpers.clone();
+        participants.insert(self.lost_id.clone());
+        participants
+    }
+}
+
+impl<P: SchemeParams, I: PartyId> ProtocolError<I> for ShareRecoveryError<P, I> {
+    type AssociatedData = ShareRecoveryAssociatedData<P, I>;
+
+    fn required_messages(&self) -> RequiredMessages {
+        match self.error {
+            Error::R1WrongIdsY | Error::R2PublicDataMismatch | Error::R2InvalidCommitments => {
+                RequiredMessages::new(RequiredMessageParts::echo_broadcast(), None, None)
+            }
+            Error::R2InvalidPiece { .. } => RequiredMessages::new(
+                RequiredMessageParts::echo_broadcast().and_direct_message(),
+                Some([(1.into(), RequiredMessageParts::echo_broadcast())].into()),
+                Some([1.into()].into()),
+            ),
+            Error::R3InvalidSum { .. } => RequiredMessages::new(
+                RequiredMessageParts::direct_message(),
+                Some(
+                    [
+                        (1.into(), RequiredMessageParts::echo_broadcast()),
+                        (2.into(), RequiredMessageParts::echo_broadcast()),
+                    ]
+                    .into(),
+                ),
+                Some([1.into(), 2.into()].into()),
+            ),
+        }
+    }
+
+    fn verify_messages_constitute_error(
+        &self,
+        format: &BoxedFormat,
+        guilty_party: &I,
+        shared_randomness: &[u8],
+        associated_data: &Self::AssociatedData,
+        message: ProtocolMessage,
+        previous_messages: BTreeMap<RoundId, ProtocolMessage>,
+        combined_echos: BTreeMap<RoundId, BTreeMap<I, EchoBroadcast>>,
+    ) -> Result<(), ProtocolValidationError> {
+        let public_data = associated_data.public_data();
+        let participants = associated_data.participants();
+
+        match &self.error {
+            Error::R1WrongIdsY => {
+                let r1_eb = message
+                    .echo_broadcast
+                    .deserialize::<Round1EchoBroadcast<P, I>>(format)?;
+                verify_that(r1_eb.cap_ys.keys().cloned().collect::<BTreeSet<_>>() != participants.without(guilty_party))
+            }
+            Error::R2PublicDataMismatch => {
+                let r2_eb = message
+                    .echo_broadcast
+                    .deserialize::<Round2EchoBroadcast<P, I>>(format)?;
+                verify_that(r2_eb.public_data != public_data)
+            }
+            Error::R2InvalidCommitments => {
+                let r2_eb = message
+                    .echo_broadcast
+                    .deserialize::<Round2EchoBroadcast<P, I>>(format)?;
+                let valid = public_data
+                    .commitments_are_valid(
+                        &associated_data.helpers,
+                        &associated_data.lost_id,
+                        guilty_party,
+                        &r2_eb.cap_deltas,
+                    )
+                    .ok_or_else(|| ProtocolValidationError::InvalidEvidence("Invalid associated data".into()))?;
+                verify_that(!valid)
+            }
+            Error::R2InvalidPiece { reported_by, y } => {
+                // Check that `y` attached to the evidence is correct
+                // (that is, can be verified against something signed by `guilty_party`).
+                // It is `y_{k,j}` where `k == reported_by` and `j == guilty_party`
+                let r1_eb_k = combined_echos
+                    .get_round(1)?
+                    .try_get("combined echos for Round 1", reported_by)?
+                    .deserialize::<Round1EchoBroadcast<P, I>>(format)?;
+                let cap_y_kj = r1_eb_k.cap_ys.try_get("ephemeral keys", guilty_party)?;
+                if &y.mul_by_generator() != cap_y_kj {
+                    return Err(ProtocolValidationError::InvalidEvidence(
+                        "The provided `y` is invalid".into(),
+                    ));
+                }
+
+                let r1_eb = previous_messages
+                    .get_round(1)?
+                    .echo_broadcast
+                    .deserialize::<Round1EchoBroadcast<P, I>>(format)?;
+                let r2_eb = message
+                    .echo_broadcast
+                    .deserialize::<Round2EchoBroadcast<P, I>>(format)?;
+                let r2_dm = message
+                    .direct_message
+                    .deserialize::<Round2DirectMessage<P, I>>(format)?;
+
+                // Make sure the message was actually addressed to the reporting node.
+                if &r2_dm.destination != reported_by {
+                    return Err(ProtocolValidationError::InvalidEvidence(
+                        "The direct message was not addressed to the reporting node".into(),
+                    ));
+                }
+
+                let sid = Sid::new::<P, I>(shared_randomness, &participants);
+                let cap_y_jk = r1_eb.cap_ys.try_get("ephemeral keys", reported_by)?;
+                let cap_d = cap_y_jk * y;
+
+                let cap_deltas = r2_eb.cap_deltas.try_get("commitments to the pieces", reported_by)?;
+                verify_that(
+                    r2_dm.cap_cs.len() != cap_deltas.len()
+                        || r2_dm
+                            .cap_cs
+                            .iter()
+                            .zip(cap_deltas)
+                            .enumerate()
+                            .any(|(idx, (cap_c, cap_delta))| {
+                                let delta = *cap_c - dh_mask::<P, I>(MASK_DST, &sid, guilty_party, &cap_d, idx);
+                                &delta.mul_by_generator() != cap_delta
+                            }),
+                )
+            }
+            Error::R3InvalidSum { y } => {
+                let lost_id = &associated_data.lost_id;
+
+                // Check that `y` attached to the evidence is correct, same as for `R2InvalidPiece`.
+                let r1_eb_k = combined_echos
+                    .get_round(1)?
+                    .try_get("combined echos for Round 1", lost_id)?
+                    .deserialize::<Round1EchoBroadcast<P, I>>(format)?;
+                let cap_y_kj = r1_eb_k.cap_ys.try_get("ephemeral keys", guilty_party)?;
+                if &y.mul_by_generator() != cap_y_kj {
+                    return Err(ProtocolValidationError::InvalidEvidence(
+                        "The provided `y` is invalid".into(),
+                    ));
+                }
+
+                let r1_eb = previous_messages
+                    .get_round(1)?
+                    .echo_broadcast
+                    .deserialize::<Round1EchoBroadcast<P, I>>(format)?;
+                let r2_eb = previous_messages
+                    .get_round(2)?
+                    .echo_broadcast
+                    .deserialize::<Round2EchoBroadcast<P, I>>(format)?;
+                let mut r2_ebs = combined_echos
+                    .get_round(2)?
+                    .deserialize_all::<Round2EchoBroadcast<P, I>>(format)?;
+                r2_ebs.insert(guilty_party.clone(), r2_eb);
+                let r3_dm = message.direct_message.deserialize::<Round3DirectMessage<P>>(format)?;
+
+                let num_lost_shares = public_data.public_key_shares().weight(lost_id);
+                let cap_sigmas = (0..num_lost_shares)
+                    .map(|idx| {
+                        associated_data
+                            .helpers
+                            .iter()
+                            .map(|id| {
+                                r2_ebs
+                                    .try_get("Round 2 echo broadcasts", id)?
+                                    .cap_deltas
+                                    .try_get("commitments to the pieces", guilty_party)?
+                                    .get(idx)
+                                    .copied()
+                                    .ok_or_else(|| {
+                                        ProtocolValidationError::InvalidEvidence(
+                                            "A commitment to a piece is missing".into(),
+                                        )
+                                    })
+                            })
+                            .sum::<Result<Point<P>, _>>()
+                    })
+                    .collect::<Result<Vec<_>, _>>()?;
+
+                let sid = Sid::new::<P, I>(shared_randomness, &participants);
+                let cap_y_jk = r1_eb.cap_ys.try_get("ephemeral keys", lost_id)?;
+                let cap_d = cap_y_jk * y;
+                verify_that(
+                    r3_dm.cap_cs.len() != cap_sigmas.len()
+                        || r3_dm
+                            .cap_cs
+                            .iter()
+                            .zip(cap_sigmas)
+                            .enumerate()
+                            .any(|(idx, (cap_c, cap_sigma))| {
+                                let sigma = *cap_c - dh_mask::<P, I>(MASK_DST, &sid, guilty_party, &cap_d, idx);
+                                sigma.mul_by_generator() != cap_sigma
+                            }),
+                )
+            }
+        }
+    }
+}
+
+fn ensure_not_hierarchical<P: SchemeParams, I: PartyId>(key_share: &ThresholdKeyShare<P, I>) -> Result<(), LocalError> {
+    if key_share.access_structure().is_some() {
+        return Err(LocalError::new(
+            "Key shares with a hierarchical access structure are not supported by ShareRecovery",
+        ));
+    }
+    Ok(())
+}
+
+/// The public data of the key share.
+#[derive(Debug, Clone, PartialEq)]
+#[derive_where::derive_where(Serialize, Deserialize)]
+pub(super) struct PublicData<P: SchemeParams, I: PartyId> {
+    pub(super) threshold: u32,
+    share_ids: SerializableMap<I, ShareId<P>>,
+    public_shares: SerializableMap<I, Point<P>>,
+    extra_shares: SerializableMap<I, Vec<PublicShareWithId<P>>>,
+}
+
+impl<P: SchemeParams, I: PartyId> PublicData<P, I> {
+    fn new(key_share: &ThresholdKeyShare<P, I>) -> Self {
+        Self {
+            threshold: key_share.threshold,
+            share_ids: key_share.share_ids.clone(),
+            public_shares: key_share.public_shares.clone(),
+            extra_shares: key_share.extra_shares.clone(),
+        }
+    }
+
+    fn public_key_shares(&self) -> ThresholdPublicKeyShares<P, I> {
+        ThresholdPublicKeyShares {
+            threshold: self.threshold,
+            share_ids: self.share_ids.clone(),
+            public_shares: self.public_shares.clone(),
+            extra_shares: self.extra_shares.clone(),
+            access_structure: None,
+        }
+    }
+
+    /// Returns `true` if the commitments to the pieces of `id`'s weighted shares (one for each lost share)
+    /// add up to its weighted public shares, or `None` if the public data is inconsistent with the given IDs.
+    fn commitments_are_valid(
+        &self,
+        helpers: &BTreeSet<I>,
+        lost_id: &I,
+        id: &I,
+        cap_deltas: &BTreeMap<I, Vec<Point<P>>>,
+    ) -> Option<bool> {
+        let public_key_shares = self.public_key_shares();
+        let lost_shares = public_key_shares.holder_shares(lost_id).ok()?;
+        let shares = public_key_shares.holder_shares(id).ok()?;
+        let helper_share_ids = helper_share_ids(&public_key_shares, helpers).ok()?;
+
+        if !cap_deltas.keys().eq(helpers.iter()) || cap_deltas.values().any(|pieces| pieces.len() != lost_shares.len())
+        {
+            return Some(false);
+        }
+
+        Some(
+            lost_shares
+                .iter()
+                .enumerate()
+                .all(|(idx, (lost_share_id, _public_share))| {
+                    let weighted_public_share = shares
+                        .iter()
+                        .map(|(share_id, public_share)| {
+                            public_share * interpolation_coeff_at(helper_share_ids.iter(), share_id, lost_share_id)
+                        })
+                        .sum::<Point<P>>();
+                    cap_deltas
+                        .values()
+                        .filter_map(|pieces| pieces.get(idx))
+                        .copied()
+                        .sum::<Point<P>>()
+                        == weighted_public_share
+                }),
+        )
+    }
+}
+
+/// Returns the share IDs of all the shares held by the helpers.
+fn helper_share_ids<P: SchemeParams, I: PartyId>(
+    public_key_shares: &ThresholdPublicKeyShares<P, I>,
+    helpers: &BTreeSet<I>,
+) -> Result<BTreeSet<ShareId<P>>, LocalError> {
+    Ok(helpers
+        .iter()
+        .map(|id| public_key_shares.holder_shares(id))
+        .collect::<Result<Vec<_>, _>>()?
+        .into_iter()
+        .flatten()
+        .map(|(share_id, _public_share)| share_id)
+        .collect())
+}
+
+#[derive(Debug, Clone)]
+enum Role<P: SchemeParams, I: PartyId> {
+    Helper {
+        key_share: ThresholdKeyShare<P, I>,
+        lost_id: I,
+    },
+    Recovering {
+        verifying_key: VerifyingKey<P::Curve>,
+    },
+}
+
+/// An entry point for the [`ShareRecoveryProtocol`].
+#[derive(Debug, Clone)]
+pub struct ShareRecovery<P: SchemeParams, I: PartyId> {
+    helpers: BTreeSet<I>,
+    role: Role<P, I>,
+}
+
+impl<P: SchemeParams, I: PartyId> ShareRecovery<P, I> {
+    /// Creates a new entry point for a node helping to recover the share of `lost_id`.
+    ///
+    /// The `helpers` must be a minimal set of holders reaching the threshold of the key share
+    /// (for the holders with one share each, their number must be equal to the threshold).
+    pub fn new_helper(
+        key_share: ThresholdKeyShare<P, I>,
+        helpers: BTreeSet<I>,
+        lost_id: I,
+    ) -> Result<Self, LocalError> {
+        ensure_not_hierarchical(&key_share)?;
+        check_threshold_signers(&key_share, &helpers)?;
+        if helpers.contains(&lost_id) {
+            return Err(LocalError::new(
+                "The node recovering its share cannot be one of the helpers",
+            ));
+        }
+        if let Some(id) = helpers
+            .iter()
+            .chain([&lost_id])
+            .find(|id| !key_share.share_ids.contains_key(id))
+        {
+            return Err(LocalError::new(format!("{id:?} is not a holder of the key share")));
+        }
+        Ok(Self {
+            helpers,
+            role: Role::Helper { key_share, lost_id },
+        })
+    }
+
+    /// Creates a new entry point for the node recovering its share.
+    ///
+    /// The verifying key of the shared key is used to check the public data received from the helpers.
+    pub fn new_recovering(verifying_key: VerifyingKey<P::Curve>, helpers: BTreeSet<I>) -> Self {
+        Self {
+            helpers,
+            role: Role::Recovering { verifying_key },
+        }
+    }
+}
+
+impl<P: SchemeParams, I: PartyId> EntryPoint<I> for ShareRecovery<P, I> {
+    type Protocol = ShareRecoveryProtocol<P, I>;
+
+    fn entry_round_id() -> RoundId {
+        1.into()
+    }
+
+    fn make_round(
+        self,
+        rng: &mut dyn CryptoRngCore,
+        shared_randomness: &[u8],
+        id: &I,
+    ) -> Result<BoxedRound<I, Self::Protocol>, LocalError> {
+        let (lost_id, helper, recovering) = match self.role {
+            Role::Helper { key_share, lost_id } => {
+                if &key_share.owner != id {
+                    return Err(LocalError::new("The key share must belong to this node"));
+                }
+                if !self.helpers.contains(id) {
+                    return Err(LocalError::new("The given helper IDs must contain this node's ID"));
+                }
+
+                let public_data = PublicData::new(&key_share);
+                let helper_share_ids = helper_share_ids(&key_share.public_key_shares(), &self.helpers)?;
+                let own_shares = key_share.owner_secret_shares()?;
+
+                // The weighted sums of our shares, one for each lost share.
+                let weighted_shares = key_share
+                    .holder_shares(&lost_id)?
+                    .into_iter()
+                    .map(|(lost_share_id, _public_share)| {
+                        own_shares
+                            .iter()
+                            .fold(Secret::init_with(|| Scalar::ZERO), |acc, (share_id, secret_share)| {
+                                let coeff = interpolation_coeff_at(helper_share_ids.iter(), share_id, &lost_share_id);
+                                acc + secret_share.clone() * coeff
+                            })
+                    })
+                    .collect::<Vec<_>>();
+
+                // Split each weighted share into random additive pieces, one for each helper.
+                let mut deltas = self
+                    .helpers
+                    .iter()
+                    .filter(|helper_id| helper_id != &id)
+                    .map(|helper_id| {
+                        let pieces = weighted_shares
+                            .iter()
+                            .map(|_| Secret::init_with(|| Scalar::random(rng)))
+                            .collect::<Vec<_>>();
+                        (helper_id.clone(), pieces)
+                    })
+                    .collect::<BTreeMap<_, _>>();
+                let own_deltas = weighted_shares
+                    .into_iter()
+                    .enumerate()
+                    .map(|(idx, weighted_share)| {
+                        let others_sum = deltas
+                            .values()
+                            .filter_map(|pieces| pieces.get(idx))
+                            .sum::<Secret<Scalar<P>>>();
+                        weighted_share - others_sum
+                    })
+                    .collect();
+                deltas.insert(id.clone(), own_deltas);
+
+                let cap_deltas = deltas
+                    .iter()
+                    .map(|(id, pieces)| {
+                        let cap_pieces = pieces.iter().map(|delta| delta.mul_by_generator()).collect();
+                        (id.clone(), cap_pieces)
+                    })
+                    .collect::<BTreeMap<_, _>>()
+                    .into();
+
+                let helper = HelperData {
+                    public_data,
+                    deltas,
+                    cap_deltas,
+                };
+                (lost_id, Some(helper), None)
+            }
+            Role::Recovering { verifying_key } => {
+                if self.helpers.contains(id) {
+                    return Err(LocalError::new(
+                        "The node recovering its share cannot be one of the helpers",
+                    ));
+                }
+
+                let recovering = RecoveringData {
+                    verifying_key: Point::from_verifying_key(&verifying_key),
+                };
+                (id.clone(), None, Some(recovering))
+            }
+        };
+
+        let mut participants = self.helpers.clone();
+        participants.insert(lost_id.clone());
+        let sid = Sid::new::<P, I>(shared_randomness, &participants);
+        let other_ids = participants.without(id);
+
+        // Ephemeral Diffie-Hellman secrets for masking the values sent to and received from each other node.
+        let ys = other_ids
+            .iter()
+            .map(|id| (id.clone(), Secret::init_with(|| Scalar::random(rng))))
+            .collect();
+
+        let context = Context {
+            my_id: id.clone(),
+            helpers: self.helpers,
+            lost_id,
+            other_ids,
+            ys,
+            sid,
+        };
+
+        Ok(BoxedRound::new_dynamic(Round1 {
+            context,
+            helper,
+            recovering,
+        }))
+    }
+}
+
+#[derive(Debug)]
+struct Context<P: SchemeParams, I: PartyId> {
+    my_id: I,
+    helpers: BTreeSet<I>,
+    lost_id: I,
+    other_ids: BTreeSet<I>,
+    ys: BTreeMap<I, Secret<Scalar<P>>>, // $y_{i,j}$ where $i$ is this party's index
+    sid: Sid,
+}
+
+#[derive(Debug)]
+struct HelperData<P: SchemeParams, I: PartyId> {
+    public_data: PublicData<P, I>,
+    deltas: BTreeMap<I, Vec<Secret<Scalar<P>>>>,
+    cap_deltas: SerializableMap<I, Vec<Point<P>>>,
+}
+
+#[derive(Debug)]
+struct RecoveringData<P: SchemeParams> {
+    verifying_key: Point<P>,
+}
+
+#[derive(Debug)]
+struct Round1<P: SchemeParams, I: PartyId> {
+    context: Context<P, I>,
+    helper: Option<HelperData<P, I>>,
+    recovering: Option<RecoveringData<P>>,
+}
+
+#[derive(Debug, Clone)]
+#[derive_where::derive_where(Serialize, Deserialize)]
+pub(super) struct Round1EchoBroadcast<P: SchemeParams, I: PartyId> {
+    pub(super) cap_ys: SerializableMap<I, Point<P>>, // $Y_{i,j}$ where $i$ is this party's index
+}
+
+struct Round1Payload<P: SchemeParams> {
+    cap_y: Point<P>, // $Y_{j,i}$ where $i$ is this party's index
+}
+
+impl<P: SchemeParams, I: PartyId> Round<I> for Round1<P, I> {
+    type Protocol = ShareRecoveryProtocol<P, I>;
+
+    fn transition_info(&self) -> TransitionInfo {
+        TransitionInfo::new_linear(1)
+    }
+
+    fn communication_info(&self) -> CommunicationInfo<I> {
+        CommunicationInfo::regular(&self.context.other_ids)
+    }
+
+    fn make_echo_broadcast(
+        &self,
+        _rng: &mut dyn CryptoRngCore,
+        format: &BoxedFormat,
+    ) -> Result<EchoBroadcast, LocalError> {
+        let cap_ys = self.context.ys.map_values_ref(|y| y.mul_by_generator());
+        EchoBroadcast::new(format, Round1EchoBroadcast { cap_ys: cap_ys.into() })
+    }
+
+    fn receive_message(
+        &self,
+        format: &BoxedFormat,
+        from: &I,
+        message: ProtocolMessage,
+    ) -> Result<Payload, ReceiveError<I, Self::Protocol>> {
+        message.normal_broadcast.assert_is_none()?;
+        message.direct_message.assert_is_none()?;
+        let echo_broadcast = message
+            .echo_broadcast
+            .deserialize::<Round1EchoBroadcast<P, I>>(format)?;
+
+        let mut expected_ids = self.context.other_ids.clone().without(from);
+        expected_ids.insert(self.context.my_id.clone());
+        if echo_broadcast.cap_ys.keys().cloned().collect::<BTreeSet<_>>() != expected_ids {
+            return Err(ReceiveError::protocol(Error::R1WrongIdsY.into()));
+        }
+
+        let cap_y = *echo_broadcast.cap_ys.safe_get("ephemeral keys", &self.context.my_id)?;
+        Ok(Payload::new(Round1Payload { cap_y }))
+    }
+
+    fn finalize(
+        self: Box<Self>,
+        _rng: &mut dyn CryptoRngCore,
+        payloads: BTreeMap<I, Payload>,
+        _artifacts: BTreeMap<I, Artifact>,
+    ) -> Result<FinalizeOutcome<I, Self::Protocol>, LocalError> {
+        let payloads = payloads.downcast_all::<Round1Payload<P>>()?;
+        let cap_ys = payloads.map_values(|payload| payload.cap_y);
+
+        let my_id = &self.context.my_id;
+        let helpers = &self.context.helpers;
+        let communication_info = if self.helper.is_some() {
+            CommunicationInfo {
+                message_destinations: self.context.other_ids.clone(),
+                expecting_messages_from: helpers.clone().without(my_id),
+                echo_round_participation: EchoRoundParticipation::Default,
+            }
+        } else {
+            CommunicationInfo {
+                message_destinations: BTreeSet::new(),
+                expecting_messages_from: helpers.clone(),
+                echo_round_participation: EchoRoundParticipation::Receive {
+                    echo_targets: helpers.clone(),
+                },
+            }
+        };
+
+        Ok(FinalizeOutcome::AnotherRound(BoxedRound::new_dynamic(Round2 {
+            context: self.context,
+            cap_ys,
+            helper: self.helper,
+            recovering: self.recovering,
+            communication_info,
+        })))
+    }
+}
+
+#[derive(Debug)]
+struct Round2<P: SchemeParams, I: PartyId> {
+    context: Context<P, I>,
+    cap_ys: BTreeMap<I, Point<P>>, // $Y_{j,i}$ where $i$ is this party's index
+    helper: Option<HelperData<P, I>>,
+    recovering: Option<RecoveringData<P>>,
+    communication_info: CommunicationInfo<I>,
+}
+
+#[derive(Debug, Clone)]
+#[derive_where::derive_where(Serialize, Deserialize)]
+pub(super) struct Round2EchoBroadcast<P: SchemeParams, I: PartyId> {
+    pub(super) public_data: PublicData<P, I>,
+    /// The commitments $\Delta_{i,j}$ to the pieces of the weighted shares, one for each lost share.
+    pub(super) cap_deltas: SerializableMap<I, Vec<Point<P>>>,
+}
+
+#[derive(Debug, Clone)]
+#[derive_where::derive_where(Serialize, Deserialize)]
+pub(super) struct Round2DirectMessage<P: SchemeParams, I: PartyId> {
+    // Included so that the message could not be used as evidence by a node it was not addressed to.
+    pub(super) destination: I,
+    /// The masked pieces $\delta_{i,j}$ of the weighted shares, one for each lost share.
+    pub(super) cap_cs: Vec<Scalar<P>>,
+}
+
+struct Round2Payload<P: SchemeParams, I: PartyId> {
+    public_data: PublicData<P, I>,
+    cap_deltas: SerializableMap<I, Vec<Point<P>>>,
+    // Only set for the helpers
+    deltas: Option<Vec<Secret<Scalar<P>>>>,
+}
+
+impl<P: SchemeParams, I: PartyId> Round<I> for Round2<P, I> {
+    type Protocol = ShareRecoveryProtocol<P, I>;
+
+    fn transition_info(&self) -> TransitionInfo {
+        TransitionInfo::new_linear(2)
+    }
+
+    fn communication_info(&self) -> CommunicationInfo<I> {
+        self.communication_info.clone()
+    }
+
+    fn make_echo_broadcast(
+        &self,
+        _rng: &mut dyn CryptoRngCore,
+        format: &BoxedFormat,
+    ) -> Result<EchoBroadcast, LocalError> {
+        if let Some(helper) = self.helper.as_ref() {
+            EchoBroadcast::new(
+                format,
+                Round2EchoBroadcast {
+                    public_data: helper.public_data.clone(),
+                    cap_deltas: helper.cap_deltas.clone(),
+                },
+            )
+        } else {
+            Ok(EchoBroadcast::none())
+        }
+    }
+
+    fn make_direct_message(
+        &self,
+        _rng: &mut dyn CryptoRngCore,
+        format: &BoxedFormat,
+        destination: &I,
+    ) -> Result<(DirectMessage, Option<Artifact>), LocalError> {
+        // Only the other helpers get a direct message.
+        match self.helper.as_ref() {
+            Some(helper) if destination != &self.context.lost_id => {
+                let deltas = helper.deltas.safe_get("pieces of the weighted shares", destination)?;
+                let cap_y = self.cap_ys.safe_get("ephemeral public keys", destination)?;
+                let y = self.context.ys.safe_get("ephemeral secrets", destination)?;
+                let cap_d = cap_y * y;
+                let cap_cs = deltas
+                    .iter()
+                    .enumerate()
+                    .map(|(idx, delta)| {
+                        let rho = dh_mask::<P, I>(MASK_DST, &self.context.sid, &self.context.my_id, &cap_d, idx);
+                        *(delta + &rho).expose_secret()
+                    })
+                    .collect();
+                let direct_message = DirectMessage::new(
+                    format,
+                    Round2DirectMessage {
+                        destination: destination.clone(),
+                        cap_cs,
+                    },
+                )?;
+                Ok((direct_message, None))
+            }
+            _ => Ok((DirectMessage::none(), None)),
+        }
+    }
+
+    fn receive_message(
+        &self,
+        format: &BoxedFormat,
+        from: &I,
+        message: ProtocolMessage,
+    ) -> Result<Payload, ReceiveError<I, Self::Protocol>> {
+        message.normal_broadcast.assert_is_none()?;
+        let echo_broadcast = message
+            .echo_broadcast
+            .deserialize::<Round2EchoBroadcast<P, I>>(format)?;
+
+        let helper = match self.helper.as_ref() {
+            Some(helper) => helper,
+            None => {
+                message.direct_message.assert_is_none()?;
+                return Ok(Payload::new(Round2Payload {
+                    public_data: echo_broadcast.public_data,
+                    cap_deltas: echo_broadcast.cap_deltas,
+                    deltas: None,
+                }));
+            }
+        };
+
+        let direct_message = message
+            .direct_message
+            .deserialize::<Round2DirectMessage<P, I>>(format)?;
+
+        if echo_broadcast.public_data != helper.public_data {
+            return Err(ReceiveError::protocol(Error::R2PublicDataMismatch.into()));
+        }
+
+        let valid = helper
+            .public_data
+            .commitments_are_valid(
+                &self.context.helpers,
+                &self.context.lost_id,
+                from,
+                &echo_broadcast.cap_deltas,
+            )
+            .ok_or_else(|| LocalError::new("The public data is inconsistent with the helper IDs"))?;
+        if !valid {
+            return Err(ReceiveError::protocol(Error::R2InvalidCommitments.into()));
+        }
+
+        if direct_message.destination != self.context.my_id {
+            return Err(ReceiveError::unprovable(
+                "The direct message is addressed to another node",
+            ));
+        }
+
+        let cap_y = self.cap_ys.safe_get("ephemeral public keys", from)?;
+        let y = self.context.ys.safe_get("ephemeral secrets", from)?;
+        let cap_d = cap_y * y;
+        let deltas = direct_message
+            .cap_cs
+            .iter()
+            .enumerate()
+            .map(|(idx, cap_c)| {
+                let rho = dh_mask::<P, I>(MASK_DST, &self.context.sid, from, &cap_d, idx);
+                Secret::init_with(|| *cap_c - rho)
+            })
+            .collect::<Vec<_>>();
+
+        let cap_deltas = echo_broadcast
+            .cap_deltas
+            .safe_get("commitments to the pieces", &self.context.my_id)?;
+        if deltas.len() != cap_deltas.len()
+            || deltas
+                .iter()
+                .zip(cap_deltas)
+                .any(|(delta, cap_delta)| &delta.mul_by_generator() != cap_delta)
+        {
+            return Err(ReceiveError::protocol(
+                Error::R2InvalidPiece {
+                    reported_by: self.context.my_id.clone(),
+                    y: *y.expose_secret(),
+                }
+                .into(),
+            ));
+        }
+
+        Ok(Payload::new(Round2Payload {
+            public_data: echo_broadcast.public_data,
+            cap_deltas: echo_broadcast.cap_deltas,
+            deltas: Some(deltas),
+        }))
+    }
+
+    fn finalize(
+        self: Box<Self>,
+        _rng: &mut dyn CryptoRngCore,
+        payloads: BTreeMap<I, Payload>,
+        _artifacts: BTreeMap<I, Artifact>,
+    ) -> Result<FinalizeOutcome<I, Self::Protocol>, LocalError> {
+        let payloads = payloads.downcast_all::<Round2Payload<P, I>>()?;
+
+        if let Some(helper) = self.helper {
+            // The sums of the pieces received from the other helpers and the pieces we kept for ourselves,
+            // one for each lost share.
+            let own_deltas = helper
+                .deltas
+                .safe_get("pieces of the weighted shares", &self.context.my_id)?;
+            let sigmas = own_deltas
+                .iter()
+                .enumerate()
+                .map(|(idx, own_delta)| {
+                    payloads.values().try_fold(own_delta.clone(), |acc, payload| {
+                        let delta = payload
+                            .deltas
+                            .as_ref()
+                            .and_then(|deltas| deltas.get(idx))
+                            .ok_or_else(|| LocalError::new("A piece of the weighted shares is missing"))?;
+                        Ok(acc + delta.clone())
+                    })
+                })
+                .collect::<Result<Vec<_>, LocalError>>()?;
+
+            return Ok(FinalizeOutcome::AnotherRound(BoxedRound::new_dynamic(Round3 {
+                communication_info: CommunicationInfo {
+                    message_destinations: [self.context.lost_id.clone()].into(),
+                    expecting_messages_from: BTreeSet::new(),
+                    echo_round_participation: EchoRoundParticipation::Default,
+                },
+                context: self.context,
+                cap_ys: self.cap_ys,
+                sigmas: Some(sigmas),
+                recovering: None,
+            })));
+        }
+
+        let recovering = self
+            .recovering
+            .ok_or_else(|| LocalError::new("A node must be either a helper or the recovering node"))?;
+
+        // Each helper checked the others' public data against its own, so if at least one of them is honest,
+        // they all sent the correct one. Otherwise it is caught by the check against the verifying key.
+        let mut public_datas = payloads.values().map(|payload| &payload.public_data);
+        let public_data = public_datas
+            .next()
+            .ok_or_else(|| LocalError::new("No messages from the helpers"))?
+            .clone();
+        if public_datas.any(|data| data != &public_data) {
+            return Err(LocalError::new("The helpers sent different public data"));
+        }
+
+        let public_key_shares = public_data.public_key_shares();
+        let helpers_weight = self
+            .context
+            .helpers
+            .iter()
+            .map(|id| public_key_shares.weight(id))
+            .sum::<usize>();
+        if helpers_weight < public_key_shares.threshold() {
+            return Err(LocalError::new(format!(
+                "The total weight of the helpers ({helpers_weight}) must reach the threshold ({})",
+                public_key_shares.threshold()
+            )));
+        }
+
+        if public_key_shares.verifying_key_as_point()? != recovering.verifying_key {
+            return Err(LocalError::new(
+                "The public data received from the helpers does not match the verifying key",
+            ));
+        }
+
+        // The expected $g * \sigma_j$ for each helper $j$ and each lost share.
+        let num_lost_shares = public_key_shares.holder_shares(&self.context.my_id)?.len();
+        let cap_sigmas = self
+            .context
+            .helpers
+            .iter()
+            .map(|helper_id| {
+                let cap_sigmas = (0..num_lost_shares)
+                    .map(|idx| {
+                        payloads
+                            .values()
+                            .map(|payload| {
+                                payload
+                                    .cap_deltas
+                                    .safe_get("commitments to the pieces", helper_id)?
+                                    .get(idx)
+                                    .copied()
+                                    .ok_or_else(|| LocalError::new("A commitment to a piece is missing"))
+                            })
+                            .sum::<Result<Point<P>, _>>()
+                    })
+                    .collect::<Result<Vec<_>, _>>()?;
+                Ok((helper_id.clone(), cap_sigmas))
+            })
+            .collect::<Result<BTreeMap<_, _>, LocalError>>()?;
+
+        Ok(FinalizeOutcome::AnotherRound(BoxedRound::new_dynamic(Round3 {
+            communication_info: CommunicationInfo {
+                message_destinations: BTreeSet::new(),
+                expecting_messages_from: self.context.helpers.clone(),
+                echo_round_participation: EchoRoundParticipation::Default,
+            },
+            context: self.context,
+            cap_ys: self.cap_ys,
+            sigmas: None,
+            recovering: Some(Round3RecoveringData {
+                public_key_shares,
+                cap_sigmas,
+            }),
+        })))
+    }
+}
+
+#[derive(Debug)]
+struct Round3RecoveringData<P: SchemeParams, I: PartyId> {
+    public_key_shares: ThresholdPublicKeyShares<P, I>,
+    cap_sigmas: BTreeMap<I, Vec<Point<P>>>,
+}
+
+#[derive(Debug)]
+struct Round3<P: SchemeParams, I: PartyId> {
+    context: Context<P, I>,
+    cap_ys: BTreeMap<I, Point<P>>, // $Y_{j,i}$ where $i$ is this party's index
+    communication_info: CommunicationInfo<I>,
+    // Only set for the helpers
+    sigmas: Option<Vec<Secret<Scalar<P>>>>,
+    recovering: Option<Round3RecoveringData<P, I>>,
+}
+
+#[derive(Debug, Clone)]
+#[derive_where::derive_where(Serialize, Deserialize)]
+pub(super) struct Round3DirectMessage<P: SchemeParams> {
+    /// The masked sums $\sigma_j$ of the pieces received by the helper, one for each lost share.
+    pub(super) cap_cs: Vec<Scalar<P>>,
+}
+
+struct Round3Payload<P: SchemeParams> {
+    sigmas: Vec<Secret<Scalar<P>>>,
+}
+
+impl<P: SchemeParams, I: PartyId> Round<I> for Round3<P, I> {
+    type Protocol = ShareRecoveryProtocol<P, I>;
+
+    fn transition_info(&self) -> TransitionInfo {
+        TransitionInfo::new_linear_terminating(3)
+    }
+
+    fn communication_info(&self) -> CommunicationInfo<I> {
+        self.communication_info.clone()
+    }
+
+    fn make_direct_message(
+        &self,
+        _rng: &mut dyn CryptoRngCore,
+        format: &BoxedFormat,
+        destination: &I,
+    ) -> Result<(DirectMessage, Option<Artifact>), LocalError> {
+        // Only the helpers send messages, and only to the recovering node.
+        let sigmas = self
+            .sigmas
+            .as_ref()
+            .ok_or_else(|| LocalError::new("Only the helpers send messages in Round 3"))?;
+        let cap_y = self.cap_ys.safe_get("ephemeral public keys", destination)?;
+        let y = self.context.ys.safe_get("ephemeral secrets", destination)?;
+        let cap_d = cap_y * y;
+        let cap_cs = sigmas
+            .iter()
+            .enumerate()
+            .map(|(idx, sigma)| {
+                let rho = dh_mask::<P, I>(MASK_DST, &self.context.sid, &self.context.my_id, &cap_d, idx);
+                *(sigma + &rho).expose_secret()
+            })
+            .collect();
+        let direct_message = DirectMessage::new(format, Round3DirectMessage { cap_cs })?;
+        Ok((direct_message, None))
+    }
+
+    fn receive_message(
+        &self,
+        format: &BoxedFormat,
+        from: &I,
+        message: ProtocolMessage,
+    ) -> Result<Payload, ReceiveError<I, Self::Protocol>> {
+        message.echo_broadcast.assert_is_none()?;
+        message.normal_broadcast.assert_is_none()?;
+        let direct_message = message.direct_message.deserialize::<Round3DirectMessage<P>>(format)?;
+
+        let recovering = self
+            .recovering
+            .as_ref()
+            .ok_or_else(|| LocalError::new("Only the recovering node receives messages in Round 3"))?;
+
+        let cap_y = self.cap_ys.safe_get("ephemeral public keys", from)?;
+        let y = self.context.ys.safe_get("ephemeral secrets", from)?;
+        let cap_d = cap_y * y;
+        let sigmas = direct_message
+            .cap_cs
+            .iter()
+            .enumerate()
+            .map(|(idx, cap_c)| {
+                let rho = dh_mask::<P, I>(MASK_DST, &self.context.sid, from, &cap_d, idx);
+                Secret::init_with(|| *cap_c - rho)
+            })
+            .collect::<Vec<_>>();
+
+        let cap_sigmas = recovering.cap_sigmas.safe_get("expected sums", from)?;
+        if sigmas.len() != cap_sigmas.len()
+            || sigmas
+                .iter()
+                .zip(cap_sigmas)
+                .any(|(sigma, cap_sigma)| &sigma.mul_by_generator() != cap_sigma)
+        {
+            return Err(ReceiveError::protocol(
+                Error::R3InvalidSum { y: *y.expose_secret() }.into(),
+            ));
+        }
+
+        Ok(Payload::new(Round3Payload { sigmas }))
+    }
+
+    fn finalize(
+        self: Box<Self>,
+        _rng: &mut dyn CryptoRngCore,
+        payloads: BTreeMap<I, Payload>,
+        _artifacts: BTreeMap<I, Artifact>,
+    ) -> Result<FinalizeOutcome<I, Self::Protocol>, LocalError> {
+        // If this party is not the recovering node, exit.
+        let recovering = match self.recovering {
+            Some(recovering) => recovering,
+            None => return Ok(FinalizeOutcome::Result(None)),
+        };
+
+        let payloads = payloads.downcast_all::<Round3Payload<P>>()?;
+        let public_key_shares = recovering.public_key_shares;
+        let lost_shares = public_key_shares.holder_shares(&self.context.my_id)?;
+        let secret_shares = (0..lost_shares.len())
+            .map(|idx| {
+                payloads
+                    .values()
+                    .try_fold(Secret::init_with(|| Scalar::ZERO), |acc, payload| {
+                        let sigma = payload
+                            .sigmas
+                            .get(idx)
+                            .ok_or_else(|| LocalError::new("A sum of the pieces is missing"))?;
+                        Ok(acc + sigma.clone())
+                    })
+            })
+            .collect::<Result<Vec<_>, LocalError>>()?;
+
+        // Each sum matches the commitments, and each helper checked that the commitments of the others
+        // add up to their weighted public shares, so this can only happen if all the helpers are colluding.
+        if secret_shares
+            .iter()
+            .zip(&lost_shares)
+            .any(|(secret_share, (_share_id, public_share))| &secret_share.mul_by_generator() != public_share)
+        {
+            return Err(LocalError::new("The recovered shares do not match the public shares"));
+        }
+
+        let holders = public_key_shares
+            .holders()
+            .into_iter()
+            .map(|id| {
+                let shares = public_key_shares.holder_shares(&id)?;
+                Ok((id, shares))
+            })
+            .collect::<Result<BTreeMap<_, _>, LocalError>>()?;
+        let key_share = ThresholdKeyShare::from_weighted_parts(
+            self.context.my_id,
+            public_key_shares.threshold(),
+            secret_shares,
+            holders,
+        )?;
+
+        Ok(FinalizeOutcome::Result(Some(key_share)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use alloc::collections::{BTreeMap, BTreeSet};
+
+    use manul::{
+        dev::{run_sync, BinaryFormat, TestSessionParams, TestSigner, TestVerifier},
+        signature::Keypair,
+    };
+    use rand_core::OsRng;
+
+    use super::ShareRecovery;
+    use crate::{dev::TestParams, ShareIdAssignment, ThresholdKeyShare};
+
+    fn recover(
+        signers: Vec<TestSigner>,
+        key_shares: &BTreeMap<TestVerifier, ThresholdKeyShare<TestParams, TestVerifier>>,
+        helpers: &BTreeSet<TestVerifier>,
+        lost_id: TestVerifier,
+    ) -> ThresholdKeyShare<TestParams, TestVerifier> {
+        let verifying_key = key_shares[&lost_id].verifying_key().unwrap();
+
+        let entry_points = signers
+            .into_iter()
+            .filter(|signer| helpers.contains(&signer.verifying_key()) || signer.verifying_key() == lost_id)
+            .map(|signer| {
+                let id = signer.verifying_key();
+                let entry_point = if id == lost_id {
+                    ShareRecovery::new_recovering(verifying_key, helpers.clone())
+                } else {
+                    ShareRecovery::new_helper(key_shares[&id].clone(), helpers.clone(), lost_id).unwrap()
+                };
+                (signer, entry_point)
+            })
+            .collect::<Vec<_>>();
+
+        let mut results = run_sync::<_, TestSessionParams<BinaryFormat>>(&mut OsRng, entry_points)
+            .unwrap()
+            .results()
+            .unwrap();
+
+        assert!(helpers.iter().all(|id| results[id].is_none()));
+
+        results.remove(&lost_id).unwrap().unwrap()
+    }
+
+    #[test]
+    fn execute_share_recovery() {
+        let signers = (0..5).map(TestSigner::new).collect::<Vec<_>>();
+        let ids = signers.iter().map(|signer| signer.verifying_key()).collect::<Vec<_>>();
+        let all_ids = ids.iter().cloned().collect::<BTreeSet<_>>();
+
+        let key_shares =
+            ThresholdKeyShare::<TestParams, TestVerifier>::new_centralized(&mut OsRng, &all_ids, 3, None).unwrap();
+
+        let helpers = BTreeSet::from([ids[0], ids[2], ids[3]]);
+        let lost_id = ids[4];
+
+        // The number of helpers must be equal to the threshold
+        assert!(
+            ShareRecovery::new_helper(key_shares[&ids[0]].clone(), BTreeSet::from([ids[0], ids[2]]), lost_id).is_err()
+        );
+
+        // The recovering node cannot be one of the helpers
+        assert!(ShareRecovery::new_helper(key_shares[&ids[0]].clone(), helpers.clone(), ids[0]).is_err());
+
+        let recovered = recover(signers, &key_shares, &helpers, lost_id);
+        let original = &key_shares[&lost_id];
+        assert_eq!(
+            recovered.secret_share.expose_secret(),
+            original.secret_share.expose_secret()
+        );
+        assert_eq!(recovered.share_ids, original.share_ids);
+        assert_eq!(recovered.public_shares, original.public_shares);
+        assert_eq!(recovered.threshold(), original.threshold());
+    }
+
+    #[test]
+    fn execute_weighted_share_recovery() {
+        let signers = (0..4).map(TestSigner::new).collect::<Vec<_>>();
+        let ids = signers.iter().map(|signer| signer.verifying_key()).collect::<Vec<_>>();
+        let weights = BTreeMap::from([(ids[0], 2), (ids[1], 1), (ids[2], 1), (ids[3], 2)]);
+
+        let key_shares = ThresholdKeyShare::<TestParams, TestVerifier>::new_centralized_weighted(
+            &mut OsRng,
+            &weights,
+            3,
+            None,
+            &ShareIdAssignment::Sequential,
+        )
+        .unwrap();
+
+        // The total weight of the helpers reaches the threshold with fewer helpers than the threshold,
+        // and the lost node has two shares.
+        let helpers = BTreeSet::from([ids[0], ids[2]]);
+        let lost_id = ids[3];
+
+        // The helpers must be a minimal set reaching the threshold
+        assert!(ShareRecovery::new_helper(
+            key_shares[&ids[0]].clone(),
+            BTreeSet::from([ids[0], ids[1], ids[2]]),
+            lost_id
+        )
+        .is_err());
+
+        let recovered = recover(signers, &key_shares, &helpers, lost_id);
+        let original = &key_shares[&lost_id];
+        let recovered_secret_shares = recovered.owner_secret_shares().unwrap();
+        let original_secret_shares = original.owner_secret_shares().unwrap();
+        assert_eq!(recovered_secret_shares.len(), 2);
+        for ((recovered_id, recovered_share), (original_id, original_share)) in
+            recovered_secret_shares.iter().zip(&original_secret_shares)
+        {
+            assert_eq!(recovered_id, original_id);
+            assert_eq!(recovered_share.expose_secret(), original_share.expose_secret());
+        }
+        assert_eq!(recovered.share_ids, original.share_ids);
+        assert_eq!(recovered.public_shares, original.public_shares);
+        assert_eq!(recovered.extra_shares, original.extra_shares);
+        assert_eq!(recovered.threshold(), original.threshold());
+        assert_eq!(recovered.verifying_key().unwrap(), original.verifying_key().unwrap());
+    }
+}
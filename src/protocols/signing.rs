@@ -0,0 +1,399 @@
+//! ECDSA Signing (Fig. 10), using a [`Presignature`] created by the Presigning protocol.
+//!
+//! Along with its signature share, every party broadcasts a commitment to the presigning session,
+//! to the part of the presigning transcript its share depends on, and to the message.
+//! This way the evidence of an invalid signature share is bound to the transcript the guilty party actually used,
+//! and cannot be created from a forged one.
+
+use alloc::{
+    boxed::Box,
+    collections::{BTreeMap, BTreeSet},
+};
+use core::{
+    fmt::{self, Debug, Display},
+    marker::PhantomData,
+};
+
+use manul::protocol::{
+    Artifact, BoxedFormat, BoxedRound, CommunicationInfo, DirectMessage, EchoBroadcast, EntryPoint, FinalizeOutcome,
+    LocalError, MessageValidationError, NormalBroadcast, PartyId, Payload, Protocol, ProtocolError, ProtocolMessage,
+    ProtocolMessagePart, ProtocolValidationError, ReceiveError, RequiredMessageParts, RequiredMessages, Round, RoundId,
+    TransitionInfo,
+};
+use rand_core::CryptoRngCore;
+use serde::{Deserialize, Serialize};
+
+use super::interactive_signing::{PrehashedMessage, Presignature, PresignatureTranscript};
+use crate::{
+    curve::{RecoverableSignature, Scalar},
+    params::SchemeParams,
+    tools::{
+        hashing::HashOutput,
+        protocol_shortcuts::{verify_that, DowncastMap, Without},
+    },
+};
+
+/// A protocol for signing a message given a [`Presignature`].
+#[derive(Debug, Clone, Copy)]
+pub struct SigningProtocol<P: SchemeParams, Id: Debug>(PhantomData<(P, Id)>);
+
+impl<P: SchemeParams, Id: PartyId> Protocol<Id> for SigningProtocol<P, Id> {
+    type Result = RecoverableSignature<P>;
+    type ProtocolError = SigningError<P, Id>;
+
+    fn verify_direct_message_is_invalid(
+        _format: &BoxedFormat,
+        round_id: &RoundId,
+        message: &DirectMessage,
+    ) -> Result<(), MessageValidationError> {
+        match round_id {
+            r if r == &1 => message.verify_is_some(),
+            _ => Err(MessageValidationError::InvalidEvidence("Invalid round number".into())),
+        }
+    }
+
+    fn verify_echo_broadcast_is_invalid(
+        _format: &BoxedFormat,
+        round_id: &RoundId,
+        message: &EchoBroadcast,
+    ) -> Result<(), MessageValidationError> {
+        match round_id {
+            r if r == &1 => message.verify_is_some(),
+            _ => Err(MessageValidationError::InvalidEvidence("Invalid round number".into())),
+        }
+    }
+
+    fn verify_normal_broadcast_is_invalid(
+        format: &BoxedFormat,
+        round_id: &RoundId,
+        message: &NormalBroadcast,
+    ) -> Result<(), MessageValidationError> {
+        match round_id {
+            r if r == &1 => message.verify_is_not::<Round1NormalBroadcast<P>>(format),
+            _ => Err(MessageValidationError::InvalidEvidence("Invalid round number".into())),
+        }
+    }
+}
+
+/// Possible verifiable errors of the Signing protocol.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+pub struct SigningError<P, Id> {
+    error: Error,
+    phantom: PhantomData<(P, Id)>,
+}
+
+impl<P, Id> Display for SigningError<P, Id> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> Result<(), fmt::Error> {
+        write!(f, "{}", self.error)
+    }
+}
+
+impl<P, Id> From<Error> for SigningError<P, Id> {
+    fn from(source: Error) -> Self {
+        Self {
+            error: source,
+            phantom: PhantomData,
+        }
+    }
+}
+
+#[derive(displaydoc::Display, Debug, Clone, Copy, Serialize, Deserialize)]
+enum Error {
+    /// Round 1: signature share verification failed.
+    R1InvalidSignatureShare,
+}
+
+/// Associated data for Signing protocol.
+///
+/// The evidence is only valid if `transcript` and `message` are the same as the ones the guilty party used,
+/// which is checked against the commitment signed by the guilty party.
+#[derive(Debug, Clone)]
+pub struct SigningAssociatedData<P: SchemeParams, Id: PartyId> {
+    /// The public part of the presignatures used for signing.
+    pub transcript: PresignatureTranscript<P, Id>,
+    /// The message that was signed.
+    pub message: PrehashedMessage<P::Curve>,
+}
+
+impl<P: SchemeParams, Id: PartyId> ProtocolError<Id> for SigningError<P, Id> {
+    type AssociatedData = SigningAssociatedData<P, Id>;
+
+    fn required_messages(&self) -> RequiredMessages {
+        match self.error {
+            Error::R1InvalidSignatureShare => {
+                RequiredMessages::new(RequiredMessageParts::normal_broadcast(), None, None)
+            }
+        }
+    }
+
+    fn verify_messages_constitute_error(
+        &self,
+        format: &BoxedFormat,
+        guilty_party: &Id,
+        _shared_randomness: &[u8],
+        associated_data: &Self::AssociatedData,
+        message: ProtocolMessage,
+        _previous_messages: BTreeMap<RoundId, ProtocolMessage>,
+        _combined_echos: BTreeMap<RoundId, BTreeMap<Id, EchoBroadcast>>,
+    ) -> Result<(), ProtocolValidationError> {
+        match self.error {
+            Error::R1InvalidSignatureShare => {
+                let r1_nb = message
+                    .normal_broadcast
+                    .deserialize::<Round1NormalBroadcast<P>>(format)?;
+                let scalar_message = Scalar::from_reduced_bytes(&associated_data.message);
+                let commitment = associated_data
+                    .transcript
+                    .share_commitment(guilty_party, &scalar_message)
+                    .ok_or_else(|| {
+                        ProtocolValidationError::InvalidEvidence("Malformed presignature transcript".into())
+                    })?;
+                if commitment != r1_nb.commitment {
+                    return Err(ProtocolValidationError::InvalidEvidence(
+                        "The presignature transcript or the message are not the ones the guilty party committed to"
+                            .into(),
+                    ));
+                }
+                let share_is_valid = associated_data
+                    .transcript
+                    .signature_share_is_valid(guilty_party, &scalar_message, &r1_nb.sigma)
+                    .ok_or_else(|| {
+                        ProtocolValidationError::InvalidEvidence("Malformed presignature transcript".into())
+                    })?;
+                verify_that(!share_is_valid)
+            }
+        }
+    }
+}
+
+/// An entry point for the [`SigningProtocol`].
+#[derive(Debug, Clone)]
+pub struct Signing<P, Id>
+where
+    P: SchemeParams,
+    Id: PartyId,
+{
+    presignature: Presignature<P, Id>,
+    message: PrehashedMessage<P::Curve>,
+}
+
+impl<P: SchemeParams, Id: PartyId> Signing<P, Id> {
+    /// Creates a new entry point given a presignature.
+    ///
+    /// **Warning:** the presignature must not be used again after this.
+    pub fn new(message: PrehashedMessage<P::Curve>, presignature: Presignature<P, Id>) -> Self {
+        Self { presignature, message }
+    }
+}
+
+impl<P: SchemeParams, Id: PartyId> EntryPoint<Id> for Signing<P, Id> {
+    type Protocol = SigningProtocol<P, Id>;
+
+    fn entry_round_id() -> RoundId {
+        1.into()
+    }
+
+    fn make_round(
+        self,
+        _rng: &mut dyn CryptoRngCore,
+        _shared_randomness: &[u8],
+        id: &Id,
+    ) -> Result<BoxedRound<Id, Self::Protocol>, LocalError> {
+        if id != self.presignature.owner() {
+            return Err(LocalError::new("ID mismatch between the signer and the presignature"));
+        }
+
+        let other_ids = self.presignature.transcript().ids().without(id);
+        let scalar_message = Scalar::from_reduced_bytes(self.message);
+        let sigma = self.presignature.signature_share(&scalar_message);
+        let commitment = self
+            .presignature
+            .transcript()
+            .share_commitment(id, &scalar_message)
+            .ok_or_else(|| LocalError::new("Malformed presignature transcript"))?;
+
+        Ok(BoxedRound::new_dynamic(Round1 {
+            other_ids,
+            transcript: self.presignature.transcript().clone(),
+            scalar_message,
+            sigma,
+            commitment,
+        }))
+    }
+}
+
+#[derive(Debug)]
+struct Round1<P: SchemeParams, Id: PartyId> {
+    other_ids: BTreeSet<Id>,
+    transcript: PresignatureTranscript<P, Id>,
+    scalar_message: Scalar<P>,
+    sigma: Scalar<P>,
+    commitment: HashOutput,
+}
+
+#[derive(Clone)]
+#[derive_where::derive_where(Serialize, Deserialize)]
+pub(super) struct Round1NormalBroadcast<P: SchemeParams> {
+    pub(super) sigma: Scalar<P>,
+    /// The commitment to the presigning transcript and the message the signature share was created for.
+    pub(super) commitment: HashOutput,
+}
+
+struct Round1Payload<P: SchemeParams> {
+    sigma: Scalar<P>,
+}
+
+impl<P: SchemeParams, Id: PartyId> Round<Id> for Round1<P, Id> {
+    type Protocol = SigningProtocol<P, Id>;
+
+    fn transition_info(&self) -> TransitionInfo {
+        TransitionInfo::new_linear_terminating(1)
+    }
+
+    fn communication_info(&self) -> CommunicationInfo<Id> {
+        CommunicationInfo::regular(&self.other_ids)
+    }
+
+    fn make_normal_broadcast(
+        &self,
+        _rng: &mut dyn CryptoRngCore,
+        format: &BoxedFormat,
+    ) -> Result<NormalBroadcast, LocalError> {
+        NormalBroadcast::new(
+            format,
+            Round1NormalBroadcast {
+                sigma: self.sigma,
+                commitment: self.commitment.clone(),
+            },
+        )
+    }
+
+    fn receive_message(
+        &self,
+        format: &BoxedFormat,
+        from: &Id,
+        message: ProtocolMessage,
+    ) -> Result<Payload, ReceiveError<Id, Self::Protocol>> {
+        message.echo_broadcast.assert_is_none()?;
+        message.direct_message.assert_is_none()?;
+        let normal_broadcast = message
+            .normal_broadcast
+            .deserialize::<Round1NormalBroadcast<P>>(format)?;
+
+        // An honest party commits to the same echoed presigning values and the same message as we have,
+        // and to its own values it sent to everyone, so a mismatch means the sender is misbehaving.
+        // It cannot be proven though, since the presigning messages are not a part of this session.
+        let commitment = self
+            .transcript
+            .share_commitment(from, &self.scalar_message)
+            .ok_or_else(|| LocalError::new("Malformed presignature transcript"))?;
+        if commitment != normal_broadcast.commitment {
+            return Err(ReceiveError::unprovable(
+                "The signature share is committed to a different presigning transcript or message",
+            ));
+        }
+
+        let share_is_valid = self
+            .transcript
+            .signature_share_is_valid(from, &self.scalar_message, &normal_broadcast.sigma)
+            .ok_or_else(|| LocalError::new("Malformed presignature transcript"))?;
+        if !share_is_valid {
+            return Err(ReceiveError::protocol(Error::R1InvalidSignatureShare.into()));
+        }
+
+        Ok(Payload::new(Round1Payload {
+            sigma: normal_broadcast.sigma,
+        }))
+    }
+
+    fn finalize(
+        self: Box<Self>,
+        _rng: &mut dyn CryptoRngCore,
+        payloads: BTreeMap<Id, Payload>,
+        _artifacts: BTreeMap<Id, Artifact>,
+    ) -> Result<FinalizeOutcome<Id, Self::Protocol>, LocalError> {
+        let payloads = payloads.downcast_all::<Round1Payload<P>>()?;
+
+        let assembled_sigma = payloads.values().map(|payload| payload.sigma).sum::<Scalar<P>>() + self.sigma;
+
+        self.transcript
+            .assemble_signature(assembled_sigma, self.scalar_message)
+            .map(FinalizeOutcome::Result)
+            .ok_or_else(|| LocalError::new("Failed to assemble the signature"))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use alloc::collections::BTreeSet;
+
+    use ecdsa::{signature::hazmat::PrehashVerifier, VerifyingKey};
+    use elliptic_curve::FieldBytes;
+    use manul::{
+        dev::{run_sync, BinaryFormat, TestSessionParams, TestSigner, TestVerifier},
+        signature::Keypair,
+    };
+    use rand_core::{OsRng, RngCore};
+
+    use super::Signing;
+    use crate::{
+        dev::TestParams,
+        entities::{AuxInfo, KeyShare},
+        protocols::Presigning,
+        SchemeParams,
+    };
+    type Curve = <TestParams as SchemeParams>::Curve;
+
+    #[test]
+    fn execute_presigning_and_signing() {
+        let signers = (0..3).map(TestSigner::new).collect::<Vec<_>>();
+        let ids = signers.iter().map(|signer| signer.verifying_key()).collect::<Vec<_>>();
+        let ids_set = BTreeSet::from_iter(ids.clone());
+
+        let key_shares = KeyShare::<TestParams, TestVerifier>::new_centralized(&mut OsRng, &ids_set, None);
+        let aux_infos = AuxInfo::new_centralized(&mut OsRng, &ids_set);
+
+        let entry_points = signers
+            .iter()
+            .map(|signer| {
+                let id = signer.verifying_key();
+                let entry_point = Presigning::new(key_shares[&id].clone(), aux_infos[&id].clone()).unwrap();
+                (*signer, entry_point)
+            })
+            .collect();
+
+        let presignatures = run_sync::<_, TestSessionParams<BinaryFormat>>(&mut OsRng, entry_points)
+            .unwrap()
+            .results()
+            .unwrap();
+
+        let mut message = FieldBytes::<Curve>::default();
+        OsRng.fill_bytes(&mut message);
+
+        let entry_points = signers
+            .into_iter()
+            .map(|signer| {
+                let id = signer.verifying_key();
+                let entry_point = Signing::new(message, presignatures[&id].clone());
+                (signer, entry_point)
+            })
+            .collect();
+
+        let mut signatures = run_sync::<_, TestSessionParams<BinaryFormat>>(&mut OsRng, entry_points)
+            .unwrap()
+            .results()
+            .unwrap();
+
+        while let Some((_, signature)) = signatures.pop_first() {
+            let (sig, rec_id) = signature.to_backend();
+            let vkey = key_shares[&ids[0]].verifying_key();
+
+            // Check that the signature can be verified
+            vkey.verify_prehash(&message, &sig).unwrap();
+
+            // Check that the key can be recovered
+            let recovered_key = VerifyingKey::recover_from_prehash(message.as_ref(), &sig, rec_id).unwrap();
+            assert_eq!(recovered_key, vkey);
+        }
+    }
+}
@@ -0,0 +1,1099 @@
+//! ThresholdKeyInit protocol: a dealerless distributed key generation producing threshold key shares.
+//!
+//! Based on the Pedersen DKG (Feldman VSS by each party, with the sum of the dealt secrets being the shared key).
+//! Following the KeyInit protocol (Fig. 6), the public data is committed to before being revealed,
+//! and each party proves the knowledge of its dealt secret with `П^{sch}`.
+//! Subshares that do not match the sender's public polynomial constitute provable faults.
+//!
+//! The subshares are masked with ephemeral Diffie-Hellman keys:
+//! in Round 1 each party echo-broadcasts an ephemeral Diffie-Hellman key for each other party,
+//! and in Round 2 sends it the subshares masked with a hash of their shared point.
+//! A party reporting an invalid subshare reveals its ephemeral secret for the sender, so that the subshare
+//! can be unmasked during evidence verification.
+//!
+//! A party can be given a weight greater than 1, in which case it receives that many shares
+//! (that is, its subshares from each dealer are the evaluations of the dealer's polynomial at several points).
+//!
+//! The shares can also follow a hierarchical access structure (see [`ThresholdKeyInit::new_hierarchical`]),
+//! in which case a party's subshare from each dealer is the derivative of the dealer's polynomial
+//! of the party's order evaluated at its share ID (as in [`ThresholdKeyShare::new_centralized_hierarchical`]).
+//!
+//! This is not a part of the CGGMP proper, but is an alternative to running KeyInit and then KeyResharing
+//! to obtain threshold key shares.
+
+use alloc::{
+    boxed::Box,
+    collections::{BTreeMap, BTreeSet},
+    format,
+    vec::Vec,
+};
+use core::{
+    fmt::{self, Debug, Display},
+    marker::PhantomData,
+};
+
+use manul::protocol::{
+    Artifact, BoxedFormat, BoxedRound, CommunicationInfo, DirectMessage, EchoBroadcast, EntryPoint, FinalizeOutcome,
+    LocalError, MessageValidationError, NormalBroadcast, PartyId, Payload, Protocol, ProtocolError, ProtocolMessage,
+    ProtocolMessagePart, ProtocolValidationError, ReceiveError, RequiredMessageParts, RequiredMessages, Round, RoundId,
+    TransitionInfo,
+};
+use manul::utils::SerializableMap;
+use rand_core::CryptoRngCore;
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    curve::{Point, Scalar},
+    entities::{HierarchicalAccessStructure, Sid, ThresholdKeyShare},
+    params::SchemeParams,
+    tools::{
+        bitvec::BitVec,
+        dh_mask,
+        hashing::{Chain, HashOutput, Hasher},
+        protocol_shortcuts::{verify_that, DeserializeAll, DowncastMap, GetRound, MapValues, SafeGet, Without},
+        sss::{assign_weighted_share_ids, Polynomial, PublicPolynomial, SharePoint},
+        Secret,
+    },
+    zk::{SchCommitment, SchProof, SchSecret},
+};
+
+/// The domain separation tag of the masks of the values sent in ThresholdKeyInit.
+const MASK_DST: &[u8] = b"ThresholdKeyInit mask";
+
+/// A protocol that generates threshold shares of a new secret key on each node.
+#[derive(Debug)]
+pub struct ThresholdKeyInitProtocol<P: SchemeParams, Id: Debug>(PhantomData<(P, Id)>);
+
+impl<P: SchemeParams, Id: PartyId> Protocol<Id> for ThresholdKeyInitProtocol<P, Id> {
+    type Result = ThresholdKeyShare<P, Id>;
+    type ProtocolError = ThresholdKeyInitError<P, Id>;
+
+    fn verify_direct_message_is_invalid(
+        format: &BoxedFormat,
+        round_id: &RoundId,
+        message: &DirectMessage,
+    ) -> Result<(), MessageValidationError> {
+        match round_id {
+            r if r == &1 => message.verify_is_some(),
+            r if r == &2 => message.verify_is_not::<Round2DirectMessage<P, Id>>(format),
+            r if r == &3 => message.verify_is_some(),
+            _ => Err(MessageValidationError::InvalidEvidence("Invalid round number".into())),
+        }
+    }
+
+    fn verify_echo_broadcast_is_invalid(
+        format: &BoxedFormat,
+        round_id: &RoundId,
+        message: &EchoBroadcast,
+    ) -> Result<(), MessageValidationError> {
+        match round_id {
+            r if r == &1 => message.verify_is_not::<Round1EchoBroadcast<P, Id>>(format),
+            r if r == &2 => message.verify_is_not::<Round2EchoBroadcast>(format),
+            r if r == &3 => message.verify_is_some(),
+            _ => Err(MessageValidationError::InvalidEvidence("Invalid round number".into())),
+        }
+    }
+
+    fn verify_normal_broadcast_is_invalid(
+        format: &BoxedFormat,
+        round_id: &RoundId,
+        message: &NormalBroadcast,
+    ) -> Result<(), MessageValidationError> {
+        match round_id {
+            r if r == &1 => message.verify_is_some(),
+            r if r == &2 => message.verify_is_not::<Round2NormalBroadcast<P>>(format),
+            r if r == &3 => message.verify_is_not::<Round3NormalBroadcast<P>>(format),
+            _ => Err(MessageValidationError::InvalidEvidence("Invalid round number".into())),
+        }
+    }
+}
+
+/// Possible verifiable errors of the ThresholdKeyInit protocol.
+#[derive(Debug, Clone)]
+#[derive_where::derive_where(Serialize, Deserialize)]
+pub struct ThresholdKeyInitError<P: SchemeParams, Id: PartyId> {
+    error: Error<P, Id>,
+}
+
+impl<P: SchemeParams, Id: PartyId> Display for ThresholdKeyInitError<P, Id> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> Result<(), fmt::Error> {
+        write!(f, "{}", self.error)
+    }
+}
+
+impl<P: SchemeParams, Id: PartyId> From<Error<P, Id>> for ThresholdKeyInitError<P, Id> {
+    fn from(source: Error<P, Id>) -> Self {
+        Self { error: source }
+    }
+}
+
+#[derive(displaydoc::Display, Debug, Clone)]
+#[derive_where::derive_where(Serialize, Deserialize)]
+enum Error<P: SchemeParams, Id: PartyId> {
+    /// Round 1: wrong IDs in the ephemeral keys map.
+    R1WrongIdsY,
+    /// Round 2: the previously sent hash does not match the public data.
+    R2HashMismatch,
+    /// Round 2: the public polynomial has a wrong number of coefficients.
+    R2WrongPolynomialDegree,
+    /// Round 2: the subshare does not match the public polynomial.
+    R2InvalidSubshare {
+        /// The ID $k$ of the node that received the subshare.
+        reported_by: Id,
+        /// The ephemeral secret $y_{k,j}$, where $j$ is the ID of the guilty party.
+        y: Scalar<P>,
+    },
+    /// Round 3: failed to verify `П^{{sch}}`.
+    R3InvalidSchProof,
+}
+
+/// Associated data for ThresholdKeyInit protocol.
+#[derive(Debug, Clone)]
+pub struct ThresholdKeyInitAssociatedData<Id: PartyId> {
+    /// IDs of all participating nodes.
+    pub ids: BTreeSet<Id>,
+    /// The threshold of the generated shares.
+    pub threshold: usize,
+    /// The weights of the nodes, if some of them hold several shares.
+    /// The nodes missing from the map have the weight 1.
+    pub weights: BTreeMap<Id, usize>,
+    /// The access structure of the shares, if they are hierarchical.
+    pub access_structure: Option<HierarchicalAccessStructure<Id>>,
+}
+
+impl<Id: PartyId> ThresholdKeyInitAssociatedData<Id> {
+    fn all_weights(&self) -> BTreeMap<Id, usize> {
+        self.ids
+            .iter()
+            .map(|id| (id.clone(), self.weights.get(id).copied().unwrap_or(1)))
+            .collect()
+    }
+}
+
+/// Returns the share IDs of all the holders, each along with the order of the derivative
+/// of the dealt polynomials the share is evaluated from (zero unless the shares are hierarchical).
+fn holder_points<P: SchemeParams, Id: PartyId>(
+    weights: &BTreeMap<Id, usize>,
+    access_structure: Option<&HierarchicalAccessStructure<Id>>,
+) -> Result<BTreeMap<Id, Vec<SharePoint<P>>>, LocalError> {
+    match access_structure {
+        Some(access_structure) => Ok(access_structure.holder_points()?.map_values(|point| Vec::from([point]))),
+        None => Ok(assign_weighted_share_ids(weights)
+            .map_values(|share_ids| share_ids.into_iter().map(|share_id| (share_id, 0)).collect())),
+    }
+}
+
+impl<P: SchemeParams, Id: PartyId> ProtocolError<Id> for ThresholdKeyInitError<P, Id> {
+    type AssociatedData = ThresholdKeyInitAssociatedData<Id>;
+
+    fn required_messages(&self) -> RequiredMessages {
+        match self.error {
+            Error::R1WrongIdsY => RequiredMessages::new(RequiredMessageParts::echo_broadcast(), None, None),
+            Error::R2HashMismatch => RequiredMessages::new(
+                RequiredMessageParts::echo_broadcast().and_normal_broadcast(),
+                Some([(1.into(), RequiredMessageParts::echo_broadcast())].into()),
+                None,
+            ),
+            Error::R2WrongPolynomialDegree => {
+                RequiredMessages::new(RequiredMessageParts::normal_broadcast(), None, None)
+            }
+            Error::R2InvalidSubshare { .. } => RequiredMessages::new(
+                RequiredMessageParts::normal_broadcast().and_direct_message(),
+                Some([(1.into(), RequiredMessageParts::echo_broadcast())].into()),
+                Some([1.into()].into()),
+            ),
+            Error::R3InvalidSchProof => RequiredMessages::new(
+                RequiredMessageParts::normal_broadcast(),
+                Some([(2.into(), RequiredMessageParts::echo_broadcast().and_normal_broadcast())].into()),
+                Some([2.into()].into()),
+            ),
+        }
+    }
+
+    fn verify_messages_constitute_error(
+        &self,
+        format: &BoxedFormat,
+        guilty_party: &Id,
+        shared_randomness: &[u8],
+        associated_data: &Self::AssociatedData,
+        message: ProtocolMessage,
+        previous_messages: BTreeMap<RoundId, ProtocolMessage>,
+        combined_echos: BTreeMap<RoundId, BTreeMap<Id, EchoBroadcast>>,
+    ) -> Result<(), ProtocolValidationError> {
+        let sid = Sid::new::<P, Id>(shared_randomness, &associated_data.ids);
+
+        match &self.error {
+            Error::R1WrongIdsY => {
+                let r1_eb = message
+                    .echo_broadcast
+                    .deserialize::<Round1EchoBroadcast<P, Id>>(format)?;
+                verify_that(
+                    r1_eb.cap_ys.keys().cloned().collect::<BTreeSet<_>>()
+                        != associated_data.ids.clone().without(guilty_party),
+                )
+            }
+            Error::R2HashMismatch => {
+                let r1_eb = previous_messages
+                    .get_round(1)?
+                    .echo_broadcast
+                    .deserialize::<Round1EchoBroadcast<P, Id>>(format)?;
+                let r2_nb = message
+                    .normal_broadcast
+                    .deserialize::<Round2NormalBroadcast<P>>(format)?;
+                let r2_eb = message.echo_broadcast.deserialize::<Round2EchoBroadcast>(format)?;
+                let data = PublicData {
+                    public_polynomial: r2_nb.public_polynomial,
+                    cap_a: r2_nb.cap_a,
+                    u: r2_nb.u,
+                    rho: r2_eb.rho,
+                };
+                verify_that(data.hash(&sid, guilty_party) != r1_eb.cap_v)
+            }
+            Error::R2WrongPolynomialDegree => {
+                let r2_nb = message
+                    .normal_broadcast
+                    .deserialize::<Round2NormalBroadcast<P>>(format)?;
+                verify_that(r2_nb.public_polynomial.num_coeffs() != associated_data.threshold)
+            }
+            Error::R2InvalidSubshare { reported_by, y } => {
+                // Check that `y` attached to the evidence is correct
+                // (that is, can be verified against something signed by `guilty_party`).
+                // It is `y_{k,j}` where `k == reported_by` and `j == guilty_party`
+                let r1_eb_k = combined_echos
+                    .get_round(1)?
+                    .try_get("combined echos for Round 1", reported_by)?
+                    .deserialize::<Round1EchoBroadcast<P, Id>>(format)?;
+                let cap_y_kj = r1_eb_k.cap_ys.try_get("ephemeral keys", guilty_party)?;
+                if &y.mul_by_generator() != cap_y_kj {
+                    return Err(ProtocolValidationError::InvalidEvidence(
+                        "The provided `y` is invalid".into(),
+                    ));
+                }
+
+                let r1_eb = previous_messages
+                    .get_round(1)?
+                    .echo_broadcast
+                    .deserialize::<Round1EchoBroadcast<P, Id>>(format)?;
+                let r2_nb = message
+                    .normal_broadcast
+                    .deserialize::<Round2NormalBroadcast<P>>(format)?;
+                let r2_dm = message
+                    .direct_message
+                    .deserialize::<Round2DirectMessage<P, Id>>(format)?;
+
+                // Make sure the message was actually addressed to the reporting node.
+                if &r2_dm.destination != reported_by {
+                    return Err(ProtocolValidationError::InvalidEvidence(
+                        "The direct message was not addressed to the reporting node".into(),
+                    ));
+                }
+
+                let points = holder_points::<P, Id>(
+                    &associated_data.all_weights(),
+                    associated_data.access_structure.as_ref(),
+                )
+                .map_err(|_| ProtocolValidationError::InvalidEvidence("Failed to assign the share IDs".into()))?;
+                let points = points.try_get("share IDs", reported_by)?;
+
+                let cap_y_jk = r1_eb.cap_ys.try_get("ephemeral keys", reported_by)?;
+                let cap_d = cap_y_jk * y;
+                verify_that(
+                    r2_dm.cap_cs.len() != points.len()
+                        || r2_dm
+                            .cap_cs
+                            .iter()
+                            .zip(points)
+                            .enumerate()
+                            .any(|(idx, (cap_c, (share_id, order)))| {
+                                let subshare = *cap_c - dh_mask::<P, Id>(MASK_DST, &sid, guilty_party, &cap_d, idx);
+                                subshare.mul_by_generator()
+                                    != r2_nb.public_polynomial.evaluate_derivative(share_id, *order)
+                            }),
+                )
+            }
+            Error::R3InvalidSchProof => {
+                let r2_ebs = combined_echos
+                    .get_round(2)?
+                    .deserialize_all::<Round2EchoBroadcast>(format)?;
+                let r2_nb = previous_messages
+                    .get_round(2)?
+                    .normal_broadcast
+                    .deserialize::<Round2NormalBroadcast<P>>(format)?;
+                let r2_eb = previous_messages
+                    .get_round(2)?
+                    .echo_broadcast
+                    .deserialize::<Round2EchoBroadcast>(format)?;
+                let r3_nb = message
+                    .normal_broadcast
+                    .deserialize::<Round3NormalBroadcast<P>>(format)?;
+
+                let mut rho = r2_eb.rho;
+                for message in r2_ebs.values() {
+                    rho ^= &message.rho;
+                }
+
+                let cap_x = r2_nb
+                    .public_polynomial
+                    .coeff0()
+                    .map_err(|_| ProtocolValidationError::InvalidEvidence("Empty public polynomial".into()))?;
+                let aux = (&sid, guilty_party, &rho);
+                verify_that(!r3_nb.psi.verify(&r2_nb.cap_a, cap_x, &aux))
+            }
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub(super) struct PublicData<P: SchemeParams> {
+    pub(super) public_polynomial: PublicPolynomial<P>,
+    pub(super) cap_a: SchCommitment<P>,
+    rho: BitVec,
+    u: BitVec,
+}
+
+impl<P> PublicData<P>
+where
+    P: SchemeParams,
+{
+    pub(super) fn hash<Id: Serialize>(&self, sid: &Sid, id: &Id) -> HashOutput {
+        Hasher::<P::Digest>::new_with_dst(b"ThresholdKeyInit")
+            .chain(sid)
+            .chain(id)
+            .chain(&self.public_polynomial)
+            .chain(&self.cap_a)
+            .chain(&self.rho)
+            .chain(&self.u)
+            .finalize(P::SECURITY_BITS)
+    }
+}
+
+/// An entry point for the [`ThresholdKeyInitProtocol`].
+#[derive(Debug, Clone)]
+pub struct ThresholdKeyInit<P, Id: PartyId> {
+    weights: BTreeMap<Id, usize>,
+    threshold: usize,
+    access_structure: Option<HierarchicalAccessStructure<Id>>,
+    phantom: PhantomData<P>,
+}
+
+impl<P, Id: PartyId> ThresholdKeyInit<P, Id> {
+    /// Creates a new entry point given the set of the participants' IDs
+    /// (including this node's), and the threshold of the resulting key shares.
+    pub fn new(all_ids: BTreeSet<Id>, threshold: usize) -> Result<Self, LocalError> {
+        Self::new_weighted(all_ids.into_iter().map(|id| (id, 1)).collect(), threshold)
+    }
+
+    /// Creates a new entry point given the participants' IDs (including this node's) mapped to the number
+    /// of shares each of them will hold, and the threshold (the total weight required to sign).
+    pub fn new_weighted(weights: BTreeMap<Id, usize>, threshold: usize) -> Result<Self, LocalError> {
+        if weights.values().any(|weight| *weight == 0) {
+            return Err(LocalError::new("The weights of the parties must be non-zero"));
+        }
+        let total_weight = weights.values().sum::<usize>();
+        if threshold == 0 || threshold > total_weight {
+            return Err(LocalError::new(format!(
+                "The threshold must be between 1 and the total weight of the parties ({total_weight}), got {threshold}",
+            )));
+        }
+        Ok(Self {
+            weights,
+            threshold,
+            access_structure: None,
+            phantom: PhantomData,
+        })
+    }
+
+    /// Creates a new entry point given a hierarchical access structure
+    /// (see [`ThresholdKeyShare::new_centralized_hierarchical`]).
+    ///
+    /// The participants are the holders in the access structure, and the threshold is its threshold.
+    pub fn new_hierarchical(access_structure: HierarchicalAccessStructure<Id>) -> Result<Self, LocalError> {
+        let all_ids = access_structure.holders();
+        let threshold = access_structure.threshold();
+        Ok(Self {
+            access_structure: Some(access_structure),
+            ..Self::new(all_ids, threshold)?
+        })
+    }
+}
+
+impl<P: SchemeParams, Id: PartyId> EntryPoint<Id> for ThresholdKeyInit<P, Id> {
+    type Protocol = ThresholdKeyInitProtocol<P, Id>;
+
+    fn entry_round_id() -> RoundId {
+        1.into()
+    }
+
+    fn make_round(
+        self,
+        rng: &mut dyn CryptoRngCore,
+        shared_randomness: &[u8],
+        id: &Id,
+    ) -> Result<BoxedRound<Id, Self::Protocol>, LocalError> {
+        if !self.weights.contains_key(id) {
+            return Err(LocalError::new("The given node IDs must contain this node's ID"));
+        }
+
+        let all_ids = self.weights.keys().cloned().collect::<BTreeSet<_>>();
+        let other_ids = all_ids.clone().without(id);
+        let points = holder_points(&self.weights, self.access_structure.as_ref())?;
+
+        let sid = Sid::new::<P, Id>(shared_randomness, &all_ids);
+
+        // The dealt secret
+        let x = Secret::init_with(|| Scalar::random(rng));
+        let polynomial = Polynomial::random(rng, x.clone(), self.threshold);
+
+        let rho = BitVec::random(rng, P::SECURITY_PARAMETER);
+        let tau = SchSecret::random(rng);
+        let cap_a = SchCommitment::new(&tau);
+        let u = BitVec::random(rng, P::SECURITY_PARAMETER);
+
+        // Ephemeral Diffie-Hellman secrets for masking the subshares sent to and received from each other node.
+        let ys = other_ids
+            .iter()
+            .map(|id| (id.clone(), Secret::init_with(|| Scalar::random(rng))))
+            .collect();
+
+        let public_data = PublicData {
+            public_polynomial: polynomial.public(),
+            cap_a,
+            rho,
+            u,
+        };
+
+        let context = Context {
+            other_ids,
+            my_id: id.clone(),
+            points,
+            threshold: self.threshold,
+            access_structure: self.access_structure,
+            x,
+            polynomial,
+            tau,
+            ys,
+            public_data,
+            sid,
+        };
+
+        Ok(BoxedRound::new_dynamic(Round1 { context }))
+    }
+}
+
+#[derive(Debug)]
+pub(super) struct Context<P: SchemeParams, Id: PartyId> {
+    other_ids: BTreeSet<Id>,
+    pub(super) my_id: Id,
+    // The share IDs of the holders along with the derivative orders.
+    points: BTreeMap<Id, Vec<SharePoint<P>>>,
+    pub(super) threshold: usize,
+    access_structure: Option<HierarchicalAccessStructure<Id>>,
+    x: Secret<Scalar<P>>,
+    polynomial: Polynomial<P>,
+    pub(super) tau: SchSecret<P>,
+    ys: BTreeMap<Id, Secret<Scalar<P>>>, // $y_{i,j}$ where $i$ is this party's index
+    pub(super) public_data: PublicData<P>,
+    pub(super) sid: Sid,
+}
+
+#[derive(Debug)]
+pub(super) struct Round1<P: SchemeParams, Id: PartyId> {
+    pub(super) context: Context<P, Id>,
+}
+
+#[derive(Debug, Clone)]
+#[derive_where::derive_where(Serialize, Deserialize)]
+pub(super) struct Round1EchoBroadcast<P: SchemeParams, Id: PartyId> {
+    pub(super) cap_v: HashOutput,
+    pub(super) cap_ys: SerializableMap<Id, Point<P>>, // $Y_{i,j}$ where $i$ is this party's index
+}
+
+struct Round1Payload<P: SchemeParams> {
+    cap_v: HashOutput,
+    cap_y: Point<P>, // $Y_{j,i}$ where $i$ is this party's index
+}
+
+impl<P, Id> Round<Id> for Round1<P, Id>
+where
+    P: SchemeParams,
+    Id: PartyId,
+{
+    type Protocol = ThresholdKeyInitProtocol<P, Id>;
+
+    fn transition_info(&self) -> TransitionInfo {
+        TransitionInfo::new_linear(1)
+    }
+
+    fn communication_info(&self) -> CommunicationInfo<Id> {
+        CommunicationInfo::regular(&self.context.other_ids)
+    }
+
+    fn make_echo_broadcast(
+        &self,
+        _rng: &mut dyn CryptoRngCore,
+        format: &BoxedFormat,
+    ) -> Result<EchoBroadcast, LocalError> {
+        let cap_v = self.context.public_data.hash(&self.context.sid, &self.context.my_id);
+        let cap_ys = self.context.ys.map_values_ref(|y| y.mul_by_generator());
+        EchoBroadcast::new(
+            format,
+            Round1EchoBroadcast {
+                cap_v,
+                cap_ys: cap_ys.into(),
+            },
+        )
+    }
+
+    fn receive_message(
+        &self,
+        format: &BoxedFormat,
+        from: &Id,
+        message: ProtocolMessage,
+    ) -> Result<Payload, ReceiveError<Id, Self::Protocol>> {
+        message.normal_broadcast.assert_is_none()?;
+        message.direct_message.assert_is_none()?;
+        let echo_broadcast = message
+            .echo_broadcast
+            .deserialize::<Round1EchoBroadcast<P, Id>>(format)?;
+
+        let mut expected_ids = self.context.other_ids.clone().without(from);
+        expected_ids.insert(self.context.my_id.clone());
+        if echo_broadcast.cap_ys.keys().cloned().collect::<BTreeSet<_>>() != expected_ids {
+            return Err(ReceiveError::protocol(Error::R1WrongIdsY.into()));
+        }
+
+        let cap_y = *echo_broadcast.cap_ys.safe_get("ephemeral keys", &self.context.my_id)?;
+        Ok(Payload::new(Round1Payload {
+            cap_v: echo_broadcast.cap_v,
+            cap_y,
+        }))
+    }
+
+    fn finalize(
+        self: Box<Self>,
+        _rng: &mut dyn CryptoRngCore,
+        payloads: BTreeMap<Id, Payload>,
+        _artifacts: BTreeMap<Id, Artifact>,
+    ) -> Result<FinalizeOutcome<Id, Self::Protocol>, LocalError> {
+        let payloads = payloads.downcast_all::<Round1Payload<P>>()?;
+        let cap_ys = payloads.map_values_ref(|payload| payload.cap_y);
+        let cap_vs = payloads.map_values(|payload| payload.cap_v);
+        let next_round = Round2 {
+            context: self.context,
+            cap_vs,
+            cap_ys,
+        };
+        Ok(FinalizeOutcome::AnotherRound(BoxedRound::new_dynamic(next_round)))
+    }
+}
+
+#[derive(Debug)]
+pub(super) struct Round2<P: SchemeParams, Id: PartyId> {
+    pub(super) context: Context<P, Id>,
+    cap_vs: BTreeMap<Id, HashOutput>,
+    cap_ys: BTreeMap<Id, Point<P>>, // $Y_{j,i}$ where $i$ is this party's index
+}
+
+#[derive(Clone, Serialize, Deserialize)]
+struct Round2EchoBroadcast {
+    rho: BitVec,
+}
+
+#[derive(Clone)]
+#[derive_where::derive_where(Serialize, Deserialize)]
+pub(super) struct Round2NormalBroadcast<P: SchemeParams> {
+    pub(super) public_polynomial: PublicPolynomial<P>,
+    cap_a: SchCommitment<P>,
+    pub(super) u: BitVec,
+}
+
+#[derive(Clone)]
+#[derive_where::derive_where(Serialize, Deserialize)]
+pub(super) struct Round2DirectMessage<P: SchemeParams, Id: PartyId> {
+    // Included so that the message could not be used as evidence by a node it was not addressed to.
+    pub(super) destination: Id,
+    // The masked subshares, one for each share ID of the destination.
+    pub(super) cap_cs: Vec<Scalar<P>>,
+}
+
+struct Round2Payload<P: SchemeParams> {
+    public_polynomial: PublicPolynomial<P>,
+    cap_a: SchCommitment<P>,
+    rho: BitVec,
+    subshares: Vec<Secret<Scalar<P>>>,
+}
+
+impl<P: SchemeParams, Id: PartyId> Round<Id> for Round2<P, Id> {
+    type Protocol = ThresholdKeyInitProtocol<P, Id>;
+
+    fn transition_info(&self) -> TransitionInfo {
+        TransitionInfo::new_linear(2)
+    }
+
+    fn communication_info(&self) -> CommunicationInfo<Id> {
+        CommunicationInfo::regular(&self.context.other_ids)
+    }
+
+    fn make_echo_broadcast(
+        &self,
+        _rng: &mut dyn CryptoRngCore,
+        format: &BoxedFormat,
+    ) -> Result<EchoBroadcast, LocalError> {
+        EchoBroadcast::new(
+            format,
+            Round2EchoBroadcast {
+                rho: self.context.public_data.rho.clone(),
+            },
+        )
+    }
+
+    fn make_normal_broadcast(
+        &self,
+        _rng: &mut dyn CryptoRngCore,
+        format: &BoxedFormat,
+    ) -> Result<NormalBroadcast, LocalError> {
+        NormalBroadcast::new(
+            format,
+            Round2NormalBroadcast {
+                public_polynomial: self.context.public_data.public_polynomial.clone(),
+                cap_a: self.context.public_data.cap_a.clone(),
+                u: self.context.public_data.u.clone(),
+            },
+        )
+    }
+
+    fn make_direct_message(
+        &self,
+        _rng: &mut dyn CryptoRngCore,
+        format: &BoxedFormat,
+        destination: &Id,
+    ) -> Result<(DirectMessage, Option<Artifact>), LocalError> {
+        let their_points = self.context.points.safe_get("share IDs", destination)?;
+        let cap_y = self.cap_ys.safe_get("ephemeral public keys", destination)?;
+        let y = self.context.ys.safe_get("ephemeral secrets", destination)?;
+        let cap_d = cap_y * y;
+        let cap_cs = their_points
+            .iter()
+            .enumerate()
+            .map(|(idx, (share_id, order))| {
+                let subshare = self.context.polynomial.evaluate_derivative(share_id, *order);
+                let rho = dh_mask::<P, Id>(MASK_DST, &self.context.sid, &self.context.my_id, &cap_d, idx);
+                *(subshare + &rho).expose_secret()
+            })
+            .collect();
+        let direct_message = DirectMessage::new(
+            format,
+            Round2DirectMessage {
+                destination: destination.clone(),
+                cap_cs,
+            },
+        )?;
+        Ok((direct_message, None))
+    }
+
+    fn receive_message(
+        &self,
+        format: &BoxedFormat,
+        from: &Id,
+        message: ProtocolMessage,
+    ) -> Result<Payload, ReceiveError<Id, Self::Protocol>> {
+        let normal_broadcast = message
+            .normal_broadcast
+            .deserialize::<Round2NormalBroadcast<P>>(format)?;
+        let echo_broadcast = message.echo_broadcast.deserialize::<Round2EchoBroadcast>(format)?;
+        let direct_message = message
+            .direct_message
+            .deserialize::<Round2DirectMessage<P, Id>>(format)?;
+
+        let cap_v = self.cap_vs.safe_get("vector `V`", from)?;
+        let data = PublicData {
+            public_polynomial: normal_broadcast.public_polynomial,
+            cap_a: normal_broadcast.cap_a,
+            u: normal_broadcast.u,
+            rho: echo_broadcast.rho,
+        };
+
+        if &data.hash(&self.context.sid, from) != cap_v {
+            return Err(ReceiveError::protocol(Error::R2HashMismatch.into()));
+        }
+
+        if data.public_polynomial.num_coeffs() != self.context.threshold {
+            return Err(ReceiveError::protocol(Error::R2WrongPolynomialDegree.into()));
+        }
+
+        if direct_message.destination != self.context.my_id {
+            return Err(ReceiveError::unprovable(
+                "The direct message is addressed to another node",
+            ));
+        }
+
+        let cap_y = self.cap_ys.safe_get("ephemeral public keys", from)?;
+        let y = self.context.ys.safe_get("ephemeral secrets", from)?;
+        let cap_d = cap_y * y;
+        let subshares = direct_message
+            .cap_cs
+            .iter()
+            .enumerate()
+            .map(|(idx, cap_c)| {
+                let rho = dh_mask::<P, Id>(MASK_DST, &self.context.sid, from, &cap_d, idx);
+                Secret::init_with(|| *cap_c - rho)
+            })
+            .collect::<Vec<_>>();
+
+        let my_points = self.context.points.safe_get("share IDs", &self.context.my_id)?;
+        if subshares.len() != my_points.len()
+            || subshares.iter().zip(my_points).any(|(subshare, (share_id, order))| {
+                subshare.mul_by_generator() != data.public_polynomial.evaluate_derivative(share_id, *order)
+            })
+        {
+            return Err(ReceiveError::protocol(
+                Error::R2InvalidSubshare {
+                    reported_by: self.context.my_id.clone(),
+                    y: *y.expose_secret(),
+                }
+                .into(),
+            ));
+        }
+
+        Ok(Payload::new(Round2Payload {
+            public_polynomial: data.public_polynomial,
+            cap_a: data.cap_a,
+            rho: data.rho,
+            subshares,
+        }))
+    }
+
+    fn finalize(
+        self: Box<Self>,
+        _rng: &mut dyn CryptoRngCore,
+        payloads: BTreeMap<Id, Payload>,
+        _artifacts: BTreeMap<Id, Artifact>,
+    ) -> Result<FinalizeOutcome<Id, Self::Protocol>, LocalError> {
+        let payloads = payloads.downcast_all::<Round2Payload<P>>()?;
+
+        let mut rho_combined = self.context.public_data.rho.clone();
+        for payload in payloads.values() {
+            rho_combined ^= &payload.rho;
+        }
+
+        let subshares = payloads.map_values_ref(|payload| payload.subshares.clone());
+        let public_polynomials = payloads.map_values_ref(|payload| payload.public_polynomial.clone());
+        let cap_as = payloads.map_values_ref(|payload| payload.cap_a.clone());
+
+        let next_round = Round3 {
+            context: self.context,
+            subshares,
+            public_polynomials,
+            cap_as,
+            rho_combined,
+        };
+
+        Ok(FinalizeOutcome::AnotherRound(BoxedRound::new_dynamic(next_round)))
+    }
+}
+
+#[derive(Debug)]
+pub(super) struct Round3<P: SchemeParams, Id: PartyId> {
+    pub(super) context: Context<P, Id>,
+    subshares: BTreeMap<Id, Vec<Secret<Scalar<P>>>>,
+    public_polynomials: BTreeMap<Id, PublicPolynomial<P>>,
+    cap_as: BTreeMap<Id, SchCommitment<P>>,
+    pub(super) rho_combined: BitVec,
+}
+
+#[derive(Clone)]
+#[derive_where::derive_where(Serialize, Deserialize)]
+pub(super) struct Round3NormalBroadcast<P: SchemeParams> {
+    pub(super) psi: SchProof<P>,
+}
+
+impl<P: SchemeParams, Id: PartyId> Round<Id> for Round3<P, Id> {
+    type Protocol = ThresholdKeyInitProtocol<P, Id>;
+
+    fn transition_info(&self) -> TransitionInfo {
+        TransitionInfo::new_linear_terminating(3)
+    }
+
+    fn communication_info(&self) -> CommunicationInfo<Id> {
+        CommunicationInfo::regular(&self.context.other_ids)
+    }
+
+    fn make_normal_broadcast(
+        &self,
+        _rng: &mut dyn CryptoRngCore,
+        format: &BoxedFormat,
+    ) -> Result<NormalBroadcast, LocalError> {
+        let aux = (&self.context.sid, &self.context.my_id, &self.rho_combined);
+        let psi = SchProof::new(
+            &self.context.tau,
+            &self.context.x,
+            &self.context.public_data.cap_a,
+            self.context.public_data.public_polynomial.coeff0()?,
+            &aux,
+        );
+        NormalBroadcast::new(format, Round3NormalBroadcast { psi })
+    }
+
+    fn receive_message(
+        &self,
+        format: &BoxedFormat,
+        from: &Id,
+        message: ProtocolMessage,
+    ) -> Result<Payload, ReceiveError<Id, Self::Protocol>> {
+        message.echo_broadcast.assert_is_none()?;
+        message.direct_message.assert_is_none()?;
+        let normal_broadcast = message
+            .normal_broadcast
+            .deserialize::<Round3NormalBroadcast<P>>(format)?;
+
+        let cap_a = self.cap_as.safe_get("`A` map", from)?;
+        let cap_x = self.public_polynomials.safe_get("public polynomials", from)?.coeff0()?;
+
+        let aux = (&self.context.sid, from, &self.rho_combined);
+        if !normal_broadcast.psi.verify(cap_a, cap_x, &aux) {
+            return Err(ReceiveError::protocol(Error::R3InvalidSchProof.into()));
+        }
+        Ok(Payload::empty())
+    }
+
+    fn finalize(
+        self: Box<Self>,
+        _rng: &mut dyn CryptoRngCore,
+        _payloads: BTreeMap<Id, Payload>,
+        _artifacts: BTreeMap<Id, Artifact>,
+    ) -> Result<FinalizeOutcome<Id, Self::Protocol>, LocalError> {
+        let my_id = self.context.my_id.clone();
+        let my_points = self.context.points.safe_get("share IDs", &my_id)?;
+
+        // Add our own polynomial to the ones received from other nodes
+        let mut public_polynomials = self.public_polynomials;
+        public_polynomials.insert(my_id.clone(), self.context.public_data.public_polynomial.clone());
+
+        // Each secret share is the sum of the evaluations of all the dealt polynomials at the corresponding share ID.
+        let secret_shares = my_points
+            .iter()
+            .enumerate()
+            .map(|(idx, (share_id, order))| {
+                let own_subshare = self.context.polynomial.evaluate_derivative(share_id, *order);
+                self.subshares.values().try_fold(own_subshare, |acc, subshares| {
+                    let subshare = subshares.get(idx).ok_or_else(|| {
+                        LocalError::new("The number of subshares does not match the number of share IDs")
+                    })?;
+                    Ok(acc + subshare.clone())
+                })
+            })
+            .collect::<Result<Vec<_>, LocalError>>()?;
+
+        let holders = self
+            .context
+            .points
+            .iter()
+            .map(|(id, points)| {
+                let shares = points
+                    .iter()
+                    .map(|(share_id, order)| {
+                        let public_share = public_polynomials
+                            .values()
+                            .map(|polynomial| polynomial.evaluate_derivative(share_id, *order))
+                            .sum::<Point<P>>();
+                        (*share_id, public_share)
+                    })
+                    .collect();
+                (id.clone(), shares)
+            })
+            .collect::<BTreeMap<_, _>>();
+
+        let verifying_key = public_polynomials
+            .values()
+            .map(|polynomial| polynomial.coeff0().copied())
+            .sum::<Result<Point<P>, _>>()?;
+
+        // This can fail if the dealt secrets add up to zero.
+        // Can't really protect from it, and it should be extremely rare.
+        // If that happens one can only restart the whole thing.
+        if verifying_key == Point::identity() {
+            return Err(LocalError::new("The dealt secrets add up to zero"));
+        }
+
+        let key_share = ThresholdKeyShare::from_weighted_parts(my_id, self.context.threshold, secret_shares, holders)?;
+        let key_share = ThresholdKeyShare {
+            access_structure: self.context.access_structure,
+            ..key_share
+        };
+
+        Ok(FinalizeOutcome::Result(key_share))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use alloc::collections::{BTreeMap, BTreeSet};
+
+    use manul::{
+        dev::{run_sync, BinaryFormat, TestSessionParams, TestSigner, TestVerifier},
+        signature::Keypair,
+    };
+    use rand_core::OsRng;
+
+    use super::ThresholdKeyInit;
+    use crate::{curve::Scalar, dev::TestParams, HierarchicalAccessStructure};
+
+    #[test]
+    fn execute_threshold_keygen() {
+        let signers = (0..4).map(TestSigner::new).collect::<Vec<_>>();
+        let ids = signers.iter().map(|signer| signer.verifying_key()).collect::<Vec<_>>();
+        let all_ids = ids.iter().cloned().collect::<BTreeSet<_>>();
+        let threshold = 3;
+
+        // Invalid thresholds are rejected
+        assert!(ThresholdKeyInit::<TestParams, TestVerifier>::new(all_ids.clone(), 0).is_err());
+        assert!(ThresholdKeyInit::<TestParams, TestVerifier>::new(all_ids.clone(), 5).is_err());
+
+        let entry_points = signers
+            .into_iter()
+            .map(|signer| {
+                let entry_point =
+                    ThresholdKeyInit::<TestParams, TestVerifier>::new(all_ids.clone(), threshold).unwrap();
+                (signer, entry_point)
+            })
+            .collect::<Vec<_>>();
+
+        let shares = run_sync::<_, TestSessionParams<BinaryFormat>>(&mut OsRng, entry_points)
+            .unwrap()
+            .results()
+            .unwrap();
+
+        // Check that the public data is the same at each node
+        let share0 = &shares[&ids[0]];
+        assert!(shares.values().all(|share| share.public_shares == share0.public_shares
+            && share.share_ids == share0.share_ids
+            && share.threshold() == threshold));
+
+        // Check that the public keys correspond to the secret key shares
+        for share in shares.values() {
+            assert_eq!(share.secret_share.mul_by_generator(), share.public_shares[&share.owner]);
+        }
+
+        // Check that any `threshold` shares produce the same verifying key
+        let vkey = share0.verifying_key().unwrap();
+        for excluded in &ids {
+            let subset = all_ids
+                .iter()
+                .filter(|id| id != &excluded)
+                .cloned()
+                .collect::<BTreeSet<_>>();
+            for id in &subset {
+                let key_share = shares[id].to_key_share(&subset).unwrap();
+                assert_eq!(key_share.verifying_key(), vkey);
+            }
+        }
+    }
+
+    #[test]
+    fn execute_weighted_threshold_keygen() {
+        let signers = (0..3).map(TestSigner::new).collect::<Vec<_>>();
+        let ids = signers.iter().map(|signer| signer.verifying_key()).collect::<Vec<_>>();
+        let weights = BTreeMap::from([(ids[0], 1), (ids[1], 2), (ids[2], 1)]);
+        let threshold = 3;
+
+        // Invalid weights and thresholds are rejected
+        assert!(ThresholdKeyInit::<TestParams, TestVerifier>::new_weighted(weights.clone(), 5).is_err());
+        assert!(ThresholdKeyInit::<TestParams, TestVerifier>::new_weighted(
+            BTreeMap::from([(ids[0], 1), (ids[1], 0), (ids[2], 1)]),
+            2
+        )
+        .is_err());
+
+        let entry_points = signers
+            .into_iter()
+            .map(|signer| {
+                let entry_point =
+                    ThresholdKeyInit::<TestParams, TestVerifier>::new_weighted(weights.clone(), threshold).unwrap();
+                (signer, entry_point)
+            })
+            .collect::<Vec<_>>();
+
+        let shares = run_sync::<_, TestSessionParams<BinaryFormat>>(&mut OsRng, entry_points)
+            .unwrap()
+            .results()
+            .unwrap();
+
+        for (id, share) in shares.iter() {
+            assert_eq!(share.weight(id), weights[id]);
+        }
+
+        // Check that any set of signers with the total weight equal to the threshold
+        // produces the same verifying key
+        let vkey = shares[&ids[0]].verifying_key().unwrap();
+        for subset in [
+            BTreeSet::from([ids[0], ids[1]]),
+            BTreeSet::from([ids[1], ids[2]]),
+            BTreeSet::from([ids[0], ids[1], ids[2]]),
+        ] {
+            let key_shares = subset
+                .iter()
+                .map(|id| shares[id].to_key_share(&subset).unwrap())
+                .collect::<Vec<_>>();
+            let secret = key_shares
+                .iter()
+                .map(|key_share| *key_share.secret_share().expose_secret())
+                .sum::<Scalar<TestParams>>();
+            assert_eq!(secret.mul_by_generator().to_verifying_key().unwrap(), vkey);
+        }
+    }
+
+    #[test]
+    fn execute_hierarchical_threshold_keygen() {
+        let signers = (0..5).map(TestSigner::new).collect::<Vec<_>>();
+        let ids = signers.iter().map(|signer| signer.verifying_key()).collect::<Vec<_>>();
+        let executives = BTreeSet::from([ids[0], ids[1]]);
+        let operators = BTreeSet::from([ids[2], ids[3], ids[4]]);
+
+        // Any 4 holders including both of the executives
+        let access_structure = HierarchicalAccessStructure::new([(executives, 2), (operators, 4)].into()).unwrap();
+
+        let entry_points = signers
+            .into_iter()
+            .map(|signer| {
+                let entry_point =
+                    ThresholdKeyInit::<TestParams, TestVerifier>::new_hierarchical(access_structure.clone()).unwrap();
+                (signer, entry_point)
+            })
+            .collect::<Vec<_>>();
+
+        let shares = run_sync::<_, TestSessionParams<BinaryFormat>>(&mut OsRng, entry_points)
+            .unwrap()
+            .results()
+            .unwrap();
+
+        let share0 = &shares[&ids[0]];
+        for share in shares.values() {
+            assert_eq!(share.public_shares, share0.public_shares);
+            assert_eq!(share.threshold(), 4);
+            assert!(share.access_structure().is_some());
+            assert_eq!(share.secret_share.mul_by_generator(), share.public_shares[&share.owner]);
+        }
+
+        let vkey = share0.verifying_key().unwrap();
+        for authorized in [
+            BTreeSet::from([ids[0], ids[1], ids[2], ids[3]]),
+            BTreeSet::from([ids[0], ids[1], ids[3], ids[4]]),
+        ] {
+            let secret = authorized
+                .iter()
+                .map(|id| {
+                    *shares[id]
+                        .to_key_share(&authorized)
+                        .unwrap()
+                        .secret_share()
+                        .expose_secret()
+                })
+                .sum::<Scalar<TestParams>>();
+            assert_eq!(secret.mul_by_generator().to_verifying_key().unwrap(), vkey);
+        }
+
+        // Not enough executives
+        let unauthorized = BTreeSet::from([ids[0], ids[2], ids[3], ids[4]]);
+        assert!(shares[&ids[0]].to_key_share(&unauthorized).is_err());
+    }
+}
@@ -0,0 +1,958 @@
+//! ThresholdKeyRefresh protocol: updates the shares of a [`ThresholdKeyShare`] without changing the shared key,
+//! and generates new auxiliary info at the same time.
+//!
+//! The share update is the threshold counterpart of the share update part of KeyRefresh (Fig. 7):
+//! each holder deals a Feldman VSS of zero (that is, a random polynomial of degree `threshold - 1`
+//! with the zero constant coefficient), and echo-broadcasts the public polynomial.
+//! A public polynomial with a non-zero constant coefficient or of a wrong degree,
+//! and subshares that do not match the sender's public polynomial constitute provable faults.
+//!
+//! The subshares are sent masked rather than over confidential channels:
+//! in Round 1 each holder also echo-broadcasts an ephemeral Diffie-Hellman key for each other holder,
+//! and in Round 2 sends it the subshares masked with a hash of their shared point.
+//! A holder reporting an invalid subshare reveals its ephemeral secret for the sender, so that the subshare
+//! can be unmasked during evidence verification.
+//!
+//! A holder with several shares receives a subshare for each of them.
+//! If the shares follow a hierarchical access structure, the subshares are the derivatives
+//! of the dealt polynomials of the same orders as the holders' shares.
+//!
+//! The share update is then chained with AuxGen, so the session returns both the updated key share
+//! and the new auxiliary info. The share IDs, the weights, the access structure, and the threshold
+//! of the key share are preserved, and all the holders of the key share must participate.
+//!
+//! The share update can also be run on its own with [`ThresholdShareUpdate`], keeping the current auxiliary info.
+//! This way a [`PrecomputedAuxInfo`] can be used for signing with both the old and the updated key share,
+//! and its precomputation is only repeated when the auxiliary info is refreshed.
+
+use alloc::{
+    boxed::Box,
+    collections::{BTreeMap, BTreeSet},
+    vec::Vec,
+};
+use core::{
+    fmt::{self, Debug, Display},
+    marker::PhantomData,
+};
+
+use manul::{
+    combinators::chain::{ChainedJoin, ChainedMarker, ChainedProtocol, ChainedSplit},
+    protocol::{
+        Artifact, BoxedFormat, BoxedRound, CommunicationInfo, DirectMessage, EchoBroadcast, EntryPoint,
+        FinalizeOutcome, LocalError, MessageValidationError, NormalBroadcast, PartyId, Payload, Protocol,
+        ProtocolError, ProtocolMessage, ProtocolMessagePart, ProtocolValidationError, ReceiveError,
+        RequiredMessageParts, RequiredMessages, Round, RoundId, TransitionInfo,
+    },
+    utils::SerializableMap,
+};
+use rand_core::CryptoRngCore;
+
+use super::aux_gen::{AuxGenWith, AuxGenWithProtocol};
+use crate::{
+    curve::{Point, Scalar},
+    entities::{PrecomputedAuxInfo, Sid, ThresholdKeyShare, ThresholdPublicKeyShares},
+    params::SchemeParams,
+    tools::{
+        dh_mask,
+        protocol_shortcuts::{verify_that, DowncastMap, GetRound, MapValues, SafeGet, Without},
+        sss::{Polynomial, PublicPolynomial},
+        Secret,
+    },
+};
+
+/// The domain separation tag of the masks of the values sent in ThresholdKeyRefresh.
+const MASK_DST: &[u8] = b"ThresholdKeyRefresh mask";
+
+/// A protocol for refreshing the shares of a [`ThresholdKeyShare`] and generating new auxiliary info.
+#[derive(Debug)]
+pub struct ThresholdKeyRefreshProtocol<P: SchemeParams, Id: Debug>(PhantomData<(P, Id)>);
+
+impl<P: SchemeParams, Id: PartyId> ChainedProtocol<Id> for ThresholdKeyRefreshProtocol<P, Id> {
+    type Protocol1 = ThresholdShareUpdateProtocol<P, Id>;
+    type Protocol2 = AuxGenWithProtocol<P, Id, ThresholdKeyShare<P, Id>>;
+}
+
+impl<P: SchemeParams, Id: Debug> ChainedMarker for ThresholdKeyRefreshProtocol<P, Id> {}
+
+/// The share update part of [`ThresholdKeyRefreshProtocol`], also available as a separate protocol.
+#[derive(Debug)]
+pub struct ThresholdShareUpdateProtocol<P: SchemeParams, Id: Debug>(PhantomData<(P, Id)>);
+
+impl<P: SchemeParams, Id: PartyId> Protocol<Id> for ThresholdShareUpdateProtocol<P, Id> {
+    type Result = ThresholdKeyShare<P, Id>;
+    type ProtocolError = ThresholdShareUpdateError<P, Id>;
+
+    fn verify_direct_message_is_invalid(
+        format: &BoxedFormat,
+        round_id: &RoundId,
+        message: &DirectMessage,
+    ) -> Result<(), MessageValidationError> {
+        match round_id {
+            r if r == &1 => message.verify_is_some(),
+            r if r == &2 => message.verify_is_not::<Round2DirectMessage<P, Id>>(format),
+            _ => Err(MessageValidationError::InvalidEvidence("Invalid round number".into())),
+        }
+    }
+
+    fn verify_echo_broadcast_is_invalid(
+        format: &BoxedFormat,
+        round_id: &RoundId,
+        message: &EchoBroadcast,
+    ) -> Result<(), MessageValidationError> {
+        match round_id {
+            r if r == &1 => message.verify_is_not::<Round1EchoBroadcast<P, Id>>(format),
+            r if r == &2 => message.verify_is_some(),
+            _ => Err(MessageValidationError::InvalidEvidence("Invalid round number".into())),
+        }
+    }
+
+    fn verify_normal_broadcast_is_invalid(
+        _format: &BoxedFormat,
+        round_id: &RoundId,
+        message: &NormalBroadcast,
+    ) -> Result<(), MessageValidationError> {
+        match round_id {
+            r if r == &1 => message.verify_is_some(),
+            r if r == &2 => message.verify_is_some(),
+            _ => Err(MessageValidationError::InvalidEvidence("Invalid round number".into())),
+        }
+    }
+}
+
+/// Possible verifiable errors of the share update part of the ThresholdKeyRefresh protocol.
+#[derive(Debug, Clone)]
+#[derive_where::derive_where(Serialize, Deserialize)]
+pub struct ThresholdShareUpdateError<P: SchemeParams, Id: PartyId> {
+    error: Error<P, Id>,
+}
+
+impl<P: SchemeParams, Id: PartyId> Display for ThresholdShareUpdateError<P, Id> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> Result<(), fmt::Error> {
+        write!(f, "{}", self.error)
+    }
+}
+
+impl<P: SchemeParams, Id: PartyId> From<Error<P, Id>> for ThresholdShareUpdateError<P, Id> {
+    fn from(source: Error<P, Id>) -> Self {
+        Self { error: source }
+    }
+}
+
+#[derive(displaydoc::Display, Debug, Clone)]
+#[derive_where::derive_where(Serialize, Deserialize)]
+enum Error<P: SchemeParams, Id: PartyId> {
+    /// Round 1: the public polynomial has a wrong number of coefficients.
+    R1WrongPolynomialDegree,
+    /// Round 1: the constant coefficient of the public polynomial is not zero.
+    R1NonZeroConstant,
+    /// Round 1: wrong IDs in the ephemeral keys map.
+    R1WrongIdsY,
+    /// Round 2: the subshare does not match the public polynomial.
+    R2InvalidSubshare {
+        /// The ID $k$ of the node that received the subshare.
+        reported_by: Id,
+        /// The ephemeral secret $y_{k,j}$, where $j$ is the ID of the guilty party.
+        y: Scalar<P>,
+    },
+}
+
+/// Associated data for the share update part of the ThresholdKeyRefresh protocol.
+#[derive(Debug, Clone)]
+pub struct ThresholdShareUpdateAssociatedData<P: SchemeParams, Id: PartyId> {
+    /// The public part of the key share being refreshed.
+    pub public_key_shares: ThresholdPublicKeyShares<P, Id>,
+}
+
+impl<P: SchemeParams, Id: PartyId> ThresholdShareUpdateAssociatedData<P, Id> {
+    /// Creates the associated data for evidence verification of ThresholdKeyRefresh.
+    ///
+    /// Only the public part of `key_share` is used.
+    pub fn new(key_share: &ThresholdKeyShare<P, Id>) -> Self {
+        Self {
+            public_key_shares: key_share.public_key_shares(),
+        }
+    }
+}
+
+impl<P: SchemeParams, Id: PartyId> ProtocolError<Id> for ThresholdShareUpdateError<P, Id> {
+    type AssociatedData = ThresholdShareUpdateAssociatedData<P, Id>;
+
+    fn required_messages(&self) -> RequiredMessages {
+        match self.error {
+            Error::R1WrongPolynomialDegree | Error::R1NonZeroConstant | Error::R1WrongIdsY => {
+                RequiredMessages::new(RequiredMessageParts::echo_broadcast(), None, None)
+            }
+            Error::R2InvalidSubshare { .. } => RequiredMessages::new(
+                RequiredMessageParts::direct_message(),
+                Some([(1.into(), RequiredMessageParts::echo_broadcast())].into()),
+                Some([1.into()].into()),
+            ),
+        }
+    }
+
+    fn verify_messages_constitute_error(
+        &self,
+        format: &BoxedFormat,
+        guilty_party: &Id,
+        shared_randomness: &[u8],
+        associated_data: &Self::AssociatedData,
+        message: ProtocolMessage,
+        previous_messages: BTreeMap<RoundId, ProtocolMessage>,
+        combined_echos: BTreeMap<RoundId, BTreeMap<Id, EchoBroadcast>>,
+    ) -> Result<(), ProtocolValidationError> {
+        let public_key_shares = &associated_data.public_key_shares;
+        let holders = public_key_shares.holders();
+
+        match &self.error {
+            Error::R1WrongPolynomialDegree => {
+                let r1_eb = message
+                    .echo_broadcast
+                    .deserialize::<Round1EchoBroadcast<P, Id>>(format)?;
+                verify_that(r1_eb.public_polynomial.num_coeffs() != public_key_shares.threshold())
+            }
+            Error::R1NonZeroConstant => {
+                let r1_eb = message
+                    .echo_broadcast
+                    .deserialize::<Round1EchoBroadcast<P, Id>>(format)?;
+                let coeff0 = r1_eb
+                    .public_polynomial
+                    .coeff0()
+                    .map_err(|_| ProtocolValidationError::InvalidEvidence("Empty public polynomial".into()))?;
+                verify_that(coeff0 != &Point::identity())
+            }
+            Error::R1WrongIdsY => {
+                let r1_eb = message
+                    .echo_broadcast
+                    .deserialize::<Round1EchoBroadcast<P, Id>>(format)?;
+                verify_that(r1_eb.cap_ys.keys().cloned().collect::<BTreeSet<_>>() != holders.without(guilty_party))
+            }
+            Error::R2InvalidSubshare { reported_by, y } => {
+                // Check that `y` attached to the evidence is correct
+                // (that is, can be verified against something signed by `guilty_party`).
+                // It is `y_{k,j}` where `k == reported_by` and `j == guilty_party`
+                let r1_eb_k = combined_echos
+                    .get_round(1)?
+                    .try_get("combined echos for Round 1", reported_by)?
+                    .deserialize::<Round1EchoBroadcast<P, Id>>(format)?;
+                let cap_y_kj = r1_eb_k.cap_ys.try_get("ephemeral keys", guilty_party)?;
+                if &y.mul_by_generator() != cap_y_kj {
+                    return Err(ProtocolValidationError::InvalidEvidence(
+                        "The provided `y` is invalid".into(),
+                    ));
+                }
+
+                let r1_eb = previous_messages
+                    .get_round(1)?
+                    .echo_broadcast
+                    .deserialize::<Round1EchoBroadcast<P, Id>>(format)?;
+                let r2_dm = message
+                    .direct_message
+                    .deserialize::<Round2DirectMessage<P, Id>>(format)?;
+
+                // Make sure the message was actually addressed to the reporting node.
+                if &r2_dm.destination != reported_by {
+                    return Err(ProtocolValidationError::InvalidEvidence(
+                        "The direct message was not addressed to the reporting node".into(),
+                    ));
+                }
+
+                let points = public_key_shares.holder_points(reported_by).map_err(|_| {
+                    ProtocolValidationError::InvalidEvidence("The reporting node is not a holder".into())
+                })?;
+
+                let sid = Sid::new::<P, Id>(shared_randomness, &holders);
+                let cap_y_jk = r1_eb.cap_ys.try_get("ephemeral keys", reported_by)?;
+                let cap_d = cap_y_jk * y;
+                verify_that(
+                    r2_dm.cap_cs.len() != points.len()
+                        || r2_dm
+                            .cap_cs
+                            .iter()
+                            .zip(points)
+                            .enumerate()
+                            .any(|(idx, (cap_c, (share_id, order)))| {
+                                let subshare = *cap_c - dh_mask::<P, Id>(MASK_DST, &sid, guilty_party, &cap_d, idx);
+                                subshare.mul_by_generator()
+                                    != r1_eb.public_polynomial.evaluate_derivative(&share_id, order)
+                            }),
+                )
+            }
+        }
+    }
+}
+
+/// An entry point for the [`ThresholdKeyRefreshProtocol`].
+#[derive(Debug, Clone)]
+pub struct ThresholdKeyRefresh<P: SchemeParams, Id: PartyId> {
+    key_share: ThresholdKeyShare<P, Id>,
+}
+
+impl<P: SchemeParams, Id: PartyId> ThresholdKeyRefresh<P, Id> {
+    /// Creates a new entry point given the key share to refresh.
+    ///
+    /// All the holders of the key share must participate in the protocol.
+    pub fn new(key_share: ThresholdKeyShare<P, Id>) -> Self {
+        Self { key_share }
+    }
+}
+
+impl<P: SchemeParams, Id: PartyId> ChainedMarker for ThresholdKeyRefresh<P, Id> {}
+
+impl<P: SchemeParams, Id: PartyId> ChainedSplit<Id> for ThresholdKeyRefresh<P, Id> {
+    type Protocol = ThresholdKeyRefreshProtocol<P, Id>;
+    type EntryPoint = ThresholdShareUpdate<P, Id>;
+
+    fn make_entry_point1(self) -> (Self::EntryPoint, impl ChainedJoin<Id, Protocol = Self::Protocol>) {
+        let all_ids = self.key_share.share_ids.keys().cloned().collect::<BTreeSet<_>>();
+        (
+            ThresholdShareUpdate::new(self.key_share),
+            ThresholdKeyRefreshJoin {
+                all_ids,
+                phantom: PhantomData,
+            },
+        )
+    }
+}
+
+/// Starts the AuxGen part of [`ThresholdKeyRefreshProtocol`] once the share update is finished.
+#[derive(Debug)]
+struct ThresholdKeyRefreshJoin<P, Id> {
+    all_ids: BTreeSet<Id>,
+    phantom: PhantomData<P>,
+}
+
+impl<P: SchemeParams, Id: PartyId> ChainedJoin<Id> for ThresholdKeyRefreshJoin<P, Id> {
+    type Protocol = ThresholdKeyRefreshProtocol<P, Id>;
+    type EntryPoint = AuxGenWith<P, Id, ThresholdKeyShare<P, Id>>;
+
+    fn make_entry_point2(self, result: ThresholdKeyShare<P, Id>) -> Self::EntryPoint {
+        AuxGenWith::new(self.all_ids, result)
+    }
+}
+
+/// An entry point for the [`ThresholdShareUpdateProtocol`].
+#[derive(Debug, Clone)]
+pub struct ThresholdShareUpdate<P: SchemeParams, Id: PartyId> {
+    key_share: ThresholdKeyShare<P, Id>,
+}
+
+impl<P: SchemeParams, Id: PartyId> ThresholdShareUpdate<P, Id> {
+    /// Creates a new entry point given the key share to update.
+    ///
+    /// All the holders of the key share must participate in the protocol.
+    pub fn new(key_share: ThresholdKeyShare<P, Id>) -> Self {
+        Self { key_share }
+    }
+
+    /// Creates a new entry point given the key share to update
+    /// and the precomputed auxiliary info it is used with.
+    ///
+    /// The share update does not change the auxiliary info,
+    /// so `aux_info` can be used for signing with the updated key share as well.
+    /// Returns an error if `aux_info` does not belong to the owner of the key share,
+    /// or does not contain the public auxiliary info of all the holders.
+    pub fn new_precomputed(
+        key_share: &ThresholdKeyShare<P, Id>,
+        aux_info: &PrecomputedAuxInfo<P, Id>,
+    ) -> Result<Self, LocalError> {
+        if aux_info.owner() != &key_share.owner {
+            return Err(LocalError::new(
+                "The key share and the auxiliary info must have secret parts belonging to the same party",
+            ));
+        }
+        if !key_share.share_ids.keys().eq(aux_info.public().as_map().keys()) {
+            return Err(LocalError::new(
+                "The key share and the auxiliary info must have information for the same set of parties",
+            ));
+        }
+        Ok(Self::new(key_share.clone()))
+    }
+}
+
+impl<P: SchemeParams, Id: PartyId> EntryPoint<Id> for ThresholdShareUpdate<P, Id> {
+    type Protocol = ThresholdShareUpdateProtocol<P, Id>;
+
+    fn entry_round_id() -> RoundId {
+        1.into()
+    }
+
+    fn make_round(
+        self,
+        rng: &mut dyn CryptoRngCore,
+        shared_randomness: &[u8],
+        id: &Id,
+    ) -> Result<BoxedRound<Id, Self::Protocol>, LocalError> {
+        if &self.key_share.owner != id {
+            return Err(LocalError::new("The key share must belong to this node"));
+        }
+
+        let all_ids = self.key_share.share_ids.keys().cloned().collect::<BTreeSet<_>>();
+        let other_ids = all_ids.clone().without(id);
+
+        let sid = Sid::new::<P, Id>(shared_randomness, &all_ids);
+
+        let polynomial = Polynomial::random(rng, Secret::init_with(|| Scalar::ZERO), self.key_share.threshold());
+
+        // Ephemeral Diffie-Hellman secrets for masking the subshares sent to and received from each other node.
+        let ys = other_ids
+            .iter()
+            .map(|id| (id.clone(), Secret::init_with(|| Scalar::random(rng))))
+            .collect();
+
+        let context = Context {
+            other_ids,
+            my_id: id.clone(),
+            key_share: self.key_share,
+            public_polynomial: polynomial.public(),
+            polynomial,
+            ys,
+            sid,
+        };
+
+        Ok(BoxedRound::new_dynamic(Round1 { context }))
+    }
+}
+
+#[derive(Debug)]
+struct Context<P: SchemeParams, Id: PartyId> {
+    other_ids: BTreeSet<Id>,
+    my_id: Id,
+    key_share: ThresholdKeyShare<P, Id>,
+    polynomial: Polynomial<P>,
+    public_polynomial: PublicPolynomial<P>,
+    ys: BTreeMap<Id, Secret<Scalar<P>>>, // $y_{i,j}$ where $i$ is this party's index
+    sid: Sid,
+}
+
+#[derive(Debug)]
+struct Round1<P: SchemeParams, Id: PartyId> {
+    context: Context<P, Id>,
+}
+
+#[derive(Debug, Clone)]
+#[derive_where::derive_where(Serialize, Deserialize)]
+pub(super) struct Round1EchoBroadcast<P: SchemeParams, Id: PartyId> {
+    pub(super) public_polynomial: PublicPolynomial<P>,
+    pub(super) cap_ys: SerializableMap<Id, Point<P>>, // $Y_{i,j}$ where $i$ is this party's index
+}
+
+struct Round1Payload<P: SchemeParams> {
+    public_polynomial: PublicPolynomial<P>,
+    cap_y: Point<P>, // $Y_{j,i}$ where $i$ is this party's index
+}
+
+impl<P: SchemeParams, Id: PartyId> Round<Id> for Round1<P, Id> {
+    type Protocol = ThresholdShareUpdateProtocol<P, Id>;
+
+    fn transition_info(&self) -> TransitionInfo {
+        TransitionInfo::new_linear(1)
+    }
+
+    fn communication_info(&self) -> CommunicationInfo<Id> {
+        CommunicationInfo::regular(&self.context.other_ids)
+    }
+
+    fn make_echo_broadcast(
+        &self,
+        _rng: &mut dyn CryptoRngCore,
+        format: &BoxedFormat,
+    ) -> Result<EchoBroadcast, LocalError> {
+        let cap_ys = self.context.ys.map_values_ref(|y| y.mul_by_generator());
+        EchoBroadcast::new(
+            format,
+            Round1EchoBroadcast {
+                public_polynomial: self.context.public_polynomial.clone(),
+                cap_ys: cap_ys.into(),
+            },
+        )
+    }
+
+    fn receive_message(
+        &self,
+        format: &BoxedFormat,
+        from: &Id,
+        message: ProtocolMessage,
+    ) -> Result<Payload, ReceiveError<Id, Self::Protocol>> {
+        message.normal_broadcast.assert_is_none()?;
+        message.direct_message.assert_is_none()?;
+        let echo_broadcast = message
+            .echo_broadcast
+            .deserialize::<Round1EchoBroadcast<P, Id>>(format)?;
+
+        let public_polynomial = echo_broadcast.public_polynomial;
+
+        if public_polynomial.num_coeffs() != self.context.key_share.threshold() {
+            return Err(ReceiveError::protocol(Error::R1WrongPolynomialDegree.into()));
+        }
+
+        if public_polynomial.coeff0()? != &Point::identity() {
+            return Err(ReceiveError::protocol(Error::R1NonZeroConstant.into()));
+        }
+
+        let mut expected_ids = self.context.other_ids.clone().without(from);
+        expected_ids.insert(self.context.my_id.clone());
+        if echo_broadcast.cap_ys.keys().cloned().collect::<BTreeSet<_>>() != expected_ids {
+            return Err(ReceiveError::protocol(Error::R1WrongIdsY.into()));
+        }
+
+        let cap_y = *echo_broadcast.cap_ys.safe_get("ephemeral keys", &self.context.my_id)?;
+        Ok(Payload::new(Round1Payload {
+            public_polynomial,
+            cap_y,
+        }))
+    }
+
+    fn finalize(
+        self: Box<Self>,
+        _rng: &mut dyn CryptoRngCore,
+        payloads: BTreeMap<Id, Payload>,
+        _artifacts: BTreeMap<Id, Artifact>,
+    ) -> Result<FinalizeOutcome<Id, Self::Protocol>, LocalError> {
+        let payloads = payloads.downcast_all::<Round1Payload<P>>()?;
+        let cap_ys = payloads.map_values_ref(|payload| payload.cap_y);
+        let public_polynomials = payloads.map_values(|payload| payload.public_polynomial);
+        let next_round = Round2 {
+            context: self.context,
+            public_polynomials,
+            cap_ys,
+        };
+        Ok(FinalizeOutcome::AnotherRound(BoxedRound::new_dynamic(next_round)))
+    }
+}
+
+#[derive(Debug)]
+struct Round2<P: SchemeParams, Id: PartyId> {
+    context: Context<P, Id>,
+    public_polynomials: BTreeMap<Id, PublicPolynomial<P>>,
+    cap_ys: BTreeMap<Id, Point<P>>, // $Y_{j,i}$ where $i$ is this party's index
+}
+
+#[derive(Clone)]
+#[derive_where::derive_where(Serialize, Deserialize)]
+pub(super) struct Round2DirectMessage<P: SchemeParams, Id: PartyId> {
+    // Included so that the message could not be used as evidence by a node it was not addressed to.
+    pub(super) destination: Id,
+    // The masked subshares, one for each share of the destination.
+    pub(super) cap_cs: Vec<Scalar<P>>,
+}
+
+struct Round2Payload<P: SchemeParams> {
+    subshares: Vec<Secret<Scalar<P>>>,
+}
+
+impl<P: SchemeParams, Id: PartyId> Round<Id> for Round2<P, Id> {
+    type Protocol = ThresholdShareUpdateProtocol<P, Id>;
+
+    fn transition_info(&self) -> TransitionInfo {
+        TransitionInfo::new_linear_terminating(2)
+    }
+
+    fn communication_info(&self) -> CommunicationInfo<Id> {
+        CommunicationInfo::regular(&self.context.other_ids)
+    }
+
+    fn make_direct_message(
+        &self,
+        _rng: &mut dyn CryptoRngCore,
+        format: &BoxedFormat,
+        destination: &Id,
+    ) -> Result<(DirectMessage, Option<Artifact>), LocalError> {
+        let their_points = self.context.key_share.holder_points(destination)?;
+        let cap_y = self.cap_ys.safe_get("ephemeral public keys", destination)?;
+        let y = self.context.ys.safe_get("ephemeral secrets", destination)?;
+        let cap_d = cap_y * y;
+        let cap_cs = their_points
+            .iter()
+            .enumerate()
+            .map(|(idx, (share_id, order))| {
+                let subshare = self.context.polynomial.evaluate_derivative(share_id, *order);
+                let rho = dh_mask::<P, Id>(MASK_DST, &self.context.sid, &self.context.my_id, &cap_d, idx);
+                *(subshare + &rho).expose_secret()
+            })
+            .collect();
+        let direct_message = DirectMessage::new(
+            format,
+            Round2DirectMessage {
+                destination: destination.clone(),
+                cap_cs,
+            },
+        )?;
+        Ok((direct_message, None))
+    }
+
+    fn receive_message(
+        &self,
+        format: &BoxedFormat,
+        from: &Id,
+        message: ProtocolMessage,
+    ) -> Result<Payload, ReceiveError<Id, Self::Protocol>> {
+        message.echo_broadcast.assert_is_none()?;
+        message.normal_broadcast.assert_is_none()?;
+        let direct_message = message
+            .direct_message
+            .deserialize::<Round2DirectMessage<P, Id>>(format)?;
+
+        if direct_message.destination != self.context.my_id {
+            return Err(ReceiveError::unprovable(
+                "The direct message is addressed to another node",
+            ));
+        }
+
+        let cap_y = self.cap_ys.safe_get("ephemeral public keys", from)?;
+        let y = self.context.ys.safe_get("ephemeral secrets", from)?;
+        let cap_d = cap_y * y;
+        let subshares = direct_message
+            .cap_cs
+            .iter()
+            .enumerate()
+            .map(|(idx, cap_c)| {
+                let rho = dh_mask::<P, Id>(MASK_DST, &self.context.sid, from, &cap_d, idx);
+                Secret::init_with(|| *cap_c - rho)
+            })
+            .collect::<Vec<_>>();
+
+        let public_polynomial = self.public_polynomials.safe_get("public polynomials", from)?;
+        let my_points = self.context.key_share.holder_points(&self.context.my_id)?;
+        if subshares.len() != my_points.len()
+            || subshares.iter().zip(&my_points).any(|(subshare, (share_id, order))| {
+                subshare.mul_by_generator() != public_polynomial.evaluate_derivative(share_id, *order)
+            })
+        {
+            return Err(ReceiveError::protocol(
+                Error::R2InvalidSubshare {
+                    reported_by: self.context.my_id.clone(),
+                    y: *y.expose_secret(),
+                }
+                .into(),
+            ));
+        }
+
+        Ok(Payload::new(Round2Payload { subshares }))
+    }
+
+    fn finalize(
+        self: Box<Self>,
+        _rng: &mut dyn CryptoRngCore,
+        payloads: BTreeMap<Id, Payload>,
+        _artifacts: BTreeMap<Id, Artifact>,
+    ) -> Result<FinalizeOutcome<Id, Self::Protocol>, LocalError> {
+        let payloads = payloads.downcast_all::<Round2Payload<P>>()?;
+        let context = self.context;
+        let key_share = &context.key_share;
+
+        // Add our own polynomial to the ones received from other nodes
+        let mut public_polynomials = self.public_polynomials;
+        public_polynomials.insert(context.my_id.clone(), context.public_polynomial.clone());
+
+        // Each secret share is shifted by the sum of the evaluations of all the dealt polynomials
+        // at the corresponding point.
+        let my_points = key_share.holder_points(&context.my_id)?;
+        let secret_shares = key_share
+            .owner_secret_shares()?
+            .into_iter()
+            .zip(my_points)
+            .enumerate()
+            .map(|(idx, ((_share_id, secret_share), (share_id, order)))| {
+                payloads.values().try_fold(
+                    secret_share + context.polynomial.evaluate_derivative(&share_id, order),
+                    |acc, payload| {
+                        let subshare = payload.subshares.get(idx).ok_or_else(|| {
+                            LocalError::new("The number of subshares does not match the number of shares")
+                        })?;
+                        Ok(acc + subshare.clone())
+                    },
+                )
+            })
+            .collect::<Result<Vec<_>, LocalError>>()?;
+
+        let holders = key_share
+            .share_ids
+            .keys()
+            .map(|id| {
+                let shares = key_share
+                    .holder_shares(id)?
+                    .into_iter()
+                    .zip(key_share.holder_points(id)?)
+                    .map(|((share_id, public_share), (_share_id, order))| {
+                        let change = public_polynomials
+                            .values()
+                            .map(|polynomial| polynomial.evaluate_derivative(&share_id, order))
+                            .sum::<Point<P>>();
+                        (share_id, public_share + change)
+                    })
+                    .collect();
+                Ok((id.clone(), shares))
+            })
+            .collect::<Result<BTreeMap<_, _>, LocalError>>()?;
+
+        let key_share = ThresholdKeyShare {
+            access_structure: key_share.access_structure.clone(),
+            ..ThresholdKeyShare::from_weighted_parts(context.my_id, key_share.threshold(), secret_shares, holders)?
+        };
+
+        Ok(FinalizeOutcome::Result(key_share))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use alloc::collections::{BTreeMap, BTreeSet};
+
+    use ecdsa::signature::hazmat::PrehashVerifier;
+    use elliptic_curve::FieldBytes;
+    use manul::{
+        dev::{run_sync, BinaryFormat, TestSessionParams, TestSigner, TestVerifier},
+        signature::Keypair,
+    };
+    use rand_core::{OsRng, RngCore};
+
+    use super::{ThresholdKeyRefresh, ThresholdShareUpdate};
+    use crate::{
+        dev::TestParams, AuxInfo, HierarchicalAccessStructure, InteractiveSigning, SchemeParams, ShareIdAssignment,
+        ThresholdKeyShare,
+    };
+
+    type Curve = <TestParams as SchemeParams>::Curve;
+
+    fn refresh(
+        signers: Vec<TestSigner>,
+        old_shares: &BTreeMap<TestVerifier, ThresholdKeyShare<TestParams, TestVerifier>>,
+    ) -> BTreeMap<TestVerifier, ThresholdKeyShare<TestParams, TestVerifier>> {
+        let entry_points = signers
+            .into_iter()
+            .map(|signer| {
+                let entry_point = ThresholdKeyRefresh::new(old_shares[&signer.verifying_key()].clone());
+                (signer, entry_point)
+            })
+            .collect::<Vec<_>>();
+
+        let results = run_sync::<_, TestSessionParams<BinaryFormat>>(&mut OsRng, entry_points)
+            .unwrap()
+            .results()
+            .unwrap();
+
+        results
+            .into_iter()
+            .map(|(id, (share, aux_info))| {
+                assert_eq!(aux_info.owner(), &id);
+                (id, share)
+            })
+            .collect()
+    }
+
+    /// Checks that the public data is the same at each node and corresponds to all the secret shares,
+    /// and that the shares have changed.
+    fn check_refreshed_shares(
+        old_shares: &BTreeMap<TestVerifier, ThresholdKeyShare<TestParams, TestVerifier>>,
+        new_shares: &BTreeMap<TestVerifier, ThresholdKeyShare<TestParams, TestVerifier>>,
+    ) {
+        let public_key_shares = new_shares.values().next().unwrap().public_key_shares();
+        for (id, share) in new_shares.iter() {
+            assert_eq!(share.share_ids, old_shares[id].share_ids);
+            assert_eq!(share.threshold(), old_shares[id].threshold());
+            assert_eq!(share.public_shares, public_key_shares.public_shares);
+            assert_eq!(share.extra_shares, public_key_shares.extra_shares);
+
+            let old_secret_shares = old_shares[id].owner_secret_shares().unwrap();
+            let secret_shares = share.owner_secret_shares().unwrap();
+            let public_shares = share.holder_shares(id).unwrap();
+            assert_eq!(secret_shares.len(), old_secret_shares.len());
+            for (((share_id, secret_share), (_, old_secret_share)), (public_share_id, public_share)) in
+                secret_shares.iter().zip(old_secret_shares.iter()).zip(public_shares)
+            {
+                assert_eq!(share_id, &public_share_id);
+                assert_ne!(secret_share.expose_secret(), old_secret_share.expose_secret());
+                assert_eq!(secret_share.mul_by_generator(), public_share);
+            }
+        }
+    }
+
+    #[test]
+    fn execute_threshold_key_refresh() {
+        let signers = (0..4).map(TestSigner::new).collect::<Vec<_>>();
+        let ids = signers.iter().map(|signer| signer.verifying_key()).collect::<Vec<_>>();
+        let all_ids = ids.iter().cloned().collect::<BTreeSet<_>>();
+        let threshold = 3;
+
+        let old_shares =
+            ThresholdKeyShare::<TestParams, TestVerifier>::new_centralized(&mut OsRng, &all_ids, threshold, None)
+                .unwrap();
+        let vkey = old_shares[&ids[0]].verifying_key().unwrap();
+
+        let entry_points = signers
+            .into_iter()
+            .map(|signer| {
+                let entry_point = ThresholdKeyRefresh::new(old_shares[&signer.verifying_key()].clone());
+                (signer, entry_point)
+            })
+            .collect::<Vec<_>>();
+
+        let results = run_sync::<_, TestSessionParams<BinaryFormat>>(&mut OsRng, entry_points)
+            .unwrap()
+            .results()
+            .unwrap();
+
+        let share0 = &results[&ids[0]].0;
+        for (id, (share, aux_info)) in results.iter() {
+            // The shares have changed, but the share IDs and the threshold did not
+            assert_ne!(
+                share.secret_share.expose_secret(),
+                old_shares[id].secret_share.expose_secret()
+            );
+            assert_eq!(share.share_ids, old_shares[id].share_ids);
+            assert_eq!(share.threshold(), threshold);
+            assert_eq!(aux_info.owner(), id);
+
+            // The public data is the same at each node and corresponds to the secret shares
+            assert_eq!(share.public_shares, share0.public_shares);
+            assert_eq!(share.secret_share.mul_by_generator(), share.public_shares[id]);
+        }
+
+        // Check that any `threshold` shares produce the same verifying key as before
+        for excluded in &ids {
+            let subset = all_ids
+                .iter()
+                .filter(|id| id != &excluded)
+                .cloned()
+                .collect::<BTreeSet<_>>();
+            for id in &subset {
+                let key_share = results[id].0.to_key_share(&subset).unwrap();
+                assert_eq!(key_share.verifying_key(), vkey);
+            }
+        }
+    }
+
+    #[test]
+    fn execute_weighted_threshold_key_refresh() {
+        let signers = (0..3).map(TestSigner::new).collect::<Vec<_>>();
+        let ids = signers.iter().map(|signer| signer.verifying_key()).collect::<Vec<_>>();
+        let weights = BTreeMap::from([(ids[0], 1), (ids[1], 2), (ids[2], 1)]);
+        let threshold = 3;
+
+        let old_shares = ThresholdKeyShare::<TestParams, TestVerifier>::new_centralized_weighted(
+            &mut OsRng,
+            &weights,
+            threshold,
+            None,
+            &ShareIdAssignment::Sequential,
+        )
+        .unwrap();
+        let vkey = old_shares[&ids[0]].verifying_key().unwrap();
+
+        let new_shares = refresh(signers, &old_shares);
+        check_refreshed_shares(&old_shares, &new_shares);
+
+        for (id, share) in new_shares.iter() {
+            assert_eq!(share.weight(id), weights[id]);
+        }
+
+        // Check that any set of signers with the total weight equal to the threshold
+        // produces the same verifying key as before
+        for subset in [BTreeSet::from([ids[0], ids[1]]), BTreeSet::from([ids[1], ids[2]])] {
+            for id in &subset {
+                let key_share = new_shares[id].to_key_share(&subset).unwrap();
+                assert_eq!(key_share.verifying_key(), vkey);
+            }
+        }
+    }
+
+    #[test]
+    fn execute_hierarchical_threshold_key_refresh() {
+        let signers = (0..5).map(TestSigner::new).collect::<Vec<_>>();
+        let ids = signers.iter().map(|signer| signer.verifying_key()).collect::<Vec<_>>();
+        let executives = BTreeSet::from([ids[0], ids[1]]);
+        let operators = BTreeSet::from([ids[2], ids[3], ids[4]]);
+
+        // Any 4 holders including both of the executives
+        let access_structure = HierarchicalAccessStructure::new([(executives, 2), (operators, 4)].into()).unwrap();
+
+        let old_shares = ThresholdKeyShare::<TestParams, TestVerifier>::new_centralized_hierarchical(
+            &mut OsRng,
+            &access_structure,
+            None,
+        )
+        .unwrap();
+        let vkey = old_shares[&ids[0]].verifying_key().unwrap();
+
+        let new_shares = refresh(signers, &old_shares);
+        check_refreshed_shares(&old_shares, &new_shares);
+
+        for share in new_shares.values() {
+            assert_eq!(share.access_structure().unwrap().holders(), access_structure.holders());
+            assert_eq!(share.verifying_key().unwrap(), vkey);
+        }
+
+        // Check that the authorized sets produce the same verifying key as before
+        for subset in [
+            BTreeSet::from([ids[0], ids[1], ids[2], ids[3]]),
+            BTreeSet::from([ids[0], ids[1], ids[3], ids[4]]),
+        ] {
+            for id in &subset {
+                let key_share = new_shares[id].to_key_share(&subset).unwrap();
+                assert_eq!(key_share.verifying_key(), vkey);
+            }
+        }
+    }
+
+    #[test]
+    fn execute_threshold_share_update_precomputed() {
+        let signers = (0..3).map(TestSigner::new).collect::<Vec<_>>();
+        let ids = signers.iter().map(|signer| signer.verifying_key()).collect::<Vec<_>>();
+        let all_ids = ids.iter().cloned().collect::<BTreeSet<_>>();
+
+        let old_shares =
+            ThresholdKeyShare::<TestParams, TestVerifier>::new_centralized(&mut OsRng, &all_ids, 2, None).unwrap();
+        let aux_infos = AuxInfo::new_centralized(&mut OsRng, &all_ids)
+            .into_iter()
+            .map(|(id, aux_info)| (id, aux_info.precompute()))
+            .collect::<BTreeMap<_, _>>();
+        let vkey = old_shares[&ids[0]].verifying_key().unwrap();
+
+        // The aux info must belong to the owner of the key share
+        assert!(ThresholdShareUpdate::new_precomputed(&old_shares[&ids[0]], &aux_infos[&ids[1]]).is_err());
+
+        let entry_points = signers
+            .iter()
+            .map(|signer| {
+                let id = signer.verifying_key();
+                let entry_point = ThresholdShareUpdate::new_precomputed(&old_shares[&id], &aux_infos[&id]).unwrap();
+                (*signer, entry_point)
+            })
+            .collect::<Vec<_>>();
+
+        let new_shares = run_sync::<_, TestSessionParams<BinaryFormat>>(&mut OsRng, entry_points)
+            .unwrap()
+            .results()
+            .unwrap();
+        check_refreshed_shares(&old_shares, &new_shares);
+
+        // The same precomputed aux info is used for signing with the updated shares
+        let mut message = FieldBytes::<Curve>::default();
+        OsRng.fill_bytes(&mut message);
+        let selected_ids = BTreeSet::from([ids[0], ids[2]]);
+        let entry_points = signers
+            .into_iter()
+            .filter(|signer| selected_ids.contains(&signer.verifying_key()))
+            .map(|signer| {
+                let id = signer.verifying_key();
+                let entry_point = InteractiveSigning::new_threshold_precomputed(
+                    message,
+                    &new_shares[&id],
+                    &aux_infos[&id],
+                    &selected_ids,
+                )
+                .unwrap();
+                (signer, entry_point)
+            })
+            .collect::<Vec<_>>();
+
+        let signatures = run_sync::<_, TestSessionParams<BinaryFormat>>(&mut OsRng, entry_points)
+            .unwrap()
+            .results()
+            .unwrap();
+        for signature in signatures.values() {
+            let (sig, _rec_id) = signature.clone().to_backend();
+            vkey.verify_prehash(&message, &sig).unwrap();
+        }
+    }
+}
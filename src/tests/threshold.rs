@@ -54,7 +54,7 @@ fn full_sequence() {
     // Convert to t-of-t threshold keyshares
     let t_key_shares = key_shares
         .into_iter()
-        .map(|(verifier, key_share)| (verifier, ThresholdKeyShare::from_key_share(&key_share)))
+        .map(|(verifier, key_share)| (verifier, ThresholdKeyShare::from_key_share(&key_share).unwrap()))
         .collect::<BTreeMap<_, _>>();
 
     #[cfg(feature = "bip32")]
@@ -83,6 +83,7 @@ fn full_sequence() {
         verifying_key: t_key_shares[&verifiers[0]].verifying_key().unwrap(),
         old_threshold: t_key_shares[&verifiers[0]].threshold(),
         old_holders,
+        old_public_key_shares: t_key_shares[&verifiers[0]].public_key_shares(),
     };
 
     // Old holders' sessions (which will also hold the newly reshared parts)
@@ -91,6 +92,7 @@ fn full_sequence() {
             let entry_point = KeyResharing::<Params, TestVerifier>::new(
                 Some(OldHolder {
                     key_share: t_key_shares[&verifiers[idx]].clone(),
+                    old_holders: new_holder.old_holders.clone(),
                 }),
                 Some(new_holder.clone()),
                 all_verifiers.clone(),
@@ -121,7 +123,7 @@ fn full_sequence() {
     // All the nodes are holders now, we can unwrap the Options.
     let new_t_key_shares = new_t_key_shares
         .into_iter()
-        .map(|(verifier, key_share)| (verifier, key_share.unwrap()))
+        .map(|(verifier, result)| (verifier, result.unwrap().0))
         .collect::<BTreeMap<_, _>>();
 
     assert_eq!(
@@ -1,5 +1,6 @@
 pub(crate) mod bitvec;
 mod boxed_rng;
+mod dh_mask;
 pub(crate) mod hashing;
 pub(crate) mod protocol_shortcuts;
 mod secret;
@@ -9,4 +10,5 @@ pub(crate) mod sss;
 pub(crate) mod protocol_shortcuts_dev;
 
 pub(crate) use boxed_rng::BoxedRng;
+pub(crate) use dh_mask::dh_mask;
 pub(crate) use secret::Secret;
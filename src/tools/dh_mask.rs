@@ -0,0 +1,28 @@
+use serde::Serialize;
+
+use super::hashing::{Chain, Hasher};
+use crate::{
+    curve::{Point, Scalar},
+    entities::Sid,
+    params::SchemeParams,
+};
+
+/// Returns the mask for the `idx`-th value sent by `sender` in the session `sid`,
+/// derived from the Diffie-Hellman shared point `cap_d` of the sender and the receiver.
+///
+/// `dst` is the domain separation tag of the protocol the value is sent in.
+pub(crate) fn dh_mask<P: SchemeParams, I: Serialize>(
+    dst: &[u8],
+    sid: &Sid,
+    sender: &I,
+    cap_d: &Point<P>,
+    idx: usize,
+) -> Scalar<P> {
+    let mut reader = Hasher::<P::Digest>::new_with_dst(dst)
+        .chain(sid)
+        .chain(sender)
+        .chain(cap_d)
+        .chain(&(idx as u64))
+        .finalize_to_reader();
+    Scalar::from_xof_reader(&mut reader)
+}
@@ -1,4 +1,7 @@
-use alloc::{collections::BTreeMap, vec::Vec};
+use alloc::{
+    collections::{BTreeMap, BTreeSet},
+    vec::Vec,
+};
 use core::ops::{Add, Mul};
 
 use manul::session::LocalError;
@@ -10,6 +13,7 @@ use crate::{
     tools::Secret,
 };
 
+/// The ID of a threshold share (the point at which the sharing polynomial is evaluated to obtain the share).
 #[derive(Debug, Copy, Clone, PartialEq, Eq, PartialOrd, Ord)]
 #[derive_where::derive_where(Serialize, Deserialize)]
 pub struct ShareId<P: SchemeParams>(Scalar<P>);
@@ -18,28 +22,60 @@ impl<P> ShareId<P>
 where
     P: SchemeParams,
 {
+    /// Creates a share ID from an integer. Must be non-zero.
     pub fn new(idx: u64) -> Self {
         Self(Scalar::from(idx))
     }
+
+    pub(crate) fn from_scalar(scalar: Scalar<P>) -> Self {
+        Self(scalar)
+    }
+
+    pub(crate) fn is_zero(&self) -> bool {
+        self.0 == Scalar::ZERO
+    }
 }
 
 pub(crate) fn shamir_evaluation_points<P>(num_shares: usize) -> Vec<ShareId<P>>
 where
     P: SchemeParams,
 {
-    // For now we are hardcoding the points to be 1, 2, ..., n.
-    // Potentially we can derive them from Session ID.
+    // The default points are 1, 2, ..., n.
+    // See `ShareIdAssignment` for the other options.
     (1..=u64::try_from(num_shares).expect("no more than 2^64-1 shares needed"))
         .map(|idx| ShareId(Scalar::from(idx)))
         .collect()
 }
 
+/// Assigns share IDs `1, 2, ..., n` (see [`shamir_evaluation_points`]) to the parties in the order of their IDs.
+pub(crate) fn assign_share_ids<P, I>(ids: &BTreeSet<I>) -> BTreeMap<I, ShareId<P>>
+where
+    P: SchemeParams,
+    I: Ord + Clone,
+{
+    ids.iter().cloned().zip(shamir_evaluation_points(ids.len())).collect()
+}
+
+/// Assigns share IDs `1, 2, ..., n` (see [`shamir_evaluation_points`]) to the parties in the order of their IDs,
+/// where `n` is the total weight, and each party gets the number of consecutive share IDs equal to its weight.
+pub(crate) fn assign_weighted_share_ids<P, I>(weights: &BTreeMap<I, usize>) -> BTreeMap<I, Vec<ShareId<P>>>
+where
+    P: SchemeParams,
+    I: Ord + Clone,
+{
+    let mut points = shamir_evaluation_points(weights.values().sum()).into_iter();
+    weights
+        .iter()
+        .map(|(id, weight)| (id.clone(), points.by_ref().take(*weight).collect()))
+        .collect()
+}
+
 fn evaluate_polynomial<Coef, P>(coeffs: &[Coef], x: &Scalar<P>) -> Coef
 where
     Coef: Copy + Add<Coef, Output = Coef> + for<'a> Mul<&'a Scalar<P>, Output = Coef> + Clone,
     P: SchemeParams,
 {
-    assert!(coeffs.len() > 1, "Expected coefficients to be non-empty");
+    assert!(!coeffs.is_empty(), "Expected coefficients to be non-empty");
     // Evaluate in reverse to save on multiplications.
     // Basically: a0 + a1 x + a2 x^2 + a3 x^3 == (((a3 x) + a2) x + a1) x + a0
 
@@ -76,6 +112,19 @@ where
         })
     }
 
+    /// Evaluates the derivative of the given order at `x`.
+    pub fn evaluate_derivative(&self, x: &ShareId<P>, order: usize) -> Secret<Scalar<P>> {
+        // The coefficient at `x^(k - order)` in the derivative is `a_k * k! / (k - order)!`.
+        self.0
+            .iter()
+            .enumerate()
+            .skip(order)
+            .rev()
+            .fold(Secret::init_with(|| Scalar::ZERO), |acc, (k, coeff)| {
+                acc * x.0 + coeff.clone() * falling_factorial(k, order)
+            })
+    }
+
     pub fn public(&self) -> PublicPolynomial<P> {
         PublicPolynomial(
             self.0
@@ -98,6 +147,22 @@ where
         evaluate_polynomial(&self.0, &x.0)
     }
 
+    /// Evaluates the derivative of the given order at `x`.
+    pub fn evaluate_derivative(&self, x: &ShareId<P>, order: usize) -> Point<P> {
+        self.0
+            .iter()
+            .enumerate()
+            .skip(order)
+            .rev()
+            .fold(Point::identity(), |acc, (k, coeff)| {
+                acc * x.0 + *coeff * falling_factorial(k, order)
+            })
+    }
+
+    pub fn num_coeffs(&self) -> usize {
+        self.0.len()
+    }
+
     pub fn coeff0(&self) -> Result<&Point<P>, LocalError> {
         self.0
             .first()
@@ -118,6 +183,102 @@ where
     indices.iter().map(|idx| (*idx, polynomial.evaluate(idx))).collect()
 }
 
+/// A point a share is evaluated at: the share ID, and the order of the derivative of the sharing polynomial
+/// (zero unless the shares follow a hierarchical access structure).
+pub(crate) type SharePoint<P> = (ShareId<P>, usize);
+
+/// Splits the secret into shares given as `(share_id, derivative_order)` pairs,
+/// where each share is the derivative of the given order of the sharing polynomial at the share ID
+/// (the scheme from T. Tassa "Hierarchical Threshold Secret Sharing", <https://doi.org/10.1007/s00145-006-0334-8>).
+pub(crate) fn birkhoff_split<P>(
+    rng: &mut impl CryptoRngCore,
+    secret: Secret<Scalar<P>>,
+    threshold: usize,
+    points: &[SharePoint<P>],
+) -> BTreeMap<ShareId<P>, Secret<Scalar<P>>>
+where
+    P: SchemeParams,
+{
+    let polynomial = Polynomial::random(rng, secret, threshold);
+    points
+        .iter()
+        .map(|(share_id, order)| (*share_id, polynomial.evaluate_derivative(share_id, *order)))
+        .collect()
+}
+
+/// `k * (k - 1) * ... * (k - order + 1)`
+fn falling_factorial<P: SchemeParams>(k: usize, order: usize) -> Scalar<P> {
+    (0..order)
+        .map(|i| Scalar::from(u64::try_from(k - i).expect("the polynomial degree fits into `u64`")))
+        .product()
+}
+
+/// Returns the coefficients of the Birkhoff interpolation at zero for the shares created by [`birkhoff_split`]:
+/// the secret is the sum of the shares at `points` multiplied by the corresponding coefficients.
+///
+/// The number of points must be equal to the threshold.
+/// Returns `None` if the shares at these points do not determine the secret
+/// (which is the case if the set of their holders is not authorized).
+pub(crate) fn birkhoff_coeffs<P>(points: &[SharePoint<P>]) -> Option<Vec<Scalar<P>>>
+where
+    P: SchemeParams,
+{
+    // The shares are `s = M a`, where `a` are the coefficients of the polynomial,
+    // and `M_{j,k}` is the derivative of the order `d_j` of `x^k` at `x_j`.
+    // We need `c` such that `c^T s = a_0`, that is `M^T c = e_0`, which we solve with Gaussian elimination.
+    // Everything here is public, so the elimination does not have to be constant-time.
+    let n = points.len();
+    let mut matrix = (0..n)
+        .map(|k| {
+            points
+                .iter()
+                .map(|(share_id, order)| {
+                    if k < *order {
+                        Scalar::ZERO
+                    } else {
+                        let power = (*order..k).fold(Scalar::ONE, |acc, _| acc * share_id.0);
+                        falling_factorial(k, *order) * power
+                    }
+                })
+                .collect::<Vec<_>>()
+        })
+        .collect::<Vec<_>>();
+    let mut rhs = (0..n)
+        .map(|k| if k == 0 { Scalar::ONE } else { Scalar::ZERO })
+        .collect::<Vec<_>>();
+
+    for col in 0..n {
+        let pivot = (col..n).find(|row| {
+            matrix
+                .get(*row)
+                .and_then(|matrix_row| matrix_row.get(col))
+                .is_some_and(|value| *value != Scalar::ZERO)
+        })?;
+        matrix.swap(col, pivot);
+        rhs.swap(col, pivot);
+
+        let pivot_row = matrix.get(col)?;
+        let pivot_inv = Option::<Scalar<P>>::from(pivot_row.get(col)?.invert())?;
+        let pivot_row = pivot_row.iter().map(|value| *value * pivot_inv).collect::<Vec<_>>();
+        let pivot_rhs = *rhs.get(col)? * pivot_inv;
+
+        for (row, (matrix_row, rhs_value)) in matrix.iter_mut().zip(rhs.iter_mut()).enumerate() {
+            if row == col {
+                matrix_row.clone_from(&pivot_row);
+                *rhs_value = pivot_rhs;
+            } else {
+                let factor = *matrix_row.get(col)?;
+                for (value, pivot_value) in matrix_row.iter_mut().zip(pivot_row.iter()) {
+                    *value = *value - factor * pivot_value;
+                }
+                *rhs_value = *rhs_value - factor * pivot_rhs;
+            }
+        }
+    }
+
+    Some(rhs)
+}
+
 pub(crate) fn interpolation_coeff<'a, P>(
     share_ids: impl Iterator<Item = &'a ShareId<P>>,
     share_id: &ShareId<P>,
@@ -135,6 +296,28 @@ where
         .product()
 }
 
+/// Returns the Lagrange coefficient of the share `share_id` for the interpolation at the point `x`
+/// (the generalization of [`interpolation_coeff`], which interpolates at zero).
+pub(crate) fn interpolation_coeff_at<'a, P>(
+    share_ids: impl Iterator<Item = &'a ShareId<P>>,
+    share_id: &ShareId<P>,
+    x: &ShareId<P>,
+) -> Scalar<P>
+where
+    P: SchemeParams,
+{
+    share_ids
+        .filter(|id| *id != share_id)
+        .map(|id: &'a ShareId<P>| {
+            (x.0 - id.0)
+                * (share_id.0 - id.0)
+                    .invert()
+                    .expect("all share IDs are distinct as enforced by BTreeSet")
+        })
+        .product()
+}
+
+#[cfg(test)]
 pub(crate) fn shamir_join_scalars<P>(pairs: BTreeMap<ShareId<P>, Secret<Scalar<P>>>) -> Secret<Scalar<P>>
 where
     P: SchemeParams,
@@ -162,7 +345,10 @@ where
 mod tests {
     use rand_core::OsRng;
 
-    use super::{evaluate_polynomial, shamir_evaluation_points, shamir_join_scalars, shamir_split};
+    use super::{
+        birkhoff_coeffs, birkhoff_split, evaluate_polynomial, interpolation_coeff_at, shamir_evaluation_points,
+        shamir_join_scalars, shamir_split, Polynomial, ShareId,
+    };
     use crate::{curve::Scalar, dev::TestParams, tools::Secret};
 
     #[test]
@@ -176,6 +362,25 @@ mod tests {
         assert_eq!(actual, expected);
     }
 
+    #[test]
+    fn evaluate_derivative_public() {
+        let secret = Secret::init_with(|| Scalar::<TestParams>::random(&mut OsRng));
+        let polynomial = Polynomial::random(&mut OsRng, secret, 4);
+        let public_polynomial = polynomial.public();
+        let x = ShareId::new(3);
+
+        for order in 0..5 {
+            assert_eq!(
+                public_polynomial.evaluate_derivative(&x, order),
+                polynomial.evaluate_derivative(&x, order).mul_by_generator()
+            );
+        }
+        assert_eq!(
+            public_polynomial.evaluate_derivative(&x, 0),
+            public_polynomial.evaluate(&x)
+        );
+    }
+
     #[test]
     fn split_and_join() {
         let threshold = 3;
@@ -190,4 +395,83 @@ mod tests {
         let recovered_secret = shamir_join_scalars(shares);
         assert_eq!(recovered_secret.expose_secret(), secret.expose_secret());
     }
+
+    #[test]
+    fn interpolate_at_point() {
+        let threshold = 3;
+        let num_shares = 5;
+        let secret = Secret::init_with(|| Scalar::<TestParams>::random(&mut OsRng));
+        let points = shamir_evaluation_points(num_shares);
+        let shares = shamir_split(&mut OsRng, secret, threshold, &points);
+
+        // Recover the share at `points[4]` from the shares at `points[0..3]`
+        let share_ids = &points[0..3];
+        let recovered_share = share_ids
+            .iter()
+            .map(|share_id| shares[share_id].clone() * interpolation_coeff_at(share_ids.iter(), share_id, &points[4]))
+            .sum::<Secret<_>>();
+        assert_eq!(recovered_share.expose_secret(), shares[&points[4]].expose_secret());
+    }
+
+    #[test]
+    fn birkhoff_split_and_join() {
+        // Three levels with the cumulative thresholds 2, 4, and 6: the shares at the first two points
+        // are the values of the polynomial, the shares at the next three are the values of its second derivative,
+        // and the shares at the last three are the values of its fourth derivative.
+        let thresholds = [2, 4, 6];
+        let threshold = 6;
+        let level = |idx: usize| match idx {
+            0..=1 => 0,
+            2..=4 => 1,
+            _ => 2,
+        };
+        let secret = Secret::init_with(|| Scalar::<TestParams>::random(&mut OsRng));
+        let points = shamir_evaluation_points(8)
+            .into_iter()
+            .enumerate()
+            .map(|(idx, share_id)| (share_id, [0, 2, 4][level(idx)]))
+            .collect::<Vec<_>>();
+        let shares = birkhoff_split(&mut OsRng, secret.clone(), threshold, &points);
+
+        // Go over all the sets of `threshold` shares: the authorized ones (that is, having at least
+        // the cumulative threshold of shares on each level and above) determine the secret,
+        // and for the other ones the interpolation matrix is singular.
+        let mut num_authorized = 0;
+        for mask in 0u32..(1 << points.len()) {
+            if mask.count_ones() as usize != threshold {
+                continue;
+            }
+            let indices = (0..points.len())
+                .filter(|idx| mask & (1 << idx) != 0)
+                .collect::<Vec<_>>();
+            let subset = indices.iter().map(|idx| points[*idx]).collect::<Vec<_>>();
+            let is_authorized = thresholds.iter().enumerate().all(|(level_idx, level_threshold)| {
+                indices.iter().filter(|idx| level(**idx) <= level_idx).count() >= *level_threshold
+            });
+
+            match birkhoff_coeffs(&subset) {
+                Some(coeffs) => {
+                    assert!(is_authorized, "{indices:?} is not authorized but has the coefficients");
+                    let recovered_secret = subset
+                        .iter()
+                        .zip(coeffs)
+                        .map(|((share_id, _order), coeff)| shares[share_id].clone() * coeff)
+                        .sum::<Secret<_>>();
+                    assert_eq!(recovered_secret.expose_secret(), secret.expose_secret());
+                    num_authorized += 1;
+                }
+                None => assert!(!is_authorized, "{indices:?} is authorized but has no coefficients"),
+            }
+        }
+        // Both shares of the first level, and either two shares of the second level and two of the third one
+        // (`3 * 3` sets), or all three shares of the second level and one of the third one (`3` sets).
+        assert_eq!(num_authorized, 12);
+
+        // The same share taken twice does not help either
+        assert!(birkhoff_coeffs(&[points[0], points[0], points[2], points[3], points[5], points[6]]).is_none());
+
+        // The shares of the less privileged levels cannot replace a missing share of the first level,
+        // even if there are more of them than needed
+        assert!(birkhoff_coeffs(&[points[0], points[2], points[3], points[4], points[5], points[6]]).is_none());
+    }
 }
@@ -3,6 +3,7 @@
 mod aff_g;
 mod aff_g_star;
 mod dec;
+mod dleq;
 mod elog;
 mod enc_elg;
 mod fac;
@@ -13,6 +14,7 @@ mod sch;
 pub(crate) use aff_g::{AffGProof, AffGPublicInputs, AffGSecretInputs};
 pub(crate) use aff_g_star::{AffGStarProof, AffGStarPublicInputs, AffGStarSecretInputs};
 pub(crate) use dec::{DecProof, DecPublicInputs, DecSecretInputs};
+pub(crate) use dleq::{DleqProof, DleqPublicInputs, DleqSecret};
 pub(crate) use elog::{ElogProof, ElogPublicInputs, ElogSecretInputs};
 pub(crate) use enc_elg::{EncElgProof, EncElgPublicInputs, EncElgSecretInputs};
 pub(crate) use fac::FacProof;
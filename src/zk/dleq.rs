@@ -0,0 +1,131 @@
+//! Proof of equality of discrete logarithms (Chaum-Pedersen).
+//!
+//! Publish $X$ and $D$ and prove that we know a secret $x$ such that $g^x = X$ and $E^x = D$,
+//! where $g$ is the EC generator and $E$ is some other point.
+//!
+//! Not a part of the paper; used to publish a Diffie-Hellman shared point without revealing the secret key.
+
+use rand_core::CryptoRngCore;
+
+use crate::{
+    curve::{Point, Scalar},
+    params::SchemeParams,
+    tools::{
+        hashing::{Chain, Hashable, Hasher},
+        Secret,
+    },
+};
+
+const HASH_TAG: &[u8] = b"P_dleq";
+
+/// Secret data the proof is based on.
+#[derive(Debug, Clone)]
+pub(crate) struct DleqSecret<P: SchemeParams>(
+    /// `\alpha`
+    Secret<Scalar<P>>,
+);
+
+impl<P: SchemeParams> DleqSecret<P> {
+    pub fn random(rng: &mut dyn CryptoRngCore) -> Self {
+        Self(Secret::init_with(|| Scalar::random(rng)))
+    }
+}
+
+#[derive(Clone, Copy)]
+pub(crate) struct DleqPublicInputs<'a, P: SchemeParams> {
+    /// Point $X = g * x$, where $g$ is the curve generator.
+    pub cap_x: &'a Point<P>,
+    /// Point $E$.
+    pub cap_e: &'a Point<P>,
+    /// Point $D = E * x$.
+    pub cap_d: &'a Point<P>,
+}
+
+fn challenge<P: SchemeParams>(
+    public: DleqPublicInputs<'_, P>,
+    cap_a1: &Point<P>,
+    cap_a2: &Point<P>,
+    aux: &impl Hashable,
+) -> Scalar<P> {
+    let mut reader = Hasher::<P::Digest>::new_with_dst(HASH_TAG)
+        .chain(aux)
+        .chain(public.cap_x)
+        .chain(public.cap_e)
+        .chain(public.cap_d)
+        .chain(cap_a1)
+        .chain(cap_a2)
+        .finalize_to_reader();
+    Scalar::from_xof_reader(&mut reader)
+}
+
+/// ZK proof: equality of discrete logarithms.
+#[derive(Debug, Clone)]
+#[derive_where::derive_where(Serialize, Deserialize)]
+pub(crate) struct DleqProof<P: SchemeParams> {
+    cap_a1: Point<P>,
+    cap_a2: Point<P>,
+    proof: Scalar<P>,
+}
+
+impl<P: SchemeParams> DleqProof<P> {
+    pub fn new(
+        proof_secret: &DleqSecret<P>,
+        x: &Secret<Scalar<P>>,
+        public: DleqPublicInputs<'_, P>,
+        aux: &impl Hashable,
+    ) -> Self {
+        let cap_a1 = proof_secret.0.mul_by_generator();
+        let cap_a2 = public.cap_e * &proof_secret.0;
+        let challenge = challenge(public, &cap_a1, &cap_a2, aux);
+        let proof: Scalar<P> = *(&proof_secret.0 + x * challenge).expose_secret();
+        Self { cap_a1, cap_a2, proof }
+    }
+
+    pub fn verify(&self, public: DleqPublicInputs<'_, P>, aux: &impl Hashable) -> bool {
+        let challenge = challenge(public, &self.cap_a1, &self.cap_a2, aux);
+        self.proof.mul_by_generator() == self.cap_a1 + public.cap_x * challenge
+            && public.cap_e * self.proof == self.cap_a2 + public.cap_d * challenge
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use manul::{dev::BinaryFormat, session::WireFormat};
+    use rand_core::OsRng;
+
+    use super::{DleqProof, DleqPublicInputs, DleqSecret};
+    use crate::{curve::Scalar, dev::TestParams, tools::Secret};
+
+    #[test]
+    fn prove_and_verify() {
+        type Params = TestParams;
+
+        let secret = Secret::init_with(|| Scalar::<Params>::random(&mut OsRng));
+        let cap_x = secret.mul_by_generator();
+        let cap_e = Scalar::<Params>::random(&mut OsRng).mul_by_generator();
+        let cap_d = cap_e * &secret;
+        let aux: &[u8] = b"abcde";
+
+        let public = DleqPublicInputs {
+            cap_x: &cap_x,
+            cap_e: &cap_e,
+            cap_d: &cap_d,
+        };
+        let proof_secret = DleqSecret::random(&mut OsRng);
+        let proof = DleqProof::new(&proof_secret, &secret, public, &aux);
+
+        // Serialization roundtrip
+        let serialized = BinaryFormat::serialize(proof).unwrap();
+        let proof = BinaryFormat::deserialize::<DleqProof<Params>>(&serialized).unwrap();
+
+        assert!(proof.verify(public, &aux));
+
+        // A different `D` is rejected
+        let wrong_cap_d = cap_d + cap_e;
+        let public = DleqPublicInputs {
+            cap_d: &wrong_cap_d,
+            ..public
+        };
+        assert!(!proof.verify(public, &aux));
+    }
+}